@@ -5,7 +5,7 @@
 //! Capsules are used to pass information to the firmware, for example to
 //! trigger a firmware update.
 
-use crate::{Guid, PhysicalAddress};
+use crate::{Guid, PhysicalAddress, newtype_enum};
 use bitflags::bitflags;
 
 /// Descriptor that defines a scatter-gather list for passing a set of capsules
@@ -125,3 +125,117 @@ pub struct CapsuleHeader {
     /// Size in bytes of the entire capsule, including the header.
     pub capsule_image_size: u32,
 }
+
+/// Header of the EFI System Resource Table (ESRT).
+///
+/// The header is followed by `fw_resource_count` [`SystemResourceEntry`]
+/// structures.
+///
+/// Corresponds to the C type `EFI_SYSTEM_RESOURCE_TABLE`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)]
+pub struct SystemResourceTable {
+    /// Number of entries in the table.
+    pub fw_resource_count: u32,
+
+    /// Maximum number of entries the table can hold without reallocation.
+    pub fw_resource_count_max: u32,
+
+    /// Version of the [`SystemResourceEntry`] structure.
+    pub fw_resource_version: u64,
+}
+
+impl SystemResourceTable {
+    /// The only currently defined value of `fw_resource_version`.
+    pub const FIRMWARE_RESOURCE_VERSION: u64 = 1;
+}
+
+/// Entry of the EFI System Resource Table (ESRT), describing a firmware
+/// resource that can be updated with a capsule.
+///
+/// Corresponds to the C type `EFI_SYSTEM_RESOURCE_ENTRY`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)]
+pub struct SystemResourceEntry {
+    /// GUID identifying the firmware resource. This is also the GUID of
+    /// capsules that update it.
+    pub fw_class: Guid,
+
+    /// Type of the firmware resource.
+    pub fw_type: FirmwareType,
+
+    /// Current version of the firmware resource.
+    pub fw_version: u32,
+
+    /// Lowest version the firmware resource can be updated or rolled back
+    /// to.
+    pub lowest_supported_fw_version: u32,
+
+    /// Flags that must be set in the [`CapsuleHeader`] of update capsules.
+    pub capsule_flags: CapsuleFlags,
+
+    /// Version of the last attempted update.
+    pub last_attempt_version: u32,
+
+    /// Result of the last attempted update.
+    pub last_attempt_status: LastAttemptStatus,
+}
+
+newtype_enum! {
+    /// Type of a firmware resource in the ESRT.
+    #[derive(Default)]
+    pub enum FirmwareType: u32 => {
+        /// Unknown firmware type.
+        UNKNOWN = 0,
+        /// System firmware.
+        SYSTEM_FIRMWARE = 1,
+        /// Device firmware.
+        DEVICE_FIRMWARE = 2,
+        /// UEFI driver.
+        UEFI_DRIVER = 3,
+    }
+}
+
+newtype_enum! {
+    /// Result of the last attempted update of a firmware resource.
+    ///
+    /// Values in the range [`ERROR_UNSUCCESSFUL_VENDOR_RANGE_MIN`] to
+    /// [`ERROR_UNSUCCESSFUL_VENDOR_RANGE_MAX`] are vendor-specific errors.
+    ///
+    /// [`ERROR_UNSUCCESSFUL_VENDOR_RANGE_MIN`]: Self::ERROR_UNSUCCESSFUL_VENDOR_RANGE_MIN
+    /// [`ERROR_UNSUCCESSFUL_VENDOR_RANGE_MAX`]: Self::ERROR_UNSUCCESSFUL_VENDOR_RANGE_MAX
+    #[derive(Default)]
+    pub enum LastAttemptStatus: u32 => {
+        /// The update succeeded.
+        SUCCESS = 0,
+        /// The update failed for an unspecified reason.
+        ERROR_UNSUCCESSFUL = 1,
+        /// There were not enough resources to apply the update.
+        ERROR_INSUFFICIENT_RESOURCES = 2,
+        /// The update has a version that is not allowed.
+        ERROR_INCORRECT_VERSION = 3,
+        /// The update image is malformed.
+        ERROR_INVALID_FORMAT = 4,
+        /// The update image could not be authenticated.
+        ERROR_AUTH_ERROR = 5,
+        /// The update was not applied because the system is not on AC power.
+        ERROR_PWR_EVT_AC = 6,
+        /// The update was not applied because the battery level is too low.
+        ERROR_PWR_EVT_BATT = 7,
+        /// The update's dependencies are not satisfied.
+        ERROR_UNSATISFIED_DEPENDENCIES = 8,
+        /// Start of the range of vendor-specific errors.
+        ERROR_UNSUCCESSFUL_VENDOR_RANGE_MIN = 0x1000,
+        /// End (inclusive) of the range of vendor-specific errors.
+        ERROR_UNSUCCESSFUL_VENDOR_RANGE_MAX = 0x4000,
+    }
+}
+
+impl LastAttemptStatus {
+    /// Returns whether this is a vendor-specific error.
+    #[must_use]
+    pub const fn is_vendor_specific(self) -> bool {
+        self.0 >= Self::ERROR_UNSUCCESSFUL_VENDOR_RANGE_MIN.0
+            && self.0 <= Self::ERROR_UNSUCCESSFUL_VENDOR_RANGE_MAX.0
+    }
+}
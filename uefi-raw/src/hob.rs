@@ -0,0 +1,303 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Hand-off blocks (HOBs).
+//!
+//! HOBs are used by the PEI phase of the firmware to pass information about
+//! the system to the DXE phase. The HOB list starts with a
+//! [`HandoffInfoTable`] and ends with a HOB of type
+//! [`HobType::END_OF_HOB_LIST`].
+
+use crate::table::boot::MemoryType;
+use crate::{Boolean, Guid, PhysicalAddress, guid, newtype_enum};
+use bitflags::bitflags;
+
+newtype_enum! {
+    /// Type of a HOB.
+    pub enum HobType: u16 => {
+        /// Phase handoff information table (PHIT).
+        HANDOFF = 0x0001,
+        /// Memory allocation.
+        MEMORY_ALLOCATION = 0x0002,
+        /// Resource descriptor.
+        RESOURCE_DESCRIPTOR = 0x0003,
+        /// GUID extension, with vendor-defined data.
+        GUID_EXTENSION = 0x0004,
+        /// Firmware volume.
+        FV = 0x0005,
+        /// CPU.
+        CPU = 0x0006,
+        /// Memory pool.
+        MEMORY_POOL = 0x0007,
+        /// Firmware volume with name information.
+        FV2 = 0x0009,
+        /// Unused.
+        LOAD_PEIM_UNUSED = 0x000a,
+        /// UEFI capsule.
+        UEFI_CAPSULE = 0x000b,
+        /// Firmware volume with authentication information.
+        FV3 = 0x000c,
+        /// Unused HOB, which should be skipped.
+        UNUSED = 0xfffe,
+        /// End of the HOB list.
+        END_OF_HOB_LIST = 0xffff,
+    }
+}
+
+newtype_enum! {
+    /// Boot mode of the system.
+    pub enum BootMode: u32 => {
+        /// Boot with full configuration.
+        FULL_CONFIGURATION = 0x00,
+        /// Boot with minimal configuration.
+        MINIMAL_CONFIGURATION = 0x01,
+        /// Boot assuming no configuration changes.
+        ASSUMING_NO_CONFIGURATION_CHANGES = 0x02,
+        /// Boot with full configuration plus diagnostics.
+        FULL_CONFIGURATION_PLUS_DIAGNOSTICS = 0x03,
+        /// Boot with default settings.
+        DEFAULT_SETTINGS = 0x04,
+        /// Resume from S4.
+        S4_RESUME = 0x05,
+        /// Boot from S5.
+        S5_RESUME = 0x06,
+        /// Boot with manufacturing mode settings.
+        MFG_MODE_SETTINGS = 0x07,
+        /// Resume from S2.
+        S2_RESUME = 0x10,
+        /// Resume from S3.
+        S3_RESUME = 0x11,
+        /// Boot to update the firmware.
+        FLASH_UPDATE = 0x12,
+        /// Boot in recovery mode.
+        RECOVERY_MODE = 0x20,
+    }
+}
+
+newtype_enum! {
+    /// Type of a resource described by a [`ResourceDescriptorHob`].
+    pub enum ResourceType: u32 => {
+        /// System memory.
+        SYSTEM_MEMORY = 0x00,
+        /// Memory-mapped I/O.
+        MEMORY_MAPPED_IO = 0x01,
+        /// I/O ports.
+        IO = 0x02,
+        /// Memory-mapped firmware device.
+        FIRMWARE_DEVICE = 0x03,
+        /// Memory that decodes I/O cycles.
+        MEMORY_MAPPED_IO_PORT = 0x04,
+        /// Reserved memory.
+        MEMORY_RESERVED = 0x05,
+        /// Reserved I/O ports.
+        IO_RESERVED = 0x06,
+        /// Memory that must be accepted before use, on confidential
+        /// computing platforms.
+        MEMORY_UNACCEPTED = 0x07,
+    }
+}
+
+bitflags! {
+    /// Attributes of a resource described by a [`ResourceDescriptorHob`].
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct ResourceAttribute: u32 {
+        const PRESENT = 0x0000_0001;
+        const INITIALIZED = 0x0000_0002;
+        const TESTED = 0x0000_0004;
+        const SINGLE_BIT_ECC = 0x0000_0008;
+        const MULTIPLE_BIT_ECC = 0x0000_0010;
+        const ECC_RESERVED_1 = 0x0000_0020;
+        const ECC_RESERVED_2 = 0x0000_0040;
+        const READ_PROTECTED = 0x0000_0080;
+        const WRITE_PROTECTED = 0x0000_0100;
+        const EXECUTION_PROTECTED = 0x0000_0200;
+        const UNCACHEABLE = 0x0000_0400;
+        const WRITE_COMBINEABLE = 0x0000_0800;
+        const WRITE_THROUGH_CACHEABLE = 0x0000_1000;
+        const WRITE_BACK_CACHEABLE = 0x0000_2000;
+        const IO_16_BIT = 0x0000_4000;
+        const IO_32_BIT = 0x0000_8000;
+        const IO_64_BIT = 0x0001_0000;
+        const UNCACHED_EXPORTED = 0x0002_0000;
+        const READ_ONLY_PROTECTED = 0x0004_0000;
+        const READ_ONLY_PROTECTABLE = 0x0008_0000;
+        const READ_PROTECTABLE = 0x0010_0000;
+        const WRITE_PROTECTABLE = 0x0020_0000;
+        const EXECUTION_PROTECTABLE = 0x0040_0000;
+        const PERSISTENT = 0x0080_0000;
+        const PERSISTABLE = 0x0100_0000;
+        const MORE_RELIABLE = 0x0200_0000;
+        const ENCRYPTED = 0x0400_0000;
+        const SPECIAL_PURPOSE = 0x0800_0000;
+        const HOT_PLUGGABLE = 0x1000_0000;
+    }
+}
+
+/// Corresponds to the C type `EFI_HOB_GENERIC_HEADER`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct HobHeader {
+    pub hob_type: HobType,
+    /// Length of the HOB in bytes, including this header. Always a multiple
+    /// of 8.
+    pub hob_length: u16,
+    pub reserved: u32,
+}
+
+/// Corresponds to the C type `EFI_HOB_HANDOFF_INFO_TABLE`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct HandoffInfoTable {
+    pub header: HobHeader,
+    pub version: u32,
+    pub boot_mode: BootMode,
+    pub memory_top: PhysicalAddress,
+    pub memory_bottom: PhysicalAddress,
+    pub free_memory_top: PhysicalAddress,
+    pub free_memory_bottom: PhysicalAddress,
+    pub end_of_hob_list: PhysicalAddress,
+}
+
+impl HandoffInfoTable {
+    pub const VERSION: u32 = 0x0009;
+}
+
+/// Corresponds to the C type `EFI_HOB_MEMORY_ALLOCATION_HEADER`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct MemoryAllocationHeader {
+    /// GUID that identifies the purpose of the allocation, or zero.
+    pub name: Guid,
+    pub memory_base_address: PhysicalAddress,
+    pub memory_length: u64,
+    pub memory_type: MemoryType,
+    pub reserved: [u8; 4],
+}
+
+/// Corresponds to the C type `EFI_HOB_MEMORY_ALLOCATION`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct MemoryAllocationHob {
+    pub header: HobHeader,
+    pub alloc_descriptor: MemoryAllocationHeader,
+}
+
+impl MemoryAllocationHob {
+    /// Name of the allocation of the stack.
+    pub const STACK_GUID: Guid = guid!("4ed4bf27-4092-42e9-807d-527b1d00c9bd");
+
+    /// Name of the allocation of the Itanium backing store.
+    pub const BSP_STORE_GUID: Guid = guid!("564b33cd-c92a-4593-90bf-2473e43c6322");
+
+    /// Name of the allocation of a module, which is described by a
+    /// [`MemoryAllocationModuleHob`].
+    pub const MODULE_GUID: Guid = guid!("f8e21975-0899-4f58-a4be-5525a9c6d77a");
+}
+
+/// Corresponds to the C type `EFI_HOB_MEMORY_ALLOCATION_MODULE`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct MemoryAllocationModuleHob {
+    pub header: HobHeader,
+    pub memory_allocation_header: MemoryAllocationHeader,
+    pub module_name: Guid,
+    pub entry_point: PhysicalAddress,
+}
+
+/// Corresponds to the C type `EFI_HOB_RESOURCE_DESCRIPTOR`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ResourceDescriptorHob {
+    pub header: HobHeader,
+    pub owner: Guid,
+    pub resource_type: ResourceType,
+    pub resource_attribute: ResourceAttribute,
+    pub physical_start: PhysicalAddress,
+    pub resource_length: u64,
+}
+
+/// Corresponds to the C type `EFI_HOB_GUID_TYPE`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct GuidHob {
+    pub header: HobHeader,
+    pub name: Guid,
+    /// Variable-length data defined by the owner of `name`.
+    pub data: [u8; 0],
+}
+
+/// Corresponds to the C type `EFI_HOB_FIRMWARE_VOLUME`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct FirmwareVolumeHob {
+    pub header: HobHeader,
+    pub base_address: PhysicalAddress,
+    pub length: u64,
+}
+
+/// Corresponds to the C type `EFI_HOB_FIRMWARE_VOLUME2`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct FirmwareVolume2Hob {
+    pub header: HobHeader,
+    pub base_address: PhysicalAddress,
+    pub length: u64,
+    pub fv_name: Guid,
+    pub file_name: Guid,
+}
+
+/// Corresponds to the C type `EFI_HOB_FIRMWARE_VOLUME3`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct FirmwareVolume3Hob {
+    pub header: HobHeader,
+    pub base_address: PhysicalAddress,
+    pub length: u64,
+    pub authentication_status: u32,
+    pub extracted_fv: Boolean,
+    /// Padding for the 4-byte alignment of `EFI_GUID` in C.
+    pub reserved: [u8; 3],
+    pub fv_name: Guid,
+    pub file_name: Guid,
+}
+
+/// Corresponds to the C type `EFI_HOB_CPU`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct CpuHob {
+    pub header: HobHeader,
+    /// Number of physical address bits.
+    pub size_of_memory_space: u8,
+    /// Number of I/O port address bits.
+    pub size_of_io_space: u8,
+    pub reserved: [u8; 6],
+}
+
+/// Corresponds to the C type `EFI_HOB_UEFI_CAPSULE`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct UefiCapsuleHob {
+    pub header: HobHeader,
+    pub base_address: PhysicalAddress,
+    pub length: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(size_of::<HobHeader>(), 8);
+        assert_eq!(size_of::<HandoffInfoTable>(), 56);
+        assert_eq!(size_of::<MemoryAllocationHob>(), 48);
+        assert_eq!(size_of::<MemoryAllocationModuleHob>(), 72);
+        assert_eq!(size_of::<ResourceDescriptorHob>(), 48);
+        assert_eq!(size_of::<GuidHob>(), 24);
+        assert_eq!(size_of::<FirmwareVolumeHob>(), 24);
+        assert_eq!(size_of::<FirmwareVolume2Hob>(), 56);
+        assert_eq!(size_of::<FirmwareVolume3Hob>(), 64);
+        assert_eq!(size_of::<CpuHob>(), 16);
+        assert_eq!(size_of::<UefiCapsuleHob>(), 24);
+    }
+}
@@ -26,6 +26,7 @@ mod enums;
 
 pub mod capsule;
 pub mod firmware_storage;
+pub mod hob;
 pub mod protocol;
 pub mod table;
 pub mod time;
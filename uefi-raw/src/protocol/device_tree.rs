@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Device tree fixup protocol.
+
+use crate::{Guid, Status, guid};
+use core::ffi::c_void;
+
+bitflags::bitflags! {
+    /// Flags for [`DtFixupProtocol::fixup`].
+    #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+    #[repr(transparent)]
+    pub struct DtFixupFlags: u32 {
+        /// Apply the fixups of the firmware to the device tree.
+        const APPLY_FIXUPS = 1 << 0;
+        /// Reserve the memory regions of the device tree's memory
+        /// reservation block in the UEFI memory map.
+        const RESERVE_MEMORY = 1 << 1;
+        /// Install the device tree as a configuration table.
+        const INSTALL_TABLE = 1 << 2;
+    }
+}
+
+/// Protocol that lets the firmware update a device tree before it is passed
+/// to the operating system.
+///
+/// This is not part of the UEFI specification, but is implemented by U-Boot
+/// and EDK2 for Arm and RISC-V platforms.
+#[derive(Debug)]
+#[repr(C)]
+pub struct DtFixupProtocol {
+    pub revision: u64,
+    pub fixup: unsafe extern "efiapi" fn(
+        this: *mut Self,
+        fdt: *mut c_void,
+        buffer_size: *mut usize,
+        flags: DtFixupFlags,
+    ) -> Status,
+}
+
+impl DtFixupProtocol {
+    pub const GUID: Guid = guid!("e617d64c-fe08-46da-f4dc-bbd5870c7300");
+
+    pub const REVISION: u64 = 0x0001_0000;
+}
@@ -28,6 +28,7 @@ pub mod ata;
 pub mod block;
 pub mod console;
 pub mod device_path;
+pub mod device_tree;
 pub mod disk;
 pub mod driver;
 pub mod file_system;
@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Confidential computing (CC) measurement protocol.
+//!
+//! This protocol is provided by the firmware of confidential computing
+//! guests, such as Intel TDX guests, to measure into the measurement
+//! registers (MRs) of the CC technology. It is defined in the UEFI
+//! Specification, and mirrors the [TCG2 protocol][v2].
+//!
+//! [v2]: super::v2
+
+use super::EventType;
+use super::v2::{
+    Tcg2EventLogBitmap, Tcg2EventLogFormat, Tcg2HashAlgorithmBitmap, Tcg2HashLogExtendEventFlags,
+};
+use crate::{Boolean, Guid, PhysicalAddress, Status, guid, newtype_enum};
+use core::ffi::c_void;
+
+/// Version information.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct CcVersion {
+    /// Major version.
+    pub major: u8,
+    /// Minor version.
+    pub minor: u8,
+}
+
+newtype_enum! {
+    /// Confidential computing technology.
+    pub enum CcTechnology: u8 => {
+        /// No confidential computing technology.
+        NONE = 0,
+        /// AMD Secure Encrypted Virtualization.
+        AMD_SEV = 1,
+        /// Intel Trust Domain Extensions.
+        INTEL_TDX = 2,
+    }
+}
+
+/// Type of the confidential computing technology.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct CcType {
+    /// Confidential computing technology.
+    pub ty: CcTechnology,
+    /// Subtype, specific to the technology.
+    pub sub_type: u8,
+}
+
+/// Information about the protocol and the CC technology.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct CcBootServiceCapability {
+    /// Size of this structure.
+    pub size: u8,
+
+    /// Version of the structure.
+    pub structure_version: CcVersion,
+
+    /// Version of the EFI CC measurement protocol.
+    pub protocol_version: CcVersion,
+
+    /// Bitmap of supported hash algorithms.
+    pub hash_algorithm_bitmap: Tcg2HashAlgorithmBitmap,
+
+    /// Event log formats supported by the firmware.
+    pub supported_event_logs: Tcg2EventLogBitmap,
+
+    /// Type of the CC technology.
+    pub cc_type: CcType,
+}
+
+/// Header of a [`CcMeasurementProtocol::hash_log_extend_event`] event.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C, packed)]
+pub struct CcEventHeader {
+    pub header_size: u32,
+    pub header_version: u16,
+    pub mr_index: u32,
+    pub event_type: EventType,
+}
+
+/// Protocol for measuring into the measurement registers of a confidential
+/// computing guest.
+///
+/// The corresponding C type is `EFI_CC_MEASUREMENT_PROTOCOL`.
+#[derive(Debug)]
+#[repr(C)]
+pub struct CcMeasurementProtocol {
+    pub get_capability: unsafe extern "efiapi" fn(
+        this: *mut Self,
+        protocol_capability: *mut CcBootServiceCapability,
+    ) -> Status,
+
+    pub get_event_log: unsafe extern "efiapi" fn(
+        this: *mut Self,
+        event_log_format: Tcg2EventLogFormat,
+        event_log_location: *mut PhysicalAddress,
+        event_log_last_entry: *mut PhysicalAddress,
+        event_log_truncated: *mut Boolean,
+    ) -> Status,
+
+    pub hash_log_extend_event: unsafe extern "efiapi" fn(
+        this: *mut Self,
+        flags: Tcg2HashLogExtendEventFlags,
+        data_to_hash: PhysicalAddress,
+        data_to_hash_len: u64,
+        event: *const c_void,
+    ) -> Status,
+
+    pub map_pcr_to_mr_index:
+        unsafe extern "efiapi" fn(this: *mut Self, pcr_index: u32, mr_index: *mut u32) -> Status,
+}
+
+impl CcMeasurementProtocol {
+    pub const GUID: Guid = guid!("96751a3d-72f4-41a6-a794-ed5d0e67ae6b");
+
+    /// GUID of the configuration table with the events logged after
+    /// `GetEventLog` was called (`EFI_CC_FINAL_EVENTS_TABLE`).
+    pub const FINAL_EVENTS_TABLE_GUID: Guid = guid!("dd4a4648-2de7-4665-964d-21d9ef5fb446");
+}
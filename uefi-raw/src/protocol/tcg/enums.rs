@@ -24,8 +24,30 @@ newtype_enum! {
         NULL = 0x0010,
         SM3_256 = 0x0012,
         SM4 = 0x0013,
-        // TODO: there are a bunch more, but the above list is probably
-        // more than sufficient for real devices.
+        RSASSA = 0x0014,
+        RSAES = 0x0015,
+        RSAPSS = 0x0016,
+        OAEP = 0x0017,
+        ECDSA = 0x0018,
+        ECDH = 0x0019,
+        ECDAA = 0x001a,
+        SM2 = 0x001b,
+        ECSCHNORR = 0x001c,
+        ECMQV = 0x001d,
+        KDF1_SP800_56A = 0x0020,
+        KDF2 = 0x0021,
+        KDF1_SP800_108 = 0x0022,
+        ECC = 0x0023,
+        SYMCIPHER = 0x0025,
+        CAMELLIA = 0x0026,
+        SHA3_256 = 0x0027,
+        SHA3_384 = 0x0028,
+        SHA3_512 = 0x0029,
+        CTR = 0x0040,
+        OFB = 0x0041,
+        CBC = 0x0042,
+        CFB = 0x0043,
+        ECB = 0x0044,
     }
 }
 
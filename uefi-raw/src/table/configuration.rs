@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::Guid;
+use crate::protocol::loaded_image::LoadedImageProtocol;
+use crate::{Guid, Handle};
+use bitflags::bitflags;
 use core::ffi::c_void;
 
 /// UEFI configuration table.
@@ -14,3 +16,75 @@ pub struct ConfigurationTable {
     pub vendor_guid: Guid,
     pub vendor_table: *mut c_void,
 }
+
+/// Header of the memory attributes table (`EFI_MEMORY_ATTRIBUTES_TABLE`).
+///
+/// The header is followed by `number_of_entries` memory descriptors, each
+/// `descriptor_size` bytes long.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct MemoryAttributesTable {
+    pub version: u32,
+    pub number_of_entries: u32,
+    pub descriptor_size: u32,
+    pub flags: MemoryAttributesTableFlags,
+}
+
+impl MemoryAttributesTable {
+    /// Version of the table in UEFI 2.6 through 2.9.
+    pub const VERSION_1: u32 = 1;
+
+    /// Version of the table since UEFI 2.10, which added `flags`.
+    pub const VERSION_2: u32 = 2;
+}
+
+bitflags! {
+    /// Flags of the [`MemoryAttributesTable`].
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+    #[repr(transparent)]
+    pub struct MemoryAttributesTableFlags: u32 {
+        /// Runtime services code regions are compatible with forward control
+        /// flow guards such as Intel IBT and Arm BTI.
+        const RT_FORWARD_CONTROL_FLOW_GUARD = 0x1;
+    }
+}
+
+/// Header of the debug image info table
+/// (`EFI_DEBUG_IMAGE_INFO_TABLE_HEADER`).
+///
+/// The firmware updates the table when images are loaded and unloaded.
+#[derive(Debug)]
+#[repr(C)]
+pub struct DebugImageInfoTableHeader {
+    pub update_status: DebugImageInfoUpdateStatus,
+    /// Number of entries in `efi_debug_image_info_table`.
+    pub table_size: u32,
+    /// Array of pointers to entries. Entries of unloaded images are null.
+    pub efi_debug_image_info_table: *mut *mut DebugImageInfoNormal,
+}
+
+bitflags! {
+    /// Update status of the [`DebugImageInfoTableHeader`].
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+    #[repr(transparent)]
+    pub struct DebugImageInfoUpdateStatus: u32 {
+        /// The table is being updated.
+        const UPDATE_IN_PROGRESS = 0x1;
+        /// The table was modified since the flag was last cleared.
+        const TABLE_MODIFIED = 0x2;
+    }
+}
+
+/// Entry of the debug image info table (`EFI_DEBUG_IMAGE_INFO_NORMAL`).
+#[derive(Debug)]
+#[repr(C)]
+pub struct DebugImageInfoNormal {
+    pub image_info_type: u32,
+    pub loaded_image_protocol_instance: *const LoadedImageProtocol,
+    pub image_handle: Handle,
+}
+
+impl DebugImageInfoNormal {
+    /// Value of `image_info_type` for this type of entry.
+    pub const TYPE_NORMAL: u32 = 1;
+}
@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! DXE services, defined by the Platform Initialization (PI) specification.
+//!
+//! The DXE Services table is published in the configuration table. It gives
+//! access to the Global Coherency Domain (GCD) services, which manage the
+//! memory and I/O address spaces of the platform, and to the DXE dispatcher.
+
+use crate::table::Header;
+use crate::table::boot::MemoryAttribute;
+use crate::{Guid, Handle, PhysicalAddress, Status, guid, newtype_enum};
+use core::ffi::c_void;
+
+/// Table of pointers to the DXE services.
+#[derive(Debug)]
+#[repr(C)]
+pub struct DxeServices {
+    pub header: Header,
+
+    // Global Coherency Domain services.
+    pub add_memory_space: unsafe extern "efiapi" fn(
+        memory_type: GcdMemoryType,
+        base_address: PhysicalAddress,
+        length: u64,
+        capabilities: MemoryAttribute,
+    ) -> Status,
+    pub allocate_memory_space: unsafe extern "efiapi" fn(
+        allocate_type: GcdAllocateType,
+        memory_type: GcdMemoryType,
+        alignment: usize,
+        length: u64,
+        base_address: *mut PhysicalAddress,
+        image_handle: Handle,
+        device_handle: Handle,
+    ) -> Status,
+    pub free_memory_space:
+        unsafe extern "efiapi" fn(base_address: PhysicalAddress, length: u64) -> Status,
+    pub remove_memory_space:
+        unsafe extern "efiapi" fn(base_address: PhysicalAddress, length: u64) -> Status,
+    pub get_memory_space_descriptor: unsafe extern "efiapi" fn(
+        base_address: PhysicalAddress,
+        descriptor: *mut GcdMemorySpaceDescriptor,
+    ) -> Status,
+    pub set_memory_space_attributes: unsafe extern "efiapi" fn(
+        base_address: PhysicalAddress,
+        length: u64,
+        attributes: MemoryAttribute,
+    ) -> Status,
+    pub get_memory_space_map: unsafe extern "efiapi" fn(
+        number_of_descriptors: *mut usize,
+        memory_space_map: *mut *mut GcdMemorySpaceDescriptor,
+    ) -> Status,
+    pub add_io_space: unsafe extern "efiapi" fn(
+        io_type: GcdIoType,
+        base_address: PhysicalAddress,
+        length: u64,
+    ) -> Status,
+    pub allocate_io_space: unsafe extern "efiapi" fn(
+        allocate_type: GcdAllocateType,
+        io_type: GcdIoType,
+        alignment: usize,
+        length: u64,
+        base_address: *mut PhysicalAddress,
+        image_handle: Handle,
+        device_handle: Handle,
+    ) -> Status,
+    pub free_io_space:
+        unsafe extern "efiapi" fn(base_address: PhysicalAddress, length: u64) -> Status,
+    pub remove_io_space:
+        unsafe extern "efiapi" fn(base_address: PhysicalAddress, length: u64) -> Status,
+    pub get_io_space_descriptor: unsafe extern "efiapi" fn(
+        base_address: PhysicalAddress,
+        descriptor: *mut GcdIoSpaceDescriptor,
+    ) -> Status,
+    pub get_io_space_map: unsafe extern "efiapi" fn(
+        number_of_descriptors: *mut usize,
+        io_space_map: *mut *mut GcdIoSpaceDescriptor,
+    ) -> Status,
+
+    // Dispatcher services.
+    pub dispatch: unsafe extern "efiapi" fn() -> Status,
+    pub schedule:
+        unsafe extern "efiapi" fn(firmware_volume_handle: Handle, file_name: *const Guid) -> Status,
+    pub trust:
+        unsafe extern "efiapi" fn(firmware_volume_handle: Handle, file_name: *const Guid) -> Status,
+
+    // Service to process a firmware volume.
+    pub process_firmware_volume: unsafe extern "efiapi" fn(
+        firmware_volume_header: *const c_void,
+        size: usize,
+        firmware_volume_handle: *mut Handle,
+    ) -> Status,
+
+    // Added in PI 1.3.
+    pub set_memory_space_capabilities: unsafe extern "efiapi" fn(
+        base_address: PhysicalAddress,
+        length: u64,
+        capabilities: MemoryAttribute,
+    ) -> Status,
+}
+
+impl DxeServices {
+    pub const SIGNATURE: u64 = 0x5652_4553_5f45_5844;
+
+    pub const GUID: Guid = guid!("05ad34ba-6f02-4214-952e-4da0398e2bb9");
+}
+
+newtype_enum! {
+    /// Type of a region of the GCD memory space.
+    pub enum GcdMemoryType: u32 => {
+        /// No memory or device decodes the region.
+        NON_EXISTENT = 0,
+        /// Reserved memory.
+        RESERVED = 1,
+        /// System memory.
+        SYSTEM_MEMORY = 2,
+        /// Memory-mapped I/O.
+        MEMORY_MAPPED_IO = 3,
+        /// Persistent memory.
+        PERSISTENT = 4,
+        /// Memory with higher reliability than other system memory.
+        MORE_RELIABLE = 5,
+        /// Memory that must be accepted before use, on confidential
+        /// computing platforms.
+        UNACCEPTED = 6,
+    }
+}
+
+newtype_enum! {
+    /// Type of a region of the GCD I/O space.
+    pub enum GcdIoType: u32 => {
+        /// No device decodes the region.
+        NON_EXISTENT = 0,
+        /// Reserved I/O ports.
+        RESERVED = 1,
+        /// I/O ports.
+        IO = 2,
+    }
+}
+
+newtype_enum! {
+    /// How to search for a free region of the GCD memory or I/O space.
+    pub enum GcdAllocateType: u32 => {
+        /// Any region, searching from the lowest address.
+        ANY_SEARCH_BOTTOM_UP = 0,
+        /// Any region below a maximum address, searching from the lowest
+        /// address.
+        MAX_ADDRESS_SEARCH_BOTTOM_UP = 1,
+        /// The region at a specific address.
+        ADDRESS = 2,
+        /// Any region, searching from the highest address.
+        ANY_SEARCH_TOP_DOWN = 3,
+        /// Any region below a maximum address, searching from the highest
+        /// address.
+        MAX_ADDRESS_SEARCH_TOP_DOWN = 4,
+    }
+}
+
+/// Corresponds to the C type `EFI_GCD_MEMORY_SPACE_DESCRIPTOR`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct GcdMemorySpaceDescriptor {
+    pub base_address: PhysicalAddress,
+    pub length: u64,
+    /// Attributes that the region supports.
+    pub capabilities: MemoryAttribute,
+    /// Attributes that are currently set on the region.
+    pub attributes: MemoryAttribute,
+    pub memory_type: GcdMemoryType,
+    /// Image that allocated the region, or null if it is free.
+    pub image_handle: Handle,
+    /// Device for which the region was allocated, or null.
+    pub device_handle: Handle,
+}
+
+/// Corresponds to the C type `EFI_GCD_IO_SPACE_DESCRIPTOR`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct GcdIoSpaceDescriptor {
+    pub base_address: PhysicalAddress,
+    pub length: u64,
+    pub io_type: GcdIoType,
+    /// Image that allocated the region, or null if it is free.
+    pub image_handle: Handle,
+    /// Device for which the region was allocated, or null.
+    pub device_handle: Handle,
+}
@@ -7,6 +7,7 @@ mod revision;
 
 pub mod boot;
 pub mod configuration;
+pub mod dxe;
 pub mod runtime;
 pub mod system;
 
@@ -162,6 +162,32 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Attributes of a load option (`EFI_LOAD_OPTION`), such as a `Boot####`
+    /// variable.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+    #[repr(transparent)]
+    pub struct LoadOptionAttributes: u32 {
+        /// The boot manager tries to boot the option.
+        const ACTIVE = 0x0000_0001;
+
+        /// All drivers are reconnected after loading the option. Only valid
+        /// for `Driver####` options.
+        const FORCE_RECONNECT = 0x0000_0002;
+
+        /// The option is hidden from the boot menu.
+        const HIDDEN = 0x0000_0008;
+
+        /// Bits of the category of the option. Options without category bits
+        /// are boot options.
+        const CATEGORY = 0x0000_1f00;
+
+        /// The option is an application, such as a firmware setup utility,
+        /// that is not booted automatically.
+        const CATEGORY_APP = 0x0000_0100;
+    }
+}
+
 newtype_enum! {
     /// Variable vendor GUID. This serves as a namespace for variables to
     /// avoid naming conflicts between vendors. The UEFI specification
@@ -174,3 +200,146 @@ newtype_enum! {
         IMAGE_SECURITY_DATABASE = guid!("d719b2cb-3d3a-4596-a3bc-dad00e67656f"),
     }
 }
+
+newtype_enum! {
+    /// Type of the signatures in a [`SignatureListHeader`], which determines
+    /// the format of their data.
+    pub enum SignatureType: Guid => {
+        /// SHA-1 hash.
+        SHA1 = guid!("826ca512-cf10-4ac9-b187-be01496631bd"),
+
+        /// SHA-224 hash.
+        SHA224 = guid!("0b6e5233-a65c-44c9-9407-d9ab83bfc8bd"),
+
+        /// SHA-256 hash.
+        SHA256 = guid!("c1c41626-504c-4092-aca9-41f936934328"),
+
+        /// SHA-384 hash.
+        SHA384 = guid!("ff3e5307-9fd0-48c9-85f1-8ad56c701e01"),
+
+        /// SHA-512 hash.
+        SHA512 = guid!("093e0fae-a6c4-4f50-9f1b-d41e2b89c19a"),
+
+        /// RSA-2048 public key modulus.
+        RSA2048 = guid!("3c5766e8-269c-4e34-aa14-ed776e85b3b6"),
+
+        /// RSA-2048 signature of a SHA-1 hash.
+        RSA2048_SHA1 = guid!("67f8444f-8743-48f1-a328-1eaab8736080"),
+
+        /// RSA-2048 signature of a SHA-256 hash.
+        RSA2048_SHA256 = guid!("e2b36190-879b-4a3d-ad8d-f2e7bba32784"),
+
+        /// DER-encoded X.509 certificate.
+        X509 = guid!("a5c059a1-94e4-4aa7-87b5-ab155c2bf072"),
+
+        /// SHA-256 hash of the to-be-signed part of an X.509 certificate,
+        /// followed by its time of revocation.
+        X509_SHA256 = guid!("3bd2a492-96c0-4079-b420-fcf98ef103ed"),
+
+        /// SHA-384 hash of the to-be-signed part of an X.509 certificate,
+        /// followed by its time of revocation.
+        X509_SHA384 = guid!("7076876e-80c2-4ee6-aad2-28b349a6865b"),
+
+        /// SHA-512 hash of the to-be-signed part of an X.509 certificate,
+        /// followed by its time of revocation.
+        X509_SHA512 = guid!("446dbf63-2502-4cda-bcfa-2465d2b0fe9d"),
+
+        /// Email address of the external manager of the platform key.
+        EXTERNAL_MANAGEMENT = guid!("452e8ced-dfff-4b8c-ae01-5118862e682c"),
+    }
+}
+
+/// Header of a signature list in a signature database variable, such as `db`.
+///
+/// The header is followed by `signature_header_size` bytes of header data, and
+/// by signatures of `signature_size` bytes each. A signature starts with the
+/// GUID of its owner, followed by its data.
+///
+/// The corresponding C type is `EFI_SIGNATURE_LIST`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct SignatureListHeader {
+    /// Type of the signatures in the list.
+    pub signature_type: SignatureType,
+
+    /// Size in bytes of the list, including this header.
+    pub signature_list_size: u32,
+
+    /// Size in bytes of the data following this header.
+    pub signature_header_size: u32,
+
+    /// Size in bytes of each signature, including the owner GUID.
+    pub signature_size: u32,
+}
+
+newtype_enum! {
+    /// Type of the certificate in a [`WinCertificate`].
+    pub enum WinCertificateType: u16 => {
+        /// PKCS #7 `SignedData` (`WIN_CERT_TYPE_PKCS_SIGNED_DATA`).
+        PKCS_SIGNED_DATA = 0x0002,
+
+        /// PKCS #1 v1.5 signature (`WIN_CERT_TYPE_EFI_PKCS115`).
+        EFI_PKCS115 = 0x0ef0,
+
+        /// Certificate whose type is given by a GUID
+        /// (`WIN_CERT_TYPE_EFI_GUID`), see [`WinCertificateUefiGuid`].
+        EFI_GUID = 0x0ef1,
+    }
+}
+
+/// Header of a certificate, followed by the certificate data.
+///
+/// The corresponding C type is `WIN_CERTIFICATE`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct WinCertificate {
+    /// Size in bytes of the certificate, including this header.
+    pub length: u32,
+
+    /// Revision of the structure, [`WinCertificate::REVISION`].
+    pub revision: u16,
+
+    /// Type of the certificate.
+    pub certificate_type: WinCertificateType,
+}
+
+impl WinCertificate {
+    /// Current revision of the structure.
+    pub const REVISION: u16 = 0x0200;
+}
+
+/// Certificate whose type is given by a GUID, followed by the certificate
+/// data.
+///
+/// The corresponding C type is `WIN_CERTIFICATE_UEFI_GUID`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct WinCertificateUefiGuid {
+    /// Header, with the type [`WinCertificateType::EFI_GUID`].
+    pub header: WinCertificate,
+
+    /// Type of the certificate data.
+    pub cert_type: Guid,
+}
+
+impl WinCertificateUefiGuid {
+    /// Certificate data type of a DER-encoded PKCS #7 `SignedData`
+    /// (`EFI_CERT_TYPE_PKCS7_GUID`).
+    pub const CERT_TYPE_PKCS7: Guid = guid!("4aafd29d-68df-49ee-8aa9-347d375665a7");
+}
+
+/// Authentication header of the data written to a variable with the
+/// [`VariableAttributes::TIME_BASED_AUTHENTICATED_WRITE_ACCESS`] attribute.
+/// The header is followed by the certificate data and the variable payload.
+///
+/// The corresponding C type is `EFI_VARIABLE_AUTHENTICATION_2`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct VariableAuthentication2 {
+    /// Time of the write, in UTC and with the `pad1`, `nanosecond`,
+    /// `time_zone`, `daylight` and `pad2` fields set to zero.
+    pub time_stamp: Time,
+
+    /// Certificate, with the type [`WinCertificateUefiGuid::CERT_TYPE_PKCS7`].
+    pub auth_info: WinCertificateUefiGuid,
+}
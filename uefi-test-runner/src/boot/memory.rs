@@ -5,18 +5,28 @@ pub fn test() {
 
     bootservices::allocate_pages();
     bootservices::allocate_pool();
+    bootservices::page_box();
+    bootservices::page_vec();
     bootservices::memory_map();
 
     global::alloc_vec();
     global::alloc_alignment();
+
+    memory_type::pool_box();
+    memory_type::tracking();
+    #[cfg(feature = "unstable")]
+    memory_type::allocator_api();
 }
 
 /// Tests that directly use UEFI boot services to allocate memory.
 mod bootservices {
     use alloc::vec::Vec;
+    use uefi::Status;
     use uefi::boot;
     use uefi::boot::AllocateType;
+    use uefi::boot::PAGE_SIZE;
     use uefi::mem::memory_map::{MemoryMap, MemoryMapMut};
+    use uefi::mem::{PageBox, PageVec};
     use uefi_raw::table::boot::MemoryType;
 
     /// Tests the `allocate_pages` boot service.
@@ -50,6 +60,69 @@ mod bootservices {
         unsafe { boot::free_pool(ptr) }.unwrap();
     }
 
+    /// Tests allocating pages with `PageBox`.
+    pub fn page_box() {
+        let mut pages = PageBox::new(MemoryType::LOADER_DATA, 2).unwrap();
+        assert_eq!(pages.len(), 2 * PAGE_SIZE);
+        assert_eq!(pages.physical_address() % PAGE_SIZE as u64, 0);
+        assert!(pages.iter().all(|b| *b == 0));
+        pages[PAGE_SIZE] = 0xff;
+        let words = unsafe { pages.as_slice::<u32>() };
+        assert_eq!(words.len(), 2 * PAGE_SIZE / 4);
+        assert_eq!(words[PAGE_SIZE / 4], 0xff);
+
+        // Allocate below 4GiB.
+        let low = PageBox::new_below(0xffff_ffff, MemoryType::LOADER_DATA, 1).unwrap();
+        assert!(low.physical_address() + PAGE_SIZE as u64 - 1 <= 0xffff_ffff);
+
+        // Free a page, then allocate it again at the same address.
+        let addr = low.physical_address();
+        drop(low);
+        let fixed = PageBox::new_at(addr, MemoryType::LOADER_DATA, 1).unwrap();
+        assert_eq!(fixed.physical_address(), addr);
+
+        // Misaligned and empty allocations are rejected.
+        assert_eq!(
+            PageBox::new_at(addr + 1, MemoryType::LOADER_DATA, 1)
+                .unwrap_err()
+                .status(),
+            Status::INVALID_PARAMETER
+        );
+        assert_eq!(
+            PageBox::new(MemoryType::LOADER_DATA, 0)
+                .unwrap_err()
+                .status(),
+            Status::INVALID_PARAMETER
+        );
+
+        // Hand the pages off and take them back.
+        let (ptr, count) = fixed.into_raw();
+        let fixed = unsafe { PageBox::from_raw(ptr, count, MemoryType::LOADER_DATA) };
+        assert_eq!(fixed.physical_address(), addr);
+    }
+
+    /// Tests the growable `PageVec` buffer.
+    pub fn page_vec() {
+        let mut vec = PageVec::new_below(0xffff_ffff, MemoryType::LOADER_DATA);
+        assert_eq!(vec.capacity(), 0);
+        assert_eq!(vec.physical_address(), None);
+
+        for i in 0..2000u64 {
+            vec.push(i);
+        }
+        assert_eq!(vec.len(), 2000);
+        assert!(vec.capacity() >= 2000);
+        assert!(vec.physical_address().unwrap() <= 0xffff_ffff);
+        assert!(vec.iter().copied().eq(0..2000));
+
+        vec.extend_from_slice(&[1, 2, 3]).unwrap();
+        assert_eq!(vec.pop(), Some(3));
+        vec.truncate(10);
+        assert_eq!(*vec, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        vec.clear();
+        assert!(vec.is_empty());
+    }
+
     /// Tests getting the memory map and performing a few sanity checks on it.
     pub fn memory_map() {
         info!("Testing memory map functions");
@@ -131,3 +204,89 @@ mod global {
         }
     }
 }
+
+/// Tests that allocate memory of a specific memory type.
+mod memory_type {
+    use uefi::boot::{self, MemoryType};
+    use uefi::mem::PoolBox;
+    use uefi::mem::memory_map::MemoryMap;
+
+    /// Check that the memory map contains `addr` in a region of `ty`.
+    fn assert_memory_type(addr: u64, ty: MemoryType) {
+        let memory_map = boot::memory_map(MemoryType::LOADER_DATA).unwrap();
+        let desc = memory_map
+            .entries()
+            .find(|desc| {
+                (desc.phys_start..desc.phys_start + desc.page_count * 4096).contains(&addr)
+            })
+            .expect("address is not in the memory map");
+        assert_eq!(desc.ty, ty);
+    }
+
+    pub fn pool_box() {
+        let buffer = PoolBox::from_slice(MemoryType::RUNTIME_SERVICES_DATA, b"hello").unwrap();
+        assert_eq!(&*buffer, b"hello");
+        assert_eq!(buffer.memory_type(), MemoryType::RUNTIME_SERVICES_DATA);
+        assert_memory_type(buffer.as_ptr() as u64, MemoryType::RUNTIME_SERVICES_DATA);
+    }
+
+    pub fn tracking() {
+        use core::alloc::{GlobalAlloc, Layout};
+        use uefi::allocator::MemoryTypeAllocator;
+        use uefi::allocator::tracking;
+
+        let allocator = MemoryTypeAllocator::new(MemoryType::ACPI_RECLAIM);
+        let stats = || {
+            tracking::stats()
+                .find(|stats| stats.memory_type == MemoryType::ACPI_RECLAIM)
+                .map_or((0, 0), |stats| (stats.live_allocations, stats.live_bytes))
+        };
+        let (live_allocations, live_bytes) = stats();
+
+        let layout = Layout::from_size_align(100, 16).unwrap();
+        let ptr = unsafe { allocator.alloc(layout) };
+        assert!(!ptr.is_null());
+        assert_eq!(stats(), (live_allocations + 1, live_bytes + 100));
+
+        let mut found = None;
+        tracking::for_each_outstanding(|allocation| {
+            if allocation.address == ptr as usize {
+                found = Some(*allocation);
+            }
+        })
+        .unwrap();
+        let found = found.expect("allocation was not recorded");
+        assert_eq!(found.size, 100);
+        assert_eq!(found.align, 16);
+        assert_eq!(found.memory_type, MemoryType::ACPI_RECLAIM);
+        assert!(found.location.unwrap().file().ends_with("memory.rs"));
+
+        unsafe { allocator.dealloc(ptr, layout) };
+        assert_eq!(stats(), (live_allocations, live_bytes));
+    }
+
+    #[cfg(feature = "unstable")]
+    pub fn allocator_api() {
+        use alloc::boxed::Box;
+        use alloc::vec::Vec;
+        use uefi::allocator::MemoryTypeAllocator;
+
+        static ALLOCATOR: MemoryTypeAllocator =
+            MemoryTypeAllocator::new(MemoryType::RUNTIME_SERVICES_DATA);
+
+        let boxed = Box::new_in(123u64, &ALLOCATOR);
+        assert_memory_type(
+            &*boxed as *const u64 as u64,
+            MemoryType::RUNTIME_SERVICES_DATA,
+        );
+
+        let mut vec = Vec::new_in(&ALLOCATOR);
+        vec.extend_from_slice(&[1u8, 2, 3]);
+        assert_eq!(vec, [1, 2, 3]);
+        assert_memory_type(vec.as_ptr() as u64, MemoryType::RUNTIME_SERVICES_DATA);
+
+        // Zero-sized allocations.
+        let empty: Box<[u8], _> = Vec::new_in(&ALLOCATOR).into_boxed_slice();
+        assert!(empty.is_empty());
+    }
+}
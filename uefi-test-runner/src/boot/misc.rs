@@ -6,8 +6,10 @@ use core::time::Duration;
 use uefi::boot::{
     EventType, OpenProtocolAttributes, OpenProtocolParams, SearchType, TimerTrigger, Tpl,
 };
+use uefi::clock::{self, Instant};
 use uefi::mem::memory_map::MemoryType;
 use uefi::proto::unsafe_protocol;
+use uefi::table::cfg::ConfigTable;
 use uefi::{Event, Guid, Identify, boot, guid, system};
 
 pub fn test() {
@@ -26,8 +28,11 @@ pub fn test() {
     test_reinstall_protocol_interface();
     test_uninstall_protocol_interface();
     test_install_configuration_table();
+    test_install_config_table();
     info!("Testing crc32...");
     test_calculate_crc32();
+    info!("Testing clock...");
+    test_clock();
 }
 
 fn test_tpl() {
@@ -253,6 +258,50 @@ fn test_install_configuration_table() {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+struct TestConfigTable {
+    signature: u64,
+    value: u32,
+}
+
+impl TestConfigTable {
+    const SIGNATURE: u64 = u64::from_le_bytes(*b"TESTCFG\0");
+}
+
+impl ConfigTable for &'static TestConfigTable {
+    const GUID: Guid = guid!("0bd0c7c1-8ab4-4b24-9d6b-2a3f0c1b5e77");
+    type Error = &'static str;
+    const NOT_FOUND: &'static str = "not found";
+
+    unsafe fn from_entry(_guid: Guid, address: *const c_void) -> Result<Self, &'static str> {
+        let table = unsafe { &*address.cast::<TestConfigTable>() };
+        if table.signature != TestConfigTable::SIGNATURE {
+            return Err("invalid signature");
+        }
+        Ok(table)
+    }
+}
+
+fn test_install_config_table() {
+    static GUID: Guid = <&TestConfigTable as ConfigTable>::GUID;
+    let table = TestConfigTable {
+        signature: TestConfigTable::SIGNATURE,
+        value: 123,
+    };
+
+    let installed = boot::install_config_table(&GUID, &table).unwrap();
+    assert_eq!(*installed, table);
+    let found = system::find_config_table::<&TestConfigTable>().unwrap();
+    assert!(ptr::eq(found, installed));
+
+    boot::uninstall_config_table(&GUID).unwrap();
+    assert_eq!(
+        system::find_config_table::<&TestConfigTable>(),
+        Err("not found")
+    );
+}
+
 fn test_calculate_crc32() {
     let data = "uefi-rs";
 
@@ -260,3 +309,22 @@ fn test_calculate_crc32() {
 
     assert_eq!(crc, 0xcfc96a3e);
 }
+
+fn test_clock() {
+    let source = clock::calibrate().unwrap();
+    info!(
+        "Clock source: {source:?}, frequency: {} Hz",
+        clock::frequency().unwrap()
+    );
+
+    let start = Instant::now();
+    boot::stall(Duration::from_millis(20));
+    let elapsed = start.elapsed();
+    info!("Stalled for {elapsed:?}");
+
+    // Allow some slack for imprecise calibration and for slow emulation.
+    assert!(elapsed >= Duration::from_millis(15));
+    assert!(elapsed < Duration::from_secs(5));
+    assert!(Instant::now().duration_since(start) >= elapsed);
+    assert_eq!(start + Duration::ZERO, start);
+}
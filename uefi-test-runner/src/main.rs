@@ -2,6 +2,7 @@
 
 #![no_std]
 #![no_main]
+#![cfg_attr(feature = "unstable", feature(allocator_api))]
 
 #[macro_use]
 extern crate log;
@@ -22,6 +23,7 @@ mod boot;
 mod fs;
 mod proto;
 mod runtime;
+mod table;
 
 #[entry]
 fn efi_main() -> Status {
@@ -48,6 +50,8 @@ fn efi_main() -> Status {
 
     boot::test();
 
+    table::test();
+
     // Test all the supported protocols.
     proto::test();
 
@@ -210,9 +214,31 @@ fn shutdown() -> ! {
 
     info!("Testing complete, exiting boot services...");
 
+    // Keep the global allocator working after exiting boot services.
+    uefi::allocator::reserve_exit_arena(64 * 1024).unwrap();
+    let allocated_before_ebs = vec![1u8; 16];
+
     // Exit boot services as a proof that it works :)
+    let before_ebs = uefi::clock::Instant::now();
     let mmap = unsafe { uefi::boot::exit_boot_services(None) };
 
+    // The architecture counters keep working after exiting boot services.
+    if uefi::clock::source() != Some(uefi::clock::ClockSource::TimestampProtocol) {
+        info!("Exiting boot services took {:?}", before_ebs.elapsed());
+    }
+
+    // Allocations are served from the arena, and freeing memory allocated
+    // before exiting boot services is tolerated.
+    drop(allocated_before_ebs);
+    let mut values = Vec::new();
+    for i in 0..1000u32 {
+        values.push(i);
+    }
+    assert_eq!(values.iter().sum::<u32>(), 499_500);
+    let usage = uefi::allocator::exit_arena_usage().unwrap();
+    info!("Exit arena usage: {usage:?}");
+    assert!(usage.used >= 4000 && usage.used <= usage.size);
+
     info!("Memory Map:");
     for desc in mmap.entries() {
         info!(
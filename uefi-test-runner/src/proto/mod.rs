@@ -25,6 +25,7 @@ pub fn test() {
     pci::test();
     pi::test();
     rng::test();
+    security::test();
     shell_params::test();
     string::test();
     usb::test();
@@ -121,6 +122,7 @@ mod pci;
 mod pi;
 mod rng;
 mod scsi;
+mod security;
 #[cfg(any(
     target_arch = "x86",
     target_arch = "x86_64",
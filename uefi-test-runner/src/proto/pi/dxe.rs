@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use uefi::Status;
+use uefi::proto::pi::dxe::{self, GcdMemoryType};
+
+pub fn test() {
+    info!("Running DXE services test");
+    let map = match dxe::memory_space_map() {
+        Ok(map) => map,
+        Err(err) if err.status() == Status::NOT_FOUND => {
+            info!("DXE services are not available");
+            return;
+        }
+        Err(err) => panic!("failed to get the memory space map: {err:?}"),
+    };
+    assert!(!map.is_empty());
+    for pair in map.windows(2) {
+        assert_eq!(pair[0].range.end, pair[1].range.start);
+    }
+    let memory = map
+        .iter()
+        .find(|descriptor| descriptor.memory_type == GcdMemoryType::SYSTEM_MEMORY)
+        .expect("no system memory in the memory space map");
+    let descriptor = dxe::memory_space_descriptor(memory.range.start).unwrap();
+    assert_eq!(&descriptor, memory);
+
+    let io_map = dxe::io_space_map().unwrap();
+    info!(
+        "GCD: {} memory space regions, {} I/O space regions",
+        map.len(),
+        io_map.len()
+    );
+}
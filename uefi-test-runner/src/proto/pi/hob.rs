@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use uefi::proto::pi::hob::{HobError, HobList, ResourceType};
+
+pub fn test() {
+    info!("Running HOB list test");
+    let hobs = match HobList::find() {
+        Ok(hobs) => hobs,
+        Err(HobError::NotFound) => {
+            info!("HOB list is not available");
+            return;
+        }
+        Err(err) => panic!("invalid HOB list: {err}"),
+    };
+    let phit = hobs.handoff();
+    info!(
+        "HOB list: version {:#x}, boot mode {:?}, {} HOBs",
+        phit.version,
+        phit.boot_mode,
+        hobs.iter().count()
+    );
+    let system_memory: u64 = hobs
+        .resource_descriptors()
+        .filter(|resource| resource.resource_type == ResourceType::SYSTEM_MEMORY)
+        .map(|resource| resource.resource_length)
+        .sum();
+    info!("System memory: {system_memory:#x} bytes");
+    assert!(system_memory > 0);
+}
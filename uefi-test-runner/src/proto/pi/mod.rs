@@ -3,7 +3,11 @@
 pub fn test() {
     info!("Testing Platform Initialization protocols");
 
+    dxe::test();
+    hob::test();
     mp::test();
 }
 
+mod dxe;
+mod hob;
 mod mp;
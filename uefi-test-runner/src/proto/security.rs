@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use uefi::Status;
+use uefi::boot::{self, AllocateType, MemoryType, PAGE_SIZE};
+use uefi::mem::memory_map::MemoryAttribute;
+use uefi::proto::security::MemoryProtection;
+
+pub fn test() {
+    test_memory_protection();
+}
+
+fn test_memory_protection() {
+    info!("Running MemoryProtection protocol test");
+
+    let protection = match MemoryProtection::open() {
+        Ok(protection) => protection,
+        Err(err) if err.status() == Status::UNSUPPORTED => {
+            info!("MemoryProtection protocol is not supported");
+            return;
+        }
+        Err(err) => panic!("failed to open MemoryProtection protocol: {err:?}"),
+    };
+
+    let pages = boot::allocate_pages(AllocateType::AnyPages, MemoryType::LOADER_DATA, 2).unwrap();
+    let start = pages.as_ptr() as u64;
+    let region = start..start + 2 * PAGE_SIZE as u64;
+    let original = protection.get_memory_attributes(region.clone()).unwrap();
+
+    // Unaligned ranges are rejected.
+    assert_eq!(
+        protection
+            .make_writable(start + 1..region.end)
+            .unwrap_err()
+            .status(),
+        Status::INVALID_PARAMETER
+    );
+
+    {
+        let guard = protection.make_read_only(region.clone()).unwrap();
+        assert_eq!(guard.previous_attributes(), original);
+        let attributes = protection.get_memory_attributes(region.clone()).unwrap();
+        assert!(attributes.contains(MemoryAttribute::READ_ONLY | MemoryAttribute::EXECUTE_PROTECT));
+    }
+    assert_eq!(
+        protection.get_memory_attributes(region.clone()).unwrap(),
+        original
+    );
+
+    protection
+        .make_writable(region.clone())
+        .unwrap()
+        .restore()
+        .unwrap();
+    assert_eq!(
+        protection.get_memory_attributes(region.clone()).unwrap(),
+        original
+    );
+
+    unsafe { boot::free_pages(pages, 2) }.unwrap();
+}
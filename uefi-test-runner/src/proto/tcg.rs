@@ -1,8 +1,17 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use alloc::string::ToString;
 use alloc::vec::Vec;
-use uefi::boot;
-use uefi::proto::tcg::{AlgorithmId, EventType, HashAlgorithm, PcrIndex, v1, v2};
+use uefi::proto::tcg::event_data::EventData;
+use uefi::proto::tcg::measure::{self, Measurer};
+use uefi::proto::tcg::replay::PcrReplay;
+use uefi::proto::tcg::tpm2::{
+    AuthSession, NvAttributes, NvPublic, ObjectAttributes, PcrSelection, Public, PublicParameters,
+    PublicUnique, ResponseCode, Scheme, SensitiveCreate, SessionType, Tpm, TpmError, TpmHandle,
+    TpmProperty,
+};
+use uefi::proto::tcg::{AlgorithmId, EventType, HashAlgorithm, PcrIndex, cc, v1, v2};
+use uefi::{boot, cstr16};
 
 // Environmental note:
 //
@@ -355,9 +364,249 @@ pub fn test_tcg_v2() {
             0xd1, 0x50, 0x64, 0x73, 0x2f, 0x87,
         ]
     );
+
+    // Replay the event log, and check it against the PCRs.
+    let log = tcg.get_event_log_v2().unwrap();
+    assert!(!log.is_truncated());
+    let replay = PcrReplay::from_log(&log).unwrap();
+    assert_eq!(
+        replay.banks().collect::<Vec<_>>(),
+        [
+            AlgorithmId::SHA1,
+            AlgorithmId::SHA256,
+            AlgorithmId::SHA384,
+            AlgorithmId::SHA512
+        ]
+    );
+    assert_eq!(
+        replay.pcr(AlgorithmId::SHA1, pcr_index),
+        Some(tcg_v2_read_pcr_8(&mut tcg).as_slice())
+    );
+    replay.verify(&mut Tpm::new(&mut *tcg)).unwrap();
+
+    // Decode the data of all events, including the event added above.
+    let log = tcg.get_event_log_v2().unwrap();
+    for event in log.iter() {
+        event.decode_event_data().unwrap();
+    }
+    let entry = log.iter().last().unwrap();
+    assert!(matches!(
+        entry.decode_event_data(),
+        Ok(EventData::Text(text)) if text.bytes() == event_data
+    ));
+    let dump = log.dump().to_string();
+    assert!(dump.contains("  EventType: IPL\n"));
+    assert!(dump.contains("  Event:\n    String: |-\n      12131415\n"));
+
+    test_tpm2_commands(&mut tcg);
+}
+
+/// Test the typed TPM 2.0 commands of `tpm2::Tpm`.
+fn test_tpm2_commands(tcg: &mut v2::Tcg) {
+    info!("Running TPM 2.0 command tests");
+
+    let expected_pcr_8 = tcg_v2_read_pcr_8(tcg);
+    let mut tpm = Tpm::new(tcg);
+
+    // The value read with `PCR_Read` matches the hand-written command.
+    assert_eq!(
+        tpm.read_pcr(AlgorithmId::SHA1, PcrIndex(8)).unwrap(),
+        Some(expected_pcr_8.to_vec())
+    );
+
+    let mut random = [0; 100];
+    tpm.get_random(&mut random).unwrap();
+    assert_ne!(random, [0; 100]);
+
+    assert!(
+        tpm.get_tpm_property(TpmProperty::MANUFACTURER)
+            .unwrap()
+            .is_some()
+    );
+    let banks = tpm.pcr_banks().unwrap();
+    assert!(banks.iter().any(|bank| bank.hash == AlgorithmId::SHA256));
+
+    // Define an NV index with a password, then write and read it.
+    let owner = AuthSession::Password(b"");
+    let nv_index = TpmHandle(0x0150_0000);
+    let nv_auth = AuthSession::Password(b"nv-password");
+    let nv_public = NvPublic {
+        nv_index,
+        name_alg: AlgorithmId::SHA256,
+        attributes: NvAttributes::AUTHWRITE | NvAttributes::AUTHREAD | NvAttributes::NO_DA,
+        auth_policy: Vec::new(),
+        data_size: 8,
+    };
+    tpm.nv_define_space(TpmHandle::OWNER, &owner, b"nv-password", &nv_public)
+        .unwrap();
+    assert_eq!(
+        tpm.nv_read(nv_index, nv_index, &nv_auth, 0, 8).unwrap_err(),
+        TpmError::Response(ResponseCode::NV_UNINITIALIZED)
+    );
+    tpm.nv_write(nv_index, nv_index, &nv_auth, 0, b"12345678")
+        .unwrap();
+    assert_eq!(
+        tpm.nv_read(nv_index, nv_index, &nv_auth, 2, 4).unwrap(),
+        b"3456"
+    );
+    let (public, _name) = tpm.nv_read_public(nv_index).unwrap();
+    assert!(public.attributes.contains(NvAttributes::WRITTEN));
+    let TpmError::Response(code) = tpm
+        .nv_read(nv_index, nv_index, &AuthSession::Password(b"wrong"), 0, 8)
+        .unwrap_err()
+    else {
+        panic!("unexpected error");
+    };
+    assert_eq!(code.base(), ResponseCode::AUTH_FAIL);
+    tpm.nv_undefine_space(TpmHandle::OWNER, &owner, nv_index)
+        .unwrap();
+
+    // Compute the policy that PCR 8 is unchanged.
+    let pcrs = [PcrSelection::new(AlgorithmId::SHA256, &[PcrIndex(8)])];
+    let trial = tpm
+        .start_auth_session(SessionType::TRIAL, AlgorithmId::SHA256)
+        .unwrap();
+    tpm.policy_pcr(trial, &[], &pcrs).unwrap();
+    let policy = tpm.policy_get_digest(trial).unwrap();
+    tpm.flush_context(trial).unwrap();
+
+    // Seal data to the policy.
+    let secret = b"sealed-data";
+    let template = Public {
+        name_alg: AlgorithmId::SHA256,
+        object_attributes: ObjectAttributes::FIXED_TPM
+            | ObjectAttributes::FIXED_PARENT
+            | ObjectAttributes::NO_DA,
+        auth_policy: policy.clone(),
+        parameters: PublicParameters::KeyedHash {
+            scheme: Scheme::Null,
+        },
+        unique: PublicUnique::Digest(Vec::new()),
+    };
+    let sealed = tpm
+        .create_primary(
+            TpmHandle::OWNER,
+            &owner,
+            &SensitiveCreate {
+                user_auth: b"",
+                data: secret,
+            },
+            &template,
+        )
+        .unwrap();
+    assert_eq!(sealed.public.auth_policy, policy);
+    assert_eq!(tpm.read_public(sealed.handle).unwrap().name, sealed.name);
+
+    // Unseal the data with a policy session.
+    let session = tpm
+        .start_auth_session(SessionType::POLICY, AlgorithmId::SHA256)
+        .unwrap();
+    tpm.policy_pcr(session, &[], &pcrs).unwrap();
+    assert_eq!(
+        tpm.unseal(sealed.handle, &AuthSession::Policy(session))
+            .unwrap(),
+        secret
+    );
+    tpm.flush_context(session).unwrap();
+    tpm.flush_context(sealed.handle).unwrap();
+}
+
+/// Test `measure::Measurer`, which uses whichever TCG protocol is available.
+fn test_measure() {
+    // Skip the test if neither TPM feature is enabled.
+    if cfg!(not(any(feature = "tpm_v1", feature = "tpm_v2"))) {
+        return;
+    }
+
+    info!("Running TCG measure test");
+
+    let command_line = cstr16!("root=/dev/sda2 quiet");
+    let mut measurer = Measurer::open().expect("failed to open TCG protocol");
+    assert_eq!(measurer.is_tcg2(), cfg!(feature = "tpm_v2"));
+    measurer.measure_initrd(b"initrd").unwrap();
+    measurer.measure_command_line(command_line).unwrap();
+    drop(measurer);
+
+    let check_events = |initrd: EventData, cmdline: EventData| {
+        let EventData::Tagged(tagged) = initrd else {
+            panic!("unexpected initrd event: {initrd:?}");
+        };
+        assert_eq!(tagged.tag(), measure::LINUX_INITRD_EVENT_TAG);
+        assert_eq!(tagged.data(), b"Linux initrd\0");
+        assert!(matches!(
+            cmdline,
+            EventData::Text(text) if text.bytes() == command_line.as_bytes()
+        ));
+    };
+
+    if cfg!(feature = "tpm_v2") {
+        let handle = boot::get_handle_for_protocol::<v2::Tcg>().unwrap();
+        let mut tcg = boot::open_protocol_exclusive::<v2::Tcg>(handle).unwrap();
+        let log = tcg.get_event_log_v2().unwrap();
+        let events: Vec<_> = log.iter().collect();
+        let [.., initrd, cmdline] = events.as_slice() else {
+            panic!("missing events");
+        };
+        assert_eq!(initrd.pcr_index(), measure::INITRD_PCR);
+        assert_eq!(cmdline.pcr_index(), measure::KERNEL_CONFIG_PCR);
+        check_events(
+            initrd.decode_event_data().unwrap(),
+            cmdline.decode_event_data().unwrap(),
+        );
+    } else {
+        let handle = boot::get_handle_for_protocol::<v1::Tcg>().unwrap();
+        let mut tcg = boot::open_protocol_exclusive::<v1::Tcg>(handle).unwrap();
+        let status = tcg.status_check().unwrap();
+        let events: Vec<_> = status.event_log.iter().collect();
+        let [.., initrd, cmdline] = events.as_slice() else {
+            panic!("missing events");
+        };
+        assert_eq!(initrd.pcr_index(), measure::INITRD_PCR);
+        assert_eq!(cmdline.pcr_index(), measure::KERNEL_CONFIG_PCR);
+        check_events(
+            initrd.decode_event_data().unwrap(),
+            cmdline.decode_event_data().unwrap(),
+        );
+    }
+}
+
+/// Test the CC measurement protocol, which is only available in
+/// confidential computing guests.
+fn test_cc() {
+    let Ok(handle) = boot::get_handle_for_protocol::<cc::CcMeasurement>() else {
+        return;
+    };
+
+    info!("Running CC measurement test");
+
+    let mut cc = boot::open_protocol_exclusive::<cc::CcMeasurement>(handle)
+        .expect("failed to open CC measurement protocol");
+    let capability = cc.get_capability().unwrap();
+    assert!(
+        capability
+            .supported_event_logs
+            .contains(cc::EventLogFormat::TCG_2)
+    );
+
+    let mr_index = cc.map_pcr_to_mr_index(PcrIndex(8)).unwrap();
+    if capability.cc_type.ty == cc::CcTechnology::INTEL_TDX {
+        assert_eq!(Some(mr_index), cc::MrIndex::from_tdx_pcr(PcrIndex(8)));
+    }
+
+    let event_data = b"uefi-rs CC test";
+    let event = cc::CcEventInputs::new_in_box(mr_index, EventType::IPL, event_data).unwrap();
+    cc.hash_log_extend_event(cc::HashLogExtendEventFlags::empty(), event_data, &event)
+        .unwrap();
+
+    let log = cc.get_event_log().unwrap();
+    let last = log.iter().last().unwrap();
+    assert_eq!(last.pcr_index(), PcrIndex(mr_index.0));
+    assert_eq!(last.event_data(), event_data);
 }
 
 pub fn test() {
     test_tcg_v1();
     test_tcg_v2();
+    test_cc();
+    test_measure();
 }
@@ -2,12 +2,15 @@
 
 mod vars;
 
+use core::time::Duration;
+use uefi::Status;
 use uefi::runtime::{self, Daylight, Time, TimeParams};
 
 pub fn test() {
     info!("Testing runtime services");
     vars::test();
     test_time();
+    test_wakeup_time();
 }
 
 fn test_time() {
@@ -37,3 +40,41 @@ fn test_time() {
     info!("After setting time: {now}");
     assert_eq!(now.year(), 2020);
 }
+
+fn test_wakeup_time() {
+    let wakeup = match runtime::get_wakeup_time() {
+        Ok(wakeup) => wakeup,
+        Err(err) if err.status() == Status::UNSUPPORTED => {
+            info!("Wakeup timer is not supported");
+            return;
+        }
+        Err(err) => panic!("failed to get wakeup time: {err:?}"),
+    };
+    info!("Wakeup time: {wakeup:?}");
+
+    // Set an alarm a few minutes after the current time. Firmware such as
+    // PcRtc rejects alarms more than 24 hours ahead, and the alarm can't fire
+    // before the test disables it again.
+    let now = runtime::get_time().unwrap();
+    let time = now
+        .checked_add(Duration::from_secs(5 * 60))
+        .expect("alarm time is out of range");
+    match unsafe { runtime::set_wakeup_time(&time) } {
+        Ok(()) => {}
+        Err(err) if err.status() == Status::UNSUPPORTED => {
+            info!("Setting the wakeup time is not supported");
+            return;
+        }
+        Err(err) => panic!("failed to set wakeup time: {err:?}"),
+    }
+
+    let wakeup = runtime::get_wakeup_time().unwrap();
+    info!("After setting wakeup time: {wakeup:?}");
+    assert!(wakeup.enabled);
+    assert_eq!(wakeup.time.day(), time.day());
+    assert_eq!(wakeup.time.hour(), time.hour());
+    assert_eq!(wakeup.time.minute(), time.minute());
+
+    unsafe { runtime::disable_wakeup_time().unwrap() };
+    assert!(!runtime::get_wakeup_time().unwrap().enabled);
+}
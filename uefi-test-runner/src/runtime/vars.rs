@@ -1,7 +1,14 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use alloc::vec::Vec;
 use log::info;
 use uefi::prelude::*;
+use uefi::proto::device_path::LoadedImageDevicePath;
+use uefi::runtime::auth_variable::{AuthenticatedWrite, AuthenticatedWriteError};
+use uefi::runtime::load_option::{self, LoadOption, LoadOptionType};
+use uefi::runtime::secure_boot::{
+    SecureBootState, Signature, SignatureDatabase, SignatureDatabaseVariable, SignatureType,
+};
 use uefi::runtime::{VariableAttributes, VariableVendor};
 use uefi::{CStr16, Error, guid, runtime};
 
@@ -86,7 +93,152 @@ fn test_variable_info() {
     info!("Storage for volatile runtime variables: {info:?}");
 }
 
+/// Test the Secure Boot variables and signature databases.
+fn test_secure_boot() {
+    let state = SecureBootState::read().unwrap();
+    info!("Secure Boot state: {state:?}");
+    assert!(state.mode().is_some());
+
+    for variable in [
+        SignatureDatabaseVariable::PK,
+        SignatureDatabaseVariable::KEK,
+        SignatureDatabaseVariable::DB,
+        SignatureDatabaseVariable::DBX,
+    ] {
+        match SignatureDatabase::read(variable) {
+            Ok(db) => info!(
+                "{}: {} signatures",
+                variable.name(),
+                db.lists().signatures().count()
+            ),
+            Err(err) => assert_eq!(err.status(), Status::NOT_FOUND),
+        }
+    }
+
+    // Write a database with one SHA-256 hash, and read it back.
+    let hash = [0x5a; 32];
+    let mut data = Vec::new();
+    data.extend_from_slice(&SignatureType::SHA256.0.to_bytes());
+    data.extend_from_slice(&76u32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&48u32.to_le_bytes());
+    data.extend_from_slice(&VENDOR.0.to_bytes());
+    data.extend_from_slice(&hash);
+    let variable = SignatureDatabaseVariable::new(cstr16!("UefiRsTestDb"), *VENDOR);
+    runtime::set_variable(variable.name(), VENDOR, ATTRS, &data).unwrap();
+
+    let db = SignatureDatabase::read(variable).unwrap();
+    assert_eq!(db.as_bytes(), data);
+    assert_eq!(db.attributes(), ATTRS);
+    assert!(db.lists().contains(SignatureType::SHA256, &hash));
+    let signature = db.lists().signatures().next().unwrap();
+    assert_eq!(signature.owner(), VENDOR.0);
+    assert_eq!(signature.decode(), Signature::Hash(&hash));
+
+    // A truncated database is rejected.
+    runtime::set_variable(variable.name(), VENDOR, ATTRS, &data[..50]).unwrap();
+    assert_eq!(
+        SignatureDatabase::read(variable).unwrap_err().status(),
+        Status::VOLUME_CORRUPTED
+    );
+    runtime::delete_variable(variable.name(), VENDOR).unwrap();
+}
+
+/// Test time-based authenticated variable writes.
+fn test_authenticated_variables() {
+    // A private authenticated variable with an invalid signature is rejected.
+    let name = cstr16!("UefiRsTestAuthVar");
+    let write =
+        AuthenticatedWrite::new(name, VENDOR, VALUE).with_timestamp(runtime::get_time().unwrap());
+    match write.write(b"not a PKCS #7 signature") {
+        Err(AuthenticatedWriteError::SecurityViolation) => {}
+        Err(AuthenticatedWriteError::SetVariable(err))
+            if matches!(
+                err.status(),
+                Status::INVALID_PARAMETER | Status::UNSUPPORTED
+            ) =>
+        {
+            info!("Authenticated variables are not supported");
+            return;
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+    assert!(!runtime::variable_exists(name, VENDOR).unwrap());
+
+    // In setup mode, `db` can be written without a valid signature. Save it
+    // so that it can be restored after the test.
+    if !SecureBootState::read().unwrap().setup_mode {
+        return;
+    }
+    let original = match SignatureDatabase::read(SignatureDatabaseVariable::DB) {
+        Ok(db) => Some(db.as_bytes().to_vec()),
+        Err(err) if err.status() == Status::NOT_FOUND => None,
+        Err(err) => panic!("failed to read db: {err:?}"),
+    };
+    let hash = [0xa5; 32];
+    let mut list = Vec::new();
+    list.extend_from_slice(&SignatureType::SHA256.0.to_bytes());
+    list.extend_from_slice(&76u32.to_le_bytes());
+    list.extend_from_slice(&0u32.to_le_bytes());
+    list.extend_from_slice(&48u32.to_le_bytes());
+    list.extend_from_slice(&VENDOR.0.to_bytes());
+    list.extend_from_slice(&hash);
+    AuthenticatedWrite::for_database(&SignatureDatabaseVariable::DB, &list)
+        .append()
+        .write(&[])
+        .unwrap();
+    let db = SignatureDatabase::read(SignatureDatabaseVariable::DB).unwrap();
+    assert!(db.lists().contains(SignatureType::SHA256, &hash));
+    assert_eq!(db.attributes(), AuthenticatedWrite::DEFAULT_ATTRIBUTES);
+
+    // Restore `db`. Writing an empty payload deletes it.
+    let original = original.unwrap_or_default();
+    AuthenticatedWrite::for_database(&SignatureDatabaseVariable::DB, &original)
+        .with_timestamp(runtime::get_time().unwrap())
+        .write(&[])
+        .unwrap();
+    match SignatureDatabase::read(SignatureDatabaseVariable::DB) {
+        Ok(db) => assert_eq!(db.as_bytes(), original),
+        Err(err) => {
+            assert_eq!(err.status(), Status::NOT_FOUND);
+            assert!(original.is_empty());
+        }
+    }
+}
+
+fn test_load_options() {
+    let ty = LoadOptionType::Boot;
+    let order = load_option::order(ty).unwrap();
+    for number in &order {
+        match LoadOption::read(ty, *number) {
+            Ok(option) => info!("Boot{number:04X}: {}", option.description()),
+            Err(err) => info!("Boot{number:04X}: {err:?}"),
+        }
+    }
+
+    // Add an option that is tried last, and boot it next.
+    let file_path =
+        boot::open_protocol_exclusive::<LoadedImageDevicePath>(boot::image_handle()).unwrap();
+    let option = LoadOption::new(cstr16!("uefi-rs test"), &file_path).with_optional_data(VALUE);
+    let number = load_option::insert(ty, &option, usize::MAX).unwrap();
+    assert_eq!(LoadOption::read(ty, number).unwrap(), option);
+    assert_eq!(load_option::order(ty).unwrap().last(), Some(&number));
+    assert!(load_option::numbers(ty).unwrap().contains(&number));
+
+    let boot_next = load_option::boot_next().unwrap();
+    load_option::set_boot_next(Some(number)).unwrap();
+    assert_eq!(load_option::boot_next().unwrap(), Some(number));
+
+    load_option::delete(ty, number).unwrap();
+    assert_eq!(load_option::boot_next().unwrap(), None);
+    assert_eq!(load_option::order(ty).unwrap(), order);
+    load_option::set_boot_next(boot_next).unwrap();
+}
+
 pub fn test() {
     test_variable_info();
     test_variables();
+    test_secure_boot();
+    test_authenticated_variables();
+    test_load_options();
 }
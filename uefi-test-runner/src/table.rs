@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use uefi::boot::{self, MemoryType};
+use uefi::mem::memory_map::{MemoryAttribute, MemoryMap};
+use uefi::table::acpi::{AcpiError, AcpiTables, Fadt, Madt};
+use uefi::table::debug_image_info::{DebugImageInfoError, DebugImageInfoTable};
+use uefi::table::esrt::{Esrt, EsrtError};
+use uefi::table::fdt::{Fdt, FdtEditor, FdtError};
+use uefi::table::memory_attributes::{MemoryAttributesTable, MemoryAttributesTableError};
+use uefi::table::smbios::{
+    BiosInformation, DecodedStructure, Smbios, SmbiosError, SystemInformation,
+};
+
+pub fn test() {
+    info!("Testing configuration tables");
+    test_memory_attributes_table();
+    test_esrt();
+    test_acpi();
+    test_smbios();
+    test_fdt();
+    test_debug_image_info();
+}
+
+fn test_memory_attributes_table() {
+    let table = match MemoryAttributesTable::find() {
+        Ok(table) => table,
+        Err(MemoryAttributesTableError::NotFound) => {
+            info!("Memory attributes table is not available");
+            return;
+        }
+        Err(err) => panic!("invalid memory attributes table: {err}"),
+    };
+    info!(
+        "Memory attributes table: version {}, {} entries, flags {:?}",
+        table.version(),
+        table.len(),
+        table.flags()
+    );
+
+    // Every entry must be part of a runtime region of the memory map.
+    let memory_map = boot::memory_map(MemoryType::LOADER_DATA).unwrap();
+    for desc in table.entries() {
+        assert!(desc.att.contains(MemoryAttribute::RUNTIME));
+        assert!(matches!(
+            desc.ty,
+            MemoryType::RUNTIME_SERVICES_CODE | MemoryType::RUNTIME_SERVICES_DATA
+        ));
+        assert!(memory_map.entries().any(|region| {
+            region.ty == desc.ty
+                && region.phys_start <= desc.phys_start
+                && desc.phys_start + desc.page_count * 4096
+                    <= region.phys_start + region.page_count * 4096
+        }));
+    }
+}
+
+fn test_esrt() {
+    let esrt = match Esrt::find() {
+        Ok(esrt) => esrt,
+        Err(EsrtError::NotFound) => {
+            info!("ESRT is not available");
+            return;
+        }
+        Err(err) => panic!("invalid ESRT: {err}"),
+    };
+    info!("ESRT: {} of at most {} entries", esrt.len(), esrt.max_len());
+    assert_eq!(esrt.entries().count(), esrt.len());
+    for entry in esrt.entries() {
+        info!(
+            "{}: {:?} version {:#x}, last attempt {:?}",
+            entry.fw_class, entry.fw_type, entry.fw_version, entry.last_attempt_status
+        );
+    }
+}
+
+fn test_acpi() {
+    let tables = match AcpiTables::find() {
+        Ok(tables) => tables,
+        Err(AcpiError::RsdpNotFound) => {
+            info!("ACPI tables are not available");
+            return;
+        }
+        Err(err) => panic!("invalid ACPI tables: {err}"),
+    };
+    info!(
+        "ACPI revision {}, root table {}",
+        tables.rsdp().revision(),
+        tables.root().signature()
+    );
+    for sdt in tables.tables() {
+        let sdt = sdt.unwrap();
+        info!("{}: {} bytes", sdt.signature(), sdt.length());
+    }
+
+    // QEMU provides a FADT, DSDT, and MADT on all supported architectures.
+    let fadt = tables.find_table::<Fadt>().unwrap();
+    info!("FADT flags: {:?}", fadt.flags());
+    let dsdt = tables.dsdt().unwrap();
+    info!("DSDT: {} bytes", dsdt.length());
+    let madt = tables.find_table::<Madt>().unwrap();
+    assert!(madt.entries().count() > 0);
+}
+
+fn test_smbios() {
+    let smbios = match Smbios::find() {
+        Ok(smbios) => smbios,
+        Err(SmbiosError::NotFound) => {
+            info!("SMBIOS is not available");
+            return;
+        }
+        Err(err) => panic!("invalid SMBIOS entry point: {err}"),
+    };
+    info!(
+        "SMBIOS {}, {} structures",
+        smbios.version(),
+        smbios.structures().count()
+    );
+    for structure in smbios.structures() {
+        match structure.decode() {
+            DecodedStructure::Processor(cpu) => info!(
+                "Processor {:?}: {:?} cores",
+                cpu.socket_designation(),
+                cpu.core_count()
+            ),
+            DecodedStructure::MemoryDevice(dimm) => info!(
+                "Memory device {:?}: {:?} bytes",
+                dimm.device_locator(),
+                dimm.size()
+            ),
+            _ => {}
+        }
+    }
+
+    // QEMU always provides BIOS and system information.
+    let bios = smbios.find_structure::<BiosInformation>().unwrap();
+    info!("BIOS vendor: {:?}", bios.vendor());
+    let system = smbios.find_structure::<SystemInformation>().unwrap();
+    info!(
+        "System: {:?} {:?}",
+        system.manufacturer(),
+        system.product_name()
+    );
+}
+
+fn test_fdt() {
+    // The device tree is only provided on Arm and RISC-V.
+    let fdt = match Fdt::find() {
+        Ok(fdt) => fdt,
+        Err(FdtError::NotFound) => {
+            info!("Device tree is not available");
+            return;
+        }
+        Err(err) => panic!("invalid device tree: {err}"),
+    };
+    info!(
+        "Device tree version {}, {} bytes",
+        fdt.version(),
+        fdt.total_size()
+    );
+    for region in fdt.memory() {
+        info!("Memory: {:#x}, {:#x} bytes", region.address, region.size);
+    }
+    assert!(fdt.memory().count() > 0);
+
+    let mut editor = FdtEditor::new(&fdt);
+    editor.set_bootargs("console=ttyAMA0").unwrap();
+    let bytes = editor.build(0);
+    let edited = Fdt::from_bytes(&bytes).unwrap();
+    assert_eq!(edited.chosen().unwrap().bootargs(), Some("console=ttyAMA0"));
+}
+
+fn test_debug_image_info() {
+    let table = match DebugImageInfoTable::find() {
+        Ok(table) => table,
+        Err(DebugImageInfoError::NotFound) => {
+            info!("Debug image info table is not available");
+            return;
+        }
+        Err(err) => panic!("invalid debug image info table: {err}"),
+    };
+    for image in table.images() {
+        let range = image.range();
+        match image.name() {
+            Some(name) => info!("Image {:#x}..{:#x}: {name}", range.start, range.end),
+            None => info!("Image {:#x}..{:#x}", range.start, range.end),
+        }
+    }
+
+    // The test runner itself must be listed.
+    let address = test_debug_image_info as *const () as usize;
+    let location = table.resolve(address).expect("test runner image not found");
+    info!("Test runner function at {location}");
+    assert_eq!(location.image.handle(), Some(boot::image_handle()));
+}
@@ -0,0 +1,506 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! This module exports [`Allocator`] and [`MemoryTypeAllocator`].
+//!
+//! [`Allocator`] can be used as global Rust allocator using the
+//! `global_allocator` crate feature. See [`helpers`] for more info.
+//!
+//! By default, [`Allocator`] stops working when boot services are exited. To
+//! keep allocating during the hand-off phase, for example while building boot
+//! information for a kernel, reserve an arena with [`reserve_exit_arena`]
+//! before calling [`boot::exit_boot_services`]. See [`ExitArenaUsage`] for how
+//! much of it has been used.
+//!
+//! [`MemoryTypeAllocator`] allocates memory of a specific [`MemoryType`]. With
+//! the `unstable` feature, it implements [`core::alloc::Allocator`], so it can
+//! be used with `Box::new_in` and `Vec::new_in`.
+//!
+//! With the `allocation_tracking` feature, allocations made through both
+//! allocators are tracked, see the `tracking` module.
+//!
+//! [`helpers`]: uefi::helpers
+
+use crate::boot::{self, AllocateType};
+use crate::mem::memory_map::MemoryType;
+use crate::proto::loaded_image::LoadedImage;
+use crate::{Result, Status};
+#[cfg(feature = "unstable")]
+use core::alloc::AllocError;
+use core::alloc::{GlobalAlloc, Layout};
+use core::panic::Location;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use uefi_raw::table::boot::PAGE_SIZE;
+
+#[cfg(feature = "allocation_tracking")]
+pub mod tracking;
+
+/// Get the memory type to use for allocation.
+///
+/// The first time this is called, the data type of the loaded image will be
+/// retrieved. That value is cached in a static and reused on subsequent
+/// calls. If the memory type of the loaded image cannot be retrieved for some
+/// reason, a default of `LOADER_DATA` is used.
+fn get_memory_type() -> MemoryType {
+    // Initialize to a `RESERVED` to indicate the actual value hasn't been set yet.
+    static MEMORY_TYPE: AtomicU32 = AtomicU32::new(MemoryType::RESERVED.0);
+
+    let memory_type = MEMORY_TYPE.load(Ordering::Acquire);
+    if memory_type == MemoryType::RESERVED.0 {
+        let memory_type = if let Ok(loaded_image) =
+            boot::open_protocol_exclusive::<LoadedImage>(boot::image_handle())
+        {
+            loaded_image.data_type()
+        } else {
+            MemoryType::LOADER_DATA
+        };
+        MEMORY_TYPE.store(memory_type.0, Ordering::Release);
+        memory_type
+    } else {
+        MemoryType(memory_type)
+    }
+}
+
+/// Start address of the arena reserved by [`reserve_exit_arena`], or zero if
+/// no arena has been reserved.
+static ARENA_START: AtomicUsize = AtomicUsize::new(0);
+
+/// End address (exclusive) of the arena.
+static ARENA_END: AtomicUsize = AtomicUsize::new(0);
+
+/// Address of the first unused byte of the arena.
+static ARENA_NEXT: AtomicUsize = AtomicUsize::new(0);
+
+/// Reserve an arena of at least `size` bytes that [`Allocator`] serves
+/// allocations from after exiting boot services.
+///
+/// The arena is allocated with [`boot::allocate_pages`] using the loaded
+/// image's data type, rounded up to whole pages. It is not used while boot
+/// services are active. After [`boot::exit_boot_services`], allocations are
+/// carved out of it with a simple bump allocator:
+///
+/// * Freeing the most recent arena allocation returns its memory to the
+///   arena, and growing it happens in place if possible. Other arena memory
+///   is only reclaimed when the program ends.
+/// * Freeing memory that was allocated before exiting boot services is
+///   ignored, as that memory can no longer be returned to the firmware.
+///
+/// Calling this function again replaces the previous arena, which is freed.
+///
+/// # Errors
+///
+/// * [`Status::UNSUPPORTED`]: boot services are no longer active.
+/// * [`Status::OUT_OF_RESOURCES`]: the arena could not be allocated.
+pub fn reserve_exit_arena(size: usize) -> Result {
+    if !boot::are_boot_services_active() {
+        return Err(Status::UNSUPPORTED.into());
+    }
+
+    let count = size.div_ceil(PAGE_SIZE).max(1);
+    let start = boot::allocate_pages(AllocateType::AnyPages, get_memory_type(), count)?;
+    let start = start.as_ptr() as usize;
+
+    let old_start = ARENA_START.swap(0, Ordering::AcqRel);
+    let old_end = ARENA_END.load(Ordering::Acquire);
+    ARENA_NEXT.store(start, Ordering::Release);
+    ARENA_END.store(start + count * PAGE_SIZE, Ordering::Release);
+    ARENA_START.store(start, Ordering::Release);
+
+    if let Some(old_start) = NonNull::new(old_start as *mut u8) {
+        let old_count = (old_end - old_start.as_ptr() as usize) / PAGE_SIZE;
+        // Ignore errors since the new arena is already in place.
+        let _ = unsafe { boot::free_pages(old_start, old_count) };
+    }
+
+    Ok(())
+}
+
+/// Get the usage of the arena reserved by [`reserve_exit_arena`], or `None`
+/// if no arena has been reserved.
+#[must_use]
+pub fn exit_arena_usage() -> Option<ExitArenaUsage> {
+    let start = ARENA_START.load(Ordering::Acquire);
+    if start == 0 {
+        return None;
+    }
+    Some(ExitArenaUsage {
+        size: ARENA_END.load(Ordering::Acquire) - start,
+        used: ARENA_NEXT.load(Ordering::Acquire) - start,
+    })
+}
+
+/// Usage of the arena reserved by [`reserve_exit_arena`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ExitArenaUsage {
+    /// Total size of the arena in bytes.
+    pub size: usize,
+
+    /// Number of bytes of the arena handed out so far, including padding for
+    /// alignment.
+    pub used: usize,
+}
+
+/// Check whether `ptr` points into the arena.
+fn arena_contains(ptr: *mut u8) -> bool {
+    let addr = ptr as usize;
+    let start = ARENA_START.load(Ordering::Acquire);
+    start != 0 && (start..ARENA_END.load(Ordering::Acquire)).contains(&addr)
+}
+
+/// Allocate memory for `layout` from the arena. Returns a null pointer if
+/// there is no arena or it is exhausted.
+fn arena_alloc(layout: Layout) -> *mut u8 {
+    let end = ARENA_END.load(Ordering::Acquire);
+    if ARENA_START.load(Ordering::Acquire) == 0 {
+        return ptr::null_mut();
+    }
+
+    let mut next = ARENA_NEXT.load(Ordering::Acquire);
+    loop {
+        let Some(new_next) = next
+            .checked_next_multiple_of(layout.align())
+            .and_then(|addr| addr.checked_add(layout.size()))
+            .filter(|new_next| *new_next <= end)
+        else {
+            return ptr::null_mut();
+        };
+        match ARENA_NEXT.compare_exchange_weak(next, new_next, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => return (new_next - layout.size()) as *mut u8,
+            Err(current) => next = current,
+        }
+    }
+}
+
+/// Resize the arena allocation at `ptr` in place, which is only possible if
+/// it is the most recent one. Returns `false` if that is not possible.
+fn arena_resize_in_place(ptr: *mut u8, old_size: usize, new_size: usize) -> bool {
+    let addr = ptr as usize;
+    let Some(new_next) = addr.checked_add(new_size) else {
+        return false;
+    };
+    if new_next > ARENA_END.load(Ordering::Acquire) {
+        return false;
+    }
+    ARENA_NEXT
+        .compare_exchange(
+            addr + old_size,
+            new_next,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        )
+        .is_ok()
+}
+
+/// Helper to get a custom alignment out of an allocation with an alignment of
+/// eight (UEFI default alignment). This works by allocating extra space and
+/// storing a pointer to the actual allocation right above the allocation
+/// handed out via the public API.
+fn alloc_pool_aligned(memory_type: MemoryType, size: usize, align: usize) -> *mut u8 {
+    let full_alloc_ptr = boot::allocate_pool(memory_type, size + align);
+    let full_alloc_ptr = if let Ok(ptr) = full_alloc_ptr {
+        ptr.as_ptr()
+    } else {
+        return ptr::null_mut();
+    };
+
+    // Calculate the offset needed to get an aligned pointer within the
+    // full allocation. If that offset is zero, increase it to `align`
+    // so that we still have space to store the extra pointer described
+    // below.
+    let mut offset = full_alloc_ptr.align_offset(align);
+    if offset == 0 {
+        offset = align;
+    }
+
+    // Before returning the aligned allocation, store a pointer to the
+    // full unaligned allocation in the bytes just before the aligned
+    // allocation. We know we have at least eight bytes there due to
+    // adding `align` to the memory allocation size. We also know the
+    // write is appropriately aligned for a `*mut u8` pointer because
+    // `align_ptr` is aligned, and alignments are always powers of two
+    // (as enforced by the `Layout` type).
+    unsafe {
+        let aligned_ptr = full_alloc_ptr.add(offset);
+        (aligned_ptr.cast::<*mut u8>()).sub(1).write(full_alloc_ptr);
+        aligned_ptr
+    }
+}
+
+/// Returns whether the allocation is a multiple of a [`PAGE_SIZE`] and is
+/// aligned to [`PAGE_SIZE`].
+///
+/// This does not only check the alignment but also the size. For types
+/// allocated by Rust itself (e.g., `Box<T>`), the size is always at least the
+/// alignment, as specified in the [Rust type layout]. However, to be also safe
+/// when it comes to manual invocations, we additionally check if the size is
+/// a multiple of [`PAGE_SIZE`].
+///
+/// [Rust type layout]: https://doc.rust-lang.org/reference/type-layout.html
+const fn layout_allows_page_alloc_shortcut(layout: &Layout) -> bool {
+    layout.size().is_multiple_of(PAGE_SIZE) && layout.align() == PAGE_SIZE
+}
+
+/// Allocator using UEFI boot services.
+///
+/// This type implements [`GlobalAlloc`] and can be marked with the
+/// `#[global_allocator]` attribute to be used as global Rust allocator.
+///
+/// Note that if boot services are not active (anymore), [`Allocator::alloc`]
+/// will return a null pointer and [`Allocator::dealloc`] will panic, unless
+/// an arena was reserved with [`reserve_exit_arena`].
+#[derive(Debug)]
+pub struct Allocator;
+
+/// Allocate memory of `memory_type` for `layout` using the UEFI boot
+/// services. Returns a null pointer on failure.
+fn alloc_with_type(memory_type: MemoryType, layout: Layout) -> *mut u8 {
+    if !boot::are_boot_services_active() {
+        return ptr::null_mut();
+    }
+
+    let use_page_shortcut = layout_allows_page_alloc_shortcut(&layout);
+
+    match (use_page_shortcut, layout.align()) {
+        // Allocating pages is actually very expected in UEFI OS loaders, so
+        // it makes sense to provide this optimization.
+        (true, _) => {
+            // To spammy, but useful for manual testing.
+            // log::trace!("Taking PAGE_SIZE shortcut for layout={layout:?}");
+            let count = layout.size().div_ceil(PAGE_SIZE);
+            boot::allocate_pages(AllocateType::AnyPages, memory_type, count)
+                .map(|ptr| ptr.as_ptr())
+                .unwrap_or(ptr::null_mut())
+        }
+        (false, 0..=8 /* UEFI default alignment */) => {
+            // The requested alignment is less than or equal to eight, and
+            // `allocate_pool` always provides eight-byte alignment, so we can
+            // use `allocate_pool` directly.
+            boot::allocate_pool(memory_type, layout.size())
+                .map(|ptr| ptr.as_ptr())
+                .unwrap_or(ptr::null_mut())
+        }
+        (false, 9..) => alloc_pool_aligned(memory_type, layout.size(), layout.align()),
+    }
+}
+
+/// Free memory allocated by [`alloc_with_type`] using the UEFI boot services.
+///
+/// This will panic after exiting boot services.
+unsafe fn dealloc_with_type(ptr: *mut u8, layout: Layout) {
+    let ptr = NonNull::new(ptr).unwrap();
+
+    let use_page_shortcut = layout_allows_page_alloc_shortcut(&layout);
+
+    match (use_page_shortcut, layout.align()) {
+        (true, _) => {
+            // To spammy, but useful for manual testing.
+            // log::trace!("Taking PAGE_SIZE shortcut for layout={layout:?}");
+            let count = layout.size().div_ceil(PAGE_SIZE);
+            unsafe { boot::free_pages(ptr, count).unwrap() }
+        }
+        (false, 0..=8 /* UEFI default alignment */) => {
+            // Warning: this will panic after exiting boot services.
+            unsafe { boot::free_pool(ptr) }.unwrap();
+        }
+        (false, 9..) => {
+            let ptr = ptr.as_ptr().cast::<*mut u8>();
+            // Retrieve the pointer to the full allocation that was packed right
+            // before the aligned allocation in `alloc`.
+            let actual_alloc_ptr = unsafe { ptr.sub(1).read() };
+            let ptr = NonNull::new(actual_alloc_ptr).unwrap();
+            // Warning: this will panic after exiting boot services.
+            unsafe { boot::free_pool(ptr) }.unwrap();
+        }
+    }
+}
+
+/// Record a successful allocation if the `allocation_tracking` feature is
+/// enabled. Returns `ptr`.
+#[cfg_attr(
+    not(feature = "allocation_tracking"),
+    allow(clippy::missing_const_for_fn, unused_variables)
+)]
+fn track_alloc(
+    memory_type: impl FnOnce() -> MemoryType,
+    ptr: *mut u8,
+    layout: Layout,
+    location: Option<&'static Location<'static>>,
+) -> *mut u8 {
+    #[cfg(feature = "allocation_tracking")]
+    if !ptr.is_null() {
+        tracking::record(memory_type(), ptr, layout, location);
+    }
+    ptr
+}
+
+/// Record a deallocation if the `allocation_tracking` feature is enabled.
+#[cfg_attr(
+    not(feature = "allocation_tracking"),
+    allow(clippy::missing_const_for_fn, unused_variables)
+)]
+fn track_dealloc(memory_type: impl FnOnce() -> MemoryType, ptr: *mut u8, layout: Layout) {
+    #[cfg(feature = "allocation_tracking")]
+    tracking::forget(memory_type(), ptr, layout);
+}
+
+unsafe impl GlobalAlloc for Allocator {
+    /// Allocate memory using the UEFI boot services.
+    ///
+    /// The allocation's [memory type] matches the current image's [data type].
+    ///
+    /// After exiting boot services, memory is allocated from the arena
+    /// reserved with [`reserve_exit_arena`], if any.
+    ///
+    /// [memory type]: MemoryType
+    /// [data type]: LoadedImage::data_type
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // This is usually called through the global allocator, which does not
+        // propagate caller locations, so none is recorded.
+        if !boot::are_boot_services_active() {
+            // The memory type was cached when reserving the arena.
+            return track_alloc(get_memory_type, arena_alloc(layout), layout, None);
+        }
+
+        // Checked above already, as `get_memory_type` needs boot services.
+        let memory_type = get_memory_type();
+        track_alloc(
+            || memory_type,
+            alloc_with_type(memory_type, layout),
+            layout,
+            None,
+        )
+    }
+
+    /// Deallocate memory using the UEFI boot services.
+    ///
+    /// This will panic after exiting boot services, unless an arena was
+    /// reserved with [`reserve_exit_arena`].
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // The memory type was cached when `ptr` was allocated.
+        track_dealloc(get_memory_type, ptr, layout);
+
+        if arena_contains(ptr) {
+            // Only the most recent allocation can be returned to the arena.
+            arena_resize_in_place(ptr, layout.size(), 0);
+        } else if boot::are_boot_services_active() || exit_arena_usage().is_none() {
+            unsafe { dealloc_with_type(ptr, layout) }
+        }
+        // Otherwise, this memory was allocated before exiting boot services
+        // and can no longer be freed, so it is leaked.
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if arena_contains(ptr) && arena_resize_in_place(ptr, layout.size(), new_size) {
+            let new_layout = unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) };
+            track_dealloc(get_memory_type, ptr, layout);
+            return track_alloc(get_memory_type, ptr, new_layout, None);
+        }
+
+        // Same as the default implementation of `realloc`.
+        let new_layout = unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) };
+        let new_ptr = unsafe { self.alloc(new_layout) };
+        if !new_ptr.is_null() {
+            unsafe {
+                ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+                self.dealloc(ptr, layout);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// Allocator using UEFI boot services with a fixed [`MemoryType`].
+///
+/// Unlike [`Allocator`], which always uses the loaded image's data type, this
+/// allocator uses the memory type it was created with. This is useful for
+/// loaders that need some allocations to have a particular type, for example
+/// [`MemoryType::RUNTIME_SERVICES_DATA`] or a custom OS type, so that they
+/// can be found in the memory map after exiting boot services.
+///
+/// This type implements [`GlobalAlloc`]. With the `unstable` feature, it also
+/// implements [`core::alloc::Allocator`]:
+///
+/// ```ignore
+/// #![feature(allocator_api)]
+///
+/// use uefi::allocator::MemoryTypeAllocator;
+/// use uefi::boot::MemoryType;
+///
+/// static RUNTIME_DATA: MemoryTypeAllocator =
+///     MemoryTypeAllocator::new(MemoryType::RUNTIME_SERVICES_DATA);
+///
+/// let boxed = Box::new_in(123, &RUNTIME_DATA);
+/// let mut vec = Vec::new_in(&RUNTIME_DATA);
+/// vec.push(456);
+/// ```
+///
+/// For owned byte buffers on stable Rust, see [`PoolBox`] and [`PageBox`].
+///
+/// Note that if boot services are not active (anymore), allocations fail and
+/// deallocations panic.
+///
+/// With the `allocation_tracking` feature, only calls to
+/// [`GlobalAlloc::alloc`] record their caller location. Allocations made
+/// through [`core::alloc::Allocator`], such as `Box::new_in`, have no
+/// location.
+///
+/// [`PageBox`]: crate::mem::PageBox
+/// [`PoolBox`]: crate::mem::PoolBox
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MemoryTypeAllocator {
+    memory_type: MemoryType,
+}
+
+impl MemoryTypeAllocator {
+    /// Create an allocator for memory of `memory_type`.
+    #[must_use]
+    pub const fn new(memory_type: MemoryType) -> Self {
+        Self { memory_type }
+    }
+
+    /// Get the memory type used for allocations.
+    #[must_use]
+    pub const fn memory_type(&self) -> MemoryType {
+        self.memory_type
+    }
+}
+
+unsafe impl GlobalAlloc for MemoryTypeAllocator {
+    #[track_caller]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = alloc_with_type(self.memory_type, layout);
+        track_alloc(|| self.memory_type, ptr, layout, Some(Location::caller()))
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        track_dealloc(|| self.memory_type, ptr, layout);
+        unsafe { dealloc_with_type(ptr, layout) }
+    }
+}
+
+#[cfg(feature = "unstable")]
+unsafe impl core::alloc::Allocator for MemoryTypeAllocator {
+    fn allocate(&self, layout: Layout) -> core::result::Result<NonNull<[u8]>, AllocError> {
+        // Zero-sized allocations must succeed without allocating.
+        if layout.size() == 0 {
+            let ptr =
+                NonNull::new(ptr::without_provenance_mut(layout.align())).ok_or(AllocError)?;
+            return Ok(NonNull::slice_from_raw_parts(ptr, 0));
+        }
+
+        let ptr = alloc_with_type(self.memory_type, layout);
+        // Collections call `allocate` from inside `alloc`, so the caller
+        // location would not point to user code.
+        let ptr = track_alloc(|| self.memory_type, ptr, layout, None);
+        let ptr = NonNull::new(ptr).ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            track_dealloc(|| self.memory_type, ptr.as_ptr(), layout);
+            unsafe { dealloc_with_type(ptr.as_ptr(), layout) }
+        }
+    }
+}
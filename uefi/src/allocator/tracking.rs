@@ -0,0 +1,259 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Allocation tracking for [`Allocator`] and [`MemoryTypeAllocator`].
+//!
+//! With the `allocation_tracking` feature, every allocation and deallocation
+//! made through these allocators updates per-[`MemoryType`] statistics, see
+//! [`stats`]. In addition, up to [`MAX_TRACKED_ALLOCATIONS`] live allocations
+//! are recorded individually, see [`for_each_outstanding`]. This helps to find
+//! memory leaks in long-running applications. [`helpers`] can log all of this
+//! information on demand.
+//!
+//! # Caller locations
+//!
+//! Allocations made by calling [`GlobalAlloc::alloc`] on a
+//! [`MemoryTypeAllocator`] directly record the location of the call. Other
+//! allocations have no location: Rust does not propagate caller locations
+//! through the global allocator, which is usually [`Allocator`], nor through
+//! collections such as `Box::new_in` or `Vec::new_in` that use
+//! `MemoryTypeAllocator` as an `allocator_api` allocator.
+//!
+//! # Reentrancy
+//!
+//! Allocations can happen from event callbacks that interrupt another
+//! allocation. To avoid deadlocks, the list of individual allocations is not
+//! updated if it is already being accessed; such allocations are only
+//! reflected in the statistics.
+//!
+//! [`Allocator`]: super::Allocator
+//! [`MemoryTypeAllocator`]: super::MemoryTypeAllocator
+//! [`GlobalAlloc::alloc`]: core::alloc::GlobalAlloc::alloc
+//! [`helpers`]: crate::helpers
+
+use crate::boot::MemoryType;
+use core::alloc::Layout;
+use core::cell::UnsafeCell;
+use core::panic::Location;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+
+/// Maximum number of distinct memory types that statistics are kept for.
+pub const MAX_TRACKED_MEMORY_TYPES: usize = 16;
+
+/// Maximum number of live allocations that are recorded individually.
+pub const MAX_TRACKED_ALLOCATIONS: usize = 512;
+
+/// Marker for an unused [`TypeStats`] slot.
+const UNUSED_MEMORY_TYPE: u32 = u32::MAX;
+
+/// Statistics for allocations of one memory type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AllocationStats {
+    /// Memory type of the allocations.
+    pub memory_type: MemoryType,
+
+    /// Number of allocations that have not been freed.
+    pub live_allocations: usize,
+
+    /// Number of bytes in allocations that have not been freed.
+    pub live_bytes: usize,
+
+    /// Highest value of `live_bytes` so far.
+    pub peak_bytes: usize,
+
+    /// Number of allocations made so far, including freed ones.
+    pub total_allocations: usize,
+}
+
+/// An allocation that has not been freed yet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OutstandingAllocation {
+    /// Address of the allocation.
+    pub address: usize,
+
+    /// Size of the allocation in bytes.
+    pub size: usize,
+
+    /// Alignment of the allocation in bytes.
+    pub align: usize,
+
+    /// Memory type of the allocation.
+    pub memory_type: MemoryType,
+
+    /// Location of the code that requested the allocation, if available.
+    pub location: Option<&'static Location<'static>>,
+}
+
+struct TypeStats {
+    memory_type: AtomicU32,
+    live_allocations: AtomicUsize,
+    live_bytes: AtomicUsize,
+    peak_bytes: AtomicUsize,
+    total_allocations: AtomicUsize,
+}
+
+impl TypeStats {
+    const fn new() -> Self {
+        Self {
+            memory_type: AtomicU32::new(UNUSED_MEMORY_TYPE),
+            live_allocations: AtomicUsize::new(0),
+            live_bytes: AtomicUsize::new(0),
+            peak_bytes: AtomicUsize::new(0),
+            total_allocations: AtomicUsize::new(0),
+        }
+    }
+}
+
+static STATS: [TypeStats; MAX_TRACKED_MEMORY_TYPES] =
+    [const { TypeStats::new() }; MAX_TRACKED_MEMORY_TYPES];
+
+/// Statistics for memory types that did not fit into [`STATS`].
+static OTHER_STATS: TypeStats = TypeStats::new();
+
+/// Fixed-size list of live allocations, protected by a try-lock.
+struct Records {
+    locked: AtomicBool,
+    entries: UnsafeCell<[Option<OutstandingAllocation>; MAX_TRACKED_ALLOCATIONS]>,
+}
+
+// SAFETY: `entries` is only accessed while `locked` is held.
+unsafe impl Sync for Records {}
+
+impl Records {
+    /// Run `f` with exclusive access to the entries, or return `None` if they
+    /// are already being accessed.
+    fn try_with<R>(
+        &self,
+        f: impl FnOnce(&mut [Option<OutstandingAllocation>; MAX_TRACKED_ALLOCATIONS]) -> R,
+    ) -> Option<R> {
+        if self
+            .locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return None;
+        }
+        // SAFETY: the lock is held.
+        let result = f(unsafe { &mut *self.entries.get() });
+        self.locked.store(false, Ordering::Release);
+        Some(result)
+    }
+}
+
+static RECORDS: Records = Records {
+    locked: AtomicBool::new(false),
+    entries: UnsafeCell::new([None; MAX_TRACKED_ALLOCATIONS]),
+};
+
+/// Get the statistics slot for `memory_type`, claiming a free one if needed.
+fn type_stats(memory_type: MemoryType) -> &'static TypeStats {
+    for stats in &STATS {
+        let current = stats.memory_type.load(Ordering::Acquire);
+        if current == memory_type.0 {
+            return stats;
+        }
+        if current == UNUSED_MEMORY_TYPE {
+            match stats.memory_type.compare_exchange(
+                UNUSED_MEMORY_TYPE,
+                memory_type.0,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return stats,
+                // Another allocation claimed the slot for the same type.
+                Err(claimed) if claimed == memory_type.0 => return stats,
+                Err(_) => {}
+            }
+        }
+    }
+    &OTHER_STATS
+}
+
+/// Record a successful allocation made at `location`.
+pub(super) fn record(
+    memory_type: MemoryType,
+    ptr: *mut u8,
+    layout: Layout,
+    location: Option<&'static Location<'static>>,
+) {
+    let stats = type_stats(memory_type);
+    stats.live_allocations.fetch_add(1, Ordering::Relaxed);
+    stats.total_allocations.fetch_add(1, Ordering::Relaxed);
+    let live_bytes = stats.live_bytes.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+    stats.peak_bytes.fetch_max(live_bytes, Ordering::Relaxed);
+
+    let allocation = OutstandingAllocation {
+        address: ptr as usize,
+        size: layout.size(),
+        align: layout.align(),
+        memory_type,
+        location,
+    };
+    RECORDS.try_with(|entries| {
+        if let Some(entry) = entries.iter_mut().find(|entry| entry.is_none()) {
+            *entry = Some(allocation);
+        }
+    });
+}
+
+/// Record that an allocation was freed.
+pub(super) fn forget(memory_type: MemoryType, ptr: *mut u8, layout: Layout) {
+    let stats = type_stats(memory_type);
+    stats.live_allocations.fetch_sub(1, Ordering::Relaxed);
+    stats.live_bytes.fetch_sub(layout.size(), Ordering::Relaxed);
+
+    RECORDS.try_with(|entries| {
+        if let Some(entry) = entries
+            .iter_mut()
+            .find(|entry| entry.is_some_and(|a| a.address == ptr as usize))
+        {
+            *entry = None;
+        }
+    });
+}
+
+/// Get an iterator over the statistics of each memory type that has been
+/// allocated so far.
+///
+/// If more than [`MAX_TRACKED_MEMORY_TYPES`] memory types were used, the
+/// statistics for the remaining types are combined into an entry with
+/// [`MemoryType::RESERVED`].
+pub fn stats() -> impl Iterator<Item = AllocationStats> {
+    STATS
+        .iter()
+        .filter_map(|stats| {
+            let memory_type = stats.memory_type.load(Ordering::Acquire);
+            (memory_type != UNUSED_MEMORY_TYPE).then_some((MemoryType(memory_type), stats))
+        })
+        .chain(
+            (OTHER_STATS.total_allocations.load(Ordering::Relaxed) != 0)
+                .then_some((MemoryType::RESERVED, &OTHER_STATS)),
+        )
+        .map(|(memory_type, stats)| AllocationStats {
+            memory_type,
+            live_allocations: stats.live_allocations.load(Ordering::Relaxed),
+            live_bytes: stats.live_bytes.load(Ordering::Relaxed),
+            peak_bytes: stats.peak_bytes.load(Ordering::Relaxed),
+            total_allocations: stats.total_allocations.load(Ordering::Relaxed),
+        })
+}
+
+/// Call `f` for each recorded allocation that has not been freed yet.
+///
+/// Returns the number of live allocations that were not recorded
+/// individually, because the list was full or busy when they were made.
+/// Allocations made by `f` itself are not recorded individually either.
+///
+/// Returns `None` without calling `f` if the list is already being accessed,
+/// for example from `f` itself.
+pub fn for_each_outstanding(mut f: impl FnMut(&OutstandingAllocation)) -> Option<usize> {
+    let live: usize = stats().map(|stats| stats.live_allocations).sum();
+    let recorded = RECORDS.try_with(|entries| {
+        let mut recorded = 0;
+        for allocation in entries.iter().flatten() {
+            f(allocation);
+            recorded += 1;
+        }
+        recorded
+    })?;
+    Some(live.saturating_sub(recorded))
+}
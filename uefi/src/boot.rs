@@ -1457,6 +1457,56 @@ pub unsafe fn install_configuration_table(
     unsafe { (bt.install_configuration_table)(guid_entry, table_ptr) }.to_result()
 }
 
+/// Copies `table` to a new pool allocation of type
+/// [`RUNTIME_SERVICES_DATA`] and installs it in the configuration table,
+/// replacing any existing entry for `guid`.
+///
+/// This is a safe counterpart of [`install_configuration_table`]. The copy is
+/// never freed, so a reference to it is returned. The table can be read back
+/// with [`system::find_config_table`] if it implements [`ConfigTable`].
+///
+/// `T` must have an alignment of at most 8, which is the alignment of pool
+/// allocations.
+///
+/// # Errors
+///
+/// * [`Status::OUT_OF_RESOURCES`]: out of memory.
+///
+/// [`RUNTIME_SERVICES_DATA`]: MemoryType::RUNTIME_SERVICES_DATA
+/// [`system::find_config_table`]: crate::system::find_config_table
+/// [`ConfigTable`]: crate::table::cfg::ConfigTable
+pub fn install_config_table<T: Copy>(guid: &'static Guid, table: &T) -> Result<&'static T> {
+    const { assert!(align_of::<T>() <= 8, "pool allocations are 8-byte aligned") };
+
+    let ptr = allocate_pool(MemoryType::RUNTIME_SERVICES_DATA, size_of::<T>())?.cast::<T>();
+    // SAFETY: the allocation is large enough and aligned for `T`.
+    unsafe { ptr.write(*table) };
+
+    // SAFETY: the table is a pool allocation of the right type, which is
+    // never modified or freed once installed.
+    if let Err(err) = unsafe { install_configuration_table(guid, ptr.as_ptr().cast()) } {
+        // SAFETY: the allocation was not installed.
+        let _ = unsafe { free_pool(ptr.cast()) };
+        return Err(err);
+    }
+
+    // SAFETY: the table is initialized and never modified or freed.
+    Ok(unsafe { ptr.as_ref() })
+}
+
+/// Removes the entry for `guid` from the configuration table.
+///
+/// The table that the entry points to is not freed, since references to it
+/// may still exist.
+///
+/// # Errors
+///
+/// * [`Status::NOT_FOUND`]: there is no entry for `guid`.
+pub fn uninstall_config_table(guid: &'static Guid) -> Result {
+    // SAFETY: removing an entry does not access any table.
+    unsafe { install_configuration_table(guid, ptr::null()) }
+}
+
 /// Sets the watchdog timer.
 ///
 /// UEFI will start a 5-minute countdown after an UEFI image is loaded.  The
@@ -0,0 +1,459 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Monotonic clock for measuring elapsed time.
+//!
+//! [`Instant`] is an opaque point in time from a monotonically increasing
+//! counter, similar to `std::time::Instant`. It is intended for measuring
+//! durations, for example how long the individual phases of a boot take.
+//!
+//! # Counter sources
+//!
+//! The counter is chosen once, during [`calibrate`]:
+//!
+//! * On `x86` and `x86_64`, the time stamp counter (TSC) is used. The TSC
+//!   must be invariant, that is run at a constant rate in all power states,
+//!   for durations to be accurate. A warning is logged during calibration if
+//!   the CPU does not report an invariant TSC.
+//! * On `aarch64`, the generic timer's virtual counter (`CNTVCT_EL0`) is used.
+//! * On other architectures, the [`Timestamp`] protocol is used.
+//!
+//! The frequency of the generic timer is read from `CNTFRQ_EL0`. The TSC
+//! frequency is measured against the [`Timestamp`] protocol if it is present,
+//! and against [`boot::stall`] otherwise.
+//!
+//! # Exiting boot services
+//!
+//! Calibration requires boot services. It happens automatically the first
+//! time [`Instant::now`] is called, or it can be triggered explicitly with
+//! [`calibrate`]. Once calibrated, the architecture counters keep working
+//! after [`boot::exit_boot_services`], so make sure the clock is calibrated
+//! before exiting boot services. The [`Timestamp`] protocol source is only
+//! available while boot services are active.
+//!
+//! [`Timestamp`]: crate::proto::misc::Timestamp
+
+use crate::boot::{self, OpenProtocolAttributes, OpenProtocolParams};
+use crate::proto::misc::Timestamp;
+use crate::{Result, Status};
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use core::time::Duration;
+
+/// Counter frequency in Hz, or zero if the clock has not been calibrated.
+static FREQUENCY: AtomicU64 = AtomicU64::new(0);
+
+/// The [`ClockSource`] the clock was calibrated with.
+static SOURCE: AtomicU8 = AtomicU8::new(0);
+
+/// Mask applied to the difference of two counter values, to handle counters
+/// that are narrower than 64 bits.
+static COUNTER_MASK: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// How long to stall while calibrating the TSC.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const CALIBRATION_PERIOD: Duration = Duration::from_millis(10);
+
+/// Source of the counter backing [`Instant`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum ClockSource {
+    /// The x86 time stamp counter, read with `RDTSC`.
+    Tsc = 1,
+
+    /// The Arm generic timer's virtual counter, `CNTVCT_EL0`.
+    GenericTimer = 2,
+
+    /// The [`Timestamp`] protocol. Only usable while boot services are
+    /// active.
+    ///
+    /// [`Timestamp`]: crate::proto::misc::Timestamp
+    TimestampProtocol = 3,
+}
+
+impl ClockSource {
+    const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Self::Tsc),
+            2 => Some(Self::GenericTimer),
+            3 => Some(Self::TimestampProtocol),
+            _ => None,
+        }
+    }
+
+    fn read(self) -> u64 {
+        match self {
+            Self::Tsc => arch::read_tsc(),
+            Self::GenericTimer => arch::read_generic_timer(),
+            Self::TimestampProtocol => {
+                assert!(
+                    boot::are_boot_services_active(),
+                    "the Timestamp protocol clock is not available after exiting boot services"
+                );
+                with_timestamp_protocol(|ts| ts.get_timestamp())
+                    .expect("the Timestamp protocol is no longer available")
+            }
+        }
+    }
+}
+
+/// Calibrate the clock, if that has not already happened.
+///
+/// This is called automatically by [`Instant::now`], but it requires boot
+/// services to be active. Call this function before exiting boot services if
+/// the clock is used afterwards, or to handle calibration errors instead of
+/// panicking.
+///
+/// # Errors
+///
+/// * [`Status::UNSUPPORTED`]: boot services are no longer active, or no
+///   counter is available on this platform.
+/// * [`Status::DEVICE_ERROR`]: the counter frequency could not be determined.
+pub fn calibrate() -> Result<ClockSource> {
+    if let Some(source) = source() {
+        return Ok(source);
+    }
+
+    if !boot::are_boot_services_active() {
+        return Err(Status::UNSUPPORTED.into());
+    }
+
+    let (source, frequency, mask) = arch::calibrate()?;
+    if frequency == 0 {
+        return Err(Status::DEVICE_ERROR.into());
+    }
+
+    COUNTER_MASK.store(mask, Ordering::Relaxed);
+    SOURCE.store(source as u8, Ordering::Relaxed);
+    FREQUENCY.store(frequency, Ordering::Release);
+
+    Ok(source)
+}
+
+/// Get the source of the clock, or `None` if the clock has not been
+/// calibrated yet.
+#[must_use]
+pub fn source() -> Option<ClockSource> {
+    if FREQUENCY.load(Ordering::Acquire) == 0 {
+        None
+    } else {
+        ClockSource::from_u8(SOURCE.load(Ordering::Relaxed))
+    }
+}
+
+/// Get the frequency of the clock in Hz, or `None` if the clock has not been
+/// calibrated yet.
+#[must_use]
+pub fn frequency() -> Option<u64> {
+    match FREQUENCY.load(Ordering::Acquire) {
+        0 => None,
+        frequency => Some(frequency),
+    }
+}
+
+/// Calibrate the clock if necessary, and get its source and frequency.
+fn calibrated() -> (ClockSource, u64) {
+    let source = calibrate().expect("failed to calibrate the clock");
+    (source, FREQUENCY.load(Ordering::Acquire))
+}
+
+/// Run `f` with the [`Timestamp`] protocol, if it is present.
+fn with_timestamp_protocol<R>(f: impl FnOnce(&Timestamp) -> R) -> Option<R> {
+    let handle = boot::get_handle_for_protocol::<Timestamp>().ok()?;
+    // SAFETY: the protocol is only used for the duration of this function,
+    // and the Timestamp protocol is not expected to be uninstalled.
+    let timestamp = unsafe {
+        boot::open_protocol::<Timestamp>(
+            OpenProtocolParams {
+                handle,
+                agent: boot::image_handle(),
+                controller: None,
+            },
+            OpenProtocolAttributes::GetProtocol,
+        )
+    }
+    .ok()?;
+    Some(f(&timestamp))
+}
+
+/// Calibrate against the [`Timestamp`] protocol, returning the source,
+/// frequency and counter mask.
+fn calibrate_timestamp_protocol() -> Result<(ClockSource, u64, u64)> {
+    let properties =
+        with_timestamp_protocol(|ts| ts.get_properties()).ok_or(Status::UNSUPPORTED)??;
+
+    // Counters narrower than 64 bits wrap around at `end_value`. Only
+    // power-of-two ranges can be handled by masking.
+    let mask = properties.end_value;
+    if mask != u64::MAX && !mask.wrapping_add(1).is_power_of_two() {
+        return Err(Status::UNSUPPORTED.into());
+    }
+
+    Ok((ClockSource::TimestampProtocol, properties.frequency, mask))
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod arch {
+    use super::*;
+
+    pub fn read_tsc() -> u64 {
+        let low: u32;
+        let high: u32;
+        // SAFETY: RDTSC has no side effects and is available on all CPUs
+        // capable of running UEFI.
+        unsafe {
+            core::arch::asm!(
+                "rdtsc",
+                out("eax") low,
+                out("edx") high,
+                options(nomem, nostack, preserves_flags)
+            );
+        }
+        (u64::from(high) << 32) | u64::from(low)
+    }
+
+    pub fn read_generic_timer() -> u64 {
+        unreachable!("the generic timer is not available on x86")
+    }
+
+    /// Returns whether the TSC is invariant (CPUID `0x8000_0007`, EDX bit 8).
+    #[allow(unused_unsafe)] // `__cpuid` is unsafe in older versions of Rust.
+    fn has_invariant_tsc() -> bool {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::__cpuid;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::__cpuid;
+
+        // SAFETY: CPUID is available on all CPUs capable of running UEFI.
+        let max_extended_leaf = unsafe { __cpuid(0x8000_0000) }.eax;
+        max_extended_leaf >= 0x8000_0007 && unsafe { __cpuid(0x8000_0007) }.edx & (1 << 8) != 0
+    }
+
+    pub fn calibrate() -> Result<(ClockSource, u64, u64)> {
+        // Prefer measuring against the Timestamp protocol, as it is not
+        // affected by how accurately the firmware implements `stall`.
+        let reference = calibrate_timestamp_protocol().ok();
+
+        // The TSC is still used, so that the clock keeps working after
+        // exiting boot services, but its rate may change with the CPU
+        // frequency.
+        if !has_invariant_tsc() {
+            log::warn!("the TSC is not invariant, clock durations may be inaccurate");
+        }
+
+        let start_reference = reference.map(|(source, ..)| source.read());
+        let start = read_tsc();
+        boot::stall(CALIBRATION_PERIOD);
+        let end = read_tsc();
+        let end_reference = reference.map(|(source, ..)| source.read());
+
+        let ticks = u128::from(end.wrapping_sub(start));
+        let frequency = match (reference, start_reference, end_reference) {
+            (Some((_, frequency, mask)), Some(start), Some(end)) => {
+                let reference_ticks = u128::from(end.wrapping_sub(start) & mask);
+                if reference_ticks == 0 {
+                    return Err(Status::DEVICE_ERROR.into());
+                }
+                ticks * u128::from(frequency) / reference_ticks
+            }
+            _ => ticks * 1_000_000 / CALIBRATION_PERIOD.as_micros(),
+        };
+
+        Ok((
+            ClockSource::Tsc,
+            u64::try_from(frequency).map_err(|_| Status::DEVICE_ERROR)?,
+            u64::MAX,
+        ))
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod arch {
+    use super::*;
+
+    pub fn read_tsc() -> u64 {
+        unreachable!("the TSC is only available on x86")
+    }
+
+    pub fn read_generic_timer() -> u64 {
+        let value: u64;
+        // SAFETY: the virtual counter is accessible at EL1 and EL2, which is
+        // where UEFI runs. The ISB keeps the read from being speculated ahead
+        // of earlier instructions.
+        unsafe {
+            core::arch::asm!(
+                "isb",
+                "mrs {}, cntvct_el0",
+                out(reg) value,
+                options(nomem, nostack, preserves_flags)
+            );
+        }
+        value
+    }
+
+    pub fn calibrate() -> Result<(ClockSource, u64, u64)> {
+        let frequency: u64;
+        // SAFETY: reading the counter frequency has no side effects.
+        unsafe {
+            core::arch::asm!(
+                "mrs {}, cntfrq_el0",
+                out(reg) frequency,
+                options(nomem, nostack, preserves_flags)
+            );
+        }
+        // CNTFRQ_EL0 is set up by firmware. Fall back to the Timestamp
+        // protocol if it was left unset.
+        if frequency == 0 {
+            calibrate_timestamp_protocol()
+        } else {
+            Ok((ClockSource::GenericTimer, frequency, u64::MAX))
+        }
+    }
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+mod arch {
+    use super::*;
+
+    pub fn read_tsc() -> u64 {
+        unreachable!("the TSC is only available on x86")
+    }
+
+    pub fn read_generic_timer() -> u64 {
+        unreachable!("the generic timer is only available on aarch64")
+    }
+
+    pub fn calibrate() -> Result<(ClockSource, u64, u64)> {
+        calibrate_timestamp_protocol()
+    }
+}
+
+/// A point in time from a monotonically increasing clock.
+///
+/// Instants are only meaningful relative to each other, for example to get the
+/// [`Duration`] between them. See the [module documentation] for details of
+/// the underlying counter.
+///
+/// Instants don't implement [`Ord`], since counters that are narrower than 64
+/// bits wrap around. Use [`checked_duration_since`] to compare them.
+///
+/// [module documentation]: self
+/// [`checked_duration_since`]: Self::checked_duration_since
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Instant(u64);
+
+impl Instant {
+    /// Get the current instant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clock has not been calibrated and cannot be calibrated
+    /// now, see [`calibrate`].
+    #[must_use]
+    pub fn now() -> Self {
+        let (source, _) = calibrated();
+        Self(source.read())
+    }
+
+    /// Get the raw counter value of the instant.
+    #[must_use]
+    pub const fn ticks(&self) -> u64 {
+        self.0
+    }
+
+    /// Get the amount of time elapsed since this instant.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        Self::now().duration_since(*self)
+    }
+
+    /// Get the amount of time elapsed from `earlier` to `self`, or zero if
+    /// `earlier` is later than `self`.
+    #[must_use]
+    pub fn duration_since(&self, earlier: Self) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or_default()
+    }
+
+    /// Get the amount of time elapsed from `earlier` to `self`, or `None` if
+    /// `earlier` is later than `self`.
+    #[must_use]
+    pub fn checked_duration_since(&self, earlier: Self) -> Option<Duration> {
+        let (_, frequency) = calibrated();
+        let mask = COUNTER_MASK.load(Ordering::Relaxed);
+        let ticks = if mask == u64::MAX {
+            self.0.checked_sub(earlier.0)?
+        } else {
+            // Narrow counters wrap around, so the order of two instants
+            // cannot be determined from their values alone.
+            self.0.wrapping_sub(earlier.0) & mask
+        };
+        let nanos = u128::from(ticks) * 1_000_000_000 / u128::from(frequency);
+        Some(Duration::from_nanos(u64::try_from(nanos).ok()?))
+    }
+
+    /// Get the instant `duration` after `self`, or `None` on overflow.
+    #[must_use]
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        self.0.checked_add(duration_to_ticks(duration)?).map(Self)
+    }
+
+    /// Get the instant `duration` before `self`, or `None` on overflow.
+    #[must_use]
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        self.0.checked_sub(duration_to_ticks(duration)?).map(Self)
+    }
+}
+
+/// Convert a duration to a number of counter ticks, rounding down.
+fn duration_to_ticks(duration: Duration) -> Option<u64> {
+    let (_, frequency) = calibrated();
+    let ticks = duration.as_nanos() * u128::from(frequency) / 1_000_000_000;
+    u64::try_from(ticks).ok()
+}
+
+impl Add<Duration> for Instant {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// Panics on overflow. See [`Instant::checked_add`] for a version without
+    /// panic.
+    fn add(self, duration: Duration) -> Self {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to instant")
+    }
+}
+
+impl AddAssign<Duration> for Instant {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<Duration> for Instant {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// Panics on overflow. See [`Instant::checked_sub`] for a version without
+    /// panic.
+    fn sub(self, duration: Duration) -> Self {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from instant")
+    }
+}
+
+impl SubAssign<Duration> for Instant {
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = *self - duration;
+    }
+}
+
+impl Sub<Self> for Instant {
+    type Output = Duration;
+
+    /// Get the amount of time elapsed from `earlier` to `self`, or zero if
+    /// `earlier` is later than `self`.
+    fn sub(self, earlier: Self) -> Duration {
+        self.duration_since(earlier)
+    }
+}
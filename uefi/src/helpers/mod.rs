@@ -5,7 +5,9 @@
 //! ecosystem.
 //!
 //! For now, this includes:
-//! - using [`uefi::allocator::Allocator`] as global allocator (feature `global_allocator`)
+//! - using [`uefi::allocator::Allocator`] as global allocator (feature `global_allocator`).
+//!   Call [`uefi::allocator::reserve_exit_arena`] to keep it working after
+//!   exiting boot services.
 //! - an implementation of  [`log::Log`] (feature `logger`) which logs to
 //!   the stdout text protocol of UEFI (as long as boot services were not
 //!   excited) and to the [debugcon device](https://phip1611.de/blog/how-to-use-qemus-debugcon-feature/)
@@ -13,6 +15,8 @@
 //! - [`print!`][print_macro] and [`println!`][println_macro] macros defaulting
 //!   to the uefi boot service stdout stream
 //! - default panic handler (feature `panic_handler`)
+//! - a report of allocations and leaks (feature `allocation_tracking`), see
+//!   `log_allocations`
 //!
 //! **PLEASE NOTE** that these helpers are meant for the pre exit boot service
 //! epoch.
@@ -55,6 +59,47 @@ pub fn init() -> Result<()> {
     Ok(())
 }
 
+/// Log the allocation statistics of [`uefi::allocator::Allocator`] and
+/// [`uefi::allocator::MemoryTypeAllocator`], followed by all allocations that
+/// have not been freed yet.
+///
+/// Call this before exiting boot services to find leaks: the allocations
+/// that are still outstanding then are either leaks or intentional hand-offs
+/// to the next stage.
+#[cfg(feature = "allocation_tracking")]
+pub fn log_allocations() {
+    use crate::allocator::tracking;
+
+    for stats in tracking::stats() {
+        log::info!(
+            "{:?}: {} live allocations ({} bytes, peak {} bytes), {} total",
+            stats.memory_type,
+            stats.live_allocations,
+            stats.live_bytes,
+            stats.peak_bytes,
+            stats.total_allocations,
+        );
+    }
+
+    let untracked = tracking::for_each_outstanding(|allocation| {
+        log::info!(
+            "outstanding: {:#x}, {} bytes (align {}), {:?}",
+            allocation.address,
+            allocation.size,
+            allocation.align,
+            allocation.memory_type,
+        );
+        if let Some(location) = allocation.location {
+            log::info!("  allocated at {location}");
+        }
+    });
+    match untracked {
+        Some(0) => {}
+        Some(untracked) => log::info!("{untracked} more outstanding allocations not recorded"),
+        None => log::warn!("allocation list is busy, outstanding allocations not logged"),
+    }
+}
+
 #[allow(clippy::missing_const_for_fn)]
 pub(crate) fn exit() {
     #[cfg(feature = "logger")]
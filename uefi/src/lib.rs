@@ -142,6 +142,11 @@
 //!   using this feature, or no allocator at all if you don't need to
 //!   dynamically allocate any memory. Note that even without that feature,
 //!   some code might use the internal UEFI allocator.
+//! - `allocation_tracking`: Track allocations made through
+//!   [`allocator::Allocator`] and [`allocator::MemoryTypeAllocator`], see
+//!   `uefi::allocator::tracking`. A leak report is logged when exiting boot
+//!   services. Caller locations are only recorded for direct calls to
+//!   `GlobalAlloc::alloc` on a `MemoryTypeAllocator`.
 //! - `logger`: Logging implementation for the standard [`log`] crate
 //!   that prints output to the UEFI console. No buffering is done; this
 //!   is not a high-performance logger.
@@ -255,6 +260,7 @@
 //! [unstable features]: https://doc.rust-lang.org/unstable-book/
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(feature = "unstable", feature(allocator_api))]
 #![no_std]
 #![deny(
     clippy::all,
@@ -281,6 +287,7 @@ extern crate uefi_raw;
 pub mod data_types;
 pub mod allocator;
 pub mod boot;
+pub mod clock;
 #[cfg(feature = "alloc")]
 pub mod fs;
 pub mod helpers;
@@ -8,6 +8,11 @@ use core::ptr::NonNull;
 
 pub mod memory_map;
 
+mod pages;
+mod pool;
+pub use pages::{PageBox, PageVec};
+pub use pool::PoolBox;
+
 #[cfg(feature = "alloc")]
 pub(crate) mod util;
 
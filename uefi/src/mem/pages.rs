@@ -0,0 +1,570 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Owned page allocations.
+
+use crate::boot::{self, AllocateType, MemoryType, PAGE_SIZE};
+use crate::data_types::PhysicalAddress;
+use crate::{Result, Status};
+use core::fmt::{self, Debug, Formatter};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+use core::slice;
+
+/// Owned, zero-initialized memory pages allocated with
+/// [`boot::allocate_pages`].
+///
+/// The pages are freed with [`boot::free_pages`] when the `PageBox` is
+/// dropped. After exiting boot services the pages are no longer freed on drop,
+/// as the memory map then belongs to the OS.
+///
+/// To hand an allocation off to code that runs after [`exit_boot_services`],
+/// for example a kernel, use [`into_raw`] or [`leak`]. The pages remain
+/// allocated with their [`MemoryType`], so they can be found in the final
+/// memory map.
+///
+/// The pages are physically contiguous and identity mapped, so
+/// [`physical_address`] can be passed to devices and to the OS.
+///
+/// [`exit_boot_services`]: boot::exit_boot_services
+/// [`into_raw`]: Self::into_raw
+/// [`leak`]: Self::leak
+/// [`physical_address`]: Self::physical_address
+pub struct PageBox {
+    ptr: NonNull<u8>,
+    page_count: usize,
+    memory_type: MemoryType,
+}
+
+impl PageBox {
+    /// Allocate `page_count` zeroed pages anywhere in memory.
+    ///
+    /// # Errors
+    ///
+    /// See [`PageBox::allocate`].
+    pub fn new(memory_type: MemoryType, page_count: usize) -> Result<Self> {
+        Self::allocate(AllocateType::AnyPages, memory_type, page_count)
+    }
+
+    /// Allocate `page_count` zeroed pages, all of which are at or below
+    /// `max_address`.
+    ///
+    /// `max_address` is the highest address any byte of the allocation may
+    /// occupy. For example, pass `0xffff_ffff` to get memory that is
+    /// addressable with 32 bits.
+    ///
+    /// # Errors
+    ///
+    /// See [`PageBox::allocate`].
+    pub fn new_below(
+        max_address: PhysicalAddress,
+        memory_type: MemoryType,
+        page_count: usize,
+    ) -> Result<Self> {
+        Self::allocate(
+            AllocateType::MaxAddress(max_address),
+            memory_type,
+            page_count,
+        )
+    }
+
+    /// Allocate `page_count` zeroed pages starting at `address`.
+    ///
+    /// # Errors
+    ///
+    /// See [`PageBox::allocate`].
+    pub fn new_at(
+        address: PhysicalAddress,
+        memory_type: MemoryType,
+        page_count: usize,
+    ) -> Result<Self> {
+        Self::allocate(AllocateType::Address(address), memory_type, page_count)
+    }
+
+    /// Allocate `page_count` zeroed pages according to `allocation_type`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::INVALID_PARAMETER`]: `page_count` is zero, the address of
+    ///   [`AllocateType::Address`] is not page aligned, the address of
+    ///   [`AllocateType::MaxAddress`] is too low to hold `page_count` pages,
+    ///   or `memory_type` is invalid.
+    /// * [`Status::OUT_OF_RESOURCES`]: allocation failed.
+    /// * [`Status::NOT_FOUND`]: the requested pages could not be found.
+    pub fn allocate(
+        allocation_type: AllocateType,
+        memory_type: MemoryType,
+        page_count: usize,
+    ) -> Result<Self> {
+        let size = page_count
+            .checked_mul(PAGE_SIZE)
+            .ok_or(Status::INVALID_PARAMETER)?;
+        if size == 0 {
+            return Err(Status::INVALID_PARAMETER.into());
+        }
+        match allocation_type {
+            AllocateType::AnyPages => {}
+            AllocateType::MaxAddress(max_address) => {
+                // The highest usable page must be able to fit `page_count`
+                // pages below it.
+                if max_address < size as PhysicalAddress - 1 {
+                    return Err(Status::INVALID_PARAMETER.into());
+                }
+            }
+            AllocateType::Address(address) => {
+                if !address.is_multiple_of(PAGE_SIZE as PhysicalAddress) {
+                    return Err(Status::INVALID_PARAMETER.into());
+                }
+            }
+        }
+
+        let ptr = boot::allocate_pages(allocation_type, memory_type, page_count)?;
+        // SAFETY: the allocation is `size` bytes long.
+        unsafe { ptr.write_bytes(0, size) };
+
+        Ok(Self {
+            ptr,
+            page_count,
+            memory_type,
+        })
+    }
+
+    /// Take ownership of pages allocated with [`boot::allocate_pages`].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` and `page_count` must describe an allocation made by
+    /// [`boot::allocate_pages`] with `memory_type`, for example one returned
+    /// by [`into_raw`]. The memory must be initialized, and must not be used
+    /// through any other pointer while the `PageBox` exists.
+    ///
+    /// [`into_raw`]: Self::into_raw
+    #[must_use]
+    pub const unsafe fn from_raw(
+        ptr: NonNull<u8>,
+        page_count: usize,
+        memory_type: MemoryType,
+    ) -> Self {
+        Self {
+            ptr,
+            page_count,
+            memory_type,
+        }
+    }
+
+    /// Give up ownership of the pages without freeing them, returning the
+    /// pointer to the first page and the number of pages.
+    ///
+    /// Use this to hand the allocation off, for example to a kernel across
+    /// [`boot::exit_boot_services`]. The allocation can be turned back into
+    /// a `PageBox` with [`from_raw`].
+    ///
+    /// [`from_raw`]: Self::from_raw
+    #[must_use]
+    pub fn into_raw(self) -> (NonNull<u8>, usize) {
+        let this = mem::ManuallyDrop::new(self);
+        (this.ptr, this.page_count)
+    }
+
+    /// Give up ownership of the pages without freeing them, returning a
+    /// slice that is valid for the rest of the program.
+    #[must_use]
+    pub fn leak(self) -> &'static mut [u8] {
+        let len = self.len();
+        let (ptr, _) = self.into_raw();
+        // SAFETY: the pages are never freed, and are initialized.
+        unsafe { slice::from_raw_parts_mut(ptr.as_ptr(), len) }
+    }
+
+    /// Get a pointer to the first page.
+    #[must_use]
+    pub const fn as_ptr(&self) -> NonNull<u8> {
+        self.ptr
+    }
+
+    /// Get the physical address of the first page.
+    #[must_use]
+    pub fn physical_address(&self) -> PhysicalAddress {
+        self.ptr.as_ptr() as PhysicalAddress
+    }
+
+    /// Get the number of pages.
+    #[must_use]
+    pub const fn page_count(&self) -> usize {
+        self.page_count
+    }
+
+    /// Get the size of the allocation in bytes.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.page_count * PAGE_SIZE
+    }
+
+    /// Always `false`, since allocations have at least one page. Provided for
+    /// consistency with [`len`].
+    ///
+    /// [`len`]: Self::len
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Get the memory type the pages were allocated with.
+    #[must_use]
+    pub const fn memory_type(&self) -> MemoryType {
+        self.memory_type
+    }
+
+    /// View the pages as a slice of `T` values.
+    ///
+    /// Any bytes past the last whole `T` are not part of the slice.
+    ///
+    /// # Safety
+    ///
+    /// The contents of the pages must be valid `T` values. The pages are
+    /// zero-initialized, so this holds for types such as integers until other
+    /// data is written. `T` must not contain padding bytes, since the pages
+    /// are also accessible as initialized bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero-sized or needs an alignment larger than
+    /// [`PAGE_SIZE`].
+    #[must_use]
+    pub const unsafe fn as_slice<T>(&self) -> &[T] {
+        let len = typed_len::<T>(self.len());
+        // SAFETY: the pages are page aligned, and the caller guarantees the
+        // contents are valid.
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr().cast(), len) }
+    }
+
+    /// View the pages as a mutable slice of `T` values.
+    ///
+    /// # Safety
+    ///
+    /// See [`as_slice`].
+    ///
+    /// # Panics
+    ///
+    /// See [`as_slice`].
+    ///
+    /// [`as_slice`]: Self::as_slice
+    #[must_use]
+    pub const unsafe fn as_mut_slice<T>(&mut self) -> &mut [T] {
+        let len = typed_len::<T>(self.len());
+        // SAFETY: the pages are page aligned, and the caller guarantees the
+        // contents are valid.
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr().cast(), len) }
+    }
+}
+
+/// Get the number of `T` values that fit in `size` bytes of page-aligned
+/// memory.
+const fn typed_len<T>(size: usize) -> usize {
+    assert!(size_of::<T>() != 0, "zero-sized types are not supported");
+    assert!(
+        align_of::<T>() <= PAGE_SIZE,
+        "alignment larger than a page is not supported"
+    );
+    size / size_of::<T>()
+}
+
+impl Deref for PageBox {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: the pages are initialized.
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len()) }
+    }
+}
+
+impl DerefMut for PageBox {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: the pages are initialized.
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len()) }
+    }
+}
+
+impl Debug for PageBox {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PageBox")
+            .field("physical_address", &self.physical_address())
+            .field("page_count", &self.page_count)
+            .field("memory_type", &self.memory_type)
+            .finish()
+    }
+}
+
+impl Drop for PageBox {
+    fn drop(&mut self) {
+        if boot::are_boot_services_active() {
+            // Ignore errors returned by `free_pages` since we can't propagate
+            // them from `drop`.
+            let _ = unsafe { boot::free_pages(self.ptr, self.page_count) };
+        }
+    }
+}
+
+/// A growable, physically contiguous buffer of `T` values in page
+/// allocations, similar to [`Vec`].
+///
+/// The buffer is backed by a [`PageBox`]. When it has to grow, a new
+/// allocation with the same [`MemoryType`] and address constraint is made and
+/// the values are moved over, so the [`physical_address`] may change.
+///
+/// [`Vec`]: https://doc.rust-lang.org/alloc/vec/struct.Vec.html
+/// [`physical_address`]: Self::physical_address
+pub struct PageVec<T> {
+    pages: Option<PageBox>,
+    len: usize,
+    max_address: Option<PhysicalAddress>,
+    memory_type: MemoryType,
+    _marker: PhantomData<T>,
+}
+
+impl<T> PageVec<T> {
+    /// Create an empty buffer. No pages are allocated until values are added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero-sized or needs an alignment larger than
+    /// [`PAGE_SIZE`].
+    #[must_use]
+    pub const fn new(memory_type: MemoryType) -> Self {
+        typed_len::<T>(0);
+        Self {
+            pages: None,
+            len: 0,
+            max_address: None,
+            memory_type,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Create an empty buffer whose allocations are all at or below
+    /// `max_address`. See [`PageBox::new_below`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero-sized or needs an alignment larger than
+    /// [`PAGE_SIZE`].
+    #[must_use]
+    pub const fn new_below(max_address: PhysicalAddress, memory_type: MemoryType) -> Self {
+        let mut vec = Self::new(memory_type);
+        vec.max_address = Some(max_address);
+        vec
+    }
+
+    /// Create an empty buffer with space for at least `capacity` values.
+    ///
+    /// # Errors
+    ///
+    /// See [`PageBox::allocate`].
+    pub fn with_capacity(memory_type: MemoryType, capacity: usize) -> Result<Self> {
+        let mut vec = Self::new(memory_type);
+        vec.try_reserve(capacity)?;
+        Ok(vec)
+    }
+
+    /// Get the number of values in the buffer.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the buffer is empty.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the number of values the buffer can hold without reallocating.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        match &self.pages {
+            Some(pages) => typed_len::<T>(pages.len()),
+            None => 0,
+        }
+    }
+
+    /// Get the memory type of the allocations.
+    #[must_use]
+    pub const fn memory_type(&self) -> MemoryType {
+        self.memory_type
+    }
+
+    /// Get the physical address of the first value, or `None` if nothing has
+    /// been allocated yet.
+    #[must_use]
+    pub fn physical_address(&self) -> Option<PhysicalAddress> {
+        self.pages.as_ref().map(PageBox::physical_address)
+    }
+
+    /// Make sure there is space for at least `additional` more values.
+    ///
+    /// # Errors
+    ///
+    /// See [`PageBox::allocate`].
+    pub fn try_reserve(&mut self, additional: usize) -> Result {
+        let required = self
+            .len
+            .checked_add(additional)
+            .ok_or(Status::OUT_OF_RESOURCES)?;
+        if required <= self.capacity() {
+            return Ok(());
+        }
+
+        // Grow at least geometrically to amortize the cost of copying.
+        let required_size = required
+            .checked_mul(size_of::<T>())
+            .ok_or(Status::OUT_OF_RESOURCES)?;
+        let page_count = required_size
+            .div_ceil(PAGE_SIZE)
+            .max(self.pages.as_ref().map_or(0, |p| p.page_count() * 2));
+
+        let allocation_type = match self.max_address {
+            Some(max_address) => AllocateType::MaxAddress(max_address),
+            None => AllocateType::AnyPages,
+        };
+        let new_pages = PageBox::allocate(allocation_type, self.memory_type, page_count)?;
+        if let Some(old_pages) = &self.pages {
+            // SAFETY: both allocations hold at least `len` values and do not
+            // overlap. The values are moved, so the old pages are freed
+            // without dropping them.
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    old_pages.as_ptr().as_ptr().cast::<T>(),
+                    new_pages.as_ptr().as_ptr().cast::<T>(),
+                    self.len,
+                );
+            }
+        }
+        self.pages = Some(new_pages);
+        Ok(())
+    }
+
+    /// Append a value to the end of the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more pages are needed and cannot be allocated. Use
+    /// [`try_reserve`] beforehand to handle allocation errors.
+    ///
+    /// [`try_reserve`]: Self::try_reserve
+    pub fn push(&mut self, value: T) {
+        self.try_reserve(1).expect("failed to grow page buffer");
+        let pages = self.pages.as_ref().unwrap();
+        // SAFETY: there is space for the value after `len` values.
+        unsafe {
+            pages
+                .as_ptr()
+                .as_ptr()
+                .cast::<T>()
+                .add(self.len)
+                .write(value)
+        };
+        self.len += 1;
+    }
+
+    /// Remove the last value and return it, or `None` if the buffer is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let pages = self.pages.as_ref()?;
+        // SAFETY: the value at `len` is initialized and no longer part of the
+        // buffer.
+        Some(unsafe { pages.as_ptr().as_ptr().cast::<T>().add(self.len).read() })
+    }
+
+    /// Shorten the buffer to `len` values, dropping the rest. Does nothing if
+    /// the buffer is already shorter.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail: *mut [T] = &mut self[len..];
+        self.len = len;
+        // SAFETY: the values in the tail are initialized and no longer part
+        // of the buffer.
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    /// Remove all values, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Give up ownership of the buffer without freeing it, returning a slice
+    /// of the values that is valid for the rest of the program.
+    ///
+    /// This can be used to hand the buffer off across
+    /// [`boot::exit_boot_services`].
+    #[must_use]
+    pub fn leak(self) -> &'static mut [T] {
+        let mut this = mem::ManuallyDrop::new(self);
+        match this.pages.take() {
+            Some(pages) => {
+                let (ptr, _) = pages.into_raw();
+                // SAFETY: the pages are never freed, and hold `len` values.
+                unsafe { slice::from_raw_parts_mut(ptr.as_ptr().cast(), this.len) }
+            }
+            None => &mut [],
+        }
+    }
+}
+
+impl<T: Clone> PageVec<T> {
+    /// Append clones of all values in `values` to the end of the buffer.
+    ///
+    /// # Errors
+    ///
+    /// See [`PageBox::allocate`]. The buffer is unchanged on error.
+    pub fn extend_from_slice(&mut self, values: &[T]) -> Result {
+        self.try_reserve(values.len())?;
+        for value in values {
+            self.push(value.clone());
+        }
+        Ok(())
+    }
+}
+
+impl<T> Deref for PageVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match &self.pages {
+            // SAFETY: the first `len` values are initialized.
+            Some(pages) => unsafe {
+                slice::from_raw_parts(pages.as_ptr().as_ptr().cast(), self.len)
+            },
+            None => &[],
+        }
+    }
+}
+
+impl<T> DerefMut for PageVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match &mut self.pages {
+            // SAFETY: the first `len` values are initialized.
+            Some(pages) => unsafe {
+                slice::from_raw_parts_mut(pages.as_ptr().as_ptr().cast(), self.len)
+            },
+            None => &mut [],
+        }
+    }
+}
+
+impl<T: Debug> Debug for PageVec<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Drop for PageVec<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
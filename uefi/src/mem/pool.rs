@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Owned pool allocations.
+
+use crate::Result;
+use crate::boot::{self, MemoryType};
+use core::fmt::{self, Debug, Formatter};
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+use core::slice;
+
+/// Owned, zero-initialized byte buffer allocated with
+/// [`boot::allocate_pool`].
+///
+/// This is the pool counterpart of [`PageBox`], for buffers that need a
+/// particular [`MemoryType`] but not page granularity. The buffer is eight
+/// byte aligned. It is freed with [`boot::free_pool`] when dropped, unless
+/// boot services are no longer active.
+///
+/// Use [`into_raw`] or [`leak`] to hand the buffer off, for example across
+/// [`boot::exit_boot_services`].
+///
+/// [`PageBox`]: super::PageBox
+/// [`into_raw`]: Self::into_raw
+/// [`leak`]: Self::leak
+pub struct PoolBox {
+    ptr: NonNull<u8>,
+    len: usize,
+    memory_type: MemoryType,
+}
+
+impl PoolBox {
+    /// Allocate a zeroed buffer of `len` bytes of `memory_type`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::OUT_OF_RESOURCES`]: allocation failed.
+    /// * [`Status::INVALID_PARAMETER`]: `memory_type` is invalid.
+    ///
+    /// [`Status::OUT_OF_RESOURCES`]: crate::Status::OUT_OF_RESOURCES
+    /// [`Status::INVALID_PARAMETER`]: crate::Status::INVALID_PARAMETER
+    pub fn new(memory_type: MemoryType, len: usize) -> Result<Self> {
+        let ptr = boot::allocate_pool(memory_type, len)?;
+        // SAFETY: the allocation is `len` bytes long.
+        unsafe { ptr.write_bytes(0, len) };
+        Ok(Self {
+            ptr,
+            len,
+            memory_type,
+        })
+    }
+
+    /// Allocate a buffer of `memory_type` holding a copy of `data`.
+    ///
+    /// # Errors
+    ///
+    /// See [`PoolBox::new`].
+    pub fn from_slice(memory_type: MemoryType, data: &[u8]) -> Result<Self> {
+        let mut buffer = Self::new(memory_type, data.len())?;
+        buffer.copy_from_slice(data);
+        Ok(buffer)
+    }
+
+    /// Take ownership of a buffer allocated with [`boot::allocate_pool`].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`boot::allocate_pool`] with
+    /// `memory_type` and a size of at least `len` bytes, for example by
+    /// [`into_raw`]. The first `len` bytes must be initialized, and the
+    /// memory must not be used through any other pointer while the `PoolBox`
+    /// exists.
+    ///
+    /// [`into_raw`]: Self::into_raw
+    #[must_use]
+    pub const unsafe fn from_raw(ptr: NonNull<u8>, len: usize, memory_type: MemoryType) -> Self {
+        Self {
+            ptr,
+            len,
+            memory_type,
+        }
+    }
+
+    /// Give up ownership of the buffer without freeing it, returning the
+    /// pointer and length.
+    #[must_use]
+    pub fn into_raw(self) -> (NonNull<u8>, usize) {
+        let this = mem::ManuallyDrop::new(self);
+        (this.ptr, this.len)
+    }
+
+    /// Give up ownership of the buffer without freeing it, returning a slice
+    /// that is valid for the rest of the program.
+    #[must_use]
+    pub fn leak(self) -> &'static mut [u8] {
+        let (ptr, len) = self.into_raw();
+        // SAFETY: the buffer is never freed, and is initialized.
+        unsafe { slice::from_raw_parts_mut(ptr.as_ptr(), len) }
+    }
+
+    /// Get the memory type the buffer was allocated with.
+    #[must_use]
+    pub const fn memory_type(&self) -> MemoryType {
+        self.memory_type
+    }
+}
+
+impl Deref for PoolBox {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: the buffer is initialized.
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for PoolBox {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: the buffer is initialized.
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Debug for PoolBox {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolBox")
+            .field("ptr", &self.ptr)
+            .field("len", &self.len)
+            .field("memory_type", &self.memory_type)
+            .finish()
+    }
+}
+
+impl Drop for PoolBox {
+    fn drop(&mut self) {
+        if boot::are_boot_services_active() {
+            // Ignore errors returned by `free_pool` since we can't propagate
+            // them from `drop`.
+            let _ = unsafe { boot::free_pool(self.ptr) };
+        }
+    }
+}
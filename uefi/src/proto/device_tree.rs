@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Device tree fixup protocol.
+
+use crate::proto::unsafe_protocol;
+use crate::{Error, Result, Status, StatusExt};
+use uefi_raw::protocol::device_tree::DtFixupProtocol;
+
+pub use uefi_raw::protocol::device_tree::DtFixupFlags;
+
+/// Device tree fixup [`Protocol`].
+///
+/// Lets the firmware update a device tree, typically one created with
+/// [`FdtEditor`], before it is passed to the operating system. The firmware
+/// may add nodes, so the buffer should have some free space after the end of
+/// the device tree, and the total size in the header should include it.
+///
+/// This protocol is not part of the UEFI specification, but is implemented
+/// by U-Boot and EDK2 for Arm and RISC-V platforms.
+///
+/// [`Protocol`]: uefi::proto::Protocol
+/// [`FdtEditor`]: crate::table::fdt::FdtEditor
+#[derive(Debug)]
+#[repr(transparent)]
+#[unsafe_protocol(DtFixupProtocol::GUID)]
+pub struct DtFixup(DtFixupProtocol);
+
+impl DtFixup {
+    /// Apply the fixups selected by `flags` to the device tree in `fdt`.
+    ///
+    /// Use [`install`](Self::install) to also install the device tree as a
+    /// configuration table.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::BUFFER_TOO_SMALL`]: `fdt` is too small for the fixed-up
+    ///   device tree. The required size is returned in the error data.
+    /// * [`Status::INVALID_PARAMETER`]: `fdt` is not a valid device tree, or
+    ///   `flags` contains [`DtFixupFlags::INSTALL_TABLE`].
+    pub fn fixup(&mut self, fdt: &mut [u8], flags: DtFixupFlags) -> Result<(), Option<usize>> {
+        if flags.contains(DtFixupFlags::INSTALL_TABLE) {
+            return Err(Error::new(Status::INVALID_PARAMETER, None));
+        }
+        self.fixup_raw(fdt, flags)
+    }
+
+    /// Apply the fixups selected by `flags` to the device tree in `fdt`, and
+    /// install it as the device tree configuration table.
+    ///
+    /// The device tree must stay valid after it is installed, so `fdt` must
+    /// be a leaked allocation. Memory allocated with
+    /// [`MemoryType::ACPI_RECLAIM`] is preserved for the OS.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::BUFFER_TOO_SMALL`]: `fdt` is too small for the fixed-up
+    ///   device tree. The required size is returned in the error data.
+    /// * [`Status::INVALID_PARAMETER`]: `fdt` is not a valid device tree.
+    ///
+    /// [`MemoryType::ACPI_RECLAIM`]: crate::boot::MemoryType::ACPI_RECLAIM
+    pub fn install(
+        &mut self,
+        fdt: &'static mut [u8],
+        flags: DtFixupFlags,
+    ) -> Result<(), Option<usize>> {
+        self.fixup_raw(fdt, flags | DtFixupFlags::INSTALL_TABLE)
+    }
+
+    fn fixup_raw(&mut self, fdt: &mut [u8], flags: DtFixupFlags) -> Result<(), Option<usize>> {
+        let mut buffer_size = fdt.len();
+        unsafe {
+            (self.0.fixup)(
+                &mut self.0,
+                fdt.as_mut_ptr().cast(),
+                &mut buffer_size,
+                flags,
+            )
+        }
+        .to_result_with(
+            || (),
+            |status| {
+                if status == Status::BUFFER_TOO_SMALL {
+                    Some(buffer_size)
+                } else {
+                    None
+                }
+            },
+        )
+    }
+}
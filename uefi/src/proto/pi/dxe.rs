@@ -0,0 +1,546 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! DXE services.
+//!
+//! The DXE Services table is defined by the Platform Initialization (PI)
+//! specification and published by PI-based firmware, such as EDK2, in the
+//! configuration table. It provides the Global Coherency Domain (GCD)
+//! services, which manage the memory and I/O address spaces of the platform,
+//! and access to the DXE dispatcher.
+//!
+//! A driver that needs to access an MMIO region that is not yet described
+//! to the firmware can add it to the memory space and set its attributes:
+//!
+//! ```no_run
+//! use uefi::boot::MemoryAttribute;
+//! use uefi::proto::pi::dxe::{self, GcdMemoryType};
+//!
+//! # fn example() -> uefi::Result {
+//! let region = 0xfed0_0000..0xfed0_1000;
+//! let descriptor = dxe::memory_space_descriptor(region.start)?;
+//! if descriptor.memory_type == GcdMemoryType::NON_EXISTENT {
+//!     // SAFETY: the region is decoded by the HPET.
+//!     unsafe {
+//!         dxe::add_memory_space(
+//!             GcdMemoryType::MEMORY_MAPPED_IO,
+//!             region.clone(),
+//!             MemoryAttribute::UNCACHEABLE | MemoryAttribute::RUNTIME,
+//!         )?;
+//!     }
+//! }
+//! dxe::set_memory_space_attributes(region, MemoryAttribute::UNCACHEABLE)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! All functions return [`Status::NOT_FOUND`] if the firmware does not
+//! provide the DXE Services table.
+
+use crate::boot::{self, MemoryAttribute};
+use crate::data_types::PhysicalAddress;
+use crate::table::cfg::{ConfigTable, ConfigTableEntry};
+use crate::{Guid, Handle, Result, Status, StatusExt, system};
+use core::ffi::c_void;
+use core::mem::MaybeUninit;
+use core::ops::Range;
+use core::ptr;
+use uefi_raw::table::dxe::{
+    DxeServices, GcdAllocateType, GcdIoSpaceDescriptor, GcdMemorySpaceDescriptor,
+};
+
+#[cfg(feature = "alloc")]
+use {alloc::vec::Vec, core::ptr::NonNull, core::slice};
+
+pub use uefi_raw::table::dxe::{GcdIoType, GcdMemoryType};
+
+/// DXE Services table, found in the configuration table.
+struct DxeServicesTable(&'static DxeServices);
+
+impl ConfigTable for DxeServicesTable {
+    const GUID: Guid = ConfigTableEntry::DXE_SERVICES_GUID;
+    type Error = Status;
+    const NOT_FOUND: Status = Status::NOT_FOUND;
+
+    unsafe fn from_entry(
+        _guid: Guid,
+        address: *const c_void,
+    ) -> core::result::Result<Self, Status> {
+        // SAFETY: the firmware guarantees the table is valid while boot
+        // services are active.
+        let table = unsafe { &*address.cast::<DxeServices>() };
+        if table.header.signature != DxeServices::SIGNATURE {
+            return Err(Status::INCOMPATIBLE_VERSION);
+        }
+        Ok(Self(table))
+    }
+}
+
+/// Get the DXE Services table from the configuration table.
+fn dxe_services() -> Result<&'static DxeServices> {
+    Ok(system::find_config_table::<DxeServicesTable>()?.0)
+}
+
+const fn range_to_base_and_len(range: Range<PhysicalAddress>) -> (PhysicalAddress, u64) {
+    (range.start, range.end.saturating_sub(range.start))
+}
+
+/// How to search for a free region when allocating from the memory or I/O
+/// space.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GcdAllocation {
+    /// Any region, searching from the lowest address.
+    AnyBottomUp,
+    /// Any region, searching from the highest address.
+    AnyTopDown,
+    /// Any region that ends at or below the given address, searching from
+    /// the lowest address.
+    MaxAddressBottomUp(PhysicalAddress),
+    /// Any region that ends at or below the given address, searching from
+    /// the highest address.
+    MaxAddressTopDown(PhysicalAddress),
+    /// The region at the given address.
+    Address(PhysicalAddress),
+}
+
+impl GcdAllocation {
+    const fn to_raw(self) -> (GcdAllocateType, PhysicalAddress) {
+        match self {
+            Self::AnyBottomUp => (GcdAllocateType::ANY_SEARCH_BOTTOM_UP, 0),
+            Self::AnyTopDown => (GcdAllocateType::ANY_SEARCH_TOP_DOWN, 0),
+            Self::MaxAddressBottomUp(address) => {
+                (GcdAllocateType::MAX_ADDRESS_SEARCH_BOTTOM_UP, address)
+            }
+            Self::MaxAddressTopDown(address) => {
+                (GcdAllocateType::MAX_ADDRESS_SEARCH_TOP_DOWN, address)
+            }
+            Self::Address(address) => (GcdAllocateType::ADDRESS, address),
+        }
+    }
+}
+
+/// Descriptor of a region of the GCD memory space.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MemorySpaceDescriptor {
+    /// Address range of the region.
+    pub range: Range<PhysicalAddress>,
+    /// Attributes that the region supports.
+    pub capabilities: MemoryAttribute,
+    /// Attributes that are currently set on the region.
+    pub attributes: MemoryAttribute,
+    /// Type of the region.
+    pub memory_type: GcdMemoryType,
+    /// Image that allocated the region, or `None` if the region is free.
+    pub image_handle: Option<Handle>,
+    /// Device for which the region was allocated.
+    pub device_handle: Option<Handle>,
+}
+
+impl From<&GcdMemorySpaceDescriptor> for MemorySpaceDescriptor {
+    fn from(raw: &GcdMemorySpaceDescriptor) -> Self {
+        Self {
+            range: raw.base_address..raw.base_address.saturating_add(raw.length),
+            capabilities: raw.capabilities,
+            attributes: raw.attributes,
+            memory_type: raw.memory_type,
+            // SAFETY: the firmware guarantees the handles are valid.
+            image_handle: unsafe { Handle::from_ptr(raw.image_handle) },
+            device_handle: unsafe { Handle::from_ptr(raw.device_handle) },
+        }
+    }
+}
+
+/// Descriptor of a region of the GCD I/O space.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IoSpaceDescriptor {
+    /// Port range of the region.
+    pub range: Range<PhysicalAddress>,
+    /// Type of the region.
+    pub io_type: GcdIoType,
+    /// Image that allocated the region, or `None` if the region is free.
+    pub image_handle: Option<Handle>,
+    /// Device for which the region was allocated.
+    pub device_handle: Option<Handle>,
+}
+
+impl From<&GcdIoSpaceDescriptor> for IoSpaceDescriptor {
+    fn from(raw: &GcdIoSpaceDescriptor) -> Self {
+        Self {
+            range: raw.base_address..raw.base_address.saturating_add(raw.length),
+            io_type: raw.io_type,
+            // SAFETY: the firmware guarantees the handles are valid.
+            image_handle: unsafe { Handle::from_ptr(raw.image_handle) },
+            device_handle: unsafe { Handle::from_ptr(raw.device_handle) },
+        }
+    }
+}
+
+/// Add a region to the memory space, with the attributes in `capabilities`
+/// as the attributes it supports.
+///
+/// # Safety
+///
+/// `region` must be decoded as `memory_type` by the hardware. Adding
+/// [`GcdMemoryType::SYSTEM_MEMORY`] makes the region available to the memory
+/// allocation services.
+///
+/// # Errors
+///
+/// * [`Status::INVALID_PARAMETER`]: `memory_type` is invalid, or `region` is
+///   empty.
+/// * [`Status::ACCESS_DENIED`]: `region` overlaps a region that is not
+///   [`GcdMemoryType::NON_EXISTENT`].
+/// * [`Status::OUT_OF_RESOURCES`]: out of memory.
+/// * [`Status::UNSUPPORTED`]: `region` is outside the memory space.
+pub unsafe fn add_memory_space(
+    memory_type: GcdMemoryType,
+    region: Range<PhysicalAddress>,
+    capabilities: MemoryAttribute,
+) -> Result {
+    let dxe = dxe_services()?;
+    let (base_address, length) = range_to_base_and_len(region);
+    unsafe { (dxe.add_memory_space)(memory_type, base_address, length, capabilities) }.to_result()
+}
+
+/// Allocate `length` bytes of the memory space of type `memory_type`, for the
+/// current image and optionally for `device_handle`. The region is aligned
+/// to `1 << alignment_shift` bytes. Returns the address of the region.
+///
+/// # Errors
+///
+/// * [`Status::INVALID_PARAMETER`]: `memory_type` is invalid, or `length` is
+///   zero.
+/// * [`Status::NOT_FOUND`]: no region with the requested properties is free.
+/// * [`Status::OUT_OF_RESOURCES`]: out of memory.
+pub fn allocate_memory_space(
+    allocation: GcdAllocation,
+    memory_type: GcdMemoryType,
+    alignment_shift: usize,
+    length: u64,
+    device_handle: Option<Handle>,
+) -> Result<PhysicalAddress> {
+    let dxe = dxe_services()?;
+    let (allocate_type, mut address) = allocation.to_raw();
+    unsafe {
+        (dxe.allocate_memory_space)(
+            allocate_type,
+            memory_type,
+            alignment_shift,
+            length,
+            &mut address,
+            boot::image_handle().as_ptr(),
+            Handle::opt_to_ptr(device_handle),
+        )
+    }
+    .to_result_with_val(|| address)
+}
+
+/// Free a region of the memory space allocated with
+/// [`allocate_memory_space`].
+///
+/// # Safety
+///
+/// The region must not be used after it is freed.
+///
+/// # Errors
+///
+/// * [`Status::INVALID_PARAMETER`]: `region` is empty.
+/// * [`Status::NOT_FOUND`]: `region` was not allocated.
+/// * [`Status::UNSUPPORTED`]: `region` is outside the memory space.
+pub unsafe fn free_memory_space(region: Range<PhysicalAddress>) -> Result {
+    let dxe = dxe_services()?;
+    let (base_address, length) = range_to_base_and_len(region);
+    unsafe { (dxe.free_memory_space)(base_address, length) }.to_result()
+}
+
+/// Remove a region from the memory space, making it
+/// [`GcdMemoryType::NON_EXISTENT`].
+///
+/// # Safety
+///
+/// The region must not be used after it is removed.
+///
+/// # Errors
+///
+/// * [`Status::INVALID_PARAMETER`]: `region` is empty.
+/// * [`Status::NOT_FOUND`]: part of `region` is not in the memory space.
+/// * [`Status::ACCESS_DENIED`]: part of `region` is allocated.
+/// * [`Status::UNSUPPORTED`]: `region` is outside the memory space.
+pub unsafe fn remove_memory_space(region: Range<PhysicalAddress>) -> Result {
+    let dxe = dxe_services()?;
+    let (base_address, length) = range_to_base_and_len(region);
+    unsafe { (dxe.remove_memory_space)(base_address, length) }.to_result()
+}
+
+/// Get the descriptor of the region of the memory space that contains
+/// `address`.
+///
+/// # Errors
+///
+/// * [`Status::NOT_FOUND`]: `address` is outside the memory space.
+pub fn memory_space_descriptor(address: PhysicalAddress) -> Result<MemorySpaceDescriptor> {
+    let dxe = dxe_services()?;
+    let mut descriptor = MaybeUninit::<GcdMemorySpaceDescriptor>::uninit();
+    unsafe { (dxe.get_memory_space_descriptor)(address, descriptor.as_mut_ptr()) }
+        .to_result_with_val(|| {
+            // SAFETY: the descriptor was initialized by the firmware.
+            MemorySpaceDescriptor::from(unsafe { descriptor.assume_init_ref() })
+        })
+}
+
+/// Set the attributes of a region of the memory space, such as its cache
+/// type or its protection attributes.
+///
+/// # Errors
+///
+/// * [`Status::INVALID_PARAMETER`]: `region` is empty.
+/// * [`Status::UNSUPPORTED`]: the region does not support `attributes`.
+/// * [`Status::ACCESS_DENIED`]: the attributes can't be set on the region.
+pub fn set_memory_space_attributes(
+    region: Range<PhysicalAddress>,
+    attributes: MemoryAttribute,
+) -> Result {
+    let dxe = dxe_services()?;
+    let (base_address, length) = range_to_base_and_len(region);
+    unsafe { (dxe.set_memory_space_attributes)(base_address, length, attributes) }.to_result()
+}
+
+/// Set the attributes that a region of the memory space supports.
+///
+/// # Errors
+///
+/// * [`Status::INVALID_PARAMETER`]: `region` is empty.
+/// * [`Status::UNSUPPORTED`]: the capabilities can't be changed, or the
+///   firmware implements a version of the PI specification older than 1.3.
+/// * [`Status::ACCESS_DENIED`]: the capabilities can't be set on the region.
+pub fn set_memory_space_capabilities(
+    region: Range<PhysicalAddress>,
+    capabilities: MemoryAttribute,
+) -> Result {
+    let dxe = dxe_services()?;
+    if (dxe.header.size as usize) < size_of::<DxeServices>() {
+        return Err(Status::UNSUPPORTED.into());
+    }
+    let (base_address, length) = range_to_base_and_len(region);
+    unsafe { (dxe.set_memory_space_capabilities)(base_address, length, capabilities) }.to_result()
+}
+
+/// Get the descriptors of all the regions of the memory space, sorted by
+/// address.
+///
+/// # Errors
+///
+/// * [`Status::OUT_OF_RESOURCES`]: out of memory.
+#[cfg(feature = "alloc")]
+pub fn memory_space_map() -> Result<Vec<MemorySpaceDescriptor>> {
+    let dxe = dxe_services()?;
+    let mut count = 0;
+    let mut map = ptr::null_mut();
+    unsafe { (dxe.get_memory_space_map)(&mut count, &mut map) }.to_result()?;
+    // SAFETY: the firmware returned a pool allocation with `count`
+    // descriptors.
+    unsafe { collect_pool_array(map, count) }
+}
+
+/// Add a region to the I/O space.
+///
+/// # Safety
+///
+/// `region` must be decoded as `io_type` by the hardware.
+///
+/// # Errors
+///
+/// * [`Status::INVALID_PARAMETER`]: `io_type` is invalid, or `region` is
+///   empty.
+/// * [`Status::ACCESS_DENIED`]: `region` overlaps a region that is not
+///   [`GcdIoType::NON_EXISTENT`].
+/// * [`Status::OUT_OF_RESOURCES`]: out of memory.
+/// * [`Status::UNSUPPORTED`]: `region` is outside the I/O space.
+pub unsafe fn add_io_space(io_type: GcdIoType, region: Range<PhysicalAddress>) -> Result {
+    let dxe = dxe_services()?;
+    let (base_address, length) = range_to_base_and_len(region);
+    unsafe { (dxe.add_io_space)(io_type, base_address, length) }.to_result()
+}
+
+/// Allocate `length` ports of the I/O space of type `io_type`, for the
+/// current image and optionally for `device_handle`. The region is aligned
+/// to `1 << alignment_shift` ports. Returns the first port of the region.
+///
+/// # Errors
+///
+/// * [`Status::INVALID_PARAMETER`]: `io_type` is invalid, or `length` is
+///   zero.
+/// * [`Status::NOT_FOUND`]: no region with the requested properties is free.
+/// * [`Status::OUT_OF_RESOURCES`]: out of memory.
+pub fn allocate_io_space(
+    allocation: GcdAllocation,
+    io_type: GcdIoType,
+    alignment_shift: usize,
+    length: u64,
+    device_handle: Option<Handle>,
+) -> Result<PhysicalAddress> {
+    let dxe = dxe_services()?;
+    let (allocate_type, mut address) = allocation.to_raw();
+    unsafe {
+        (dxe.allocate_io_space)(
+            allocate_type,
+            io_type,
+            alignment_shift,
+            length,
+            &mut address,
+            boot::image_handle().as_ptr(),
+            Handle::opt_to_ptr(device_handle),
+        )
+    }
+    .to_result_with_val(|| address)
+}
+
+/// Free a region of the I/O space allocated with [`allocate_io_space`].
+///
+/// # Safety
+///
+/// The ports must not be used after they are freed.
+///
+/// # Errors
+///
+/// * [`Status::INVALID_PARAMETER`]: `region` is empty.
+/// * [`Status::NOT_FOUND`]: `region` was not allocated.
+/// * [`Status::UNSUPPORTED`]: `region` is outside the I/O space.
+pub unsafe fn free_io_space(region: Range<PhysicalAddress>) -> Result {
+    let dxe = dxe_services()?;
+    let (base_address, length) = range_to_base_and_len(region);
+    unsafe { (dxe.free_io_space)(base_address, length) }.to_result()
+}
+
+/// Remove a region from the I/O space, making it
+/// [`GcdIoType::NON_EXISTENT`].
+///
+/// # Safety
+///
+/// The ports must not be used after they are removed.
+///
+/// # Errors
+///
+/// * [`Status::INVALID_PARAMETER`]: `region` is empty.
+/// * [`Status::NOT_FOUND`]: part of `region` is not in the I/O space.
+/// * [`Status::ACCESS_DENIED`]: part of `region` is allocated.
+/// * [`Status::UNSUPPORTED`]: `region` is outside the I/O space.
+pub unsafe fn remove_io_space(region: Range<PhysicalAddress>) -> Result {
+    let dxe = dxe_services()?;
+    let (base_address, length) = range_to_base_and_len(region);
+    unsafe { (dxe.remove_io_space)(base_address, length) }.to_result()
+}
+
+/// Get the descriptor of the region of the I/O space that contains `port`.
+///
+/// # Errors
+///
+/// * [`Status::NOT_FOUND`]: `port` is outside the I/O space.
+pub fn io_space_descriptor(port: PhysicalAddress) -> Result<IoSpaceDescriptor> {
+    let dxe = dxe_services()?;
+    let mut descriptor = MaybeUninit::<GcdIoSpaceDescriptor>::uninit();
+    unsafe { (dxe.get_io_space_descriptor)(port, descriptor.as_mut_ptr()) }.to_result_with_val(
+        || {
+            // SAFETY: the descriptor was initialized by the firmware.
+            IoSpaceDescriptor::from(unsafe { descriptor.assume_init_ref() })
+        },
+    )
+}
+
+/// Get the descriptors of all the regions of the I/O space, sorted by port.
+///
+/// # Errors
+///
+/// * [`Status::OUT_OF_RESOURCES`]: out of memory.
+#[cfg(feature = "alloc")]
+pub fn io_space_map() -> Result<Vec<IoSpaceDescriptor>> {
+    let dxe = dxe_services()?;
+    let mut count = 0;
+    let mut map = ptr::null_mut();
+    unsafe { (dxe.get_io_space_map)(&mut count, &mut map) }.to_result()?;
+    // SAFETY: the firmware returned a pool allocation with `count`
+    // descriptors.
+    unsafe { collect_pool_array(map, count) }
+}
+
+/// Convert the `count` elements of the pool allocation at `ptr`, and free
+/// the allocation.
+///
+/// # Safety
+///
+/// `ptr` must be a pool allocation of `count` initialized elements.
+#[cfg(feature = "alloc")]
+unsafe fn collect_pool_array<T, U>(ptr: *mut T, count: usize) -> Result<Vec<U>>
+where
+    U: for<'a> From<&'a T>,
+{
+    let Some(ptr) = NonNull::new(ptr) else {
+        return Ok(Vec::new());
+    };
+    // SAFETY: guaranteed by the caller.
+    let items = unsafe { slice::from_raw_parts(ptr.as_ptr(), count) };
+    let items = items.iter().map(U::from).collect();
+    // SAFETY: the array is no longer used.
+    unsafe { boot::free_pool(ptr.cast()) }?;
+    Ok(items)
+}
+
+/// Run the DXE dispatcher, which loads and starts the drivers of the
+/// firmware volumes whose dependencies are satisfied.
+///
+/// # Errors
+///
+/// * [`Status::NOT_FOUND`]: no driver was dispatched.
+/// * [`Status::ALREADY_STARTED`]: the dispatcher is already running.
+pub fn dispatch() -> Result {
+    let dxe = dxe_services()?;
+    unsafe { (dxe.dispatch)() }.to_result()
+}
+
+/// Clear the schedule on request flag of the driver `file_name` in the
+/// firmware volume `firmware_volume`, so that it can be dispatched.
+///
+/// # Errors
+///
+/// * [`Status::NOT_FOUND`]: the driver is not in the firmware volume, or
+///   its flag is not set.
+pub fn schedule(firmware_volume: Handle, file_name: &Guid) -> Result {
+    let dxe = dxe_services()?;
+    unsafe { (dxe.schedule)(firmware_volume.as_ptr(), file_name) }.to_result()
+}
+
+/// Promote the untrusted driver `file_name` in the firmware volume
+/// `firmware_volume` to trusted, so that it can be dispatched.
+///
+/// # Errors
+///
+/// * [`Status::NOT_FOUND`]: the driver is not in the firmware volume, or it
+///   is not untrusted.
+pub fn trust(firmware_volume: Handle, file_name: &Guid) -> Result {
+    let dxe = dxe_services()?;
+    unsafe { (dxe.trust)(firmware_volume.as_ptr(), file_name) }.to_result()
+}
+
+/// Create a handle for the firmware volume in `firmware_volume`, so that
+/// the dispatcher can load its drivers. Returns the new handle.
+///
+/// The firmware volume is used in place, so it must never be freed.
+///
+/// # Errors
+///
+/// * [`Status::VOLUME_CORRUPTED`]: `firmware_volume` is not a valid
+///   firmware volume.
+/// * [`Status::OUT_OF_RESOURCES`]: out of memory.
+pub fn process_firmware_volume(firmware_volume: &'static [u8]) -> Result<Handle> {
+    let dxe = dxe_services()?;
+    let mut handle = ptr::null_mut();
+    unsafe {
+        (dxe.process_firmware_volume)(
+            firmware_volume.as_ptr().cast(),
+            firmware_volume.len(),
+            &mut handle,
+        )
+    }
+    .to_result()?;
+    // SAFETY: the firmware returned a valid handle.
+    unsafe { Handle::from_ptr(handle) }.ok_or_else(|| Status::ABORTED.into())
+}
@@ -0,0 +1,561 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Reader for the Hand-Off Block (HOB) list.
+//!
+//! The PEI phase of the firmware describes the system to the DXE phase with
+//! a list of HOBs, which the firmware publishes in the configuration table.
+//! The list describes the memory and I/O resources of the platform, the
+//! memory allocated before DXE, the firmware volumes, and vendor-specific
+//! data in GUID extension HOBs.
+//!
+//! ```no_run
+//! use uefi::proto::pi::hob::{DecodedHob, HobList};
+//!
+//! # fn example() -> Result<(), uefi::proto::pi::hob::HobError> {
+//! let hobs = HobList::find()?;
+//! log::info!("boot mode: {:?}", hobs.handoff().boot_mode);
+//! for hob in hobs.iter() {
+//!     if let DecodedHob::ResourceDescriptor(resource) = hob.decode() {
+//!         log::info!(
+//!             "{:?} at {:#x}, {:#x} bytes",
+//!             resource.resource_type,
+//!             resource.physical_start,
+//!             resource.resource_length
+//!         );
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! See the PI specification, volume 3.
+
+use crate::table::cfg::{ConfigTable, ConfigTableEntry};
+use crate::{Guid, system};
+use core::ffi::c_void;
+use core::fmt::{self, Display, Formatter};
+use core::iter::FusedIterator;
+use core::slice;
+use uefi_raw::hob::HobHeader;
+
+pub use uefi_raw::hob::{
+    BootMode, CpuHob, FirmwareVolume2Hob, FirmwareVolume3Hob, FirmwareVolumeHob, HandoffInfoTable,
+    HobType, MemoryAllocationHeader, MemoryAllocationHob, MemoryAllocationModuleHob,
+    ResourceAttribute, ResourceDescriptorHob, ResourceType, UefiCapsuleHob,
+};
+
+const HEADER_SIZE: usize = size_of::<HobHeader>();
+
+/// Errors that may happen when reading the [`HobList`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HobError {
+    /// The system table has no HOB list.
+    NotFound,
+    /// The buffer ends before the end of the HOB list.
+    InvalidSize,
+    /// The list does not start with a handoff information table.
+    MissingHandoff,
+    /// A HOB of the given type is too short for its type.
+    InvalidLength(HobType),
+}
+
+impl Display for HobError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "HOB list not found"),
+            Self::InvalidSize => write!(f, "HOB list is truncated"),
+            Self::MissingHandoff => write!(f, "HOB list does not start with a PHIT HOB"),
+            Self::InvalidLength(hob_type) => write!(f, "{hob_type:?} HOB is too short"),
+        }
+    }
+}
+
+impl core::error::Error for HobError {}
+
+/// Get the minimum length of a HOB of type `hob_type`.
+const fn min_len(hob_type: HobType) -> usize {
+    match hob_type {
+        HobType::HANDOFF => size_of::<HandoffInfoTable>(),
+        HobType::MEMORY_ALLOCATION => size_of::<MemoryAllocationHob>(),
+        HobType::RESOURCE_DESCRIPTOR => size_of::<ResourceDescriptorHob>(),
+        HobType::GUID_EXTENSION => HEADER_SIZE + size_of::<Guid>(),
+        HobType::FV => size_of::<FirmwareVolumeHob>(),
+        HobType::CPU => size_of::<CpuHob>(),
+        HobType::FV2 => size_of::<FirmwareVolume2Hob>(),
+        HobType::UEFI_CAPSULE => size_of::<UefiCapsuleHob>(),
+        HobType::FV3 => size_of::<FirmwareVolume3Hob>(),
+        _ => HEADER_SIZE,
+    }
+}
+
+/// Read a `T` from the start of `bytes`.
+///
+/// `T` must be one of the HOB types of `uefi_raw`, which are valid for all
+/// bit patterns.
+fn read<T: Copy>(bytes: &[u8]) -> T {
+    assert!(bytes.len() >= size_of::<T>());
+    // SAFETY: `bytes` is large enough, and `T` is valid for all bit patterns.
+    unsafe { bytes.as_ptr().cast::<T>().read_unaligned() }
+}
+
+/// The list of HOBs.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone, Copy, Debug)]
+pub struct HobList<'a> {
+    /// HOBs up to and including the end of list HOB.
+    bytes: &'a [u8],
+}
+
+impl<'a> HobList<'a> {
+    /// Locate the HOB list in the system configuration table.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HobError::NotFound`] if the firmware does not provide the
+    /// list, or any other error if the list is invalid.
+    pub fn find() -> Result<HobList<'static>, HobError> {
+        system::find_config_table()
+    }
+
+    /// Read the HOB list at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a HOB list that is valid for reads up to the end
+    /// of its end of list HOB, for lifetime `'a`.
+    ///
+    /// # Errors
+    ///
+    /// See [`from_bytes`](Self::from_bytes).
+    pub unsafe fn from_ptr(ptr: *const u8) -> Result<Self, HobError> {
+        let mut size = 0;
+        loop {
+            // SAFETY: the caller guarantees the HOBs up to the end of the
+            // list are readable.
+            let header = unsafe { ptr.add(size).cast::<HobHeader>().read_unaligned() };
+            let len = usize::from(header.hob_length);
+            if header.hob_type == HobType::END_OF_HOB_LIST || len < HEADER_SIZE {
+                // Let `from_bytes` report malformed HOBs.
+                size += len.max(HEADER_SIZE);
+                break;
+            }
+            size += len;
+        }
+        // SAFETY: the caller guarantees the whole list is readable.
+        Self::from_bytes(unsafe { slice::from_raw_parts(ptr, size) })
+    }
+
+    /// Parse the HOB list in `bytes`. Bytes after the end of list HOB are
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// * [`HobError::InvalidSize`]: `bytes` ends before the end of the list.
+    /// * [`HobError::MissingHandoff`]: the first HOB is not a handoff
+    ///   information table.
+    /// * [`HobError::InvalidLength`]: a HOB is too short for its type.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, HobError> {
+        let mut offset = 0;
+        loop {
+            let header = bytes
+                .get(offset..offset + HEADER_SIZE)
+                .ok_or(HobError::InvalidSize)?;
+            let header: HobHeader = read(header);
+            if offset == 0 && header.hob_type != HobType::HANDOFF {
+                return Err(HobError::MissingHandoff);
+            }
+            let len = usize::from(header.hob_length);
+            if len < min_len(header.hob_type) {
+                return Err(HobError::InvalidLength(header.hob_type));
+            }
+            offset += len;
+            if offset > bytes.len() {
+                return Err(HobError::InvalidSize);
+            }
+            if header.hob_type == HobType::END_OF_HOB_LIST {
+                return Ok(Self {
+                    bytes: &bytes[..offset],
+                });
+            }
+        }
+    }
+
+    /// Get the bytes of the list, up to and including the end of list HOB.
+    #[must_use]
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Get the phase handoff information table (PHIT), which is the first
+    /// HOB of the list.
+    #[must_use]
+    pub fn handoff(&self) -> HandoffInfoTable {
+        read(self.bytes)
+    }
+
+    /// Get an iterator over the HOBs. Unused HOBs and the end of list HOB
+    /// are skipped.
+    #[must_use]
+    pub const fn iter(&self) -> Hobs<'a> {
+        Hobs { rest: self.bytes }
+    }
+
+    /// Get an iterator over the resource descriptor HOBs.
+    pub fn resource_descriptors(&self) -> impl Iterator<Item = ResourceDescriptorHob> + use<'a> {
+        self.iter().filter_map(|hob| match hob.decode() {
+            DecodedHob::ResourceDescriptor(resource) => Some(resource),
+            _ => None,
+        })
+    }
+
+    /// Get an iterator over the descriptors of the memory allocation HOBs,
+    /// including module allocations.
+    pub fn memory_allocations(&self) -> impl Iterator<Item = MemoryAllocationHeader> + use<'a> {
+        self.iter().filter_map(|hob| match hob.decode() {
+            DecodedHob::MemoryAllocation(allocation) => Some(allocation.alloc_descriptor),
+            DecodedHob::MemoryAllocationModule(allocation) => {
+                Some(allocation.memory_allocation_header)
+            }
+            _ => None,
+        })
+    }
+
+    /// Get an iterator over the data of the GUID extension HOBs called
+    /// `name`.
+    pub fn guid_extensions(&self, name: Guid) -> impl Iterator<Item = &'a [u8]> + use<'a> {
+        self.iter().filter_map(move |hob| match hob.decode() {
+            DecodedHob::GuidExtension(extension) if extension.name == name => Some(extension.data),
+            _ => None,
+        })
+    }
+
+    /// Get the data of the first GUID extension HOB called `name`.
+    #[must_use]
+    pub fn find_guid_extension(&self, name: Guid) -> Option<&'a [u8]> {
+        self.guid_extensions(name).next()
+    }
+}
+
+impl ConfigTable for HobList<'static> {
+    const GUID: Guid = ConfigTableEntry::HAND_OFF_BLOCK_LIST_GUID;
+    type Error = HobError;
+    const NOT_FOUND: HobError = HobError::NotFound;
+
+    unsafe fn from_entry(_guid: Guid, address: *const c_void) -> Result<Self, HobError> {
+        // SAFETY: the firmware guarantees the list is valid.
+        unsafe { Self::from_ptr(address.cast()) }
+    }
+}
+
+impl<'a> IntoIterator for &HobList<'a> {
+    type Item = Hob<'a>;
+    type IntoIter = Hobs<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the HOBs of a [`HobList`].
+#[derive(Clone, Debug)]
+pub struct Hobs<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for Hobs<'a> {
+    type Item = Hob<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // The list was validated on creation.
+            let header: HobHeader = read(self.rest.get(..HEADER_SIZE)?);
+            if header.hob_type == HobType::END_OF_HOB_LIST {
+                self.rest = &[];
+                return None;
+            }
+            let (bytes, rest) = self.rest.split_at(usize::from(header.hob_length));
+            self.rest = rest;
+            if header.hob_type != HobType::UNUSED {
+                return Some(Hob { bytes });
+            }
+        }
+    }
+}
+
+impl FusedIterator for Hobs<'_> {}
+
+/// A HOB of any type.
+#[derive(Clone, Copy, Debug)]
+pub struct Hob<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Hob<'a> {
+    /// Get the type of the HOB.
+    #[must_use]
+    pub fn hob_type(&self) -> HobType {
+        read::<HobHeader>(self.bytes).hob_type
+    }
+
+    /// Get the bytes of the HOB, including the header.
+    #[must_use]
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Get the bytes of the HOB after the header.
+    #[must_use]
+    pub fn data(&self) -> &'a [u8] {
+        &self.bytes[HEADER_SIZE..]
+    }
+
+    /// Decode the HOB according to its type.
+    #[must_use]
+    pub fn decode(&self) -> DecodedHob<'a> {
+        let bytes = self.bytes;
+        match self.hob_type() {
+            HobType::HANDOFF => DecodedHob::Handoff(read(bytes)),
+            HobType::MEMORY_ALLOCATION => {
+                let allocation: MemoryAllocationHob = read(bytes);
+                if allocation.alloc_descriptor.name == MemoryAllocationHob::MODULE_GUID
+                    && bytes.len() >= size_of::<MemoryAllocationModuleHob>()
+                {
+                    DecodedHob::MemoryAllocationModule(read(bytes))
+                } else {
+                    DecodedHob::MemoryAllocation(allocation)
+                }
+            }
+            HobType::RESOURCE_DESCRIPTOR => DecodedHob::ResourceDescriptor(read(bytes)),
+            HobType::GUID_EXTENSION => {
+                let (name, data) = self.data().split_at(size_of::<Guid>());
+                DecodedHob::GuidExtension(GuidExtension {
+                    name: read(name),
+                    data,
+                })
+            }
+            HobType::FV => DecodedHob::FirmwareVolume(read(bytes)),
+            HobType::FV2 => DecodedHob::FirmwareVolume2(read(bytes)),
+            HobType::FV3 => DecodedHob::FirmwareVolume3(read(bytes)),
+            HobType::CPU => DecodedHob::Cpu(read(bytes)),
+            HobType::UEFI_CAPSULE => DecodedHob::UefiCapsule(read(bytes)),
+            _ => DecodedHob::Other(*self),
+        }
+    }
+}
+
+/// A GUID extension HOB, which holds data defined by the owner of its
+/// GUID.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GuidExtension<'a> {
+    /// GUID that identifies the format of the data.
+    pub name: Guid,
+    /// Data of the HOB, which may include padding at the end.
+    pub data: &'a [u8],
+}
+
+/// A [`Hob`] decoded according to its type.
+#[derive(Clone, Copy, Debug)]
+pub enum DecodedHob<'a> {
+    /// Phase handoff information table (PHIT).
+    Handoff(HandoffInfoTable),
+    /// Memory allocation.
+    MemoryAllocation(MemoryAllocationHob),
+    /// Memory allocation of a module.
+    MemoryAllocationModule(MemoryAllocationModuleHob),
+    /// Resource descriptor.
+    ResourceDescriptor(ResourceDescriptorHob),
+    /// GUID extension.
+    GuidExtension(GuidExtension<'a>),
+    /// Firmware volume.
+    FirmwareVolume(FirmwareVolumeHob),
+    /// Firmware volume with name information.
+    FirmwareVolume2(FirmwareVolume2Hob),
+    /// Firmware volume with authentication information.
+    FirmwareVolume3(FirmwareVolume3Hob),
+    /// CPU.
+    Cpu(CpuHob),
+    /// UEFI capsule.
+    UefiCapsule(UefiCapsuleHob),
+    /// HOB of another type.
+    Other(Hob<'a>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guid;
+    use alloc::vec::Vec;
+    use uefi_raw::table::boot::MemoryType;
+
+    const TEST_GUID: Guid = guid!("a0b1c2d3-e4f5-4607-8819-2a3b4c5d6e7f");
+
+    fn push_hob(list: &mut Vec<u8>, hob_type: HobType, data: &[u8]) {
+        let len = (HEADER_SIZE + data.len()).next_multiple_of(8);
+        list.extend_from_slice(&hob_type.0.to_le_bytes());
+        list.extend_from_slice(&(len as u16).to_le_bytes());
+        list.extend_from_slice(&[0; 4]);
+        list.extend_from_slice(data);
+        list.resize(list.len().next_multiple_of(8), 0);
+    }
+
+    fn build_hob_list() -> Vec<u8> {
+        let mut list = Vec::new();
+
+        let mut phit = Vec::new();
+        phit.extend_from_slice(&HandoffInfoTable::VERSION.to_le_bytes());
+        phit.extend_from_slice(&BootMode::S3_RESUME.0.to_le_bytes());
+        for address in [
+            0x8000_0000u64,
+            0x100_0000,
+            0x7f00_0000,
+            0x200_0000,
+            0x7e00_0000,
+        ] {
+            phit.extend_from_slice(&address.to_le_bytes());
+        }
+        push_hob(&mut list, HobType::HANDOFF, &phit);
+
+        let mut resource = Vec::from(TEST_GUID.to_bytes());
+        resource.extend_from_slice(&ResourceType::SYSTEM_MEMORY.0.to_le_bytes());
+        resource.extend_from_slice(
+            &(ResourceAttribute::PRESENT | ResourceAttribute::TESTED)
+                .bits()
+                .to_le_bytes(),
+        );
+        resource.extend_from_slice(&0x100_0000u64.to_le_bytes());
+        resource.extend_from_slice(&0x7f00_0000u64.to_le_bytes());
+        push_hob(&mut list, HobType::RESOURCE_DESCRIPTOR, &resource);
+
+        let allocation = |name: Guid, base: u64| {
+            let mut data = Vec::from(name.to_bytes());
+            data.extend_from_slice(&base.to_le_bytes());
+            data.extend_from_slice(&0x1000u64.to_le_bytes());
+            data.extend_from_slice(&MemoryType::BOOT_SERVICES_DATA.0.to_le_bytes());
+            data.extend_from_slice(&[0; 4]);
+            data
+        };
+        push_hob(
+            &mut list,
+            HobType::MEMORY_ALLOCATION,
+            &allocation(MemoryAllocationHob::STACK_GUID, 0x7e00_0000),
+        );
+        let mut module = allocation(MemoryAllocationHob::MODULE_GUID, 0x7e10_0000);
+        module.extend_from_slice(&TEST_GUID.to_bytes());
+        module.extend_from_slice(&0x7e10_0400u64.to_le_bytes());
+        push_hob(&mut list, HobType::MEMORY_ALLOCATION, &module);
+
+        push_hob(&mut list, HobType::UNUSED, &[0; 8]);
+
+        let mut extension = Vec::from(TEST_GUID.to_bytes());
+        extension.extend_from_slice(b"payload");
+        push_hob(&mut list, HobType::GUID_EXTENSION, &extension);
+
+        let mut fv = Vec::new();
+        fv.extend_from_slice(&0xffc0_0000u64.to_le_bytes());
+        fv.extend_from_slice(&0x40_0000u64.to_le_bytes());
+        push_hob(&mut list, HobType::FV, &fv);
+
+        push_hob(&mut list, HobType::CPU, &[39, 16, 0, 0, 0, 0, 0, 0]);
+        push_hob(&mut list, HobType::END_OF_HOB_LIST, &[]);
+        list
+    }
+
+    #[test]
+    fn test_hob_list() {
+        let bytes = build_hob_list();
+        let mut with_trailing = bytes.clone();
+        with_trailing.extend_from_slice(&[0xff; 16]);
+        let hobs = HobList::from_bytes(&with_trailing).unwrap();
+        assert_eq!(hobs.as_bytes(), bytes);
+        // SAFETY: `bytes` is a valid HOB list.
+        let hobs = unsafe { HobList::from_ptr(bytes.as_ptr()) }.unwrap();
+        assert_eq!(hobs.as_bytes(), bytes);
+
+        let phit = hobs.handoff();
+        assert_eq!(phit.version, HandoffInfoTable::VERSION);
+        assert_eq!(phit.boot_mode, BootMode::S3_RESUME);
+        assert_eq!(phit.memory_top, 0x8000_0000);
+        assert_eq!(phit.end_of_hob_list, 0x7e00_0000);
+
+        // The unused and end of list HOBs are skipped.
+        let types: Vec<_> = hobs.iter().map(|hob| hob.hob_type()).collect();
+        assert_eq!(
+            types,
+            [
+                HobType::HANDOFF,
+                HobType::RESOURCE_DESCRIPTOR,
+                HobType::MEMORY_ALLOCATION,
+                HobType::MEMORY_ALLOCATION,
+                HobType::GUID_EXTENSION,
+                HobType::FV,
+                HobType::CPU,
+            ]
+        );
+
+        let resources: Vec<_> = hobs.resource_descriptors().collect();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].owner, TEST_GUID);
+        assert_eq!(resources[0].resource_type, ResourceType::SYSTEM_MEMORY);
+        assert_eq!(
+            resources[0].resource_attribute,
+            ResourceAttribute::PRESENT | ResourceAttribute::TESTED
+        );
+        assert_eq!(resources[0].physical_start, 0x100_0000);
+        assert_eq!(resources[0].resource_length, 0x7f00_0000);
+
+        let allocations: Vec<_> = hobs.memory_allocations().collect();
+        assert_eq!(allocations.len(), 2);
+        assert_eq!(allocations[0].name, MemoryAllocationHob::STACK_GUID);
+        assert_eq!(allocations[1].memory_base_address, 0x7e10_0000);
+        assert_eq!(allocations[1].memory_type, MemoryType::BOOT_SERVICES_DATA);
+        let module = hobs
+            .iter()
+            .find_map(|hob| match hob.decode() {
+                DecodedHob::MemoryAllocationModule(module) => Some(module),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(module.module_name, TEST_GUID);
+        assert_eq!(module.entry_point, 0x7e10_0400);
+
+        // The data includes the padding to 8 bytes.
+        assert_eq!(hobs.find_guid_extension(TEST_GUID), Some(&b"payload\0"[..]));
+        assert_eq!(hobs.find_guid_extension(Guid::ZERO), None);
+
+        for hob in &hobs {
+            match hob.decode() {
+                DecodedHob::FirmwareVolume(fv) => {
+                    assert_eq!(fv.base_address, 0xffc0_0000);
+                    assert_eq!(fv.length, 0x40_0000);
+                }
+                DecodedHob::Cpu(cpu) => {
+                    assert_eq!(cpu.size_of_memory_space, 39);
+                    assert_eq!(cpu.size_of_io_space, 16);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_hob_list_errors() {
+        let bytes = build_hob_list();
+        assert_eq!(
+            HobList::from_bytes(&bytes[..bytes.len() - 8]).unwrap_err(),
+            HobError::InvalidSize
+        );
+        assert_eq!(
+            HobList::from_bytes(&bytes[56..]).unwrap_err(),
+            HobError::MissingHandoff
+        );
+
+        // A resource descriptor that is too short.
+        let mut bytes = bytes.clone();
+        bytes[56 + 2] = 40;
+        assert_eq!(
+            HobList::from_bytes(&bytes).unwrap_err(),
+            HobError::InvalidLength(HobType::RESOURCE_DESCRIPTOR)
+        );
+    }
+}
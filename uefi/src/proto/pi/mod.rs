@@ -5,4 +5,6 @@
 //! Contains protocols defined in UEFI's
 //! Platform Initialization (PI) Specification.
 
+pub mod dxe;
+pub mod hob;
 pub mod mp;
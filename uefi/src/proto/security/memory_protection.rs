@@ -1,21 +1,41 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use crate::boot::{self, PAGE_SIZE, ScopedProtocol};
 use crate::data_types::PhysicalAddress;
 use crate::mem::memory_map::MemoryAttribute;
 use crate::proto::unsafe_protocol;
-use crate::{Result, StatusExt};
+use crate::{Result, Status, StatusExt};
 use core::ops::Range;
 use uefi_raw::protocol::memory_protection::MemoryAttributeProtocol;
 
-#[cfg(doc)]
-use crate::Status;
-
 /// Memory Attribute [`Protocol`] for Memory Protection.
 ///
 /// Protocol for getting and setting memory protection attributes.
 ///
 /// Corresponds to the C type `EFI_MEMORY_ATTRIBUTE_PROTOCOL`.
 ///
+/// Besides the raw attribute functions, this provides guards that change the
+/// attributes of a page range and restore the previous attributes when
+/// dropped. This makes it easy to follow W^X (write xor execute), for example
+/// when relocating code:
+///
+/// ```no_run
+/// use uefi::proto::security::MemoryProtection;
+/// # fn relocate(_: u64) {}
+///
+/// # fn example(code: core::ops::Range<u64>) -> uefi::Result {
+/// let protection = MemoryProtection::open()?;
+/// {
+///     let _writable = protection.make_writable(code.clone())?;
+///     relocate(code.start);
+/// } // The previous attributes are restored here.
+///
+/// // Keep the code executable after the guard is gone.
+/// protection.make_executable(code)?.keep();
+/// # Ok(())
+/// # }
+/// ```
+///
 /// [`Protocol`]: uefi::proto::Protocol
 #[repr(transparent)]
 #[derive(Debug)]
@@ -23,6 +43,25 @@ use crate::Status;
 pub struct MemoryProtection(MemoryAttributeProtocol);
 
 impl MemoryProtection {
+    /// Open the protocol on the first handle that supports it.
+    ///
+    /// The protocol was added in UEFI 2.10 and is not provided by older
+    /// firmware. Such firmware typically maps all memory as readable,
+    /// writable, and executable, so callers may skip changing attributes if
+    /// this returns [`Status::UNSUPPORTED`].
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::UNSUPPORTED`]: the firmware does not provide the protocol.
+    /// * [`Status::ACCESS_DENIED`]: the protocol is already opened exclusively.
+    pub fn open() -> Result<ScopedProtocol<Self>> {
+        let handle = boot::get_handle_for_protocol::<Self>().map_err(|err| match err.status() {
+            Status::NOT_FOUND => Status::UNSUPPORTED.into(),
+            _ => err,
+        })?;
+        boot::open_protocol_exclusive(handle)
+    }
+
     /// Get the attributes of a memory region.
     ///
     /// The attribute mask this returns will only contain bits in the
@@ -95,6 +134,183 @@ impl MemoryProtection {
             (self.0.clear_memory_attributes)(&self.0, base_address, length, attributes).to_result()
         }
     }
+
+    /// Change the attributes of a page range to exactly `attributes`,
+    /// returning a guard that restores the previous attributes when dropped.
+    ///
+    /// Only [`READ_PROTECT`], [`EXECUTE_PROTECT`], and [`READ_ONLY`] are
+    /// changed; other bits in `attributes` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::INVALID_PARAMETER`]: the range is empty or its start or end
+    ///   is not aligned to [`PAGE_SIZE`].
+    /// * [`Status::NO_MAPPING`]: the attributes are not consistent within the
+    ///   range, so they could not be restored.
+    /// * [`Status::UNSUPPORTED`]: the firmware cannot apply `attributes`.
+    ///
+    /// [`READ_PROTECT`]: MemoryAttribute::READ_PROTECT
+    /// [`EXECUTE_PROTECT`]: MemoryAttribute::EXECUTE_PROTECT
+    /// [`READ_ONLY`]: MemoryAttribute::READ_ONLY
+    pub fn protect(
+        &self,
+        byte_region: Range<PhysicalAddress>,
+        attributes: MemoryAttribute,
+    ) -> Result<MemoryAttributeGuard<'_>> {
+        check_page_range(&byte_region)?;
+
+        let previous = self.get_memory_attributes(byte_region.clone())? & PROTECTION_ATTRIBUTES;
+        let guard = MemoryAttributeGuard {
+            protection: self,
+            byte_region,
+            previous,
+        };
+        self.apply(guard.byte_region.clone(), previous, attributes)?;
+        Ok(guard)
+    }
+
+    /// Make a page range writable and not executable, returning a guard that
+    /// restores the previous attributes when dropped.
+    ///
+    /// # Errors
+    ///
+    /// See [`protect`].
+    ///
+    /// [`protect`]: Self::protect
+    pub fn make_writable(
+        &self,
+        byte_region: Range<PhysicalAddress>,
+    ) -> Result<MemoryAttributeGuard<'_>> {
+        self.protect(byte_region, MemoryAttribute::EXECUTE_PROTECT)
+    }
+
+    /// Make a page range read-only and executable, returning a guard that
+    /// restores the previous attributes when dropped.
+    ///
+    /// # Errors
+    ///
+    /// See [`protect`].
+    ///
+    /// [`protect`]: Self::protect
+    pub fn make_executable(
+        &self,
+        byte_region: Range<PhysicalAddress>,
+    ) -> Result<MemoryAttributeGuard<'_>> {
+        self.protect(byte_region, MemoryAttribute::READ_ONLY)
+    }
+
+    /// Make a page range read-only and not executable, returning a guard that
+    /// restores the previous attributes when dropped.
+    ///
+    /// # Errors
+    ///
+    /// See [`protect`].
+    ///
+    /// [`protect`]: Self::protect
+    pub fn make_read_only(
+        &self,
+        byte_region: Range<PhysicalAddress>,
+    ) -> Result<MemoryAttributeGuard<'_>> {
+        self.protect(
+            byte_region,
+            MemoryAttribute::READ_ONLY | MemoryAttribute::EXECUTE_PROTECT,
+        )
+    }
+
+    /// Change the protection attributes of a region from `current` to `new`.
+    fn apply(
+        &self,
+        byte_region: Range<PhysicalAddress>,
+        current: MemoryAttribute,
+        new: MemoryAttribute,
+    ) -> Result {
+        let new = new & PROTECTION_ATTRIBUTES;
+        let to_set = new - current;
+        let to_clear = current - new;
+        if !to_set.is_empty() {
+            self.set_memory_attributes(byte_region.clone(), to_set)?;
+        }
+        if !to_clear.is_empty() {
+            self.clear_memory_attributes(byte_region, to_clear)?;
+        }
+        Ok(())
+    }
+}
+
+/// Attributes that can be changed with [`MemoryProtection`].
+const PROTECTION_ATTRIBUTES: MemoryAttribute = MemoryAttribute::READ_PROTECT
+    .union(MemoryAttribute::EXECUTE_PROTECT)
+    .union(MemoryAttribute::READ_ONLY);
+
+/// Guard returned by [`MemoryProtection::protect`] and related functions.
+///
+/// When dropped, the attributes of the page range are restored to what they
+/// were before the guard was created. Errors while restoring are ignored,
+/// use [`restore`] to handle them.
+///
+/// [`restore`]: Self::restore
+#[derive(Debug)]
+#[must_use = "the previous attributes are restored immediately if the guard is dropped"]
+pub struct MemoryAttributeGuard<'a> {
+    protection: &'a MemoryProtection,
+    byte_region: Range<PhysicalAddress>,
+    previous: MemoryAttribute,
+}
+
+impl MemoryAttributeGuard<'_> {
+    /// Get the page range the guard applies to.
+    #[must_use]
+    pub fn byte_region(&self) -> Range<PhysicalAddress> {
+        self.byte_region.clone()
+    }
+
+    /// Get the attributes that will be restored.
+    #[must_use]
+    pub const fn previous_attributes(&self) -> MemoryAttribute {
+        self.previous
+    }
+
+    /// Restore the previous attributes now, returning any error.
+    ///
+    /// # Errors
+    ///
+    /// See [`MemoryProtection::set_memory_attributes`] and
+    /// [`MemoryProtection::clear_memory_attributes`].
+    pub fn restore(self) -> Result {
+        let this = core::mem::ManuallyDrop::new(self);
+        this.restore_impl()
+    }
+
+    /// Keep the new attributes instead of restoring the previous ones.
+    pub const fn keep(self) {
+        core::mem::forget(self);
+    }
+
+    fn restore_impl(&self) -> Result {
+        let current = self
+            .protection
+            .get_memory_attributes(self.byte_region.clone())?
+            & PROTECTION_ATTRIBUTES;
+        self.protection
+            .apply(self.byte_region.clone(), current, self.previous)
+    }
+}
+
+impl Drop for MemoryAttributeGuard<'_> {
+    fn drop(&mut self) {
+        // Ignore errors since we can't propagate them from `drop`.
+        let _ = self.restore_impl();
+    }
+}
+
+/// Check that `r` is a non-empty, page-aligned range.
+fn check_page_range(r: &Range<PhysicalAddress>) -> Result {
+    let page_size = PAGE_SIZE as PhysicalAddress;
+    if r.start < r.end && r.start.is_multiple_of(page_size) && r.end.is_multiple_of(page_size) {
+        Ok(())
+    } else {
+        Err(Status::INVALID_PARAMETER.into())
+    }
 }
 
 /// Convert a byte `Range` to `(base_address, length)`.
@@ -110,4 +326,17 @@ mod tests {
     fn test_range_conversion() {
         assert_eq!(range_to_base_and_len(2..5), (2, 3));
     }
+
+    #[test]
+    fn test_check_page_range() {
+        assert!(check_page_range(&(0x1000..0x3000)).is_ok());
+        assert!(check_page_range(&(0x1000..0x1000)).is_err());
+        assert!(check_page_range(&(0x1001..0x2000)).is_err());
+        assert!(check_page_range(&(0x1000..0x2001)).is_err());
+        let reversed = Range {
+            start: 0x2000,
+            end: 0x1000,
+        };
+        assert!(check_page_range(&reversed).is_err());
+    }
 }
@@ -3,4 +3,4 @@
 //! Protocols related to secure technologies.
 
 mod memory_protection;
-pub use memory_protection::MemoryProtection;
+pub use memory_protection::{MemoryAttributeGuard, MemoryProtection};
@@ -0,0 +1,350 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Confidential computing (CC) measurement protocol.
+//!
+//! Confidential computing guests, such as Intel TDX guests, measure into the
+//! measurement registers (MRs) of the CC technology instead of, or in addition
+//! to, the PCRs of a TPM. The [`CcMeasurement`] protocol mirrors the
+//! [`v2::Tcg`] protocol, and its event log uses the same format, so it is
+//! returned as a [`v2::EventLog`].
+//!
+//! Measurements are made into [`MrIndex`]es. Code written for PCRs can use
+//! [`CcMeasurement::map_pcr_to_mr_index`] to find the MR of a PCR.
+
+use super::{PcrIndex, v2};
+use crate::data_types::{Align, PhysicalAddress};
+use crate::proto::unsafe_protocol;
+use crate::{Result, StatusExt};
+use core::ptr;
+use uefi_raw::Boolean;
+use uefi_raw::protocol::tcg::cc::CcMeasurementProtocol;
+
+#[cfg(feature = "alloc")]
+use {crate::mem::make_boxed, alloc::boxed::Box};
+
+pub use super::{EventType, HashAlgorithm};
+pub use uefi_raw::protocol::tcg::cc::{CcTechnology, CcType, CcVersion as Version};
+pub use v2::{EventLogFormat, HashLogExtendEventFlags};
+
+/// Measurement register (MR) index.
+///
+/// The meaning of the index depends on the CC technology. The associated
+/// constants give the indices of Intel TDX.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
+pub struct MrIndex(pub u32);
+
+impl MrIndex {
+    /// TDX build-time measurement register (`MRTD`).
+    pub const TDX_MRTD: Self = Self(0);
+
+    /// TDX runtime measurement register 0 (`RTMR[0]`).
+    pub const TDX_RTMR0: Self = Self(1);
+
+    /// TDX runtime measurement register 1 (`RTMR[1]`).
+    pub const TDX_RTMR1: Self = Self(2);
+
+    /// TDX runtime measurement register 2 (`RTMR[2]`).
+    pub const TDX_RTMR2: Self = Self(3);
+
+    /// TDX runtime measurement register 3 (`RTMR[3]`).
+    pub const TDX_RTMR3: Self = Self(4);
+
+    /// Get the TDX measurement register of a PCR, as defined by the UEFI
+    /// Specification:
+    ///
+    /// | PCR     | MR                     |
+    /// |---------|------------------------|
+    /// | 0       | [`TDX_MRTD`]           |
+    /// | 1, 7    | [`TDX_RTMR0`]          |
+    /// | 2 to 6  | [`TDX_RTMR1`]          |
+    /// | 8 to 15 | [`TDX_RTMR2`]          |
+    ///
+    /// Returns `None` for other PCRs. This is the mapping of
+    /// [`CcMeasurement::map_pcr_to_mr_index`] on TDX guests, without calling
+    /// the firmware.
+    ///
+    /// [`TDX_MRTD`]: Self::TDX_MRTD
+    /// [`TDX_RTMR0`]: Self::TDX_RTMR0
+    /// [`TDX_RTMR1`]: Self::TDX_RTMR1
+    /// [`TDX_RTMR2`]: Self::TDX_RTMR2
+    #[must_use]
+    pub const fn from_tdx_pcr(pcr: PcrIndex) -> Option<Self> {
+        match pcr.0 {
+            0 => Some(Self::TDX_MRTD),
+            1 | 7 => Some(Self::TDX_RTMR0),
+            2..=6 => Some(Self::TDX_RTMR1),
+            8..=15 => Some(Self::TDX_RTMR2),
+            _ => None,
+        }
+    }
+}
+
+/// Information about the protocol and the CC technology.
+///
+/// Layout compatible with the C type `EFI_CC_BOOT_SERVICE_CAPABILITY`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct BootServiceCapability {
+    size: u8,
+
+    /// Version of the structure.
+    pub structure_version: Version,
+
+    /// Version of the EFI CC measurement protocol.
+    pub protocol_version: Version,
+
+    /// Bitmap of supported hash algorithms.
+    pub hash_algorithm_bitmap: HashAlgorithm,
+
+    /// Event log formats supported by the firmware.
+    pub supported_event_logs: EventLogFormat,
+
+    /// Type of the CC technology.
+    pub cc_type: CcType,
+}
+
+impl Default for BootServiceCapability {
+    fn default() -> Self {
+        // OK to unwrap, the size is less than u8.
+        let struct_size = u8::try_from(size_of::<Self>()).unwrap();
+
+        Self {
+            size: struct_size,
+            structure_version: Version::default(),
+            protocol_version: Version::default(),
+            hash_algorithm_bitmap: HashAlgorithm::default(),
+            supported_event_logs: EventLogFormat::default(),
+            cc_type: CcType {
+                ty: CcTechnology::NONE,
+                sub_type: 0,
+            },
+        }
+    }
+}
+
+/// Event type passed to [`CcMeasurement::hash_log_extend_event`].
+///
+/// Layout compatible with the C type `EFI_CC_EVENT`, which has the same
+/// layout as [`v2::PcrEventInputs`], with an MR index instead of a PCR index.
+#[derive(Debug, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct CcEventInputs(v2::PcrEventInputs);
+
+impl CcEventInputs {
+    /// Create a new `CcEventInputs` using a byte buffer for storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Status::BUFFER_TOO_SMALL`] if the `buffer` is not large
+    /// enough. The required size will be returned in the error data.
+    ///
+    /// Returns [`Status::INVALID_PARAMETER`] if the `event_data` size is too
+    /// large.
+    ///
+    /// [`Status::BUFFER_TOO_SMALL`]: crate::Status::BUFFER_TOO_SMALL
+    /// [`Status::INVALID_PARAMETER`]: crate::Status::INVALID_PARAMETER
+    pub fn new_in_buffer<'buf>(
+        buffer: &'buf mut [u8],
+        mr_index: MrIndex,
+        event_type: EventType,
+        event_data: &[u8],
+    ) -> Result<&'buf mut Self, Option<usize>> {
+        let event = v2::PcrEventInputs::new_in_buffer(
+            buffer,
+            PcrIndex(mr_index.0),
+            event_type,
+            event_data,
+        )?;
+        let event: *mut v2::PcrEventInputs = event;
+        // Safety: `CcEventInputs` is a transparent wrapper of
+        // `PcrEventInputs`, which has the layout of `EFI_CC_EVENT`.
+        Ok(unsafe { &mut *(event as *mut Self) })
+    }
+
+    /// Create a new `CcEventInputs` in a [`Box`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Status::INVALID_PARAMETER`] if the `event_data` size is too
+    /// large.
+    ///
+    /// [`Status::INVALID_PARAMETER`]: crate::Status::INVALID_PARAMETER
+    #[cfg(feature = "alloc")]
+    pub fn new_in_box(
+        mr_index: MrIndex,
+        event_type: EventType,
+        event_data: &[u8],
+    ) -> Result<Box<Self>> {
+        make_boxed(|buf| Self::new_in_buffer(buf, mr_index, event_type, event_data))
+    }
+}
+
+impl Align for CcEventInputs {
+    fn alignment() -> usize {
+        1
+    }
+}
+
+/// Confidential computing measurement [`Protocol`].
+///
+/// The corresponding C type is `EFI_CC_MEASUREMENT_PROTOCOL`.
+///
+/// [`Protocol`]: uefi::proto::Protocol
+#[derive(Debug)]
+#[repr(transparent)]
+#[unsafe_protocol(CcMeasurementProtocol::GUID)]
+pub struct CcMeasurement(CcMeasurementProtocol);
+
+impl CcMeasurement {
+    /// Get information about the protocol and the CC technology.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::DEVICE_ERROR`]: the firmware failed to get the
+    ///   information.
+    ///
+    /// [`Status::DEVICE_ERROR`]: crate::Status::DEVICE_ERROR
+    pub fn get_capability(&mut self) -> Result<BootServiceCapability> {
+        let mut capability = BootServiceCapability::default();
+        unsafe {
+            (self.0.get_capability)(&mut self.0, ptr::from_mut(&mut capability).cast())
+                .to_result_with_val(|| capability)
+        }
+    }
+
+    /// Get the event log. The PCR indices of its events are MR indices.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::INVALID_PARAMETER`]: the firmware does not support the
+    ///   [`EventLogFormat::TCG_2`] format.
+    ///
+    /// [`Status::INVALID_PARAMETER`]: crate::Status::INVALID_PARAMETER
+    pub fn get_event_log(&mut self) -> Result<v2::EventLog<'_>> {
+        let mut location = 0;
+        let mut last_entry = 0;
+        let mut truncated = Boolean::default();
+        let status = unsafe {
+            (self.0.get_event_log)(
+                &mut self.0,
+                EventLogFormat::TCG_2,
+                &mut location,
+                &mut last_entry,
+                &mut truncated,
+            )
+        };
+
+        status.to_result_with_val(|| unsafe {
+            v2::EventLog::new(
+                location as *const u8,
+                last_entry as *const u8,
+                truncated.into(),
+            )
+        })
+    }
+
+    /// Extend an MR and add an entry to the event log.
+    ///
+    /// Only the runtime measurement registers can be extended.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::INVALID_PARAMETER`]: the MR index is invalid, such as
+    ///   [`MrIndex::TDX_MRTD`], or the event is malformed.
+    /// * [`Status::UNSUPPORTED`]: `flags` has
+    ///   [`HashLogExtendEventFlags::PE_COFF_IMAGE`] and the data is not a
+    ///   supported PE/COFF image.
+    /// * [`Status::VOLUME_FULL`]: the MR was extended, but the event log is
+    ///   full.
+    /// * [`Status::DEVICE_ERROR`]: the MR could not be extended.
+    ///
+    /// [`Status::DEVICE_ERROR`]: crate::Status::DEVICE_ERROR
+    /// [`Status::INVALID_PARAMETER`]: crate::Status::INVALID_PARAMETER
+    /// [`Status::UNSUPPORTED`]: crate::Status::UNSUPPORTED
+    /// [`Status::VOLUME_FULL`]: crate::Status::VOLUME_FULL
+    pub fn hash_log_extend_event(
+        &mut self,
+        flags: HashLogExtendEventFlags,
+        data_to_hash: &[u8],
+        event: &CcEventInputs,
+    ) -> Result {
+        unsafe {
+            (self.0.hash_log_extend_event)(
+                &mut self.0,
+                flags,
+                data_to_hash.as_ptr() as PhysicalAddress,
+                // OK to unwrap, usize fits in u64.
+                u64::try_from(data_to_hash.len()).unwrap(),
+                ptr::from_ref(event).cast(),
+            )
+            .to_result()
+        }
+    }
+
+    /// Get the MR that measurements into `pcr_index` are made into.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::INVALID_PARAMETER`]: the PCR has no MR.
+    ///
+    /// [`Status::INVALID_PARAMETER`]: crate::Status::INVALID_PARAMETER
+    pub fn map_pcr_to_mr_index(&mut self, pcr_index: PcrIndex) -> Result<MrIndex> {
+        let mut mr_index = 0;
+        unsafe { (self.0.map_pcr_to_mr_index)(&mut self.0, pcr_index.0, &mut mr_index) }
+            .to_result_with_val(|| MrIndex(mr_index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::slice;
+
+    #[test]
+    fn test_new_event() {
+        let mut buf = [0; 22];
+        let event = CcEventInputs::new_in_buffer(
+            &mut buf,
+            MrIndex::TDX_RTMR2,
+            EventType::IPL,
+            &[0x12, 0x13, 0x14, 0x15],
+        )
+        .unwrap();
+
+        let event_ptr: *const CcEventInputs = event;
+        let event_ptr: *const u8 = event_ptr.cast();
+        let event_bytes = unsafe { slice::from_raw_parts(event_ptr, size_of_val(event)) };
+
+        #[rustfmt::skip]
+        assert_eq!(event_bytes, [
+            // Size
+            0x16, 0x00, 0x00, 0x00,
+
+            // Header
+            // Header size
+            0x0e, 0x00, 0x00, 0x00,
+            // Header version
+            0x01, 0x00,
+            // MR index
+            0x03, 0x00, 0x00, 0x00,
+            // Event type
+            0x0d, 0x00, 0x00, 0x00,
+            // Event data
+            0x12, 0x13, 0x14, 0x15,
+        ]);
+    }
+
+    #[test]
+    fn test_from_tdx_pcr() {
+        let mr = |pcr| MrIndex::from_tdx_pcr(PcrIndex(pcr));
+        assert_eq!(mr(0), Some(MrIndex::TDX_MRTD));
+        assert_eq!(mr(1), Some(MrIndex::TDX_RTMR0));
+        assert_eq!(mr(2), Some(MrIndex::TDX_RTMR1));
+        assert_eq!(mr(6), Some(MrIndex::TDX_RTMR1));
+        assert_eq!(mr(7), Some(MrIndex::TDX_RTMR0));
+        assert_eq!(mr(8), Some(MrIndex::TDX_RTMR2));
+        assert_eq!(mr(15), Some(MrIndex::TDX_RTMR2));
+        assert_eq!(mr(16), None);
+    }
+}
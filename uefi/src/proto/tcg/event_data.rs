@@ -0,0 +1,1189 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Decoding of the data of well-known events.
+//!
+//! [`v2::PcrEvent::event_data`] returns the raw bytes of an event, whose
+//! layout depends on the [`EventType`]. [`EventData::parse`] decodes the
+//! common layouts of the [TCG PC Client Platform Firmware Profile][spec]:
+//!
+//! ```no_run
+//! use uefi::proto::tcg::event_data::EventData;
+//! use uefi::proto::tcg::v2;
+//!
+//! # fn example(log: &v2::EventLog) {
+//! for event in log.iter() {
+//!     if let Ok(EventData::Variable(variable)) = event.decode_event_data() {
+//!         let name: String = variable.name().collect();
+//!         log::info!("PCR {}: {name}", event.pcr_index().0);
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! [`EventLogDump`] formats a whole log in the style of `tpm2_eventlog`.
+//!
+//! [spec]: https://trustedcomputinggroup.org/resource/pc-client-specific-platform-firmware-profile-specification/
+
+use super::{AlgorithmId, EventType, v2};
+use crate::data_types::PhysicalAddress;
+use crate::proto::device_path::DevicePath;
+use crate::proto::media::partition::GptPartitionEntry;
+use crate::runtime::VariableVendor;
+use crate::{CStr16, Guid};
+use core::fmt::{self, Display, Formatter};
+use core::ptr;
+
+/// Signature of the Spec ID event, which starts the event log.
+pub(super) const SPEC_ID_SIGNATURE: &[u8; 16] = b"Spec ID Event03\0";
+
+/// Signature of the event that gives the locality in which the TPM was
+/// started, which is the initial value of PCR 0.
+pub(super) const STARTUP_LOCALITY_SIGNATURE: &[u8; 16] = b"StartupLocality\0";
+
+/// Signature of the GPT header.
+const GPT_SIGNATURE: &[u8; 8] = b"EFI PART";
+
+/// Error returned by [`EventData::parse`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EventDataError {
+    /// The data is shorter than the sizes it contains.
+    Truncated,
+    /// The data has an invalid signature, size or device path.
+    Invalid,
+}
+
+impl Display for EventDataError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "event data is truncated"),
+            Self::Invalid => write!(f, "event data is invalid"),
+        }
+    }
+}
+
+impl core::error::Error for EventDataError {}
+
+/// Reader of little endian event data.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], EventDataError> {
+        let (bytes, rest) = self
+            .0
+            .split_at_checked(len)
+            .ok_or(EventDataError::Truncated)?;
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], EventDataError> {
+        // OK to unwrap, the length is correct.
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, EventDataError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, EventDataError> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, EventDataError> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    /// Read a `UINTN` of `size` bytes.
+    fn uintn(&mut self, size: usize) -> Result<u64, EventDataError> {
+        let mut bytes = [0; 8];
+        bytes[..size].copy_from_slice(self.bytes(size)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Read a `u64` size or count.
+    fn size(&mut self) -> Result<usize, EventDataError> {
+        usize::try_from(self.u64()?).map_err(|_| EventDataError::Truncated)
+    }
+
+    fn guid(&mut self) -> Result<Guid, EventDataError> {
+        self.array().map(Guid::from_bytes)
+    }
+}
+
+/// Decoded data of an event.
+///
+/// The variant depends on the event type, so the same bytes may decode
+/// differently for different event types.
+#[derive(Clone, Debug)]
+pub enum EventData<'a> {
+    /// [`EventType::NO_ACTION`] event that starts the event log.
+    SpecId(SpecIdEvent<'a>),
+    /// [`EventType::NO_ACTION`] event with the locality in which the TPM was
+    /// started, which is the initial value of PCR 0.
+    StartupLocality(u8),
+    /// [`EventType::SEPARATOR`] event, which separates the pre-OS and OS
+    /// phases of a PCR. The value is zero, or non-zero if an error occurred.
+    Separator(u32),
+    /// Text of an [`EventType::IPL`], [`EventType::ACTION`],
+    /// [`EventType::EFI_ACTION`] or [`EventType::CRTM_VERSION`] event.
+    Text(EventText<'a>),
+    /// Measured UEFI variable, for the [`EventType::EFI_VARIABLE_DRIVER_CONFIG`],
+    /// [`EventType::EFI_VARIABLE_BOOT`], [`EventType::EFI_VARIABLE_BOOT2`]
+    /// and [`EventType::EFI_VARIABLE_AUTHORITY`] events.
+    Variable(VariableEvent<'a>),
+    /// Loaded image, for the [`EventType::EFI_BOOT_SERVICES_APPLICATION`],
+    /// [`EventType::EFI_BOOT_SERVICES_DRIVER`] and
+    /// [`EventType::EFI_RUNTIME_SERVICES_DRIVER`] events.
+    ImageLoad(ImageLoadEvent<'a>),
+    /// GPT of the boot disk, for the [`EventType::EFI_GPT_EVENT`] event.
+    Gpt(GptEvent<'a>),
+    /// Firmware blob, for the [`EventType::EFI_PLATFORM_FIRMWARE_BLOB`] and
+    /// [`EventType::EFI_PLATFORM_FIRMWARE_BLOB2`] events.
+    FirmwareBlob(FirmwareBlobEvent<'a>),
+    /// Tagged event (`TCG_PCClientTaggedEvent`), for the
+    /// [`EventType::EVENT_TAG`] event.
+    Tagged(TaggedEvent<'a>),
+    /// Data of another event type, which is not decoded.
+    Other(&'a [u8]),
+}
+
+impl<'a> EventData<'a> {
+    /// Decode the `data` of an event of type `event_type`.
+    ///
+    /// Some events contain `UINTN` fields, whose size depends on the
+    /// firmware. The size is guessed from the size of the data. Use
+    /// [`parse_in_log`] to take it from the [`SpecIdEvent`] of the log
+    /// instead.
+    ///
+    /// # Errors
+    ///
+    /// * [`EventDataError::Truncated`]: the data is too short.
+    /// * [`EventDataError::Invalid`]: the data is malformed.
+    ///
+    /// [`parse_in_log`]: Self::parse_in_log
+    pub fn parse(event_type: EventType, data: &'a [u8]) -> Result<Self, EventDataError> {
+        Self::parse_with_uintn_size(event_type, data, None)
+    }
+
+    /// Decode the `data` of an event of type `event_type`, from a log that
+    /// starts with `spec_id`.
+    ///
+    /// The size of `UINTN` fields is taken from
+    /// [`SpecIdEvent::uintn_size`], and guessed as in [`parse`] if it is not
+    /// valid.
+    ///
+    /// # Errors
+    ///
+    /// * [`EventDataError::Truncated`]: the data is too short.
+    /// * [`EventDataError::Invalid`]: the data is malformed.
+    ///
+    /// [`parse`]: Self::parse
+    pub fn parse_in_log(
+        event_type: EventType,
+        data: &'a [u8],
+        spec_id: &SpecIdEvent,
+    ) -> Result<Self, EventDataError> {
+        Self::parse_with_uintn_size(event_type, data, spec_id.uintn_bytes())
+    }
+
+    fn parse_with_uintn_size(
+        event_type: EventType,
+        data: &'a [u8],
+        uintn_size: Option<usize>,
+    ) -> Result<Self, EventDataError> {
+        Ok(match event_type {
+            EventType::NO_ACTION => {
+                if let Some(locality) = startup_locality(data) {
+                    Self::StartupLocality(locality)
+                } else if data.starts_with(SPEC_ID_SIGNATURE) {
+                    Self::SpecId(SpecIdEvent::parse(data)?)
+                } else {
+                    Self::Other(data)
+                }
+            }
+            EventType::SEPARATOR => {
+                let value = data.try_into().map_err(|_| EventDataError::Invalid)?;
+                Self::Separator(u32::from_le_bytes(value))
+            }
+            EventType::IPL
+            | EventType::ACTION
+            | EventType::EFI_ACTION
+            | EventType::CRTM_VERSION => Self::Text(EventText(data)),
+            EventType::EFI_VARIABLE_DRIVER_CONFIG
+            | EventType::EFI_VARIABLE_BOOT
+            | EventType::EFI_VARIABLE_BOOT2
+            | EventType::EFI_VARIABLE_AUTHORITY => Self::Variable(VariableEvent::parse(data)?),
+            EventType::EFI_BOOT_SERVICES_APPLICATION
+            | EventType::EFI_BOOT_SERVICES_DRIVER
+            | EventType::EFI_RUNTIME_SERVICES_DRIVER => {
+                Self::ImageLoad(ImageLoadEvent::parse(data, uintn_size)?)
+            }
+            EventType::EFI_GPT_EVENT => Self::Gpt(GptEvent::parse(data, uintn_size)?),
+            EventType::EFI_PLATFORM_FIRMWARE_BLOB => {
+                Self::FirmwareBlob(FirmwareBlobEvent::parse(data, false)?)
+            }
+            EventType::EFI_PLATFORM_FIRMWARE_BLOB2 => {
+                Self::FirmwareBlob(FirmwareBlobEvent::parse(data, true)?)
+            }
+            EventType::EVENT_TAG => Self::Tagged(TaggedEvent::parse(data)?),
+            _ => Self::Other(data),
+        })
+    }
+}
+
+/// Spec ID event (`TCG_EfiSpecIDEventStruct`), which gives the format of the
+/// event log.
+#[derive(Clone, Debug)]
+pub struct SpecIdEvent<'a> {
+    platform_class: u32,
+    spec_version: (u8, u8, u8),
+    uintn_size: u8,
+    algorithms: &'a [u8],
+    vendor_info: &'a [u8],
+}
+
+impl<'a> SpecIdEvent<'a> {
+    /// Decode the data of a Spec ID event.
+    ///
+    /// # Errors
+    ///
+    /// * [`EventDataError::Truncated`]: the data is too short.
+    /// * [`EventDataError::Invalid`]: the signature is not `Spec ID Event03`.
+    pub fn parse(data: &'a [u8]) -> Result<Self, EventDataError> {
+        let mut r = Reader(data);
+        if r.bytes(SPEC_ID_SIGNATURE.len())? != SPEC_ID_SIGNATURE {
+            return Err(EventDataError::Invalid);
+        }
+        let platform_class = r.u32()?;
+        let minor = r.u8()?;
+        let major = r.u8()?;
+        let errata = r.u8()?;
+        let uintn_size = r.u8()?;
+        let count = usize::try_from(r.u32()?).map_err(|_| EventDataError::Truncated)?;
+        let algorithms = r.bytes(count.checked_mul(4).ok_or(EventDataError::Truncated)?)?;
+        let vendor_info_size = r.u8()?;
+        let vendor_info = r.bytes(usize::from(vendor_info_size))?;
+        Ok(Self {
+            platform_class,
+            spec_version: (major, minor, errata),
+            uintn_size,
+            algorithms,
+            vendor_info,
+        })
+    }
+
+    /// Platform class, which is zero for clients and one for servers.
+    #[must_use]
+    pub const fn platform_class(&self) -> u32 {
+        self.platform_class
+    }
+
+    /// Version of the specification, as (major, minor, errata).
+    #[must_use]
+    pub const fn spec_version(&self) -> (u8, u8, u8) {
+        self.spec_version
+    }
+
+    /// Size of `UINTN` in the event data: 1 for 32 bits, 2 for 64 bits.
+    #[must_use]
+    pub const fn uintn_size(&self) -> u8 {
+        self.uintn_size
+    }
+
+    /// Size of `UINTN` in bytes, if [`uintn_size`] is valid.
+    ///
+    /// [`uintn_size`]: Self::uintn_size
+    const fn uintn_bytes(&self) -> Option<usize> {
+        match self.uintn_size {
+            1 => Some(4),
+            2 => Some(8),
+            _ => None,
+        }
+    }
+
+    /// Algorithms of the digests of the events, with their digest sizes.
+    pub fn digest_sizes(&self) -> impl Iterator<Item = (AlgorithmId, u16)> + use<'a> {
+        self.algorithms.as_chunks::<4>().0.iter().map(|entry| {
+            (
+                AlgorithmId(u16::from_le_bytes([entry[0], entry[1]])),
+                u16::from_le_bytes([entry[2], entry[3]]),
+            )
+        })
+    }
+
+    /// Vendor-specific information.
+    #[must_use]
+    pub const fn vendor_info(&self) -> &'a [u8] {
+        self.vendor_info
+    }
+}
+
+/// Text of an event.
+///
+/// The encoding is not specified: firmware usually logs ASCII, but some boot
+/// loaders log UCS-2, and some events hold binary data. The [`Display`]
+/// implementation shows the text if it is printable UTF-8, or printable ASCII
+/// encoded as UCS-2, and shows it as hexadecimal otherwise.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EventText<'a>(&'a [u8]);
+
+impl<'a> EventText<'a> {
+    /// Raw bytes of the text.
+    #[must_use]
+    pub const fn bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// Get the text if it is printable UTF-8, without trailing null bytes.
+    #[must_use]
+    pub fn as_str(&self) -> Option<&'a str> {
+        let end = self.0.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        let text = core::str::from_utf8(&self.0[..end]).ok()?;
+        text.chars().all(is_printable).then_some(text)
+    }
+
+    /// Get the characters of the text if it is printable ASCII encoded as
+    /// UCS-2, without trailing null characters. Other characters are not
+    /// accepted, since binary data is often valid UCS-2.
+    fn ucs2_chars(&self) -> Option<impl Iterator<Item = char> + use<'a>> {
+        let (units, []) = self.0.as_chunks::<2>() else {
+            return None;
+        };
+        let end = units
+            .iter()
+            .rposition(|unit| *unit != [0, 0])
+            .map_or(0, |i| i + 1);
+        let chars = char::decode_utf16(units[..end].iter().map(|unit| u16::from_le_bytes(*unit)));
+        if end == 0
+            || !chars
+                .clone()
+                .all(|c| c.is_ok_and(|c| c.is_ascii() && is_printable(c)))
+        {
+            return None;
+        }
+        Some(chars.map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)))
+    }
+}
+
+impl Display for EventText<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(text) = self.as_str() {
+            f.write_str(text)
+        } else if let Some(mut chars) = self.ucs2_chars() {
+            chars.try_for_each(|c| write!(f, "{c}"))
+        } else {
+            write!(f, "{}", Hex(self.0))
+        }
+    }
+}
+
+fn is_printable(c: char) -> bool {
+    !c.is_control() || matches!(c, '\n' | '\r' | '\t')
+}
+
+/// Measured UEFI variable (`UEFI_VARIABLE_DATA`).
+#[derive(Clone, Debug)]
+pub struct VariableEvent<'a> {
+    vendor: VariableVendor,
+    name: &'a [u8],
+    data: &'a [u8],
+}
+
+impl<'a> VariableEvent<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, EventDataError> {
+        let mut r = Reader(data);
+        let vendor = VariableVendor(r.guid()?);
+        let name_len = r.size()?;
+        let data_len = r.size()?;
+        let name = r.bytes(name_len.checked_mul(2).ok_or(EventDataError::Truncated)?)?;
+        let data = r.bytes(data_len)?;
+        Ok(Self { vendor, name, data })
+    }
+
+    /// Vendor GUID of the variable.
+    #[must_use]
+    pub const fn vendor(&self) -> VariableVendor {
+        self.vendor
+    }
+
+    /// Characters of the name of the variable. Invalid UCS-2 is replaced with
+    /// [`char::REPLACEMENT_CHARACTER`].
+    pub fn name(&self) -> impl Iterator<Item = char> + use<'a> {
+        char::decode_utf16(self.name_units()).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Returns whether this is the variable `name` of `vendor`.
+    #[must_use]
+    pub fn is_variable(&self, name: &CStr16, vendor: &VariableVendor) -> bool {
+        self.vendor == *vendor && self.name_units().eq(name.iter().map(|c| u16::from(*c)))
+    }
+
+    fn name_units(&self) -> impl Iterator<Item = u16> + Clone + use<'a> {
+        self.name
+            .as_chunks::<2>()
+            .0
+            .iter()
+            .map(|unit| u16::from_le_bytes(*unit))
+    }
+
+    /// Data of the variable.
+    #[must_use]
+    pub const fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// Get the `UINTN` sizes to try when decoding event data: `uintn_size` if it
+/// is known, and otherwise eight bytes, then four bytes for 32-bit firmware.
+/// The size that makes the fields match the size of the event is used.
+const fn uintn_sizes(uintn_size: Option<usize>) -> &'static [usize] {
+    match uintn_size {
+        Some(4) => &[4],
+        Some(8) => &[8],
+        _ => &[8, 4],
+    }
+}
+
+/// Get the locality of a `StartupLocality` event, from its data.
+pub(super) fn startup_locality(event_data: &[u8]) -> Option<u8> {
+    match event_data {
+        [signature @ .., locality] if signature == STARTUP_LOCALITY_SIGNATURE => Some(*locality),
+        _ => None,
+    }
+}
+
+/// Loaded image (`UEFI_IMAGE_LOAD_EVENT`).
+#[derive(Clone, Debug)]
+pub struct ImageLoadEvent<'a> {
+    location: PhysicalAddress,
+    length: u64,
+    link_time_address: u64,
+    device_path: Option<&'a DevicePath>,
+}
+
+impl<'a> ImageLoadEvent<'a> {
+    fn parse(data: &'a [u8], uintn_size: Option<usize>) -> Result<Self, EventDataError> {
+        // The location is a `UINT64`, the other fields are `UINTN`s.
+        let location = Reader(data).u64()?;
+        let (fields, device_path) = uintn_sizes(uintn_size)
+            .iter()
+            .find_map(|size| {
+                let mut r = Reader(&data[size_of::<u64>()..]);
+                let fields = [0; 3].map(|_| r.uintn(*size));
+                let [.., Ok(device_path_len)] = fields else {
+                    return None;
+                };
+                (usize::try_from(device_path_len).ok()? == r.0.len()).then_some((fields, r.0))
+            })
+            .ok_or(EventDataError::Invalid)?;
+        // OK to unwrap: all fields were read.
+        let [length, link_time_address, _] = fields.map(Result::unwrap);
+        let device_path = match device_path {
+            [] => None,
+            bytes => Some(<&DevicePath>::try_from(bytes).map_err(|_| EventDataError::Invalid)?),
+        };
+        Ok(Self {
+            location,
+            length,
+            link_time_address,
+            device_path,
+        })
+    }
+
+    /// Address of the image in memory.
+    #[must_use]
+    pub const fn location(&self) -> PhysicalAddress {
+        self.location
+    }
+
+    /// Size of the image in memory.
+    #[must_use]
+    pub const fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// Link-time base address of the image.
+    #[must_use]
+    pub const fn link_time_address(&self) -> u64 {
+        self.link_time_address
+    }
+
+    /// Device path of the image, if it was logged.
+    #[must_use]
+    pub const fn device_path(&self) -> Option<&'a DevicePath> {
+        self.device_path
+    }
+}
+
+/// Header of a GUID partition table (`EFI_PARTITION_TABLE_HEADER`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GptHeader {
+    /// Revision of the header format.
+    pub revision: u32,
+    /// Size of the header.
+    pub header_size: u32,
+    /// CRC32 of the header.
+    pub header_crc32: u32,
+    /// LBA of this header.
+    pub my_lba: u64,
+    /// LBA of the other header.
+    pub alternate_lba: u64,
+    /// First LBA that can be used by partitions.
+    pub first_usable_lba: u64,
+    /// Last LBA that can be used by partitions.
+    pub last_usable_lba: u64,
+    /// GUID of the disk.
+    pub disk_guid: Guid,
+    /// First LBA of the partition entries.
+    pub partition_entry_lba: u64,
+    /// Number of partition entries.
+    pub number_of_partition_entries: u32,
+    /// Size of a partition entry.
+    pub size_of_partition_entry: u32,
+    /// CRC32 of the partition entries.
+    pub partition_entry_array_crc32: u32,
+}
+
+impl GptHeader {
+    fn parse(r: &mut Reader) -> Result<Self, EventDataError> {
+        if r.bytes(GPT_SIGNATURE.len())? != GPT_SIGNATURE {
+            return Err(EventDataError::Invalid);
+        }
+        let revision = r.u32()?;
+        let header_size = r.u32()?;
+        let header_crc32 = r.u32()?;
+        let _reserved = r.u32()?;
+        Ok(Self {
+            revision,
+            header_size,
+            header_crc32,
+            my_lba: r.u64()?,
+            alternate_lba: r.u64()?,
+            first_usable_lba: r.u64()?,
+            last_usable_lba: r.u64()?,
+            disk_guid: r.guid()?,
+            partition_entry_lba: r.u64()?,
+            number_of_partition_entries: r.u32()?,
+            size_of_partition_entry: r.u32()?,
+            partition_entry_array_crc32: r.u32()?,
+        })
+    }
+}
+
+/// GPT of the boot disk (`UEFI_GPT_DATA`).
+///
+/// Only the partitions that are in use are logged.
+#[derive(Clone, Debug)]
+pub struct GptEvent<'a> {
+    header: GptHeader,
+    entries: &'a [u8],
+    entry_size: usize,
+}
+
+impl<'a> GptEvent<'a> {
+    fn parse(data: &'a [u8], uintn_size: Option<usize>) -> Result<Self, EventDataError> {
+        let mut r = Reader(data);
+        let header = GptHeader::parse(&mut r)?;
+        let entry_size = usize::try_from(header.size_of_partition_entry)
+            .ok()
+            .filter(|size| *size >= size_of::<GptPartitionEntry>())
+            .ok_or(EventDataError::Invalid)?;
+
+        // The number of partitions is a `UINTN`.
+        let rest = r.0;
+        let entries = uintn_sizes(uintn_size)
+            .iter()
+            .find_map(|size| {
+                let mut r = Reader(rest);
+                let count = usize::try_from(r.uintn(*size).ok()?).ok()?;
+                (count.checked_mul(entry_size)? == r.0.len()).then_some(r.0)
+            })
+            .ok_or(EventDataError::Invalid)?;
+
+        Ok(Self {
+            header,
+            entries,
+            entry_size,
+        })
+    }
+
+    /// Header of the partition table.
+    #[must_use]
+    pub const fn header(&self) -> &GptHeader {
+        &self.header
+    }
+
+    /// Partitions of the disk.
+    pub fn partitions(&self) -> impl Iterator<Item = GptPartitionEntry> + use<'a> {
+        self.entries.chunks_exact(self.entry_size).map(|entry| {
+            // SAFETY: the entry is at least as large as a `GptPartitionEntry`,
+            // which has an alignment of one and is valid for any bytes.
+            unsafe { ptr::read_unaligned(entry.as_ptr().cast::<GptPartitionEntry>()) }
+        })
+    }
+}
+
+/// Firmware blob (`UEFI_PLATFORM_FIRMWARE_BLOB` or
+/// `UEFI_PLATFORM_FIRMWARE_BLOB2`).
+#[derive(Clone, Debug)]
+pub struct FirmwareBlobEvent<'a> {
+    description: Option<EventText<'a>>,
+    base: PhysicalAddress,
+    length: u64,
+}
+
+impl<'a> FirmwareBlobEvent<'a> {
+    fn parse(data: &'a [u8], has_description: bool) -> Result<Self, EventDataError> {
+        let mut r = Reader(data);
+        let description = if has_description {
+            let size = r.u8()?;
+            Some(EventText(r.bytes(usize::from(size))?))
+        } else {
+            None
+        };
+        Ok(Self {
+            description,
+            base: r.u64()?,
+            length: r.u64()?,
+        })
+    }
+
+    /// Description of the blob, for [`EventType::EFI_PLATFORM_FIRMWARE_BLOB2`].
+    #[must_use]
+    pub const fn description(&self) -> Option<EventText<'a>> {
+        self.description
+    }
+
+    /// Address of the blob.
+    #[must_use]
+    pub const fn base(&self) -> PhysicalAddress {
+        self.base
+    }
+
+    /// Size of the blob.
+    #[must_use]
+    pub const fn length(&self) -> u64 {
+        self.length
+    }
+}
+
+/// Tagged event (`TCG_PCClientTaggedEvent`).
+#[derive(Clone, Debug)]
+pub struct TaggedEvent<'a> {
+    tag: u32,
+    data: &'a [u8],
+}
+
+impl<'a> TaggedEvent<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, EventDataError> {
+        let mut r = Reader(data);
+        let tag = r.u32()?;
+        let size = r.u32()?;
+        let data = r.bytes(usize::try_from(size).map_err(|_| EventDataError::Truncated)?)?;
+        if !r.0.is_empty() {
+            return Err(EventDataError::Invalid);
+        }
+        Ok(Self { tag, data })
+    }
+
+    /// Identifier of the event (`taggedEventID`).
+    #[must_use]
+    pub const fn tag(&self) -> u32 {
+        self.tag
+    }
+
+    /// Data of the event (`taggedEventData`).
+    #[must_use]
+    pub const fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// Bytes formatted as lowercase hexadecimal.
+struct Hex<'a>(&'a [u8]);
+
+impl Display for Hex<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{b:02x}"))
+    }
+}
+
+/// Human-readable dump of an event log, returned by [`v2::EventLog::dump`].
+///
+/// The [`Display`] implementation writes YAML in the style of the
+/// `tpm2_eventlog` tool. Event data that cannot be decoded is written as
+/// hexadecimal.
+#[derive(Debug)]
+pub struct EventLogDump<'a> {
+    log: &'a v2::EventLog<'a>,
+}
+
+impl<'a> EventLogDump<'a> {
+    pub(super) const fn new(log: &'a v2::EventLog<'a>) -> Self {
+        Self { log }
+    }
+}
+
+impl Display for EventLogDump<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "---")?;
+        writeln!(f, "version: 1")?;
+        writeln!(f, "events:")?;
+        let spec_id = self.log.spec_id_event();
+        if let Some(spec_id) = &spec_id {
+            writeln!(f, "- PCRIndex: 0")?;
+            writeln!(f, "  EventType: {:?}", EventType::NO_ACTION)?;
+            dump_spec_id(f, spec_id)?;
+        }
+        for (index, event) in self.log.iter().enumerate() {
+            writeln!(f, "- EventNum: {index}")?;
+            writeln!(f, "  PCRIndex: {}", event.pcr_index().0)?;
+            writeln!(f, "  EventType: {:?}", event.event_type())?;
+            let digests = event.digests();
+            writeln!(f, "  DigestCount: {}", digests.clone().into_iter().count())?;
+            writeln!(f, "  Digests:")?;
+            for (algorithm, digest) in digests {
+                writeln!(f, "  - AlgorithmId: {algorithm:?}")?;
+                writeln!(f, "    Digest: \"{}\"", Hex(digest))?;
+            }
+            writeln!(f, "  EventSize: {}", event.event_data().len())?;
+            dump_event_data(f, event.event_type(), event.event_data(), spec_id.as_ref())?;
+        }
+        Ok(())
+    }
+}
+
+fn dump_spec_id(f: &mut Formatter<'_>, spec_id: &SpecIdEvent) -> fmt::Result {
+    let (major, minor, errata) = spec_id.spec_version();
+    writeln!(f, "  SpecID:")?;
+    writeln!(f, "  - Signature: Spec ID Event03")?;
+    writeln!(f, "    platformClass: {}", spec_id.platform_class())?;
+    writeln!(f, "    specVersionMinor: {minor}")?;
+    writeln!(f, "    specVersionMajor: {major}")?;
+    writeln!(f, "    specErrata: {errata}")?;
+    writeln!(f, "    uintnSize: {}", spec_id.uintn_size())?;
+    writeln!(
+        f,
+        "    numberOfAlgorithms: {}",
+        spec_id.digest_sizes().count()
+    )?;
+    writeln!(f, "    Algorithms:")?;
+    for (index, (algorithm, size)) in spec_id.digest_sizes().enumerate() {
+        writeln!(f, "    - Algorithm[{index}]:")?;
+        writeln!(f, "      algorithmId: {algorithm:?}")?;
+        writeln!(f, "      digestSize: {size}")?;
+    }
+    writeln!(f, "    vendorInfoSize: {}", spec_id.vendor_info().len())
+}
+
+fn dump_event_data(
+    f: &mut Formatter<'_>,
+    event_type: EventType,
+    data: &[u8],
+    spec_id: Option<&SpecIdEvent>,
+) -> fmt::Result {
+    let event_data = match spec_id {
+        Some(spec_id) => EventData::parse_in_log(event_type, data, spec_id),
+        None => EventData::parse(event_type, data),
+    };
+    let Ok(event_data) = event_data else {
+        return writeln!(f, "  Event: \"{}\"", Hex(data));
+    };
+    match event_data {
+        EventData::SpecId(spec_id) => dump_spec_id(f, &spec_id),
+        EventData::StartupLocality(locality) => {
+            writeln!(f, "  Event:")?;
+            writeln!(f, "    StartupLocality: {locality}")
+        }
+        EventData::Text(text) if text.as_str() == Some("") => {
+            writeln!(f, "  Event:")?;
+            writeln!(f, "    String: \"\"")
+        }
+        EventData::Text(text) => {
+            writeln!(f, "  Event:")?;
+            writeln!(f, "    String: |-")?;
+            // The text may not end with a new line, or may contain
+            // several, so indent each line.
+            let mut writer = LineIndent {
+                f,
+                indent: "      ",
+                line_start: true,
+            };
+            fmt::Write::write_fmt(&mut writer, format_args!("{text}\n"))
+        }
+        EventData::Variable(variable) => {
+            writeln!(f, "  Event:")?;
+            writeln!(f, "    VariableName: {}", variable.vendor().0)?;
+            writeln!(f, "    UnicodeNameLength: {}", variable.name().count())?;
+            writeln!(f, "    VariableDataLength: {}", variable.data().len())?;
+            write!(f, "    UnicodeName: ")?;
+            variable.name().try_for_each(|c| write!(f, "{c}"))?;
+            writeln!(f)?;
+            writeln!(f, "    VariableData: \"{}\"", Hex(variable.data()))
+        }
+        EventData::ImageLoad(image) => {
+            writeln!(f, "  Event:")?;
+            writeln!(f, "    ImageLocationInMemory: {:#x}", image.location())?;
+            writeln!(f, "    ImageLengthInMemory: {}", image.length())?;
+            writeln!(
+                f,
+                "    ImageLinkTimeAddress: {:#x}",
+                image.link_time_address()
+            )?;
+            let device_path = image.device_path();
+            let device_path_len = device_path.map_or(0, |path| path.as_bytes().len());
+            writeln!(f, "    LengthOfDevicePath: {device_path_len}")?;
+            if let Some(device_path) = device_path {
+                writeln!(f, "    DevicePath: '{}'", GenericDevicePath(device_path))?;
+            }
+            Ok(())
+        }
+        EventData::Gpt(gpt) => {
+            let header = gpt.header();
+            writeln!(f, "  Event:")?;
+            writeln!(f, "    Header:")?;
+            writeln!(f, "      Signature: \"EFI PART\"")?;
+            writeln!(f, "      Revision: {:#x}", header.revision)?;
+            writeln!(f, "      HeaderSize: {}", header.header_size)?;
+            writeln!(f, "      HeaderCRC32: {:#010x}", header.header_crc32)?;
+            writeln!(f, "      MyLBA: {:#x}", header.my_lba)?;
+            writeln!(f, "      AlternateLBA: {:#x}", header.alternate_lba)?;
+            writeln!(f, "      FirstUsableLBA: {:#x}", header.first_usable_lba)?;
+            writeln!(f, "      LastUsableLBA: {:#x}", header.last_usable_lba)?;
+            writeln!(f, "      DiskGUID: {}", header.disk_guid)?;
+            writeln!(
+                f,
+                "      PartitionEntryLBA: {:#x}",
+                header.partition_entry_lba
+            )?;
+            writeln!(
+                f,
+                "      NumberOfPartitionEntry: {}",
+                header.number_of_partition_entries
+            )?;
+            writeln!(
+                f,
+                "      SizeOfPartitionEntry: {}",
+                header.size_of_partition_entry
+            )?;
+            writeln!(
+                f,
+                "      PartitionEntryArrayCRC32: {:#010x}",
+                header.partition_entry_array_crc32
+            )?;
+            writeln!(f, "    NumberOfPartitions: {}", gpt.partitions().count())?;
+            writeln!(f, "    Partitions:")?;
+            for partition in gpt.partitions() {
+                writeln!(
+                    f,
+                    "    - PartitionTypeGUID: {}",
+                    { partition.partition_type_guid }.0
+                )?;
+                writeln!(f, "      UniquePartitionGUID: {}", {
+                    partition.unique_partition_guid
+                })?;
+                writeln!(f, "      StartingLBA: {:#x}", { partition.starting_lba })?;
+                writeln!(f, "      EndingLBA: {:#x}", { partition.ending_lba })?;
+                writeln!(
+                    f,
+                    "      Attributes: {:#x}",
+                    { partition.attributes }.bits()
+                )?;
+                write!(f, "      PartitionName: \"")?;
+                { partition.partition_name }
+                    .iter()
+                    .take_while(|c| u16::from(**c) != 0)
+                    .try_for_each(|c| write!(f, "{}", char::from(*c)))?;
+                writeln!(f, "\"")?;
+            }
+            Ok(())
+        }
+        EventData::FirmwareBlob(blob) => {
+            writeln!(f, "  Event:")?;
+            if let Some(description) = blob.description() {
+                writeln!(f, "    BlobDescriptionSize: {}", description.bytes().len())?;
+                writeln!(f, "    BlobDescription: \"{description}\"")?;
+            }
+            writeln!(f, "    BlobBase: {:#x}", blob.base())?;
+            writeln!(f, "    BlobLength: {:#x}", blob.length())
+        }
+        EventData::Tagged(tagged) => {
+            writeln!(f, "  Event:")?;
+            writeln!(f, "    TaggedEventID: {:#010x}", tagged.tag())?;
+            writeln!(f, "    TaggedEventDataSize: {}", tagged.data().len())?;
+            writeln!(f, "    TaggedEventData: \"{}\"", Hex(tagged.data()))
+        }
+        EventData::Separator(_) | EventData::Other(_) => {
+            writeln!(f, "  Event: \"{}\"", Hex(data))
+        }
+    }
+}
+
+/// Device path formatted with the generic `Path(Type,SubType,Data)` text
+/// of each node.
+///
+/// Unlike the device path to text protocol, this does not need boot services,
+/// so event logs can also be formatted after exiting boot services.
+struct GenericDevicePath<'a>(&'a DevicePath);
+
+impl Display for GenericDevicePath<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, node) in self.0.node_iter().enumerate() {
+            if index != 0 {
+                f.write_str("/")?;
+            }
+            let (device_type, sub_type) = node.full_type();
+            write!(
+                f,
+                "Path({},{},{})",
+                device_type.0,
+                sub_type.0,
+                Hex(node.data())
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Writer that indents each line.
+struct LineIndent<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+    indent: &'static str,
+    line_start: bool,
+}
+
+impl fmt::Write for LineIndent<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for line in s.split_inclusive('\n') {
+            if self.line_start {
+                self.f.write_str(self.indent)?;
+            }
+            self.f.write_str(line)?;
+            self.line_start = line.ends_with('\n');
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cstr16;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_no_action() {
+        let mut data = STARTUP_LOCALITY_SIGNATURE.to_vec();
+        data.push(3);
+        assert!(matches!(
+            EventData::parse(EventType::NO_ACTION, &data),
+            Ok(EventData::StartupLocality(3))
+        ));
+
+        let mut data = SPEC_ID_SIGNATURE.to_vec();
+        data.extend_from_slice(&[0, 0, 0, 0, 0, 2, 0, 2, 2, 0, 0, 0]);
+        data.extend_from_slice(&[0x04, 0x00, 20, 0, 0x0b, 0x00, 32, 0]);
+        data.extend_from_slice(&[1, 0xaa]);
+        let Ok(EventData::SpecId(spec_id)) = EventData::parse(EventType::NO_ACTION, &data) else {
+            panic!("expected a Spec ID event");
+        };
+        assert_eq!(spec_id.platform_class(), 0);
+        assert_eq!(spec_id.spec_version(), (2, 0, 0));
+        assert_eq!(spec_id.uintn_size(), 2);
+        assert_eq!(
+            spec_id.digest_sizes().collect::<Vec<_>>(),
+            [(AlgorithmId::SHA1, 20), (AlgorithmId::SHA256, 32)]
+        );
+        assert_eq!(spec_id.vendor_info(), [0xaa]);
+        assert_eq!(
+            EventData::parse(EventType::NO_ACTION, &data[..data.len() - 1]).unwrap_err(),
+            EventDataError::Truncated
+        );
+
+        assert!(matches!(
+            EventData::parse(EventType::NO_ACTION, b"other"),
+            Ok(EventData::Other(b"other"))
+        ));
+    }
+
+    #[test]
+    fn test_text() {
+        let text = |event_type, data| match EventData::parse(event_type, data) {
+            Ok(EventData::Text(text)) => text,
+            _ => panic!("expected text"),
+        };
+
+        let ipl = text(EventType::IPL, b"grub_cmd: linux /vmlinuz\0");
+        assert_eq!(ipl.as_str(), Some("grub_cmd: linux /vmlinuz"));
+        assert_eq!(ipl.to_string(), "grub_cmd: linux /vmlinuz");
+
+        let version = text(EventType::CRTM_VERSION, &[b'1', 0, b'.', 0, b'0', 0, 0, 0]);
+        assert_eq!(version.as_str(), None);
+        assert_eq!(version.to_string(), "1.0");
+
+        let binary = text(EventType::IPL, &[0x12, 0x13, 0x14, 0x15]);
+        assert_eq!(binary.to_string(), "12131415");
+
+        assert!(matches!(
+            EventData::parse(EventType::SEPARATOR, &[1, 0, 0, 0]),
+            Ok(EventData::Separator(1))
+        ));
+        assert_eq!(
+            EventData::parse(EventType::SEPARATOR, &[0, 0]).unwrap_err(),
+            EventDataError::Invalid
+        );
+    }
+
+    #[test]
+    fn test_variable() {
+        let vendor = VariableVendor::GLOBAL_VARIABLE;
+        let mut data = vendor.0.to_bytes().to_vec();
+        data.extend_from_slice(&10u64.to_le_bytes());
+        data.extend_from_slice(&1u64.to_le_bytes());
+        data.extend("SecureBoot".encode_utf16().flat_map(u16::to_le_bytes));
+        data.push(1);
+
+        let Ok(EventData::Variable(variable)) =
+            EventData::parse(EventType::EFI_VARIABLE_DRIVER_CONFIG, &data)
+        else {
+            panic!("expected a variable");
+        };
+        assert_eq!(variable.vendor(), vendor);
+        assert_eq!(variable.name().collect::<String>(), "SecureBoot");
+        assert!(variable.is_variable(cstr16!("SecureBoot"), &vendor));
+        assert!(!variable.is_variable(cstr16!("SecureBoo"), &vendor));
+        assert_eq!(variable.data(), [1]);
+
+        assert_eq!(
+            EventData::parse(EventType::EFI_VARIABLE_BOOT, &data[..data.len() - 1]).unwrap_err(),
+            EventDataError::Truncated
+        );
+    }
+
+    #[test]
+    fn test_image_load() {
+        let end_node = [0x7f, 0xff, 0x04, 0x00];
+        let mut data = Vec::new();
+        // The `UINTN` size is guessed, then given explicitly.
+        for (uintn_size, known_size) in [(8, None), (4, None), (8, Some(8)), (4, Some(4))] {
+            data.clear();
+            data.extend_from_slice(&0x1_0000_1000u64.to_le_bytes());
+            for value in [0x2000u64, 0, end_node.len() as u64] {
+                data.extend_from_slice(&value.to_le_bytes()[..uintn_size]);
+            }
+            data.extend_from_slice(&end_node);
+
+            let Ok(EventData::ImageLoad(image)) = EventData::parse_with_uintn_size(
+                EventType::EFI_BOOT_SERVICES_APPLICATION,
+                &data,
+                known_size,
+            ) else {
+                panic!("expected an image load event");
+            };
+            assert_eq!(image.location(), 0x1_0000_1000);
+            assert_eq!(image.length(), 0x2000);
+            assert_eq!(image.link_time_address(), 0);
+            assert_eq!(image.device_path().unwrap().as_bytes(), end_node);
+        }
+
+        // Device path without an end node.
+        data.truncate(data.len() - end_node.len());
+        data.extend_from_slice(&[0x01, 0x01, 0x04, 0x00]);
+        assert_eq!(
+            EventData::parse(EventType::EFI_BOOT_SERVICES_DRIVER, &data).unwrap_err(),
+            EventDataError::Invalid
+        );
+    }
+
+    fn gpt_event(count_size: usize) -> Vec<u8> {
+        let mut data = GPT_SIGNATURE.to_vec();
+        for value in [0x1_0000u32, 92, 0x1234, 0] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [1u64, 0x3ff, 34, 0x3de] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&[0xab; 16]);
+        data.extend_from_slice(&2u64.to_le_bytes());
+        for value in [128u32, 128, 0x5678] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&1u64.to_le_bytes()[..count_size]);
+
+        // Partition entry.
+        data.extend_from_slice(&[0xcd; 16]);
+        data.extend_from_slice(&[0xef; 16]);
+        for value in [34u64, 0x3de, 1] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        let mut name = [0u8; 72];
+        for (i, c) in "ESP".encode_utf16().enumerate() {
+            name[i * 2..i * 2 + 2].copy_from_slice(&c.to_le_bytes());
+        }
+        data.extend_from_slice(&name);
+        data
+    }
+
+    #[test]
+    fn test_gpt() {
+        for count_size in [8, 4] {
+            let data = gpt_event(count_size);
+            let Ok(EventData::Gpt(gpt)) = EventData::parse(EventType::EFI_GPT_EVENT, &data) else {
+                panic!("expected a GPT event");
+            };
+            let header = gpt.header();
+            assert_eq!(header.revision, 0x1_0000);
+            assert_eq!(header.my_lba, 1);
+            assert_eq!(header.disk_guid, Guid::from_bytes([0xab; 16]));
+            assert_eq!(header.size_of_partition_entry, 128);
+
+            let partitions = gpt.partitions().collect::<Vec<_>>();
+            assert_eq!(partitions.len(), 1);
+            assert_eq!({ partitions[0].starting_lba }, 34);
+            assert_eq!(partitions[0].num_blocks(), Some(0x3de - 34 + 1));
+        }
+
+        let data = gpt_event(8);
+        assert_eq!(
+            EventData::parse(EventType::EFI_GPT_EVENT, &data[..data.len() - 1]).unwrap_err(),
+            EventDataError::Invalid
+        );
+    }
+
+    #[test]
+    fn test_generic_device_path() {
+        let bytes = [
+            0x01, 0x01, 0x06, 0x00, 0x02, 0x1f, // PCI
+            0x7f, 0xff, 0x04, 0x00, // End
+        ];
+        let device_path = <&DevicePath>::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(GenericDevicePath(device_path).to_string(), "Path(1,1,021f)");
+    }
+
+    #[test]
+    fn test_firmware_blob() {
+        let mut data = vec![4];
+        data.extend_from_slice(b"PEIM");
+        data.extend_from_slice(&0xff00_0000u64.to_le_bytes());
+        data.extend_from_slice(&0x1000u64.to_le_bytes());
+
+        let Ok(EventData::FirmwareBlob(blob)) =
+            EventData::parse(EventType::EFI_PLATFORM_FIRMWARE_BLOB2, &data)
+        else {
+            panic!("expected a firmware blob");
+        };
+        assert_eq!(blob.description().unwrap().as_str(), Some("PEIM"));
+        assert_eq!(blob.base(), 0xff00_0000);
+        assert_eq!(blob.length(), 0x1000);
+
+        let Ok(EventData::FirmwareBlob(blob)) =
+            EventData::parse(EventType::EFI_PLATFORM_FIRMWARE_BLOB, &data[5..])
+        else {
+            panic!("expected a firmware blob");
+        };
+        assert!(blob.description().is_none());
+        assert_eq!(blob.base(), 0xff00_0000);
+    }
+}
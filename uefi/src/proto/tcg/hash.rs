@@ -0,0 +1,761 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Hash algorithms of the TPM PCR banks.
+//!
+//! These are small `no_std` implementations of SHA-1, SHA-256, SHA-384 and
+//! SHA-512, for code that computes PCR values itself, such as the event log
+//! replay of `replay::PcrReplay`. They favor simplicity over speed.
+//!
+//! ```
+//! use uefi::proto::tcg::hash::Sha256;
+//!
+//! let mut hasher = Sha256::new();
+//! hasher.update(b"some-");
+//! hasher.update(b"data");
+//! assert_eq!(hasher.finalize(), Sha256::digest(b"some-data"));
+//! ```
+
+use super::AlgorithmId;
+
+/// Size of the largest supported digest, which is a SHA-512 digest.
+pub const MAX_DIGEST_SIZE: usize = 64;
+
+/// Get the digest size of `algorithm`, or `None` if it is not supported by
+/// this module.
+#[must_use]
+pub const fn digest_size(algorithm: AlgorithmId) -> Option<usize> {
+    match algorithm {
+        AlgorithmId::SHA1 => Some(20),
+        AlgorithmId::SHA256 => Some(32),
+        AlgorithmId::SHA384 => Some(48),
+        AlgorithmId::SHA512 => Some(64),
+        _ => None,
+    }
+}
+
+/// Hash the concatenation of `parts` with `algorithm`, and write the digest
+/// to the start of `out`.
+///
+/// Returns the size of the digest, or `None` if `algorithm` is not supported.
+///
+/// # Panics
+///
+/// Panics if `out` is smaller than the digest.
+#[must_use]
+pub fn hash_parts(algorithm: AlgorithmId, parts: &[&[u8]], out: &mut [u8]) -> Option<usize> {
+    macro_rules! hash_with {
+        ($hasher:ty) => {{
+            let mut hasher = <$hasher>::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            let digest = hasher.finalize();
+            out[..digest.len()].copy_from_slice(&digest);
+            digest.len()
+        }};
+    }
+
+    Some(match algorithm {
+        AlgorithmId::SHA1 => hash_with!(Sha1),
+        AlgorithmId::SHA256 => hash_with!(Sha256),
+        AlgorithmId::SHA384 => hash_with!(Sha384),
+        AlgorithmId::SHA512 => hash_with!(Sha512),
+        _ => return None,
+    })
+}
+
+/// Buffer of the input that does not fill a whole block yet.
+#[derive(Clone, Debug)]
+struct BlockBuffer<const N: usize> {
+    block: [u8; N],
+    len: usize,
+    total: u128,
+}
+
+impl<const N: usize> BlockBuffer<N> {
+    const fn new() -> Self {
+        Self {
+            block: [0; N],
+            len: 0,
+            total: 0,
+        }
+    }
+
+    /// Add `data`, calling `compress` for each complete block.
+    fn update(&mut self, mut data: &[u8], mut compress: impl FnMut(&[u8; N])) {
+        self.total += data.len() as u128;
+
+        if self.len > 0 {
+            let n = (N - self.len).min(data.len());
+            self.block[self.len..self.len + n].copy_from_slice(&data[..n]);
+            self.len += n;
+            data = &data[n..];
+            if self.len < N {
+                return;
+            }
+            compress(&self.block);
+            self.len = 0;
+        }
+
+        let (blocks, rest) = data.as_chunks::<N>();
+        blocks.iter().for_each(&mut compress);
+        self.block[..rest.len()].copy_from_slice(rest);
+        self.len = rest.len();
+    }
+
+    /// Pad the input, ending with its size in bits as a big endian integer of
+    /// `LEN` bytes, and compress the last blocks.
+    fn finish<const LEN: usize>(&mut self, mut compress: impl FnMut(&[u8; N])) {
+        let bits = (self.total * 8).to_be_bytes();
+
+        self.block[self.len] = 0x80;
+        self.len += 1;
+        if self.len > N - LEN {
+            self.block[self.len..].fill(0);
+            compress(&self.block);
+            self.len = 0;
+        }
+        self.block[self.len..N - LEN].fill(0);
+        self.block[N - LEN..].copy_from_slice(&bits[bits.len() - LEN..]);
+        compress(&self.block);
+    }
+}
+
+/// SHA-1 hasher.
+///
+/// SHA-1 is broken for collision resistance, but is still used by the SHA-1
+/// PCR bank.
+#[derive(Clone, Debug)]
+pub struct Sha1 {
+    state: [u32; 5],
+    buffer: BlockBuffer<64>,
+}
+
+impl Sha1 {
+    /// Create a hasher.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            state: [
+                0x6745_2301,
+                0xefcd_ab89,
+                0x98ba_dcfe,
+                0x1032_5476,
+                0xc3d2_e1f0,
+            ],
+            buffer: BlockBuffer::new(),
+        }
+    }
+
+    /// Hash `data`.
+    pub fn update(&mut self, data: &[u8]) {
+        self.buffer
+            .update(data, |block| sha1_compress(&mut self.state, block));
+    }
+
+    /// Get the digest of the data.
+    #[must_use]
+    pub fn finalize(mut self) -> [u8; 20] {
+        self.buffer
+            .finish::<8>(|block| sha1_compress(&mut self.state, block));
+        to_be_bytes(&self.state)
+    }
+
+    /// Get the digest of `data`.
+    #[must_use]
+    pub fn digest(data: &[u8]) -> [u8; 20] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn sha1_compress(state: &mut [u32; 5], block: &[u8; 64]) {
+    let mut w = [0u32; 80];
+    for (w, word) in w.iter_mut().zip(block.as_chunks::<4>().0) {
+        *w = u32::from_be_bytes(*word);
+    }
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+    for (i, w) in w.iter().enumerate() {
+        let (f, k) = match i {
+            0..20 => ((b & c) | (!b & d), 0x5a82_7999),
+            20..40 => (b ^ c ^ d, 0x6ed9_eba1),
+            40..60 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+            _ => (b ^ c ^ d, 0xca62_c1d6),
+        };
+        let t = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(*w);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = t;
+    }
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e]) {
+        *s = s.wrapping_add(v);
+    }
+}
+
+/// SHA-256 hasher.
+#[derive(Clone, Debug)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buffer: BlockBuffer<64>,
+}
+
+impl Sha256 {
+    /// Create a hasher.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            state: SHA256_IV,
+            buffer: BlockBuffer::new(),
+        }
+    }
+
+    /// Hash `data`.
+    pub fn update(&mut self, data: &[u8]) {
+        self.buffer
+            .update(data, |block| sha256_compress(&mut self.state, block));
+    }
+
+    /// Get the digest of the data.
+    #[must_use]
+    pub fn finalize(mut self) -> [u8; 32] {
+        self.buffer
+            .finish::<8>(|block| sha256_compress(&mut self.state, block));
+        to_be_bytes(&self.state)
+    }
+
+    /// Get the digest of `data`.
+    #[must_use]
+    pub fn digest(data: &[u8]) -> [u8; 32] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn sha256_compress(state: &mut [u32; 8], block: &[u8; 64]) {
+    let mut w = [0u32; 64];
+    for (w, word) in w.iter_mut().zip(block.as_chunks::<4>().0) {
+        *w = u32::from_be_bytes(*word);
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for (k, w) in SHA256_K.iter().zip(w) {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(*k)
+            .wrapping_add(w);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *s = s.wrapping_add(v);
+    }
+}
+
+/// SHA-384 hasher.
+#[derive(Clone, Debug)]
+pub struct Sha384(Sha512);
+
+impl Sha384 {
+    /// Create a hasher.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Sha512::with_iv(SHA384_IV))
+    }
+
+    /// Hash `data`.
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    /// Get the digest of the data.
+    #[must_use]
+    pub fn finalize(self) -> [u8; 48] {
+        let digest = self.0.finalize();
+        // OK to unwrap, the length is correct.
+        digest[..48].try_into().unwrap()
+    }
+
+    /// Get the digest of `data`.
+    #[must_use]
+    pub fn digest(data: &[u8]) -> [u8; 48] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+impl Default for Sha384 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SHA-512 hasher.
+#[derive(Clone, Debug)]
+pub struct Sha512 {
+    state: [u64; 8],
+    buffer: BlockBuffer<128>,
+}
+
+impl Sha512 {
+    /// Create a hasher.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_iv(SHA512_IV)
+    }
+
+    const fn with_iv(state: [u64; 8]) -> Self {
+        Self {
+            state,
+            buffer: BlockBuffer::new(),
+        }
+    }
+
+    /// Hash `data`.
+    pub fn update(&mut self, data: &[u8]) {
+        self.buffer
+            .update(data, |block| sha512_compress(&mut self.state, block));
+    }
+
+    /// Get the digest of the data.
+    #[must_use]
+    pub fn finalize(mut self) -> [u8; 64] {
+        self.buffer
+            .finish::<16>(|block| sha512_compress(&mut self.state, block));
+        to_be_bytes(&self.state)
+    }
+
+    /// Get the digest of `data`.
+    #[must_use]
+    pub fn digest(data: &[u8]) -> [u8; 64] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+impl Default for Sha512 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn sha512_compress(state: &mut [u64; 8], block: &[u8; 128]) {
+    let mut w = [0u64; 80];
+    for (w, word) in w.iter_mut().zip(block.as_chunks::<8>().0) {
+        *w = u64::from_be_bytes(*word);
+    }
+    for i in 16..80 {
+        let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+        let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for (k, w) in SHA512_K.iter().zip(w) {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(*k)
+            .wrapping_add(w);
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *s = s.wrapping_add(v);
+    }
+}
+
+/// Serialize the state words of a hasher as a big endian digest.
+fn to_be_bytes<W: Word, const N: usize, const M: usize>(state: &[W; N]) -> [u8; M] {
+    let mut out = [0; M];
+    for (chunk, word) in out.chunks_exact_mut(M / N).zip(state) {
+        word.write_be(chunk);
+    }
+    out
+}
+
+trait Word: Copy {
+    fn write_be(self, out: &mut [u8]);
+}
+
+impl Word for u32 {
+    fn write_be(self, out: &mut [u8]) {
+        out.copy_from_slice(&self.to_be_bytes());
+    }
+}
+
+impl Word for u64 {
+    fn write_be(self, out: &mut [u8]) {
+        out.copy_from_slice(&self.to_be_bytes());
+    }
+}
+
+const SHA256_K: [u32; 64] = [
+    0x428a_2f98,
+    0x7137_4491,
+    0xb5c0_fbcf,
+    0xe9b5_dba5,
+    0x3956_c25b,
+    0x59f1_11f1,
+    0x923f_82a4,
+    0xab1c_5ed5,
+    0xd807_aa98,
+    0x1283_5b01,
+    0x2431_85be,
+    0x550c_7dc3,
+    0x72be_5d74,
+    0x80de_b1fe,
+    0x9bdc_06a7,
+    0xc19b_f174,
+    0xe49b_69c1,
+    0xefbe_4786,
+    0x0fc1_9dc6,
+    0x240c_a1cc,
+    0x2de9_2c6f,
+    0x4a74_84aa,
+    0x5cb0_a9dc,
+    0x76f9_88da,
+    0x983e_5152,
+    0xa831_c66d,
+    0xb003_27c8,
+    0xbf59_7fc7,
+    0xc6e0_0bf3,
+    0xd5a7_9147,
+    0x06ca_6351,
+    0x1429_2967,
+    0x27b7_0a85,
+    0x2e1b_2138,
+    0x4d2c_6dfc,
+    0x5338_0d13,
+    0x650a_7354,
+    0x766a_0abb,
+    0x81c2_c92e,
+    0x9272_2c85,
+    0xa2bf_e8a1,
+    0xa81a_664b,
+    0xc24b_8b70,
+    0xc76c_51a3,
+    0xd192_e819,
+    0xd699_0624,
+    0xf40e_3585,
+    0x106a_a070,
+    0x19a4_c116,
+    0x1e37_6c08,
+    0x2748_774c,
+    0x34b0_bcb5,
+    0x391c_0cb3,
+    0x4ed8_aa4a,
+    0x5b9c_ca4f,
+    0x682e_6ff3,
+    0x748f_82ee,
+    0x78a5_636f,
+    0x84c8_7814,
+    0x8cc7_0208,
+    0x90be_fffa,
+    0xa450_6ceb,
+    0xbef9_a3f7,
+    0xc671_78f2,
+];
+const SHA512_K: [u64; 80] = [
+    0x428a_2f98_d728_ae22,
+    0x7137_4491_23ef_65cd,
+    0xb5c0_fbcf_ec4d_3b2f,
+    0xe9b5_dba5_8189_dbbc,
+    0x3956_c25b_f348_b538,
+    0x59f1_11f1_b605_d019,
+    0x923f_82a4_af19_4f9b,
+    0xab1c_5ed5_da6d_8118,
+    0xd807_aa98_a303_0242,
+    0x1283_5b01_4570_6fbe,
+    0x2431_85be_4ee4_b28c,
+    0x550c_7dc3_d5ff_b4e2,
+    0x72be_5d74_f27b_896f,
+    0x80de_b1fe_3b16_96b1,
+    0x9bdc_06a7_25c7_1235,
+    0xc19b_f174_cf69_2694,
+    0xe49b_69c1_9ef1_4ad2,
+    0xefbe_4786_384f_25e3,
+    0x0fc1_9dc6_8b8c_d5b5,
+    0x240c_a1cc_77ac_9c65,
+    0x2de9_2c6f_592b_0275,
+    0x4a74_84aa_6ea6_e483,
+    0x5cb0_a9dc_bd41_fbd4,
+    0x76f9_88da_8311_53b5,
+    0x983e_5152_ee66_dfab,
+    0xa831_c66d_2db4_3210,
+    0xb003_27c8_98fb_213f,
+    0xbf59_7fc7_beef_0ee4,
+    0xc6e0_0bf3_3da8_8fc2,
+    0xd5a7_9147_930a_a725,
+    0x06ca_6351_e003_826f,
+    0x1429_2967_0a0e_6e70,
+    0x27b7_0a85_46d2_2ffc,
+    0x2e1b_2138_5c26_c926,
+    0x4d2c_6dfc_5ac4_2aed,
+    0x5338_0d13_9d95_b3df,
+    0x650a_7354_8baf_63de,
+    0x766a_0abb_3c77_b2a8,
+    0x81c2_c92e_47ed_aee6,
+    0x9272_2c85_1482_353b,
+    0xa2bf_e8a1_4cf1_0364,
+    0xa81a_664b_bc42_3001,
+    0xc24b_8b70_d0f8_9791,
+    0xc76c_51a3_0654_be30,
+    0xd192_e819_d6ef_5218,
+    0xd699_0624_5565_a910,
+    0xf40e_3585_5771_202a,
+    0x106a_a070_32bb_d1b8,
+    0x19a4_c116_b8d2_d0c8,
+    0x1e37_6c08_5141_ab53,
+    0x2748_774c_df8e_eb99,
+    0x34b0_bcb5_e19b_48a8,
+    0x391c_0cb3_c5c9_5a63,
+    0x4ed8_aa4a_e341_8acb,
+    0x5b9c_ca4f_7763_e373,
+    0x682e_6ff3_d6b2_b8a3,
+    0x748f_82ee_5def_b2fc,
+    0x78a5_636f_4317_2f60,
+    0x84c8_7814_a1f0_ab72,
+    0x8cc7_0208_1a64_39ec,
+    0x90be_fffa_2363_1e28,
+    0xa450_6ceb_de82_bde9,
+    0xbef9_a3f7_b2c6_7915,
+    0xc671_78f2_e372_532b,
+    0xca27_3ece_ea26_619c,
+    0xd186_b8c7_21c0_c207,
+    0xeada_7dd6_cde0_eb1e,
+    0xf57d_4f7f_ee6e_d178,
+    0x06f0_67aa_7217_6fba,
+    0x0a63_7dc5_a2c8_98a6,
+    0x113f_9804_bef9_0dae,
+    0x1b71_0b35_131c_471b,
+    0x28db_77f5_2304_7d84,
+    0x32ca_ab7b_40c7_2493,
+    0x3c9e_be0a_15c9_bebc,
+    0x431d_67c4_9c10_0d4c,
+    0x4cc5_d4be_cb3e_42b6,
+    0x597f_299c_fc65_7e2a,
+    0x5fcb_6fab_3ad6_faec,
+    0x6c44_198c_4a47_5817,
+];
+const SHA256_IV: [u32; 8] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+const SHA384_IV: [u64; 8] = [
+    0xcbbb_9d5d_c105_9ed8,
+    0x629a_292a_367c_d507,
+    0x9159_015a_3070_dd17,
+    0x152f_ecd8_f70e_5939,
+    0x6733_2667_ffc0_0b31,
+    0x8eb4_4a87_6858_1511,
+    0xdb0c_2e0d_64f9_8fa7,
+    0x47b5_481d_befa_4fa4,
+];
+const SHA512_IV: [u64; 8] = [
+    0x6a09_e667_f3bc_c908,
+    0xbb67_ae85_84ca_a73b,
+    0x3c6e_f372_fe94_f82b,
+    0xa54f_f53a_5f1d_36f1,
+    0x510e_527f_ade6_82d1,
+    0x9b05_688c_2b3e_6c1f,
+    0x1f83_d9ab_fb41_bd6b,
+    0x5be0_cd19_137e_2179,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_BLOCKS: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+
+    #[rustfmt::skip]
+    const SHA1_EMPTY: [u8; 20] = [
+        0xda, 0x39, 0xa3, 0xee, 0x5e, 0x6b, 0x4b, 0x0d, 0x32, 0x55, 0xbf, 0xef, 0x95, 0x60, 0x18, 0x90,
+        0xaf, 0xd8, 0x07, 0x09,
+    ];
+
+    #[rustfmt::skip]
+    const SHA1_ABC: [u8; 20] = [
+        0xa9, 0x99, 0x3e, 0x36, 0x47, 0x06, 0x81, 0x6a, 0xba, 0x3e, 0x25, 0x71, 0x78, 0x50, 0xc2, 0x6c,
+        0x9c, 0xd0, 0xd8, 0x9d,
+    ];
+
+    #[rustfmt::skip]
+    const SHA1_TWO_BLOCKS: [u8; 20] = [
+        0x84, 0x98, 0x3e, 0x44, 0x1c, 0x3b, 0xd2, 0x6e, 0xba, 0xae, 0x4a, 0xa1, 0xf9, 0x51, 0x29, 0xe5,
+        0xe5, 0x46, 0x70, 0xf1,
+    ];
+
+    #[rustfmt::skip]
+    const SHA256_EMPTY: [u8; 32] = [
+        0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8, 0x99, 0x6f, 0xb9, 0x24,
+        0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c, 0xa4, 0x95, 0x99, 0x1b, 0x78, 0x52, 0xb8, 0x55,
+    ];
+
+    #[rustfmt::skip]
+    const SHA256_ABC: [u8; 32] = [
+        0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22, 0x23,
+        0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00, 0x15, 0xad,
+    ];
+
+    #[rustfmt::skip]
+    const SHA256_TWO_BLOCKS: [u8; 32] = [
+        0x24, 0x8d, 0x6a, 0x61, 0xd2, 0x06, 0x38, 0xb8, 0xe5, 0xc0, 0x26, 0x93, 0x0c, 0x3e, 0x60, 0x39,
+        0xa3, 0x3c, 0xe4, 0x59, 0x64, 0xff, 0x21, 0x67, 0xf6, 0xec, 0xed, 0xd4, 0x19, 0xdb, 0x06, 0xc1,
+    ];
+
+    #[rustfmt::skip]
+    const SHA384_EMPTY: [u8; 48] = [
+        0x38, 0xb0, 0x60, 0xa7, 0x51, 0xac, 0x96, 0x38, 0x4c, 0xd9, 0x32, 0x7e, 0xb1, 0xb1, 0xe3, 0x6a,
+        0x21, 0xfd, 0xb7, 0x11, 0x14, 0xbe, 0x07, 0x43, 0x4c, 0x0c, 0xc7, 0xbf, 0x63, 0xf6, 0xe1, 0xda,
+        0x27, 0x4e, 0xde, 0xbf, 0xe7, 0x6f, 0x65, 0xfb, 0xd5, 0x1a, 0xd2, 0xf1, 0x48, 0x98, 0xb9, 0x5b,
+    ];
+
+    #[rustfmt::skip]
+    const SHA384_ABC: [u8; 48] = [
+        0xcb, 0x00, 0x75, 0x3f, 0x45, 0xa3, 0x5e, 0x8b, 0xb5, 0xa0, 0x3d, 0x69, 0x9a, 0xc6, 0x50, 0x07,
+        0x27, 0x2c, 0x32, 0xab, 0x0e, 0xde, 0xd1, 0x63, 0x1a, 0x8b, 0x60, 0x5a, 0x43, 0xff, 0x5b, 0xed,
+        0x80, 0x86, 0x07, 0x2b, 0xa1, 0xe7, 0xcc, 0x23, 0x58, 0xba, 0xec, 0xa1, 0x34, 0xc8, 0x25, 0xa7,
+    ];
+
+    #[rustfmt::skip]
+    const SHA512_EMPTY: [u8; 64] = [
+        0xcf, 0x83, 0xe1, 0x35, 0x7e, 0xef, 0xb8, 0xbd, 0xf1, 0x54, 0x28, 0x50, 0xd6, 0x6d, 0x80, 0x07,
+        0xd6, 0x20, 0xe4, 0x05, 0x0b, 0x57, 0x15, 0xdc, 0x83, 0xf4, 0xa9, 0x21, 0xd3, 0x6c, 0xe9, 0xce,
+        0x47, 0xd0, 0xd1, 0x3c, 0x5d, 0x85, 0xf2, 0xb0, 0xff, 0x83, 0x18, 0xd2, 0x87, 0x7e, 0xec, 0x2f,
+        0x63, 0xb9, 0x31, 0xbd, 0x47, 0x41, 0x7a, 0x81, 0xa5, 0x38, 0x32, 0x7a, 0xf9, 0x27, 0xda, 0x3e,
+    ];
+
+    #[rustfmt::skip]
+    const SHA512_ABC: [u8; 64] = [
+        0xdd, 0xaf, 0x35, 0xa1, 0x93, 0x61, 0x7a, 0xba, 0xcc, 0x41, 0x73, 0x49, 0xae, 0x20, 0x41, 0x31,
+        0x12, 0xe6, 0xfa, 0x4e, 0x89, 0xa9, 0x7e, 0xa2, 0x0a, 0x9e, 0xee, 0xe6, 0x4b, 0x55, 0xd3, 0x9a,
+        0x21, 0x92, 0x99, 0x2a, 0x27, 0x4f, 0xc1, 0xa8, 0x36, 0xba, 0x3c, 0x23, 0xa3, 0xfe, 0xeb, 0xbd,
+        0x45, 0x4d, 0x44, 0x23, 0x64, 0x3c, 0xe8, 0x0e, 0x2a, 0x9a, 0xc9, 0x4f, 0xa5, 0x4c, 0xa4, 0x9f,
+    ];
+
+    #[test]
+    fn test_sha1() {
+        assert_eq!(Sha1::digest(b""), SHA1_EMPTY);
+        assert_eq!(Sha1::digest(b"abc"), SHA1_ABC);
+        assert_eq!(Sha1::digest(TWO_BLOCKS), SHA1_TWO_BLOCKS);
+    }
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(Sha256::digest(b""), SHA256_EMPTY);
+        assert_eq!(Sha256::digest(b"abc"), SHA256_ABC);
+        assert_eq!(Sha256::digest(TWO_BLOCKS), SHA256_TWO_BLOCKS);
+    }
+
+    #[test]
+    fn test_sha384() {
+        assert_eq!(Sha384::digest(b""), SHA384_EMPTY);
+        assert_eq!(Sha384::digest(b"abc"), SHA384_ABC);
+    }
+
+    #[test]
+    fn test_sha512() {
+        assert_eq!(Sha512::digest(b""), SHA512_EMPTY);
+        assert_eq!(Sha512::digest(b"abc"), SHA512_ABC);
+    }
+
+    /// Check that the result does not depend on how the data is split,
+    /// including around the padding boundaries.
+    #[test]
+    fn test_split_updates() {
+        let data: [u8; 300] = core::array::from_fn(|i| i as u8);
+        for len in [55, 56, 63, 64, 111, 112, 127, 128, 300] {
+            let data = &data[..len];
+            for split in [0, 1, len / 2, len] {
+                let (a, b) = data.split_at(split);
+                let mut sha1 = Sha1::new();
+                let mut sha512 = Sha512::new();
+                for part in [a, b] {
+                    sha1.update(part);
+                    sha512.update(part);
+                }
+                assert_eq!(sha1.finalize(), Sha1::digest(data));
+                assert_eq!(sha512.finalize(), Sha512::digest(data));
+            }
+        }
+
+        let mut out = [0; MAX_DIGEST_SIZE];
+        assert_eq!(
+            hash_parts(AlgorithmId::SHA256, &[b"a", b"bc"], &mut out),
+            Some(32)
+        );
+        assert_eq!(out[..32], SHA256_ABC);
+        assert_eq!(hash_parts(AlgorithmId::SM3_256, &[], &mut out), None);
+    }
+}
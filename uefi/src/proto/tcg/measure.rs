@@ -0,0 +1,265 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Measurement of boot components.
+//!
+//! Boot loaders must measure what they launch into the TPM, so that the boot
+//! can be attested. [`Measurer`] measures kernels, command lines, initrds
+//! and tagged events. Command lines are measured like `systemd-stub` does,
+//! and initrds like Linux does. It uses the TCG2 protocol ([`v2::Tcg`]) if
+//! available, then the confidential computing protocol
+//! ([`cc::CcMeasurement`]), and the TCG 1.2 protocol ([`v1::Tcg`])
+//! otherwise:
+//!
+//! ```no_run
+//! use uefi::cstr16;
+//! use uefi::proto::tcg::measure::Measurer;
+//!
+//! # fn example(kernel: &[u8], initrd: &[u8]) -> uefi::Result {
+//! let mut measurer = Measurer::open()?;
+//! measurer.measure_kernel(kernel, "vmlinuz")?;
+//! measurer.measure_command_line(cstr16!("root=/dev/sda2 quiet"))?;
+//! measurer.measure_initrd(initrd)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Images started with [`boot::load_image`] are measured by the firmware,
+//! and must not be measured again.
+//!
+//! [`boot::load_image`]: crate::boot::load_image
+
+use super::{EventType, PcrIndex, cc, v1, v2};
+use crate::boot::{self, ScopedProtocol};
+use crate::{CStr16, Result, Status};
+use alloc::vec::Vec;
+
+/// PCR of the code of boot loaders and kernels.
+pub const BOOT_LOADER_CODE_PCR: PcrIndex = PcrIndex(4);
+
+/// PCR of the initrd, as measured by Linux.
+pub const INITRD_PCR: PcrIndex = PcrIndex(9);
+
+/// PCR of the kernel configuration measured by kernel stubs, such as the
+/// kernel command line measured by `systemd-stub`.
+pub const KERNEL_CONFIG_PCR: PcrIndex = PcrIndex(12);
+
+/// Tag of the [`EventType::EVENT_TAG`] event of the initrd, as measured by
+/// Linux.
+pub const LINUX_INITRD_EVENT_TAG: u32 = 0x8f3b_22ed;
+
+/// Description of the initrd event, as measured by Linux.
+/// Linux measures `sizeof("Linux initrd")`, so the null terminator is
+/// included.
+const LINUX_INITRD_DESCRIPTION: &[u8] = b"Linux initrd\0";
+
+/// Opened TCG protocol.
+#[derive(Debug)]
+enum Tcg {
+    V1(ScopedProtocol<v1::Tcg>),
+    V2(ScopedProtocol<v2::Tcg>),
+    Cc(ScopedProtocol<cc::CcMeasurement>),
+}
+
+/// Measures boot components into the TPM.
+///
+/// See the [module documentation](self).
+#[derive(Debug)]
+pub struct Measurer {
+    tcg: Tcg,
+}
+
+impl Measurer {
+    /// Open the TCG2 protocol, or the CC measurement protocol if the TCG2
+    /// protocol is not available, or the TCG 1.2 protocol if neither is
+    /// available.
+    ///
+    /// The protocol is opened exclusively, until the `Measurer` is dropped.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::NOT_FOUND`]: no protocol is available, which usually
+    ///   means that there is no TPM.
+    /// * See [`boot::open_protocol_exclusive`] for errors opening the
+    ///   protocol.
+    pub fn open() -> Result<Self> {
+        if let Ok(handle) = boot::get_handle_for_protocol::<v2::Tcg>() {
+            return Ok(Self::from_v2(boot::open_protocol_exclusive(handle)?));
+        }
+        if let Ok(handle) = boot::get_handle_for_protocol::<cc::CcMeasurement>() {
+            return Ok(Self::from_cc(boot::open_protocol_exclusive(handle)?));
+        }
+        let handle = boot::get_handle_for_protocol::<v1::Tcg>()?;
+        Ok(Self::from_v1(boot::open_protocol_exclusive(handle)?))
+    }
+
+    /// Measure with an opened TCG 1.2 protocol.
+    #[must_use]
+    pub const fn from_v1(tcg: ScopedProtocol<v1::Tcg>) -> Self {
+        Self { tcg: Tcg::V1(tcg) }
+    }
+
+    /// Measure with an opened TCG2 protocol.
+    #[must_use]
+    pub const fn from_v2(tcg: ScopedProtocol<v2::Tcg>) -> Self {
+        Self { tcg: Tcg::V2(tcg) }
+    }
+
+    /// Measure with an opened CC measurement protocol.
+    #[must_use]
+    pub const fn from_cc(tcg: ScopedProtocol<cc::CcMeasurement>) -> Self {
+        Self { tcg: Tcg::Cc(tcg) }
+    }
+
+    /// Returns whether the TCG2 protocol is used.
+    #[must_use]
+    pub const fn is_tcg2(&self) -> bool {
+        matches!(self.tcg, Tcg::V2(_))
+    }
+
+    /// Returns whether the CC measurement protocol is used, in which case
+    /// PCRs are mapped to measurement registers.
+    #[must_use]
+    pub const fn is_cc(&self) -> bool {
+        matches!(self.tcg, Tcg::Cc(_))
+    }
+
+    /// Hash `data` into `pcr` in all active PCR banks, and log an event of
+    /// type `event_type` with `event_data`.
+    ///
+    /// With the CC measurement protocol, `data` is hashed into the
+    /// measurement register of `pcr`, as given by
+    /// [`cc::CcMeasurement::map_pcr_to_mr_index`].
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::INVALID_PARAMETER`]: `event_data` is too large, or `pcr`
+    ///   has no measurement register.
+    /// * [`Status::OUT_OF_RESOURCES`]: the event could not be allocated.
+    /// * [`Status::DEVICE_ERROR`]: the TPM failed to extend the PCR.
+    /// * [`Status::VOLUME_FULL`]: the event was measured, but could not be
+    ///   added to the event log.
+    pub fn measure(
+        &mut self,
+        pcr: PcrIndex,
+        event_type: EventType,
+        data: &[u8],
+        event_data: &[u8],
+    ) -> Result {
+        match &mut self.tcg {
+            Tcg::V1(tcg) => {
+                // The digest is computed by `hash_log_extend_event`.
+                let mut event = v1::PcrEvent::new_in_box(pcr, event_type, [0; 20], event_data)?;
+                tcg.hash_log_extend_event(&mut event, Some(data))
+            }
+            Tcg::V2(tcg) => {
+                let event = v2::PcrEventInputs::new_in_box(pcr, event_type, event_data)?;
+                tcg.hash_log_extend_event(v2::HashLogExtendEventFlags::empty(), data, &event)
+            }
+            Tcg::Cc(cc) => {
+                let mr_index = cc.map_pcr_to_mr_index(pcr)?;
+                let event = cc::CcEventInputs::new_in_box(mr_index, event_type, event_data)?;
+                cc.hash_log_extend_event(cc::HashLogExtendEventFlags::empty(), data, &event)
+            }
+        }
+    }
+
+    /// Measure a kernel `image` into [`BOOT_LOADER_CODE_PCR`], with an
+    /// [`EventType::IPL`] event whose data is `description`.
+    ///
+    /// # Errors
+    ///
+    /// See [`measure`](Self::measure).
+    pub fn measure_kernel(&mut self, image: &[u8], description: &str) -> Result {
+        self.measure(
+            BOOT_LOADER_CODE_PCR,
+            EventType::IPL,
+            image,
+            description.as_bytes(),
+        )
+    }
+
+    /// Measure a kernel command line into [`KERNEL_CONFIG_PCR`] like
+    /// `systemd-stub` does, with an [`EventType::IPL`] event.
+    ///
+    /// Both the measured data and the event data are the UCS-2 command line,
+    /// including its null terminator. Only [`KERNEL_CONFIG_PCR`] is extended,
+    /// not PCR 8 as `systemd-stub` also does in its legacy mode.
+    ///
+    /// # Errors
+    ///
+    /// See [`measure`](Self::measure).
+    pub fn measure_command_line(&mut self, command_line: &CStr16) -> Result {
+        let command_line = command_line.as_bytes();
+        self.measure(
+            KERNEL_CONFIG_PCR,
+            EventType::IPL,
+            command_line,
+            command_line,
+        )
+    }
+
+    /// Measure an `initrd` into [`INITRD_PCR`] like Linux does, with a tagged
+    /// event of tag [`LINUX_INITRD_EVENT_TAG`].
+    ///
+    /// # Errors
+    ///
+    /// See [`measure`](Self::measure).
+    pub fn measure_initrd(&mut self, initrd: &[u8]) -> Result {
+        self.measure_tagged(
+            INITRD_PCR,
+            LINUX_INITRD_EVENT_TAG,
+            initrd,
+            LINUX_INITRD_DESCRIPTION,
+        )
+    }
+
+    /// Measure `data` into `pcr` with an [`EventType::EVENT_TAG`] event, whose
+    /// data is `tag` and `tag_data` (`TCG_PCClientTaggedEvent`).
+    ///
+    /// # Errors
+    ///
+    /// See [`measure`](Self::measure).
+    pub fn measure_tagged(
+        &mut self,
+        pcr: PcrIndex,
+        tag: u32,
+        data: &[u8],
+        tag_data: &[u8],
+    ) -> Result {
+        let event_data = tagged_event_data(tag, tag_data)?;
+        self.measure(pcr, EventType::EVENT_TAG, data, &event_data)
+    }
+}
+
+/// Build the data of a tagged event.
+fn tagged_event_data(tag: u32, tag_data: &[u8]) -> Result<Vec<u8>> {
+    let size = u32::try_from(tag_data.len()).map_err(|_| Status::INVALID_PARAMETER)?;
+    let mut event_data = Vec::with_capacity(8 + tag_data.len());
+    event_data.extend_from_slice(&tag.to_le_bytes());
+    event_data.extend_from_slice(&size.to_le_bytes());
+    event_data.extend_from_slice(tag_data);
+    Ok(event_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::tcg::event_data::EventData;
+
+    #[test]
+    fn test_tagged_event_data() {
+        let event_data =
+            tagged_event_data(LINUX_INITRD_EVENT_TAG, LINUX_INITRD_DESCRIPTION).unwrap();
+        assert_eq!(
+            event_data[..8],
+            [0xed, 0x22, 0x3b, 0x8f, 0x0d, 0x00, 0x00, 0x00]
+        );
+
+        let Ok(EventData::Tagged(tagged)) = EventData::parse(EventType::EVENT_TAG, &event_data)
+        else {
+            panic!("expected a tagged event");
+        };
+        assert_eq!(tagged.tag(), LINUX_INITRD_EVENT_TAG);
+        assert_eq!(tagged.data(), LINUX_INITRD_DESCRIPTION);
+    }
+}
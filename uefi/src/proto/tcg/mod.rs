@@ -10,9 +10,21 @@
 //! 2.0 devices, although the spec indicates it can be used for older
 //! TPM versions as well.
 //!
+//! Confidential computing guests may provide the [`cc`] protocol, which
+//! measures into the measurement registers of the guest instead of a TPM.
+//!
 //! [TCG]: https://trustedcomputinggroup.org/
 //! [TPM]: https://en.wikipedia.org/wiki/Trusted_Platform_Module
 
+pub mod cc;
+pub mod event_data;
+pub mod hash;
+#[cfg(feature = "alloc")]
+pub mod measure;
+#[cfg(feature = "alloc")]
+pub mod replay;
+#[cfg(feature = "alloc")]
+pub mod tpm2;
 pub mod v1;
 pub mod v2;
 
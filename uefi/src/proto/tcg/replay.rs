@@ -0,0 +1,615 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Replay of the TCG2 event log.
+//!
+//! Each event of the [`v2::EventLog`] records the digests that were extended
+//! into a PCR. [`PcrReplay`] folds these digests into the expected value of
+//! each PCR, for each bank, and compares the result with the PCRs of the TPM:
+//!
+//! ```no_run
+//! use uefi::boot;
+//! use uefi::proto::tcg::replay::PcrReplay;
+//! use uefi::proto::tcg::tpm2::Tpm;
+//! use uefi::proto::tcg::v2;
+//!
+//! # fn example() -> Result<(), Box<dyn core::error::Error>> {
+//! let handle = boot::get_handle_for_protocol::<v2::Tcg>()?;
+//! let mut tcg = boot::open_protocol_exclusive::<v2::Tcg>(handle)?;
+//!
+//! let replay = PcrReplay::from_log(&tcg.get_event_log_v2()?)?;
+//! replay.verify(&mut Tpm::new(&mut *tcg))?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Only the SHA-1, SHA-256, SHA-384 and SHA-512 banks are replayed, with the
+//! implementations of the [`hash`] module. Digests of other algorithms are
+//! ignored.
+//!
+//! See the [TCG PC Client Platform Firmware Profile][spec] for the rules of
+//! the event log.
+//!
+//! [`hash`]: super::hash
+//! [spec]: https://trustedcomputinggroup.org/resource/pc-client-specific-platform-firmware-profile-specification/
+
+use super::event_data::{SpecIdEvent, startup_locality};
+use super::hash::{self, MAX_DIGEST_SIZE};
+use super::tpm2::{PcrSelection, Tpm, TpmError, Transport};
+use super::{AlgorithmId, EventType, PcrIndex, v2};
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+/// Number of PCRs that can be extended with events.
+pub const PCR_COUNT: usize = 24;
+
+/// Error returned by [`PcrReplay`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReplayError {
+    /// The event log has no Spec ID event, so its hash algorithms are
+    /// unknown.
+    MissingSpecId,
+    /// An event extends a PCR greater than or equal to [`PCR_COUNT`].
+    InvalidPcr {
+        /// Index of the event in the log.
+        event: usize,
+        /// PCR of the event.
+        pcr: PcrIndex,
+    },
+    /// An event does not have a digest for a bank that is replayed.
+    MissingDigest {
+        /// Index of the event in the log.
+        event: usize,
+        /// Algorithm of the bank.
+        hash: AlgorithmId,
+    },
+    /// The size of a digest of an event does not match its algorithm.
+    InvalidDigest {
+        /// Index of the event in the log.
+        event: usize,
+        /// Algorithm of the digest.
+        hash: AlgorithmId,
+    },
+    /// The PCRs could not be read from the TPM.
+    Tpm(TpmError),
+    /// A replayed PCR value differs from the value of the TPM.
+    Mismatch(PcrMismatch),
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSpecId => write!(f, "the event log has no Spec ID event"),
+            Self::InvalidPcr { event, pcr } => {
+                write!(f, "event {event} extends invalid PCR {}", pcr.0)
+            }
+            Self::MissingDigest { event, hash } => {
+                write!(f, "event {event} has no digest for the {hash:?} bank")
+            }
+            Self::InvalidDigest { event, hash } => {
+                write!(f, "event {event} has a {hash:?} digest of invalid size")
+            }
+            Self::Tpm(err) => write!(f, "failed to read PCRs: {err}"),
+            Self::Mismatch(mismatch) => mismatch.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for ReplayError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Tpm(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TpmError> for ReplayError {
+    fn from(err: TpmError) -> Self {
+        Self::Tpm(err)
+    }
+}
+
+/// PCR whose replayed value differs from the value of the TPM.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcrMismatch {
+    /// PCR index.
+    pub pcr: PcrIndex,
+    /// Algorithm of the bank.
+    pub hash: AlgorithmId,
+    /// Index in the log of the first event that extends the PCR.
+    ///
+    /// This is not necessarily the event that is wrong: the log cannot tell
+    /// which of the events of a PCR is wrong, or whether an event is
+    /// missing.
+    pub first_event: usize,
+    /// Value computed from the log.
+    pub expected: Vec<u8>,
+    /// Value read from the TPM.
+    pub actual: Vec<u8>,
+}
+
+impl Display for PcrMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "PCR {} in the {:?} bank, first extended by event {}, does not match \
+             the event log: expected {:02x?}, found {:02x?}",
+            self.pcr.0, self.hash, self.first_event, self.expected, self.actual
+        )
+    }
+}
+
+/// Replayed values of a PCR bank.
+#[derive(Clone, Debug)]
+struct Bank {
+    hash: AlgorithmId,
+    size: usize,
+    pcrs: [[u8; MAX_DIGEST_SIZE]; PCR_COUNT],
+}
+
+/// Expected PCR values, computed from the events of an event log.
+///
+/// The banks that are replayed are the supported algorithms of the
+/// [`SpecIdEvent`] of the log. Every event must have a digest for each of
+/// these banks.
+///
+/// Events are numbered in the order of [`v2::EventLog::iter`], starting at
+/// zero.
+#[derive(Clone, Debug)]
+pub struct PcrReplay {
+    banks: Vec<Bank>,
+    first_events: [Option<usize>; PCR_COUNT],
+    event_count: usize,
+}
+
+impl PcrReplay {
+    /// Create a replay with no events, for a log that starts with `spec_id`.
+    #[must_use]
+    pub fn new(spec_id: &SpecIdEvent<'_>) -> Self {
+        let mut banks = Vec::<Bank>::new();
+        for (hash, _) in spec_id.digest_sizes() {
+            if let Some(size) = hash::digest_size(hash)
+                && !banks.iter().any(|bank| bank.hash == hash)
+            {
+                banks.push(Bank {
+                    hash,
+                    size,
+                    pcrs: [[0; MAX_DIGEST_SIZE]; PCR_COUNT],
+                });
+            }
+        }
+        Self {
+            banks,
+            first_events: [None; PCR_COUNT],
+            event_count: 0,
+        }
+    }
+
+    /// Replay all the events of `log`.
+    ///
+    /// # Errors
+    ///
+    /// * [`ReplayError::MissingSpecId`]: the log has no Spec ID event.
+    /// * See [`add_event`](Self::add_event) for the other errors.
+    pub fn from_log(log: &v2::EventLog<'_>) -> Result<Self, ReplayError> {
+        let spec_id = log.spec_id_event().ok_or(ReplayError::MissingSpecId)?;
+        let mut replay = Self::new(&spec_id);
+        for event in log.iter() {
+            replay.add_event(&event)?;
+        }
+        Ok(replay)
+    }
+
+    /// Extend the expected PCR values with the digests of `event`.
+    ///
+    /// `EV_NO_ACTION` events are not extended, except that a
+    /// `StartupLocality` event sets the initial value of PCR 0.
+    ///
+    /// # Errors
+    ///
+    /// * [`ReplayError::InvalidPcr`]: the PCR of the event is invalid.
+    /// * [`ReplayError::MissingDigest`]: the event has no digest for a bank.
+    /// * [`ReplayError::InvalidDigest`]: the size of a digest is invalid.
+    pub fn add_event(&mut self, event: &v2::PcrEvent<'_>) -> Result<(), ReplayError> {
+        self.add(
+            event.pcr_index(),
+            event.event_type(),
+            event.event_data(),
+            event.digests(),
+        )
+    }
+
+    fn add<'a>(
+        &mut self,
+        pcr: PcrIndex,
+        event_type: EventType,
+        event_data: &[u8],
+        digests: impl IntoIterator<Item = (AlgorithmId, &'a [u8])> + Clone,
+    ) -> Result<(), ReplayError> {
+        let event = self.event_count;
+        self.event_count += 1;
+
+        if event_type == EventType::NO_ACTION {
+            if let Some(locality) = startup_locality(event_data) {
+                // The locality only applies if PCR 0 is not extended yet.
+                if pcr == PcrIndex(0) && self.first_events[0].is_none() {
+                    for bank in &mut self.banks {
+                        bank.pcrs[0][bank.size - 1] = locality;
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        let index = usize::try_from(pcr.0)
+            .ok()
+            .filter(|index| *index < PCR_COUNT)
+            .ok_or(ReplayError::InvalidPcr { event, pcr })?;
+
+        // Check all digests before extending anything. There is at most one
+        // bank per supported algorithm.
+        let mut new_values = [[0; MAX_DIGEST_SIZE]; 4];
+        for (bank, new_value) in self.banks.iter().zip(&mut new_values) {
+            let hash = bank.hash;
+            let digest = digests
+                .clone()
+                .into_iter()
+                .find_map(|(alg, digest)| (alg == hash).then_some(digest))
+                .ok_or(ReplayError::MissingDigest { event, hash })?;
+            if digest.len() != bank.size {
+                return Err(ReplayError::InvalidDigest { event, hash });
+            }
+            let current = &bank.pcrs[index][..bank.size];
+            // OK to unwrap, the algorithm of a bank is supported.
+            hash::hash_parts(hash, &[current, digest], new_value).unwrap();
+        }
+
+        for (bank, new_value) in self.banks.iter_mut().zip(new_values) {
+            bank.pcrs[index] = new_value;
+        }
+        self.first_events[index].get_or_insert(event);
+        Ok(())
+    }
+
+    /// Get the algorithms of the replayed banks.
+    pub fn banks(&self) -> impl Iterator<Item = AlgorithmId> + '_ {
+        self.banks.iter().map(|bank| bank.hash)
+    }
+
+    /// Get the expected value of `pcr` in the bank of `hash`.
+    ///
+    /// Returns `None` if the bank is not replayed or the PCR is invalid.
+    #[must_use]
+    pub fn pcr(&self, hash: AlgorithmId, pcr: PcrIndex) -> Option<&[u8]> {
+        let bank = self.banks.iter().find(|bank| bank.hash == hash)?;
+        let value = bank.pcrs.get(usize::try_from(pcr.0).ok()?)?;
+        Some(&value[..bank.size])
+    }
+
+    /// Get the index of the first event that extends `pcr`, or `None` if no
+    /// event extends it.
+    #[must_use]
+    pub fn first_event(&self, pcr: PcrIndex) -> Option<usize> {
+        *self.first_events.get(usize::try_from(pcr.0).ok()?)?
+    }
+
+    /// Compare the expected PCR values with the values of the TPM.
+    ///
+    /// Only the PCRs that are extended by at least one event are compared,
+    /// since the initial value of the others depends on the platform. Banks
+    /// that are not allocated in the TPM are skipped.
+    ///
+    /// The PCRs must not be extended between reading the event log and
+    /// calling this method.
+    ///
+    /// Returns the mismatches ordered by [`PcrMismatch::first_event`]. The
+    /// log only tells which PCRs diverge, not which of their events is
+    /// wrong.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PCRs could not be read from the TPM.
+    pub fn compare<T: Transport>(&self, tpm: &mut Tpm<T>) -> Result<Vec<PcrMismatch>, TpmError> {
+        let extended = self
+            .first_events
+            .iter()
+            .enumerate()
+            .filter(|(_, event)| event.is_some())
+            .fold(0u32, |mask, (index, _)| mask | (1 << index));
+
+        let mut mismatches = Vec::new();
+        for bank in &self.banks {
+            let mut remaining = PcrSelection {
+                hash: bank.hash,
+                pcrs: extended,
+            };
+            // The TPM may return fewer PCRs than requested.
+            while remaining.pcrs != 0 {
+                let values = tpm.pcr_read(&[remaining])?;
+                let read = values
+                    .selection
+                    .iter()
+                    .filter(|selection| selection.hash == bank.hash)
+                    .fold(0, |mask, selection| mask | selection.pcrs)
+                    & remaining.pcrs;
+                if read == 0 {
+                    // The bank is not allocated.
+                    break;
+                }
+                remaining.pcrs &= !read;
+
+                let pcrs = values
+                    .selection
+                    .iter()
+                    .flat_map(|selection| selection.iter().map(move |pcr| (selection.hash, pcr)));
+                for ((hash, pcr), actual) in pcrs.zip(values.digests) {
+                    let index = pcr.0 as usize;
+                    let Some(first_event) = self.first_events.get(index).copied().flatten() else {
+                        continue;
+                    };
+                    let expected = &bank.pcrs[index][..bank.size];
+                    if hash == bank.hash && actual != expected {
+                        mismatches.push(PcrMismatch {
+                            pcr,
+                            hash,
+                            first_event,
+                            expected: expected.to_vec(),
+                            actual,
+                        });
+                    }
+                }
+            }
+        }
+
+        mismatches.sort_by_key(|mismatch| (mismatch.first_event, mismatch.pcr.0));
+        Ok(mismatches)
+    }
+
+    /// Check that the expected PCR values match the values of the TPM.
+    ///
+    /// See [`compare`](Self::compare) for the PCRs that are checked.
+    ///
+    /// # Errors
+    ///
+    /// * [`ReplayError::Tpm`]: the PCRs could not be read from the TPM.
+    /// * [`ReplayError::Mismatch`]: a PCR does not match. The mismatch of the
+    ///   PCR that is extended first is returned.
+    pub fn verify<T: Transport>(&self, tpm: &mut Tpm<T>) -> Result<(), ReplayError> {
+        match self.compare(tpm)?.into_iter().next() {
+            Some(mismatch) => Err(ReplayError::Mismatch(mismatch)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::tcg::event_data::{SPEC_ID_SIGNATURE, STARTUP_LOCALITY_SIGNATURE};
+    use crate::proto::tcg::hash::{Sha1, Sha256};
+    use alloc::vec;
+
+    /// Transport that answers `TPM2_PCR_Read` with the given values, at most
+    /// two PCRs at a time.
+    struct FakeTpm {
+        banks: Vec<(AlgorithmId, Vec<Vec<u8>>)>,
+    }
+
+    impl Transport for FakeTpm {
+        fn submit(&mut self, command: &[u8], response: &mut [u8]) -> crate::Result {
+            // Header and a count of one.
+            assert_eq!(&command[6..10], &0x17eu32.to_be_bytes());
+            assert_eq!(&command[10..14], &1u32.to_be_bytes());
+            let hash = AlgorithmId(u16::from_be_bytes([command[14], command[15]]));
+            let select = u32::from_le_bytes([command[17], command[18], command[19], 0]);
+
+            let mut body = vec![0, 0, 0, 1];
+            let mut digests = Vec::new();
+            let mut read = 0u32;
+            if let Some((_, values)) = self.banks.iter().find(|(alg, _)| *alg == hash) {
+                for (index, value) in values.iter().enumerate() {
+                    if select & (1 << index) != 0 && read.count_ones() < 2 {
+                        read |= 1 << index;
+                        digests.push(value);
+                    }
+                }
+            }
+            body.extend_from_slice(&1u32.to_be_bytes());
+            body.extend_from_slice(&hash.0.to_be_bytes());
+            body.push(3);
+            body.extend_from_slice(&read.to_le_bytes()[..3]);
+            body.extend_from_slice(&(digests.len() as u32).to_be_bytes());
+            for digest in digests {
+                body.extend_from_slice(&(digest.len() as u16).to_be_bytes());
+                body.extend_from_slice(digest);
+            }
+
+            let size = 10 + body.len() as u32;
+            response[..2].copy_from_slice(&0x8001u16.to_be_bytes());
+            response[2..6].copy_from_slice(&size.to_be_bytes());
+            response[6..10].fill(0);
+            response[10..10 + body.len()].copy_from_slice(&body);
+            Ok(())
+        }
+    }
+
+    fn extend_sha1(pcr: &[u8], digest: &[u8]) -> [u8; 20] {
+        let mut hasher = Sha1::new();
+        hasher.update(pcr);
+        hasher.update(digest);
+        hasher.finalize()
+    }
+
+    fn extend_sha256(pcr: &[u8], digest: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(pcr);
+        hasher.update(digest);
+        hasher.finalize()
+    }
+
+    /// Replay of a few events in the SHA-1 and SHA-256 banks. The SM3 bank
+    /// is not supported, and SHA-384 is not in the Spec ID event.
+    fn replay() -> PcrReplay {
+        let mut spec_id = SPEC_ID_SIGNATURE.to_vec();
+        spec_id.extend_from_slice(&[0, 0, 0, 0, 0, 2, 0, 2]);
+        spec_id.extend_from_slice(&3u32.to_le_bytes());
+        for (hash, size) in [
+            (AlgorithmId::SHA1, 20u16),
+            (AlgorithmId::SHA256, 32),
+            (AlgorithmId::SM3_256, 32),
+        ] {
+            spec_id.extend_from_slice(&hash.0.to_le_bytes());
+            spec_id.extend_from_slice(&size.to_le_bytes());
+        }
+        spec_id.push(0);
+        let mut replay = PcrReplay::new(&SpecIdEvent::parse(&spec_id).unwrap());
+        let zeros = [0; 32];
+        let mut locality = STARTUP_LOCALITY_SIGNATURE.to_vec();
+        locality.push(3);
+        let no_action = [
+            (AlgorithmId::SHA1, &zeros[..20]),
+            (AlgorithmId::SHA256, &zeros[..]),
+            (AlgorithmId::SM3_256, &zeros[..]),
+        ];
+        replay
+            .add(PcrIndex(0), EventType::NO_ACTION, &locality, no_action)
+            .unwrap();
+
+        for (pcr, data) in [(0, &b"crtm"[..]), (4, b"boot"), (0, b"firmware")] {
+            let sha1 = Sha1::digest(data);
+            let sha256 = Sha256::digest(data);
+            let sha384 = [0; 48];
+            let digests = [
+                (AlgorithmId::SHA1, &sha1[..]),
+                (AlgorithmId::SHA256, &sha256[..]),
+                (AlgorithmId::SM3_256, &zeros[..]),
+                (AlgorithmId::SHA384, &sha384[..]),
+            ];
+            replay
+                .add(PcrIndex(pcr), EventType::IPL, &[], digests)
+                .unwrap();
+        }
+        replay
+    }
+
+    #[test]
+    fn test_replay() {
+        let replay = replay();
+        assert_eq!(
+            replay.banks().collect::<Vec<_>>(),
+            [AlgorithmId::SHA1, AlgorithmId::SHA256]
+        );
+        assert_eq!(replay.first_event(PcrIndex(0)), Some(1));
+        assert_eq!(replay.first_event(PcrIndex(4)), Some(2));
+        assert_eq!(replay.first_event(PcrIndex(5)), None);
+        assert_eq!(replay.pcr(AlgorithmId::SM3_256, PcrIndex(0)), None);
+        assert_eq!(replay.pcr(AlgorithmId::SHA384, PcrIndex(0)), None);
+
+        let mut pcr0 = [0; 20];
+        pcr0[19] = 3;
+        let pcr0 = extend_sha1(&pcr0, &Sha1::digest(b"crtm"));
+        let pcr0 = extend_sha1(&pcr0, &Sha1::digest(b"firmware"));
+        assert_eq!(replay.pcr(AlgorithmId::SHA1, PcrIndex(0)), Some(&pcr0[..]));
+
+        let pcr4 = extend_sha256(&[0; 32], &Sha256::digest(b"boot"));
+        assert_eq!(
+            replay.pcr(AlgorithmId::SHA256, PcrIndex(4)),
+            Some(&pcr4[..])
+        );
+        assert_eq!(
+            replay.pcr(AlgorithmId::SHA256, PcrIndex(5)),
+            Some(&[0; 32][..])
+        );
+    }
+
+    #[test]
+    fn test_invalid_events() {
+        let mut replay = replay();
+        let sha1 = [0; 20];
+        assert_eq!(
+            replay.add(
+                PcrIndex(24),
+                EventType::IPL,
+                &[],
+                [(AlgorithmId::SHA1, &sha1[..])]
+            ),
+            Err(ReplayError::InvalidPcr {
+                event: 4,
+                pcr: PcrIndex(24)
+            })
+        );
+        assert_eq!(
+            replay.add(
+                PcrIndex(4),
+                EventType::IPL,
+                &[],
+                [(AlgorithmId::SHA1, &sha1[..])]
+            ),
+            Err(ReplayError::MissingDigest {
+                event: 5,
+                hash: AlgorithmId::SHA256
+            })
+        );
+        assert_eq!(
+            replay.add(
+                PcrIndex(4),
+                EventType::IPL,
+                &[],
+                [
+                    (AlgorithmId::SHA1, &sha1[..]),
+                    (AlgorithmId::SHA256, &sha1[..])
+                ]
+            ),
+            Err(ReplayError::InvalidDigest {
+                event: 6,
+                hash: AlgorithmId::SHA256
+            })
+        );
+        // The SHA-1 bank was not extended by the invalid event.
+        assert_eq!(
+            replay.pcr(AlgorithmId::SHA1, PcrIndex(4)),
+            Some(&extend_sha1(&[0; 20], &Sha1::digest(b"boot"))[..])
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let replay = replay();
+        let values = |hash| -> Vec<Vec<u8>> {
+            (0..PCR_COUNT as u32)
+                .map(|pcr| replay.pcr(hash, PcrIndex(pcr)).unwrap().to_vec())
+                .collect()
+        };
+        let mut tpm = Tpm::new(FakeTpm {
+            banks: vec![
+                (AlgorithmId::SHA1, values(AlgorithmId::SHA1)),
+                (AlgorithmId::SHA256, values(AlgorithmId::SHA256)),
+            ],
+        });
+        replay.verify(&mut tpm).unwrap();
+
+        // PCRs that are not extended by the log are not compared, and PCR 4 in
+        // the SHA-256 bank is wrong.
+        let mut fake = tpm.into_inner();
+        fake.banks[1].1[4][0] ^= 1;
+        fake.banks[1].1[7][0] ^= 1;
+        let mut tpm = Tpm::new(fake);
+        let mismatches = replay.compare(&mut tpm).unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].pcr, PcrIndex(4));
+        assert_eq!(mismatches[0].hash, AlgorithmId::SHA256);
+        assert_eq!(mismatches[0].first_event, 2);
+
+        // Mismatches are ordered by their first event. The SHA-1 bank is not
+        // allocated, so it is skipped.
+        let mut fake = tpm.into_inner();
+        fake.banks[0].0 = AlgorithmId::SHA384;
+        fake.banks[1].1[0][0] ^= 1;
+        let mut tpm = Tpm::new(fake);
+        let Err(ReplayError::Mismatch(mismatch)) = replay.verify(&mut tpm) else {
+            panic!("expected a mismatch");
+        };
+        assert_eq!(mismatch.pcr, PcrIndex(0));
+        assert_eq!(mismatch.first_event, 1);
+    }
+}
@@ -0,0 +1,301 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use core::fmt::{self, Display, Formatter};
+
+/// Error returned by the commands of a [`Tpm`].
+///
+/// [`Tpm`]: super::Tpm
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TpmError {
+    /// The command could not be sent to the TPM.
+    Transport(crate::Error),
+    /// The TPM returned an error.
+    Response(ResponseCode),
+    /// The response of the TPM is malformed.
+    InvalidResponse,
+    /// A parameter is too large for the command.
+    ParameterTooLarge,
+}
+
+impl Display for TpmError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport(err) => write!(f, "failed to submit TPM command: {err}"),
+            Self::Response(code) => write!(f, "TPM error: {code}"),
+            Self::InvalidResponse => write!(f, "invalid TPM response"),
+            Self::ParameterTooLarge => write!(f, "TPM command parameter is too large"),
+        }
+    }
+}
+
+impl core::error::Error for TpmError {}
+
+impl From<crate::Error> for TpmError {
+    fn from(err: crate::Error) -> Self {
+        Self::Transport(err)
+    }
+}
+
+newtype_enum! {
+    /// TPM response code (`TPM_RC`).
+    ///
+    /// Format-one codes also encode the handle, session or parameter that caused
+    /// the error. Use [`base`](Self::base) to compare them with the constants of
+    /// this type, and [`location`](Self::location) to get the cause:
+    ///
+    /// ```
+    /// use uefi::proto::tcg::tpm2::{ErrorLocation, ResponseCode};
+    ///
+    /// // Authorization failed for the first session.
+    /// let code = ResponseCode(0x98e);
+    /// assert_eq!(code.base(), ResponseCode::AUTH_FAIL);
+    /// assert_eq!(code.location(), Some(ErrorLocation::Session(1)));
+    /// ```
+    pub enum ResponseCode: u32 => {
+        /// The command succeeded.
+        SUCCESS = 0x000,
+        /// The command has a TPM 1.2 tag, rather than a TPM 2.0 tag.
+        BAD_TAG = 0x01e,
+
+        /// The TPM is not initialized with `TPM2_Startup`.
+        INITIALIZE = 0x100,
+        /// The TPM is in failure mode.
+        FAILURE = 0x101,
+        /// Improper use of a sequence handle.
+        SEQUENCE = 0x103,
+        /// The command is disabled.
+        DISABLED = 0x120,
+        /// The command failed because an audit sequence is active.
+        EXCLUSIVE = 0x121,
+        /// The authorization handle is not correct for the command.
+        AUTH_TYPE = 0x124,
+        /// The command requires an authorization session.
+        AUTH_MISSING = 0x125,
+        /// The policy failed.
+        POLICY = 0x126,
+        /// The PCR check failed.
+        PCR = 0x127,
+        /// The PCRs changed since the policy was checked.
+        PCR_CHANGED = 0x128,
+        /// The TPM is in field upgrade mode.
+        UPGRADE = 0x12d,
+        /// Context ID counter is at its maximum.
+        TOO_MANY_CONTEXTS = 0x12e,
+        /// The authorization value or policy is not available.
+        AUTH_UNAVAILABLE = 0x12f,
+        /// The TPM must be reset.
+        REBOOT = 0x130,
+        /// The protection algorithms are unbalanced.
+        UNBALANCED = 0x131,
+        /// The command size is inconsistent.
+        COMMAND_SIZE = 0x142,
+        /// The command code is not supported.
+        COMMAND_CODE = 0x143,
+        /// The authorization size is inconsistent.
+        AUTHSIZE = 0x144,
+        /// The command does not allow authorization sessions.
+        AUTH_CONTEXT = 0x145,
+        /// The NV offset and size are out of range.
+        NV_RANGE = 0x146,
+        /// The NV size is not allowed.
+        NV_SIZE = 0x147,
+        /// The NV index is locked.
+        NV_LOCKED = 0x148,
+        /// The NV access authorization failed.
+        NV_AUTHORIZATION = 0x149,
+        /// The NV index was never written.
+        NV_UNINITIALIZED = 0x14a,
+        /// There is no space in NV memory.
+        NV_SPACE = 0x14b,
+        /// The NV index is already defined.
+        NV_DEFINED = 0x14c,
+        /// The context could not be loaded.
+        BAD_CONTEXT = 0x150,
+        /// The command parameter hash is invalid.
+        CPHASH = 0x151,
+        /// The handle is not a parent.
+        PARENT = 0x152,
+        /// A self test must be run.
+        NEEDS_TEST = 0x153,
+        /// Not enough entropy to complete the command.
+        NO_RESULT = 0x154,
+        /// The sensitive area did not unmarshal correctly.
+        SENSITIVE = 0x155,
+
+        /// The asymmetric algorithm is not supported or not correct.
+        ASYMMETRIC = 0x081,
+        /// Inconsistent attributes.
+        ATTRIBUTES = 0x082,
+        /// The hash algorithm is not supported or not appropriate.
+        HASH = 0x083,
+        /// The value is out of range or not correct.
+        VALUE = 0x084,
+        /// The hierarchy is not enabled or not correct.
+        HIERARCHY = 0x085,
+        /// The key size is not supported.
+        KEY_SIZE = 0x087,
+        /// The mask generation function is not supported.
+        MGF = 0x088,
+        /// The mode of operation is not supported.
+        MODE = 0x089,
+        /// The type of the value is not appropriate.
+        TYPE = 0x08a,
+        /// The handle is not correct.
+        HANDLE = 0x08b,
+        /// The key derivation function is not supported.
+        KDF = 0x08c,
+        /// The value is out of range.
+        RANGE = 0x08d,
+        /// The authorization HMAC check failed, or the password is wrong.
+        AUTH_FAIL = 0x08e,
+        /// Invalid nonce size or nonce value mismatch.
+        NONCE = 0x08f,
+        /// Physical presence is required.
+        PP = 0x090,
+        /// The scheme is not supported or not correct.
+        SCHEME = 0x092,
+        /// The size is not correct.
+        SIZE = 0x095,
+        /// The symmetric algorithm is not supported or not correct.
+        SYMMETRIC = 0x096,
+        /// Incorrect structure tag.
+        TAG = 0x097,
+        /// The union selector is not correct.
+        SELECTOR = 0x098,
+        /// Not enough input to unmarshal a value.
+        INSUFFICIENT = 0x09a,
+        /// The signature is not valid.
+        SIGNATURE = 0x09b,
+        /// The key fields are not compatible with the selected use.
+        KEY = 0x09c,
+        /// The policy check failed.
+        POLICY_FAIL = 0x09d,
+        /// The integrity check failed.
+        INTEGRITY = 0x09f,
+        /// The ticket is not valid.
+        TICKET = 0x0a0,
+        /// Reserved bits are not zero.
+        RESERVED_BITS = 0x0a1,
+        /// The authorization is not valid for the entity.
+        BAD_AUTH = 0x0a2,
+        /// The policy expired.
+        EXPIRED = 0x0a3,
+        /// The command code in the policy is not the command being run.
+        POLICY_CC = 0x0a4,
+        /// The public and sensitive areas are not bound.
+        BINDING = 0x0a5,
+        /// The curve is not supported.
+        CURVE = 0x0a6,
+        /// The point is not on the curve.
+        ECC_POINT = 0x0a7,
+
+        /// The gap for context IDs is too large.
+        CONTEXT_GAP = 0x901,
+        /// Out of memory for objects.
+        OBJECT_MEMORY = 0x902,
+        /// Out of memory for sessions.
+        SESSION_MEMORY = 0x903,
+        /// Out of shared object and session memory.
+        MEMORY = 0x904,
+        /// Out of session handles.
+        SESSION_HANDLES = 0x905,
+        /// Out of object handles.
+        OBJECT_HANDLES = 0x906,
+        /// Bad locality.
+        LOCALITY = 0x907,
+        /// The TPM suspended the command, which should be retried.
+        YIELDED = 0x908,
+        /// The command was canceled.
+        CANCELED = 0x909,
+        /// The TPM is running self tests.
+        TESTING = 0x90a,
+        /// The TPM is rate-limiting NV writes.
+        NV_RATE = 0x920,
+        /// Authorizations are locked out because of dictionary attack protection.
+        LOCKOUT = 0x921,
+        /// The TPM was not able to start the command; it should be retried.
+        RETRY = 0x922,
+        /// NV memory is not available.
+        NV_UNAVAILABLE = 0x923,
+    }
+}
+
+impl ResponseCode {
+    const FORMAT_ONE: u32 = 0x080;
+    const PARAMETER: u32 = 0x040;
+    const VENDOR: u32 = 0x400;
+
+    /// Returns whether this is a format-one code, which also encodes the
+    /// location of the error.
+    #[must_use]
+    pub const fn is_format_one(self) -> bool {
+        self.0 & Self::FORMAT_ONE != 0
+    }
+
+    /// Returns whether this is a warning, for which the command may be
+    /// retried.
+    #[must_use]
+    pub const fn is_warning(self) -> bool {
+        !self.is_format_one() && self.0 & 0x900 == 0x900
+    }
+
+    /// Returns whether this is a vendor-defined code.
+    #[must_use]
+    pub const fn is_vendor(self) -> bool {
+        !self.is_format_one() && self.0 & Self::VENDOR != 0
+    }
+
+    /// Get the code without the location of the error, which can be compared
+    /// with the constants of this type.
+    #[must_use]
+    pub const fn base(self) -> Self {
+        if self.is_format_one() {
+            Self(self.0 & 0xbf)
+        } else {
+            self
+        }
+    }
+
+    /// Get the location of the error, for format-one codes that have one.
+    #[must_use]
+    pub const fn location(self) -> Option<ErrorLocation> {
+        if !self.is_format_one() {
+            return None;
+        }
+        let number = ((self.0 >> 8) & 0xf) as u8;
+        if self.0 & Self::PARAMETER != 0 {
+            Some(ErrorLocation::Parameter(number))
+        } else if number == 0 {
+            None
+        } else if number < 8 {
+            Some(ErrorLocation::Handle(number))
+        } else {
+            Some(ErrorLocation::Session(number - 8))
+        }
+    }
+}
+
+impl Display for ResponseCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} ({:#x})", self.base(), self.0)?;
+        match self.location() {
+            Some(ErrorLocation::Handle(n)) => write!(f, " for handle {n}"),
+            Some(ErrorLocation::Parameter(n)) => write!(f, " for parameter {n}"),
+            Some(ErrorLocation::Session(n)) => write!(f, " for session {n}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Location of an error, encoded in a format-one [`ResponseCode`].
+///
+/// Numbers start at one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ErrorLocation {
+    /// Handle of the command.
+    Handle(u8),
+    /// Parameter of the command.
+    Parameter(u8),
+    /// Authorization session of the command.
+    Session(u8),
+}
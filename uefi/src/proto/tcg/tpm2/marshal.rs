@@ -0,0 +1,244 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Marshalling of TPM 2.0 command and response buffers.
+//!
+//! All TPM structures are big endian. Sized buffers (`TPM2B_*`) are a `u16`
+//! size followed by the bytes, and lists (`TPML_*`) are a `u32` count
+//! followed by the elements.
+
+use super::{AuthSession, CommandCode, ResponseCode, StructureTag, TpmError, TpmHandle};
+use alloc::vec::Vec;
+
+/// Size of the command and response header: tag, size and code.
+pub(super) const HEADER_SIZE: usize = 10;
+
+/// Handle of the password authorization session.
+const PASSWORD_SESSION: u32 = 0x4000_0009;
+
+/// Session attribute that keeps the session open after the command.
+const CONTINUE_SESSION: u8 = 0x01;
+
+/// Buffer that a command is written to.
+#[derive(Debug)]
+pub(super) struct Command {
+    code: CommandCode,
+    handles: Vec<u8>,
+    auth: Vec<u8>,
+    params: Vec<u8>,
+}
+
+impl Command {
+    pub(super) const fn new(code: CommandCode) -> Self {
+        Self {
+            code,
+            handles: Vec::new(),
+            auth: Vec::new(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Add a handle to the handle area.
+    pub(super) fn handle(&mut self, handle: TpmHandle) -> &mut Self {
+        self.handles.extend_from_slice(&handle.0.to_be_bytes());
+        self
+    }
+
+    /// Add an authorization session for the next handle that needs one.
+    pub(super) fn auth(&mut self, session: &AuthSession) -> Result<&mut Self, TpmError> {
+        let (handle, attributes, hmac) = match *session {
+            AuthSession::Password(password) => (PASSWORD_SESSION, CONTINUE_SESSION, password),
+            AuthSession::Policy(handle) => (handle.0, 0, &[][..]),
+        };
+        self.auth.extend_from_slice(&handle.to_be_bytes());
+        // Empty nonce.
+        self.auth.extend_from_slice(&0u16.to_be_bytes());
+        self.auth.push(attributes);
+        put_sized(&mut self.auth, hmac)?;
+        Ok(self)
+    }
+
+    /// Get the parameter area.
+    pub(super) const fn params(&mut self) -> &mut Vec<u8> {
+        &mut self.params
+    }
+
+    /// Get the complete command.
+    pub(super) fn finish(&self) -> Result<Vec<u8>, TpmError> {
+        let (tag, auth_size) = if self.auth.is_empty() {
+            (StructureTag::NO_SESSIONS, 0)
+        } else {
+            (StructureTag::SESSIONS, 4 + self.auth.len())
+        };
+        let size = HEADER_SIZE + self.handles.len() + auth_size + self.params.len();
+        let size = u32::try_from(size).map_err(|_| TpmError::ParameterTooLarge)?;
+
+        let mut bytes = Vec::with_capacity(size as usize);
+        bytes.extend_from_slice(&tag.0.to_be_bytes());
+        bytes.extend_from_slice(&size.to_be_bytes());
+        bytes.extend_from_slice(&self.code.0.to_be_bytes());
+        bytes.extend_from_slice(&self.handles);
+        if !self.auth.is_empty() {
+            put_u32(&mut bytes, self.auth.len() as u32);
+            bytes.extend_from_slice(&self.auth);
+        }
+        bytes.extend_from_slice(&self.params);
+        Ok(bytes)
+    }
+}
+
+pub(super) fn put_u8(buf: &mut Vec<u8>, value: u8) {
+    buf.push(value);
+}
+
+pub(super) fn put_u16(buf: &mut Vec<u8>, value: u16) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+pub(super) fn put_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Write a sized buffer (`TPM2B`).
+pub(super) fn put_sized(buf: &mut Vec<u8>, bytes: &[u8]) -> Result<(), TpmError> {
+    let size = u16::try_from(bytes.len()).map_err(|_| TpmError::ParameterTooLarge)?;
+    put_u16(buf, size);
+    buf.extend_from_slice(bytes);
+    Ok(())
+}
+
+/// Write a sized structure, which is written by `f` after the size.
+pub(super) fn put_sized_with(
+    buf: &mut Vec<u8>,
+    f: impl FnOnce(&mut Vec<u8>) -> Result<(), TpmError>,
+) -> Result<(), TpmError> {
+    let start = buf.len();
+    put_u16(buf, 0);
+    f(buf)?;
+    let size = u16::try_from(buf.len() - start - 2).map_err(|_| TpmError::ParameterTooLarge)?;
+    buf[start..start + 2].copy_from_slice(&size.to_be_bytes());
+    Ok(())
+}
+
+/// Write a list (`TPML`) of `items`, each written by `f`.
+pub(super) fn put_list<T>(
+    buf: &mut Vec<u8>,
+    items: &[T],
+    mut f: impl FnMut(&mut Vec<u8>, &T) -> Result<(), TpmError>,
+) -> Result<(), TpmError> {
+    let count = u32::try_from(items.len()).map_err(|_| TpmError::ParameterTooLarge)?;
+    put_u32(buf, count);
+    items.iter().try_for_each(|item| f(buf, item))
+}
+
+/// Reader of a response buffer.
+#[derive(Clone, Debug)]
+pub(super) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(super) const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub(super) fn bytes(&mut self, len: usize) -> Result<&'a [u8], TpmError> {
+        let (bytes, rest) = self
+            .bytes
+            .split_at_checked(len)
+            .ok_or(TpmError::InvalidResponse)?;
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    pub(super) fn array<const N: usize>(&mut self) -> Result<[u8; N], TpmError> {
+        // OK to unwrap, the length is correct.
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    pub(super) fn u8(&mut self) -> Result<u8, TpmError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    pub(super) fn u16(&mut self) -> Result<u16, TpmError> {
+        self.array().map(u16::from_be_bytes)
+    }
+
+    pub(super) fn u32(&mut self) -> Result<u32, TpmError> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    /// Read a sized buffer (`TPM2B`).
+    pub(super) fn sized(&mut self) -> Result<&'a [u8], TpmError> {
+        let size = self.u16()?;
+        self.bytes(usize::from(size))
+    }
+
+    /// Read a sized structure, which must be fully read by `f`.
+    pub(super) fn sized_with<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, TpmError>,
+    ) -> Result<T, TpmError> {
+        let mut inner = Self::new(self.sized()?);
+        let value = f(&mut inner)?;
+        inner.finish()?;
+        Ok(value)
+    }
+
+    /// Read a list (`TPML`), each element being read by `f`.
+    pub(super) fn list<T>(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> Result<T, TpmError>,
+    ) -> Result<Vec<T>, TpmError> {
+        let count = self.u32()?;
+        // Each element is at least one byte, which bounds the allocation.
+        if count as usize > self.bytes.len() {
+            return Err(TpmError::InvalidResponse);
+        }
+        (0..count).map(|_| f(self)).collect()
+    }
+
+    /// Check that all bytes were read.
+    pub(super) const fn finish(&self) -> Result<(), TpmError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(TpmError::InvalidResponse)
+        }
+    }
+}
+
+/// Parsed response buffer.
+#[derive(Debug)]
+pub(super) struct Response<'a> {
+    pub(super) handles: Reader<'a>,
+    pub(super) params: Reader<'a>,
+}
+
+impl<'a> Response<'a> {
+    /// Parse the response to a command with `handle_count` response handles.
+    pub(super) fn parse(bytes: &'a [u8], handle_count: usize) -> Result<Self, TpmError> {
+        let mut reader = Reader::new(bytes);
+        let tag = StructureTag(reader.u16()?);
+        let size = reader.u32()? as usize;
+        let code = ResponseCode(reader.u32()?);
+        if code != ResponseCode::SUCCESS {
+            return Err(TpmError::Response(code));
+        }
+        let body = bytes
+            .get(HEADER_SIZE..size)
+            .ok_or(TpmError::InvalidResponse)?;
+
+        let mut reader = Reader::new(body);
+        let handles = Reader::new(reader.bytes(handle_count * 4)?);
+        let params = match tag {
+            StructureTag::NO_SESSIONS => reader,
+            StructureTag::SESSIONS => {
+                let size = reader.u32()? as usize;
+                // The authorization area that follows is not checked.
+                Reader::new(reader.bytes(size)?)
+            }
+            _ => return Err(TpmError::InvalidResponse),
+        };
+        Ok(Self { handles, params })
+    }
+}
@@ -0,0 +1,875 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Typed TPM 2.0 commands.
+//!
+//! [`v2::Tcg::submit_command`] only transfers raw command and response
+//! buffers. [`Tpm`] builds these buffers for common commands, parses the
+//! responses, and decodes TPM error codes into [`TpmError`]:
+//!
+//! ```no_run
+//! use uefi::boot;
+//! use uefi::proto::tcg::tpm2::{PcrSelection, Tpm};
+//! use uefi::proto::tcg::{AlgorithmId, PcrIndex, v2};
+//!
+//! # fn example() -> Result<(), Box<dyn core::error::Error>> {
+//! let handle = boot::get_handle_for_protocol::<v2::Tcg>()?;
+//! let mut tcg = boot::open_protocol_exclusive::<v2::Tcg>(handle)?;
+//! let mut tpm = Tpm::new(&mut *tcg);
+//!
+//! let pcr = tpm.read_pcr(AlgorithmId::SHA256, PcrIndex(7))?;
+//! log::info!("PCR 7: {pcr:02x?}");
+//!
+//! let mut nonce = [0; 16];
+//! tpm.get_random(&mut nonce)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Only password and policy sessions are supported to authorize commands.
+//! HMAC sessions and parameter encryption are not supported.
+//!
+//! See the [TPM 2.0 Library Specification][spec], in particular Part 2
+//! (Structures) and Part 3 (Commands).
+//!
+//! [`v2::Tcg::submit_command`]: super::v2::Tcg::submit_command
+//! [spec]: https://trustedcomputinggroup.org/resource/tpm-library-specification/
+
+mod error;
+mod marshal;
+mod types;
+
+pub use error::{ErrorLocation, ResponseCode, TpmError};
+pub use types::{
+    AuthSession, Capability, CapabilityData, CommandCode, NvAttributes, NvPublic, ObjectAttributes,
+    PcrSelection, Public, PublicParameters, PublicUnique, Scheme, SensitiveCreate, SessionType,
+    StructureTag, SymmetricDefinition, TpmHandle, TpmProperty,
+};
+
+use super::v2::Tcg;
+use super::{AlgorithmId, PcrIndex};
+use alloc::vec;
+use alloc::vec::Vec;
+use marshal::{Command, Response, put_list, put_sized, put_u8, put_u16, put_u32};
+
+/// Size of the response buffer, which is the largest response size of
+/// common TPMs.
+const RESPONSE_BUFFER_SIZE: usize = 4096;
+
+/// Size of the nonce used to start sessions.
+const NONCE_SIZE: usize = 16;
+
+/// Interface used to send commands to a TPM.
+///
+/// It is implemented by [`Tcg`], and can be implemented by other types to
+/// send commands in a different way, or for testing.
+pub trait Transport {
+    /// Send `command` to the TPM, and write the response to `response`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command could not be sent, or if the response
+    /// does not fit in `response`.
+    fn submit(&mut self, command: &[u8], response: &mut [u8]) -> crate::Result;
+}
+
+impl Transport for Tcg {
+    fn submit(&mut self, command: &[u8], response: &mut [u8]) -> crate::Result {
+        self.submit_command(command, response)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for &mut T {
+    fn submit(&mut self, command: &[u8], response: &mut [u8]) -> crate::Result {
+        (**self).submit(command, response)
+    }
+}
+
+/// PCR values returned by [`Tpm::pcr_read`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PcrValues {
+    /// Counter that is incremented when a PCR is extended.
+    pub update_counter: u32,
+    /// PCRs that were read, which may be fewer than the requested PCRs.
+    pub selection: Vec<PcrSelection>,
+    /// Values of the PCRs that were read, in the order of the selection.
+    pub digests: Vec<Vec<u8>>,
+}
+
+/// Public area and names of an object, returned by [`Tpm::read_public`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ObjectInfo {
+    /// Public area of the object.
+    pub public: Public,
+    /// Name of the object.
+    pub name: Vec<u8>,
+    /// Qualified name of the object, which includes the names of its
+    /// ancestors.
+    pub qualified_name: Vec<u8>,
+}
+
+/// Object created by [`Tpm::create_primary`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PrimaryObject {
+    /// Transient handle of the object, which should be flushed with
+    /// [`Tpm::flush_context`] once it is not needed.
+    pub handle: TpmHandle,
+    /// Public area of the object.
+    pub public: Public,
+    /// Name of the object.
+    pub name: Vec<u8>,
+}
+
+/// Client for the commands of a TPM 2.0 device.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Debug)]
+pub struct Tpm<T: Transport> {
+    transport: T,
+}
+
+impl<T: Transport> Tpm<T> {
+    /// Create a client that sends commands with `transport`, usually a
+    /// `&mut` [`Tcg`].
+    pub const fn new(transport: T) -> Self {
+        Self { transport }
+    }
+
+    /// Get the transport.
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+
+    /// Send `command`, and parse the response with `parse`, which must read
+    /// all parameters.
+    fn execute<R>(
+        &mut self,
+        command: &Command,
+        handle_count: usize,
+        parse: impl FnOnce(&mut Response) -> Result<R, TpmError>,
+    ) -> Result<R, TpmError> {
+        let bytes = command.finish()?;
+        let mut buffer = vec![0; RESPONSE_BUFFER_SIZE];
+        self.transport.submit(&bytes, &mut buffer)?;
+
+        let mut response = Response::parse(&buffer, handle_count)?;
+        let value = parse(&mut response)?;
+        response.params.finish()?;
+        Ok(value)
+    }
+
+    /// Read PCR values (`TPM2_PCR_Read`).
+    ///
+    /// The TPM returns at most eight values. PCRs that were not read are
+    /// absent from [`PcrValues::selection`], and can be read with another
+    /// call.
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    /// * [`TpmError::ParameterTooLarge`]: a PCR greater than 23 is selected.
+    pub fn pcr_read(&mut self, selection: &[PcrSelection]) -> Result<PcrValues, TpmError> {
+        let mut command = Command::new(CommandCode::PCR_READ);
+        put_list(command.params(), selection, |buf, pcr| pcr.marshal(buf))?;
+
+        self.execute(&command, 0, |response| {
+            let r = &mut response.params;
+            Ok(PcrValues {
+                update_counter: r.u32()?,
+                selection: r.list(PcrSelection::unmarshal)?,
+                digests: r.list(|r| r.sized().map(<[u8]>::to_vec))?,
+            })
+        })
+    }
+
+    /// Read the value of `pcr` in the bank of `hash`.
+    ///
+    /// Returns `None` if the bank is not allocated.
+    ///
+    /// # Errors
+    ///
+    /// See [`pcr_read`](Self::pcr_read).
+    pub fn read_pcr(
+        &mut self,
+        hash: AlgorithmId,
+        pcr: PcrIndex,
+    ) -> Result<Option<Vec<u8>>, TpmError> {
+        let values = self.pcr_read(&[PcrSelection::new(hash, &[pcr])])?;
+        let read = values
+            .selection
+            .iter()
+            .any(|selection| selection.hash == hash && selection.contains(pcr));
+        Ok(if read {
+            values.digests.into_iter().next()
+        } else {
+            None
+        })
+    }
+
+    /// Fill `buffer` with random bytes from the TPM (`TPM2_GetRandom`).
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    pub fn get_random(&mut self, buffer: &mut [u8]) -> Result<(), TpmError> {
+        // The TPM returns at most the size of its largest digest, so several
+        // commands may be needed.
+        let mut filled = 0;
+        while filled < buffer.len() {
+            let requested = u16::try_from(buffer.len() - filled).unwrap_or(u16::MAX);
+            let mut command = Command::new(CommandCode::GET_RANDOM);
+            put_u16(command.params(), requested);
+
+            let rest = &mut buffer[filled..];
+            let len = self.execute(&command, 0, |response| {
+                let bytes = response.params.sized()?;
+                if bytes.is_empty() || bytes.len() > rest.len() {
+                    return Err(TpmError::InvalidResponse);
+                }
+                rest[..bytes.len()].copy_from_slice(bytes);
+                Ok(bytes.len())
+            })?;
+            filled += len;
+        }
+        Ok(())
+    }
+
+    /// Get information about the TPM (`TPM2_GetCapability`).
+    ///
+    /// Returns up to `count` items of `capability`, starting from `property`,
+    /// and whether more items are available.
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    pub fn get_capability(
+        &mut self,
+        capability: Capability,
+        property: u32,
+        count: u32,
+    ) -> Result<(CapabilityData, bool), TpmError> {
+        let mut command = Command::new(CommandCode::GET_CAPABILITY);
+        put_u32(command.params(), capability.0);
+        put_u32(command.params(), property);
+        put_u32(command.params(), count);
+
+        self.execute(&command, 0, |response| {
+            let r = &mut response.params;
+            let more_data = r.u8()? != 0;
+            let data = CapabilityData::unmarshal(r)?;
+            Ok((data, more_data))
+        })
+    }
+
+    /// Get the value of a TPM property.
+    ///
+    /// Returns `None` if the TPM does not have the property.
+    ///
+    /// # Errors
+    ///
+    /// See [`get_capability`](Self::get_capability).
+    pub fn get_tpm_property(&mut self, property: TpmProperty) -> Result<Option<u32>, TpmError> {
+        match self.get_capability(Capability::TPM_PROPERTIES, property.0, 1)? {
+            (CapabilityData::TpmProperties(properties), _) => Ok(properties
+                .into_iter()
+                .find(|&(p, _)| p == property)
+                .map(|(_, value)| value)),
+            _ => Err(TpmError::InvalidResponse),
+        }
+    }
+
+    /// Get the allocated PCR banks, and the PCRs in each bank.
+    ///
+    /// # Errors
+    ///
+    /// See [`get_capability`](Self::get_capability).
+    pub fn pcr_banks(&mut self) -> Result<Vec<PcrSelection>, TpmError> {
+        match self.get_capability(Capability::PCRS, 0, 1)? {
+            (CapabilityData::Pcrs(banks), _) => Ok(banks),
+            _ => Err(TpmError::InvalidResponse),
+        }
+    }
+
+    /// Define an NV index (`TPM2_NV_DefineSpace`).
+    ///
+    /// `auth_handle` is [`TpmHandle::OWNER`] or [`TpmHandle::PLATFORM`], and
+    /// `auth_value` is the authorization value of the new index.
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error, such as
+    ///   [`ResponseCode::NV_DEFINED`] if the index exists.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    /// * [`TpmError::ParameterTooLarge`]: a parameter is too large.
+    pub fn nv_define_space(
+        &mut self,
+        auth_handle: TpmHandle,
+        session: &AuthSession,
+        auth_value: &[u8],
+        public: &NvPublic,
+    ) -> Result<(), TpmError> {
+        let mut command = Command::new(CommandCode::NV_DEFINE_SPACE);
+        command.handle(auth_handle).auth(session)?;
+        put_sized(command.params(), auth_value)?;
+        marshal::put_sized_with(command.params(), |buf| public.marshal(buf))?;
+
+        self.execute(&command, 0, |_| Ok(()))
+    }
+
+    /// Delete an NV index (`TPM2_NV_UndefineSpace`).
+    ///
+    /// `auth_handle` is the hierarchy that defined the index.
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    /// * [`TpmError::ParameterTooLarge`]: the password is too large.
+    pub fn nv_undefine_space(
+        &mut self,
+        auth_handle: TpmHandle,
+        session: &AuthSession,
+        nv_index: TpmHandle,
+    ) -> Result<(), TpmError> {
+        let mut command = Command::new(CommandCode::NV_UNDEFINE_SPACE);
+        command.handle(auth_handle).handle(nv_index).auth(session)?;
+
+        self.execute(&command, 0, |_| Ok(()))
+    }
+
+    /// Read the public area and the name of an NV index
+    /// (`TPM2_NV_ReadPublic`).
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error, such as
+    ///   [`ResponseCode::HANDLE`] if the index does not exist.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    pub fn nv_read_public(&mut self, nv_index: TpmHandle) -> Result<(NvPublic, Vec<u8>), TpmError> {
+        let mut command = Command::new(CommandCode::NV_READ_PUBLIC);
+        command.handle(nv_index);
+
+        self.execute(&command, 0, |response| {
+            let r = &mut response.params;
+            let public = r.sized_with(NvPublic::unmarshal)?;
+            let name = r.sized()?.to_vec();
+            Ok((public, name))
+        })
+    }
+
+    /// Read `size` bytes at `offset` from an NV index (`TPM2_NV_Read`).
+    ///
+    /// `auth_handle` is the index itself, or the hierarchy that can read it.
+    /// `size` must not be larger than [`TpmProperty::NV_BUFFER_MAX`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error, such as
+    ///   [`ResponseCode::NV_UNINITIALIZED`] if the index was never written.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    /// * [`TpmError::ParameterTooLarge`]: the password is too large.
+    pub fn nv_read(
+        &mut self,
+        auth_handle: TpmHandle,
+        nv_index: TpmHandle,
+        session: &AuthSession,
+        offset: u16,
+        size: u16,
+    ) -> Result<Vec<u8>, TpmError> {
+        let mut command = Command::new(CommandCode::NV_READ);
+        command.handle(auth_handle).handle(nv_index).auth(session)?;
+        put_u16(command.params(), size);
+        put_u16(command.params(), offset);
+
+        self.execute(&command, 0, |response| {
+            response.params.sized().map(<[u8]>::to_vec)
+        })
+    }
+
+    /// Write `data` at `offset` to an NV index (`TPM2_NV_Write`).
+    ///
+    /// `auth_handle` is the index itself, or the hierarchy that can write
+    /// it. `data` must not be larger than [`TpmProperty::NV_BUFFER_MAX`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error, such as
+    ///   [`ResponseCode::NV_RANGE`] if the data does not fit in the index.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    /// * [`TpmError::ParameterTooLarge`]: a parameter is too large.
+    pub fn nv_write(
+        &mut self,
+        auth_handle: TpmHandle,
+        nv_index: TpmHandle,
+        session: &AuthSession,
+        offset: u16,
+        data: &[u8],
+    ) -> Result<(), TpmError> {
+        let mut command = Command::new(CommandCode::NV_WRITE);
+        command.handle(auth_handle).handle(nv_index).auth(session)?;
+        put_sized(command.params(), data)?;
+        put_u16(command.params(), offset);
+
+        self.execute(&command, 0, |_| Ok(()))
+    }
+
+    /// Read the public area and the names of a loaded object
+    /// (`TPM2_ReadPublic`).
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    pub fn read_public(&mut self, object: TpmHandle) -> Result<ObjectInfo, TpmError> {
+        let mut command = Command::new(CommandCode::READ_PUBLIC);
+        command.handle(object);
+
+        self.execute(&command, 0, |response| {
+            let r = &mut response.params;
+            Ok(ObjectInfo {
+                public: r.sized_with(Public::unmarshal)?,
+                name: r.sized()?.to_vec(),
+                qualified_name: r.sized()?.to_vec(),
+            })
+        })
+    }
+
+    /// Create a primary object in a hierarchy (`TPM2_CreatePrimary`).
+    ///
+    /// The object is created from `template` and `sensitive`. Primary keys
+    /// are derived from the seed of the hierarchy, so the same template
+    /// always creates the same key.
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error, such as
+    ///   [`ResponseCode::ATTRIBUTES`] if the template is inconsistent.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    /// * [`TpmError::ParameterTooLarge`]: a parameter is too large.
+    pub fn create_primary(
+        &mut self,
+        hierarchy: TpmHandle,
+        session: &AuthSession,
+        sensitive: &SensitiveCreate,
+        template: &Public,
+    ) -> Result<PrimaryObject, TpmError> {
+        let mut command = Command::new(CommandCode::CREATE_PRIMARY);
+        command.handle(hierarchy).auth(session)?;
+        let params = command.params();
+        sensitive.marshal(params)?;
+        marshal::put_sized_with(params, |buf| template.marshal(buf))?;
+        // No outside info and no creation PCRs.
+        put_sized(params, &[])?;
+        put_list::<PcrSelection>(params, &[], |buf, pcr| pcr.marshal(buf))?;
+
+        self.execute(&command, 1, |response| {
+            let handle = TpmHandle(response.handles.u32()?);
+            let r = &mut response.params;
+            let public = r.sized_with(Public::unmarshal)?;
+            // Skip the creation data, creation hash and creation ticket.
+            r.sized()?;
+            r.sized()?;
+            r.u16()?;
+            r.u32()?;
+            r.sized()?;
+            let name = r.sized()?.to_vec();
+            Ok(PrimaryObject {
+                handle,
+                public,
+                name,
+            })
+        })
+    }
+
+    /// Start an unbound and unsalted session (`TPM2_StartAuthSession`).
+    ///
+    /// Policy sessions are used with [`AuthSession::Policy`] once their
+    /// policy is satisfied, such as with [`policy_pcr`](Self::policy_pcr).
+    /// Trial sessions compute the digest of a policy, which is read with
+    /// [`policy_get_digest`](Self::policy_get_digest).
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error, such as
+    ///   [`ResponseCode::SESSION_MEMORY`] if too many sessions are open.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    pub fn start_auth_session(
+        &mut self,
+        session_type: SessionType,
+        auth_hash: AlgorithmId,
+    ) -> Result<TpmHandle, TpmError> {
+        let mut nonce = [0; NONCE_SIZE];
+        self.get_random(&mut nonce)?;
+
+        let mut command = Command::new(CommandCode::START_AUTH_SESSION);
+        command.handle(TpmHandle::NULL).handle(TpmHandle::NULL);
+        let params = command.params();
+        put_sized(params, &nonce)?;
+        // No salt.
+        put_sized(params, &[])?;
+        put_u8(params, session_type.0);
+        // No parameter encryption.
+        put_u16(params, AlgorithmId::NULL.0);
+        put_u16(params, auth_hash.0);
+
+        self.execute(&command, 1, |response| {
+            let handle = TpmHandle(response.handles.u32()?);
+            // Skip the TPM nonce.
+            response.params.sized()?;
+            Ok(handle)
+        })
+    }
+
+    /// Add the values of PCRs to the policy of `session` (`TPM2_PolicyPCR`).
+    ///
+    /// If `pcr_digest` is empty, the current values of the PCRs are used.
+    /// Otherwise, it is the expected digest of the PCR values, and the
+    /// command fails if they do not match.
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error, such as
+    ///   [`ResponseCode::VALUE`] if the PCR digest does not match.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    /// * [`TpmError::ParameterTooLarge`]: a parameter is too large, or a PCR
+    ///   greater than 23 is selected.
+    pub fn policy_pcr(
+        &mut self,
+        session: TpmHandle,
+        pcr_digest: &[u8],
+        pcrs: &[PcrSelection],
+    ) -> Result<(), TpmError> {
+        let mut command = Command::new(CommandCode::POLICY_PCR);
+        command.handle(session);
+        put_sized(command.params(), pcr_digest)?;
+        put_list(command.params(), pcrs, |buf, pcr| pcr.marshal(buf))?;
+
+        self.execute(&command, 0, |_| Ok(()))
+    }
+
+    /// Get the current policy digest of `session`
+    /// (`TPM2_PolicyGetDigest`).
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    pub fn policy_get_digest(&mut self, session: TpmHandle) -> Result<Vec<u8>, TpmError> {
+        let mut command = Command::new(CommandCode::POLICY_GET_DIGEST);
+        command.handle(session);
+
+        self.execute(&command, 0, |response| {
+            response.params.sized().map(<[u8]>::to_vec)
+        })
+    }
+
+    /// Get the data sealed in a keyed hash object (`TPM2_Unseal`).
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error, such as
+    ///   [`ResponseCode::POLICY_FAIL`] if the policy is not satisfied.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    /// * [`TpmError::ParameterTooLarge`]: the password is too large.
+    pub fn unseal(&mut self, item: TpmHandle, session: &AuthSession) -> Result<Vec<u8>, TpmError> {
+        let mut command = Command::new(CommandCode::UNSEAL);
+        command.handle(item).auth(session)?;
+
+        self.execute(&command, 0, |response| {
+            response.params.sized().map(<[u8]>::to_vec)
+        })
+    }
+
+    /// Unload a transient object or close a session (`TPM2_FlushContext`).
+    ///
+    /// # Errors
+    ///
+    /// * [`TpmError::Transport`]: the command could not be sent.
+    /// * [`TpmError::Response`]: the TPM returned an error, such as
+    ///   [`ResponseCode::HANDLE`] if the handle is not loaded.
+    /// * [`TpmError::InvalidResponse`]: the response is malformed.
+    pub fn flush_context(&mut self, handle: TpmHandle) -> Result<(), TpmError> {
+        let mut command = Command::new(CommandCode::FLUSH_CONTEXT);
+        put_u32(command.params(), handle.0);
+
+        self.execute(&command, 0, |_| Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::VecDeque;
+    use alloc::string::ToString;
+    use marshal::Reader;
+
+    /// Transport that checks the commands and returns canned responses.
+    struct MockTpm {
+        exchanges: VecDeque<(Vec<u8>, Vec<u8>)>,
+    }
+
+    impl MockTpm {
+        fn new(exchanges: &[(&[u8], &[u8])]) -> Self {
+            Self {
+                exchanges: exchanges
+                    .iter()
+                    .map(|(command, response)| (command.to_vec(), response.to_vec()))
+                    .collect(),
+            }
+        }
+    }
+
+    impl Transport for MockTpm {
+        fn submit(&mut self, command: &[u8], response: &mut [u8]) -> crate::Result {
+            let (expected, canned) = self.exchanges.pop_front().unwrap();
+            assert_eq!(command, expected);
+            response[..canned.len()].copy_from_slice(&canned);
+            Ok(())
+        }
+    }
+
+    impl Drop for MockTpm {
+        fn drop(&mut self) {
+            assert!(self.exchanges.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_pcr_read() {
+        #[rustfmt::skip]
+        let command = [
+            // Header: no sessions, size, TPM_CC_PCR_Read.
+            0x80, 0x01, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x01, 0x7e,
+            // One selection: SHA-1, three bytes, PCR 8.
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x04, 0x03, 0x00, 0x01, 0x00,
+        ];
+        #[rustfmt::skip]
+        let response = [
+            // Header: no sessions, size, success.
+            0x80, 0x01, 0x00, 0x00, 0x00, 0x32, 0x00, 0x00, 0x00, 0x00,
+            // Update counter.
+            0x00, 0x00, 0x00, 0x2a,
+            // One selection: SHA-1, three bytes, PCR 8.
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x04, 0x03, 0x00, 0x01, 0x00,
+            // One digest.
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x14,
+            0x16, 0x53, 0x7d, 0xaa, 0x5d, 0xbd, 0xa8, 0x45, 0xe3, 0x30,
+            0x9e, 0x40, 0xe8, 0x74, 0xd1, 0x50, 0x64, 0x73, 0x2f, 0x87,
+        ];
+        let mut tpm = Tpm::new(MockTpm::new(&[(&command, &response)]));
+        let pcr = tpm.read_pcr(AlgorithmId::SHA1, PcrIndex(8)).unwrap();
+        assert_eq!(pcr.as_deref(), Some(&response[30..]));
+
+        // The TPM did not read PCRs of an unallocated bank.
+        #[rustfmt::skip]
+        let command = [
+            0x80, 0x01, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x01, 0x7e,
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x12, 0x03, 0x01, 0x00, 0x00,
+        ];
+        #[rustfmt::skip]
+        let response = [
+            0x80, 0x01, 0x00, 0x00, 0x00, 0x16, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x2a,
+            0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        let mut tpm = Tpm::new(MockTpm::new(&[(&command, &response)]));
+        assert_eq!(tpm.read_pcr(AlgorithmId::SM3_256, PcrIndex(0)), Ok(None));
+    }
+
+    #[test]
+    fn test_get_random() {
+        #[rustfmt::skip]
+        let exchanges: [(&[u8], &[u8]); 2] = [
+            (
+                &[0x80, 0x01, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x01, 0x7b, 0x00, 0x05],
+                &[0x80, 0x01, 0x00, 0x00, 0x00, 0x0f, 0x00, 0x00, 0x00, 0x00,
+                  0x00, 0x03, 1, 2, 3],
+            ),
+            (
+                &[0x80, 0x01, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x01, 0x7b, 0x00, 0x02],
+                &[0x80, 0x01, 0x00, 0x00, 0x00, 0x0e, 0x00, 0x00, 0x00, 0x00,
+                  0x00, 0x02, 4, 5],
+            ),
+        ];
+        let mut tpm = Tpm::new(MockTpm::new(&exchanges));
+        let mut buffer = [0; 5];
+        tpm.get_random(&mut buffer).unwrap();
+        assert_eq!(buffer, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_unseal() {
+        #[rustfmt::skip]
+        let command = [
+            // Header: sessions, size, TPM_CC_Unseal.
+            0x80, 0x02, 0x00, 0x00, 0x00, 0x1e, 0x00, 0x00, 0x01, 0x5e,
+            // Item handle.
+            0x80, 0x00, 0x00, 0x00,
+            // Authorization size.
+            0x00, 0x00, 0x00, 0x0c,
+            // Password session, empty nonce, continue session, password.
+            0x40, 0x00, 0x00, 0x09, 0x00, 0x00, 0x01, 0x00, 0x03, b'p', b'w', b'd',
+        ];
+        #[rustfmt::skip]
+        let response = [
+            // Header: sessions, size, success.
+            0x80, 0x02, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00, 0x00, 0x00,
+            // Parameter size and sealed data.
+            0x00, 0x00, 0x00, 0x06, 0x00, 0x04, b'd', b'a', b't', b'a',
+            // Authorization area, which is ignored.
+            0x00, 0x00, 0x01, 0x00, 0x00,
+        ];
+        let mut tpm = Tpm::new(MockTpm::new(&[(&command, &response)]));
+        let data = tpm
+            .unseal(TpmHandle(0x8000_0000), &AuthSession::Password(b"pwd"))
+            .unwrap();
+        assert_eq!(data, b"data");
+    }
+
+    #[test]
+    fn test_error_response() {
+        #[rustfmt::skip]
+        let command = [
+            0x80, 0x02, 0x00, 0x00, 0x00, 0x1b, 0x00, 0x00, 0x01, 0x5e,
+            0x80, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x09,
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let response = [0x80, 0x01, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x09, 0x9d];
+        let mut tpm = Tpm::new(MockTpm::new(&[(&command, &response)]));
+        let err = tpm
+            .unseal(
+                TpmHandle(0x8000_0000),
+                &AuthSession::Policy(TpmHandle(0x0300_0000)),
+            )
+            .unwrap_err();
+        let TpmError::Response(code) = err else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert_eq!(code.base(), ResponseCode::POLICY_FAIL);
+        assert_eq!(code.location(), Some(ErrorLocation::Session(1)));
+        assert_eq!(
+            err.to_string(),
+            "TPM error: POLICY_FAIL (0x99d) for session 1"
+        );
+
+        assert_eq!(
+            ResponseCode(0x1c4).location(),
+            Some(ErrorLocation::Parameter(1))
+        );
+        assert_eq!(
+            ResponseCode(0x18b).location(),
+            Some(ErrorLocation::Handle(1))
+        );
+        assert_eq!(ResponseCode::NV_UNINITIALIZED.location(), None);
+        assert!(ResponseCode::RETRY.is_warning());
+        assert!(!ResponseCode::NV_RANGE.is_warning());
+    }
+
+    #[test]
+    fn test_public() {
+        let public = Public {
+            name_alg: AlgorithmId::SHA256,
+            object_attributes: ObjectAttributes::FIXED_TPM
+                | ObjectAttributes::FIXED_PARENT
+                | ObjectAttributes::SENSITIVE_DATA_ORIGIN
+                | ObjectAttributes::USER_WITH_AUTH
+                | ObjectAttributes::RESTRICTED
+                | ObjectAttributes::DECRYPT,
+            auth_policy: Vec::new(),
+            parameters: PublicParameters::Rsa {
+                symmetric: SymmetricDefinition::AES_128_CFB,
+                scheme: Scheme::Null,
+                key_bits: 2048,
+                exponent: 0,
+            },
+            unique: PublicUnique::Rsa(Vec::new()),
+        };
+        let mut bytes = Vec::new();
+        public.marshal(&mut bytes).unwrap();
+        #[rustfmt::skip]
+        assert_eq!(
+            bytes,
+            [
+                0x00, 0x01, 0x00, 0x0b, 0x00, 0x03, 0x00, 0x72, 0x00, 0x00,
+                0x00, 0x06, 0x00, 0x80, 0x00, 0x43,
+                0x00, 0x10,
+                0x08, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00,
+            ]
+        );
+        let parsed = Public::unmarshal(&mut Reader::new(&bytes)).unwrap();
+        assert_eq!(parsed, public);
+        assert_eq!(parsed.object_type(), AlgorithmId::RSA);
+
+        let public = Public {
+            name_alg: AlgorithmId::SHA256,
+            object_attributes: ObjectAttributes::FIXED_TPM | ObjectAttributes::FIXED_PARENT,
+            auth_policy: [0xaa; 32].to_vec(),
+            parameters: PublicParameters::Ecc {
+                symmetric: SymmetricDefinition::NULL,
+                scheme: Scheme::Hash {
+                    scheme: AlgorithmId::ECDSA,
+                    hash: AlgorithmId::SHA256,
+                },
+                curve: 0x0003,
+                kdf: Scheme::Null,
+            },
+            unique: PublicUnique::Ecc {
+                x: [1; 32].to_vec(),
+                y: [2; 32].to_vec(),
+            },
+        };
+        let mut bytes = Vec::new();
+        public.marshal(&mut bytes).unwrap();
+        assert_eq!(Public::unmarshal(&mut Reader::new(&bytes)).unwrap(), public);
+    }
+
+    #[test]
+    fn test_pcr_selection() {
+        let selection = PcrSelection::new(AlgorithmId::SHA256, &[PcrIndex(0), PcrIndex(7)]);
+        assert_eq!(selection.pcrs, 0x81);
+        assert!(selection.contains(PcrIndex(7)));
+        assert!(!selection.contains(PcrIndex(8)));
+        assert_eq!(
+            selection.iter().collect::<Vec<_>>(),
+            [PcrIndex(0), PcrIndex(7)]
+        );
+
+        // Selections are sent with three bytes, and only for PCRs 0 to 23.
+        let mut buf = Vec::new();
+        selection.marshal(&mut buf).unwrap();
+        assert_eq!(buf, [0x00, 0x0b, 0x03, 0x81, 0x00, 0x00]);
+        assert_eq!(
+            PcrSelection::new(AlgorithmId::SHA256, &[PcrIndex(24)]).marshal(&mut buf),
+            Err(TpmError::ParameterTooLarge)
+        );
+
+        // Larger selections are accepted if the extra PCRs are not selected.
+        let bytes = [0x00, 0x0b, 0x05, 0x01, 0x00, 0x00, 0x80, 0x00];
+        let parsed = PcrSelection::unmarshal(&mut Reader::new(&bytes)).unwrap();
+        assert_eq!(parsed.pcrs, 0x8000_0001);
+        let bytes = [0x00, 0x0b, 0x05, 0x01, 0x00, 0x00, 0x00, 0x01];
+        assert_eq!(
+            PcrSelection::unmarshal(&mut Reader::new(&bytes)),
+            Err(TpmError::InvalidResponse)
+        );
+    }
+}
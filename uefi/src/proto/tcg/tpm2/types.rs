@@ -0,0 +1,766 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use super::TpmError;
+use super::marshal::{Reader, put_sized, put_sized_with, put_u8, put_u16, put_u32};
+use crate::proto::tcg::{AlgorithmId, PcrIndex};
+use alloc::vec::Vec;
+use bitflags::bitflags;
+
+newtype_enum! {
+    /// TPM command code (`TPM_CC`).
+    pub enum CommandCode: u32 => {
+        /// `TPM2_NV_UndefineSpace`.
+        NV_UNDEFINE_SPACE = 0x0000_0122,
+        /// `TPM2_NV_DefineSpace`.
+        NV_DEFINE_SPACE = 0x0000_012a,
+        /// `TPM2_CreatePrimary`.
+        CREATE_PRIMARY = 0x0000_0131,
+        /// `TPM2_NV_Write`.
+        NV_WRITE = 0x0000_0137,
+        /// `TPM2_NV_Read`.
+        NV_READ = 0x0000_014e,
+        /// `TPM2_Unseal`.
+        UNSEAL = 0x0000_015e,
+        /// `TPM2_FlushContext`.
+        FLUSH_CONTEXT = 0x0000_0165,
+        /// `TPM2_NV_ReadPublic`.
+        NV_READ_PUBLIC = 0x0000_0169,
+        /// `TPM2_ReadPublic`.
+        READ_PUBLIC = 0x0000_0173,
+        /// `TPM2_StartAuthSession`.
+        START_AUTH_SESSION = 0x0000_0176,
+        /// `TPM2_GetCapability`.
+        GET_CAPABILITY = 0x0000_017a,
+        /// `TPM2_GetRandom`.
+        GET_RANDOM = 0x0000_017b,
+        /// `TPM2_PCR_Read`.
+        PCR_READ = 0x0000_017e,
+        /// `TPM2_PolicyPCR`.
+        POLICY_PCR = 0x0000_017f,
+        /// `TPM2_PolicyGetDigest`.
+        POLICY_GET_DIGEST = 0x0000_0189,
+    }
+}
+
+newtype_enum! {
+    /// Tag of a command or response (`TPM_ST`).
+    pub enum StructureTag: u16 => {
+        /// The command or response has no authorization area.
+        NO_SESSIONS = 0x8001,
+        /// The command or response has an authorization area.
+        SESSIONS = 0x8002,
+    }
+}
+
+newtype_enum! {
+    /// TPM handle (`TPM_HANDLE`), which refers to a hierarchy, an object, an
+    /// NV index or a session.
+    ///
+    /// Only the permanent handles have constants. The type of other handles
+    /// is given by their most significant byte: `0x01` for NV indices, `0x03`
+    /// for policy sessions, `0x80` for transient objects and `0x81` for
+    /// persistent objects.
+    pub enum TpmHandle: u32 => {
+        /// Storage hierarchy.
+        OWNER = 0x4000_0001,
+        /// No hierarchy or object.
+        NULL = 0x4000_0007,
+        /// Dictionary attack lockout authorization.
+        LOCKOUT = 0x4000_000a,
+        /// Endorsement hierarchy.
+        ENDORSEMENT = 0x4000_000b,
+        /// Platform hierarchy.
+        PLATFORM = 0x4000_000c,
+    }
+}
+
+newtype_enum! {
+    /// Type of capability data (`TPM_CAP`) returned by
+    /// [`Tpm::get_capability`].
+    ///
+    /// [`Tpm::get_capability`]: super::Tpm::get_capability
+    pub enum Capability: u32 => {
+        /// Implemented algorithms.
+        ALGORITHMS = 0x0000_0000,
+        /// Handles of a given type, selected by the start handle.
+        HANDLES = 0x0000_0001,
+        /// Implemented commands.
+        COMMANDS = 0x0000_0002,
+        /// Commands that require physical presence.
+        PP_COMMANDS = 0x0000_0003,
+        /// Commands that are audited.
+        AUDIT_COMMANDS = 0x0000_0004,
+        /// Allocated PCR banks.
+        PCRS = 0x0000_0005,
+        /// TPM properties.
+        TPM_PROPERTIES = 0x0000_0006,
+        /// PCR properties.
+        PCR_PROPERTIES = 0x0000_0007,
+        /// Implemented ECC curves.
+        ECC_CURVES = 0x0000_0008,
+    }
+}
+
+newtype_enum! {
+    /// TPM property (`TPM_PT`) returned for [`Capability::TPM_PROPERTIES`].
+    pub enum TpmProperty: u32 => {
+        /// Family of the specification, such as `"2.0\0"`.
+        FAMILY_INDICATOR = 0x100,
+        /// Level of the specification.
+        LEVEL = 0x101,
+        /// Revision of the specification, times 100.
+        REVISION = 0x102,
+        /// Day of the year of the specification.
+        DAY_OF_YEAR = 0x103,
+        /// Year of the specification.
+        YEAR = 0x104,
+        /// Vendor ID of the manufacturer.
+        MANUFACTURER = 0x105,
+        /// First four characters of the vendor string.
+        VENDOR_STRING_1 = 0x106,
+        /// Second four characters of the vendor string.
+        VENDOR_STRING_2 = 0x107,
+        /// Third four characters of the vendor string.
+        VENDOR_STRING_3 = 0x108,
+        /// Fourth four characters of the vendor string.
+        VENDOR_STRING_4 = 0x109,
+        /// Vendor-defined TPM model.
+        VENDOR_TPM_TYPE = 0x10a,
+        /// Most significant 32 bits of the firmware version.
+        FIRMWARE_VERSION_1 = 0x10b,
+        /// Least significant 32 bits of the firmware version.
+        FIRMWARE_VERSION_2 = 0x10c,
+        /// Maximum size of a parameter.
+        INPUT_BUFFER = 0x10d,
+        /// Minimum number of transient objects that can be loaded.
+        HR_TRANSIENT_MIN = 0x10e,
+        /// Minimum number of persistent objects.
+        HR_PERSISTENT_MIN = 0x10f,
+        /// Minimum number of loaded sessions.
+        HR_LOADED_MIN = 0x110,
+        /// Maximum number of active sessions.
+        ACTIVE_SESSIONS_MAX = 0x111,
+        /// Number of PCRs.
+        PCR_COUNT = 0x112,
+        /// Minimum size of a PCR selection.
+        PCR_SELECT_MIN = 0x113,
+        /// Maximum size of an NV index.
+        NV_INDEX_MAX = 0x117,
+        /// Maximum size of a command.
+        MAX_COMMAND_SIZE = 0x11e,
+        /// Maximum size of a response.
+        MAX_RESPONSE_SIZE = 0x11f,
+        /// Maximum size of a digest.
+        MAX_DIGEST = 0x120,
+        /// Maximum size of the data of [`Tpm::nv_read`] and
+        /// [`Tpm::nv_write`].
+        ///
+        /// [`Tpm::nv_read`]: super::Tpm::nv_read
+        /// [`Tpm::nv_write`]: super::Tpm::nv_write
+        NV_BUFFER_MAX = 0x12c,
+        /// Permanent attributes (`TPMA_PERMANENT`).
+        PERMANENT = 0x200,
+        /// Attributes that are cleared on `TPM2_Startup(CLEAR)`
+        /// (`TPMA_STARTUP_CLEAR`).
+        STARTUP_CLEAR = 0x201,
+    }
+}
+
+newtype_enum! {
+    /// Type of an authorization session (`TPM_SE`).
+    pub enum SessionType: u8 => {
+        /// HMAC session.
+        HMAC = 0x00,
+        /// Policy session.
+        POLICY = 0x01,
+        /// Trial policy session, which computes a policy digest without
+        /// checking it.
+        TRIAL = 0x03,
+    }
+}
+
+/// Authorization of a command.
+#[derive(Clone, Copy, Debug)]
+pub enum AuthSession<'a> {
+    /// Password authorization with the authorization value of the entity,
+    /// which is empty by default for the hierarchies.
+    Password(&'a [u8]),
+    /// Policy session, started with [`Tpm::start_auth_session`]. The policy
+    /// must not require an HMAC or password.
+    ///
+    /// The session is closed after the command.
+    ///
+    /// [`Tpm::start_auth_session`]: super::Tpm::start_auth_session
+    Policy(TpmHandle),
+}
+
+/// Selection of PCRs in a bank (`TPMS_PCR_SELECTION`).
+///
+/// Selections are sent to the TPM with a three-byte bitmap, the size that
+/// PC Client TPMs report in `TPM_PT_PCR_SELECT_MIN`. Commands fail with
+/// [`TpmError::ParameterTooLarge`] if a PCR greater than 23 is selected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct PcrSelection {
+    /// Hash algorithm of the bank.
+    pub hash: AlgorithmId,
+    /// Bitmap of the selected PCRs; bit `n` selects PCR `n`.
+    pub pcrs: u32,
+}
+
+impl PcrSelection {
+    /// Size of the bitmap sent to the TPM, in bytes, for PCRs 0 to 23.
+    const SELECT_SIZE: usize = 3;
+
+    /// Select `pcrs` in the bank of `hash`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a PCR index is greater than 31.
+    #[must_use]
+    pub fn new(hash: AlgorithmId, pcrs: &[PcrIndex]) -> Self {
+        let pcrs = pcrs.iter().fold(0, |mask, pcr| {
+            assert!(pcr.0 < 32, "PCR index is out of range");
+            mask | (1 << pcr.0)
+        });
+        Self { hash, pcrs }
+    }
+
+    /// Returns whether `pcr` is selected.
+    #[must_use]
+    pub const fn contains(&self, pcr: PcrIndex) -> bool {
+        pcr.0 < 32 && self.pcrs & (1 << pcr.0) != 0
+    }
+
+    /// Get an iterator over the selected PCRs, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = PcrIndex> + use<> {
+        let pcrs = self.pcrs;
+        (0..32).filter(move |n| pcrs & (1 << n) != 0).map(PcrIndex)
+    }
+
+    pub(super) fn marshal(&self, buf: &mut Vec<u8>) -> Result<(), TpmError> {
+        // TPMs reject bitmaps larger than the size they support.
+        if self.pcrs >> (Self::SELECT_SIZE * 8) != 0 {
+            return Err(TpmError::ParameterTooLarge);
+        }
+        put_u16(buf, self.hash.0);
+        put_u8(buf, Self::SELECT_SIZE as u8);
+        buf.extend_from_slice(&self.pcrs.to_le_bytes()[..Self::SELECT_SIZE]);
+        Ok(())
+    }
+
+    pub(super) fn unmarshal(reader: &mut Reader) -> Result<Self, TpmError> {
+        Ok(Self {
+            hash: AlgorithmId(reader.u16()?),
+            pcrs: Self::unmarshal_select(reader)?,
+        })
+    }
+
+    /// Read a sized PCR bitmap (`TPMS_PCR_SELECT`).
+    fn unmarshal_select(reader: &mut Reader) -> Result<u32, TpmError> {
+        let size = reader.u8()?;
+        let mut pcrs = 0;
+        for (i, &byte) in reader.bytes(usize::from(size))?.iter().enumerate() {
+            match i {
+                0..4 => pcrs |= u32::from(byte) << (i * 8),
+                _ if byte == 0 => {}
+                _ => return Err(TpmError::InvalidResponse),
+            }
+        }
+        Ok(pcrs)
+    }
+}
+
+bitflags! {
+    /// Attributes of an object (`TPMA_OBJECT`).
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct ObjectAttributes: u32 {
+        /// The object cannot be duplicated to another TPM.
+        const FIXED_TPM = 1 << 1;
+        /// Saved contexts of the object cannot be loaded after a TPM reset.
+        const ST_CLEAR = 1 << 2;
+        /// The parent of the object cannot change.
+        const FIXED_PARENT = 1 << 4;
+        /// The sensitive data was generated by the TPM.
+        const SENSITIVE_DATA_ORIGIN = 1 << 5;
+        /// The user role can be authorized with the authorization value.
+        const USER_WITH_AUTH = 1 << 6;
+        /// The admin role can only be authorized with a policy.
+        const ADMIN_WITH_POLICY = 1 << 7;
+        /// The object is not subject to dictionary attack protection.
+        const NO_DA = 1 << 10;
+        /// Duplication of the object requires an inner wrapper.
+        const ENCRYPTED_DUPLICATION = 1 << 11;
+        /// Key usage is restricted to TPM-generated structures.
+        const RESTRICTED = 1 << 16;
+        /// The key can be used to decrypt.
+        const DECRYPT = 1 << 17;
+        /// The key can be used to sign or encrypt.
+        const SIGN_ENCRYPT = 1 << 18;
+        /// The key can be used to sign X.509 certificates.
+        const X509_SIGN = 1 << 19;
+    }
+}
+
+bitflags! {
+    /// Attributes of an NV index (`TPMA_NV`).
+    ///
+    /// Bits 4 to 7 give the type of the index (`TPM_NT`); they are zero for an
+    /// ordinary index.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct NvAttributes: u32 {
+        /// The index can be written with platform authorization.
+        const PPWRITE = 1 << 0;
+        /// The index can be written with owner authorization.
+        const OWNERWRITE = 1 << 1;
+        /// The index can be written with its authorization value.
+        const AUTHWRITE = 1 << 2;
+        /// The index can be written with its policy.
+        const POLICYWRITE = 1 << 3;
+        /// Counter index.
+        const COUNTER = 1 << 4;
+        /// Bit field index.
+        const BITS = 2 << 4;
+        /// Extend index.
+        const EXTEND = 4 << 4;
+        /// The index can only be deleted with its policy.
+        const POLICY_DELETE = 1 << 10;
+        /// The index is write-locked.
+        const WRITELOCKED = 1 << 11;
+        /// Partial writes are not allowed.
+        const WRITEALL = 1 << 12;
+        /// The index can be permanently write-locked.
+        const WRITEDEFINE = 1 << 13;
+        /// The index can be write-locked until the next TPM reset.
+        const WRITE_STCLEAR = 1 << 14;
+        /// The index is write-locked by a global lock.
+        const GLOBALLOCK = 1 << 15;
+        /// The index can be read with platform authorization.
+        const PPREAD = 1 << 16;
+        /// The index can be read with owner authorization.
+        const OWNERREAD = 1 << 17;
+        /// The index can be read with its authorization value.
+        const AUTHREAD = 1 << 18;
+        /// The index can be read with its policy.
+        const POLICYREAD = 1 << 19;
+        /// The index is not subject to dictionary attack protection.
+        const NO_DA = 1 << 25;
+        /// The index is only saved on orderly shutdown.
+        const ORDERLY = 1 << 26;
+        /// The written state is cleared on TPM reset.
+        const CLEAR_STCLEAR = 1 << 27;
+        /// The index is read-locked.
+        const READLOCKED = 1 << 28;
+        /// The index was written.
+        const WRITTEN = 1 << 29;
+        /// The index was defined with platform authorization.
+        const PLATFORMCREATE = 1 << 30;
+        /// The index can be read-locked until the next TPM reset.
+        const READ_STCLEAR = 1 << 31;
+    }
+}
+
+/// Symmetric algorithm of an object (`TPMT_SYM_DEF_OBJECT`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct SymmetricDefinition {
+    /// Algorithm, or [`AlgorithmId::NULL`].
+    pub algorithm: AlgorithmId,
+    /// Key size in bits. Ignored if the algorithm is null.
+    pub key_bits: u16,
+    /// Block cipher mode. Ignored if the algorithm is null.
+    pub mode: AlgorithmId,
+}
+
+impl SymmetricDefinition {
+    /// No symmetric algorithm.
+    pub const NULL: Self = Self {
+        algorithm: AlgorithmId::NULL,
+        key_bits: 0,
+        mode: AlgorithmId::NULL,
+    };
+
+    /// AES-128 in CFB mode, as used by storage keys.
+    pub const AES_128_CFB: Self = Self {
+        algorithm: AlgorithmId::AES,
+        key_bits: 128,
+        mode: AlgorithmId::CFB,
+    };
+
+    fn marshal(&self, buf: &mut Vec<u8>) {
+        put_u16(buf, self.algorithm.0);
+        if self.algorithm != AlgorithmId::NULL {
+            put_u16(buf, self.key_bits);
+            put_u16(buf, self.mode.0);
+        }
+    }
+
+    fn unmarshal(reader: &mut Reader) -> Result<Self, TpmError> {
+        let algorithm = AlgorithmId(reader.u16()?);
+        if algorithm == AlgorithmId::NULL {
+            return Ok(Self::NULL);
+        }
+        Ok(Self {
+            algorithm,
+            key_bits: reader.u16()?,
+            mode: AlgorithmId(reader.u16()?),
+        })
+    }
+}
+
+/// Signing, encryption or key derivation scheme of an object.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Scheme {
+    /// No scheme.
+    Null,
+    /// [`AlgorithmId::RSAES`], which has no parameters.
+    Rsaes,
+    /// [`AlgorithmId::XOR`], for keyed hash objects.
+    Xor {
+        /// Hash algorithm.
+        hash: AlgorithmId,
+        /// Key derivation function.
+        kdf: AlgorithmId,
+    },
+    /// [`AlgorithmId::ECDAA`].
+    Ecdaa {
+        /// Hash algorithm.
+        hash: AlgorithmId,
+        /// Commit counter.
+        count: u16,
+    },
+    /// Any other scheme, which only has a hash algorithm parameter, such as
+    /// [`AlgorithmId::HMAC`], [`AlgorithmId::RSASSA`], [`AlgorithmId::OAEP`],
+    /// [`AlgorithmId::ECDSA`] or [`AlgorithmId::KDF2`].
+    Hash {
+        /// Scheme.
+        scheme: AlgorithmId,
+        /// Hash algorithm.
+        hash: AlgorithmId,
+    },
+}
+
+impl Scheme {
+    fn marshal(&self, buf: &mut Vec<u8>) {
+        match *self {
+            Self::Null => put_u16(buf, AlgorithmId::NULL.0),
+            Self::Rsaes => put_u16(buf, AlgorithmId::RSAES.0),
+            Self::Xor { hash, kdf } => {
+                put_u16(buf, AlgorithmId::XOR.0);
+                put_u16(buf, hash.0);
+                put_u16(buf, kdf.0);
+            }
+            Self::Ecdaa { hash, count } => {
+                put_u16(buf, AlgorithmId::ECDAA.0);
+                put_u16(buf, hash.0);
+                put_u16(buf, count);
+            }
+            Self::Hash { scheme, hash } => {
+                put_u16(buf, scheme.0);
+                put_u16(buf, hash.0);
+            }
+        }
+    }
+
+    fn unmarshal(reader: &mut Reader) -> Result<Self, TpmError> {
+        Ok(match AlgorithmId(reader.u16()?) {
+            AlgorithmId::NULL => Self::Null,
+            AlgorithmId::RSAES => Self::Rsaes,
+            AlgorithmId::XOR => Self::Xor {
+                hash: AlgorithmId(reader.u16()?),
+                kdf: AlgorithmId(reader.u16()?),
+            },
+            AlgorithmId::ECDAA => Self::Ecdaa {
+                hash: AlgorithmId(reader.u16()?),
+                count: reader.u16()?,
+            },
+            scheme => Self::Hash {
+                scheme,
+                hash: AlgorithmId(reader.u16()?),
+            },
+        })
+    }
+}
+
+/// Type-specific parameters of an object (`TPMU_PUBLIC_PARMS`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum PublicParameters {
+    /// Keyed hash object, which is an HMAC key or sealed data.
+    KeyedHash {
+        /// HMAC or XOR scheme, or null for sealed data.
+        scheme: Scheme,
+    },
+    /// Symmetric key.
+    SymCipher {
+        /// Symmetric algorithm.
+        symmetric: SymmetricDefinition,
+    },
+    /// RSA key.
+    Rsa {
+        /// Symmetric algorithm for child objects of a storage key.
+        symmetric: SymmetricDefinition,
+        /// Signing or encryption scheme.
+        scheme: Scheme,
+        /// Key size in bits.
+        key_bits: u16,
+        /// Public exponent, or zero for the default exponent.
+        exponent: u32,
+    },
+    /// ECC key.
+    Ecc {
+        /// Symmetric algorithm for child objects of a storage key.
+        symmetric: SymmetricDefinition,
+        /// Signing or key exchange scheme.
+        scheme: Scheme,
+        /// Curve (`TPM_ECC_CURVE`).
+        curve: u16,
+        /// Key derivation scheme.
+        kdf: Scheme,
+    },
+}
+
+/// Unique identifier of an object (`TPMU_PUBLIC_ID`).
+///
+/// In a template for [`Tpm::create_primary`], it is usually empty.
+///
+/// [`Tpm::create_primary`]: super::Tpm::create_primary
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PublicUnique {
+    /// Digest of a keyed hash object or symmetric key.
+    Digest(Vec<u8>),
+    /// RSA public modulus.
+    Rsa(Vec<u8>),
+    /// ECC public point.
+    Ecc {
+        /// X coordinate.
+        x: Vec<u8>,
+        /// Y coordinate.
+        y: Vec<u8>,
+    },
+}
+
+/// Public area of an object (`TPMT_PUBLIC`).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Public {
+    /// Hash algorithm used to compute the name of the object.
+    pub name_alg: AlgorithmId,
+    /// Attributes of the object.
+    pub object_attributes: ObjectAttributes,
+    /// Policy digest to authorize the object, or empty.
+    pub auth_policy: Vec<u8>,
+    /// Type-specific parameters, which also give the type of the object.
+    pub parameters: PublicParameters,
+    /// Unique identifier.
+    pub unique: PublicUnique,
+}
+
+impl Public {
+    /// Get the type of the object: [`AlgorithmId::KEYED_HASH`],
+    /// [`AlgorithmId::SYMCIPHER`], [`AlgorithmId::RSA`] or
+    /// [`AlgorithmId::ECC`].
+    #[must_use]
+    pub const fn object_type(&self) -> AlgorithmId {
+        match self.parameters {
+            PublicParameters::KeyedHash { .. } => AlgorithmId::KEYED_HASH,
+            PublicParameters::SymCipher { .. } => AlgorithmId::SYMCIPHER,
+            PublicParameters::Rsa { .. } => AlgorithmId::RSA,
+            PublicParameters::Ecc { .. } => AlgorithmId::ECC,
+        }
+    }
+
+    pub(super) fn marshal(&self, buf: &mut Vec<u8>) -> Result<(), TpmError> {
+        put_u16(buf, self.object_type().0);
+        put_u16(buf, self.name_alg.0);
+        put_u32(buf, self.object_attributes.bits());
+        put_sized(buf, &self.auth_policy)?;
+        match &self.parameters {
+            PublicParameters::KeyedHash { scheme } => scheme.marshal(buf),
+            PublicParameters::SymCipher { symmetric } => symmetric.marshal(buf),
+            PublicParameters::Rsa {
+                symmetric,
+                scheme,
+                key_bits,
+                exponent,
+            } => {
+                symmetric.marshal(buf);
+                scheme.marshal(buf);
+                put_u16(buf, *key_bits);
+                put_u32(buf, *exponent);
+            }
+            PublicParameters::Ecc {
+                symmetric,
+                scheme,
+                curve,
+                kdf,
+            } => {
+                symmetric.marshal(buf);
+                scheme.marshal(buf);
+                put_u16(buf, *curve);
+                kdf.marshal(buf);
+            }
+        }
+        match &self.unique {
+            PublicUnique::Digest(bytes) | PublicUnique::Rsa(bytes) => put_sized(buf, bytes),
+            PublicUnique::Ecc { x, y } => {
+                put_sized(buf, x)?;
+                put_sized(buf, y)
+            }
+        }
+    }
+
+    pub(super) fn unmarshal(reader: &mut Reader) -> Result<Self, TpmError> {
+        let object_type = AlgorithmId(reader.u16()?);
+        let name_alg = AlgorithmId(reader.u16()?);
+        let object_attributes = ObjectAttributes::from_bits_retain(reader.u32()?);
+        let auth_policy = reader.sized()?.to_vec();
+        let (parameters, unique) = match object_type {
+            AlgorithmId::KEYED_HASH => (
+                PublicParameters::KeyedHash {
+                    scheme: Scheme::unmarshal(reader)?,
+                },
+                PublicUnique::Digest(reader.sized()?.to_vec()),
+            ),
+            AlgorithmId::SYMCIPHER => (
+                PublicParameters::SymCipher {
+                    symmetric: SymmetricDefinition::unmarshal(reader)?,
+                },
+                PublicUnique::Digest(reader.sized()?.to_vec()),
+            ),
+            AlgorithmId::RSA => (
+                PublicParameters::Rsa {
+                    symmetric: SymmetricDefinition::unmarshal(reader)?,
+                    scheme: Scheme::unmarshal(reader)?,
+                    key_bits: reader.u16()?,
+                    exponent: reader.u32()?,
+                },
+                PublicUnique::Rsa(reader.sized()?.to_vec()),
+            ),
+            AlgorithmId::ECC => (
+                PublicParameters::Ecc {
+                    symmetric: SymmetricDefinition::unmarshal(reader)?,
+                    scheme: Scheme::unmarshal(reader)?,
+                    curve: reader.u16()?,
+                    kdf: Scheme::unmarshal(reader)?,
+                },
+                PublicUnique::Ecc {
+                    x: reader.sized()?.to_vec(),
+                    y: reader.sized()?.to_vec(),
+                },
+            ),
+            _ => return Err(TpmError::InvalidResponse),
+        };
+        Ok(Self {
+            name_alg,
+            object_attributes,
+            auth_policy,
+            parameters,
+            unique,
+        })
+    }
+}
+
+/// Public area of an NV index (`TPMS_NV_PUBLIC`).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct NvPublic {
+    /// Handle of the index, from `0x0100_0000` to `0x01ff_ffff`.
+    pub nv_index: TpmHandle,
+    /// Hash algorithm used to compute the name of the index.
+    pub name_alg: AlgorithmId,
+    /// Attributes of the index.
+    pub attributes: NvAttributes,
+    /// Policy digest to authorize the index, or empty.
+    pub auth_policy: Vec<u8>,
+    /// Size of the data, in bytes.
+    pub data_size: u16,
+}
+
+impl NvPublic {
+    pub(super) fn marshal(&self, buf: &mut Vec<u8>) -> Result<(), TpmError> {
+        put_u32(buf, self.nv_index.0);
+        put_u16(buf, self.name_alg.0);
+        put_u32(buf, self.attributes.bits());
+        put_sized(buf, &self.auth_policy)?;
+        put_u16(buf, self.data_size);
+        Ok(())
+    }
+
+    pub(super) fn unmarshal(reader: &mut Reader) -> Result<Self, TpmError> {
+        Ok(Self {
+            nv_index: TpmHandle(reader.u32()?),
+            name_alg: AlgorithmId(reader.u16()?),
+            attributes: NvAttributes::from_bits_retain(reader.u32()?),
+            auth_policy: reader.sized()?.to_vec(),
+            data_size: reader.u16()?,
+        })
+    }
+}
+
+/// Sensitive data of an object to create (`TPMS_SENSITIVE_CREATE`).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SensitiveCreate<'a> {
+    /// Authorization value of the object.
+    pub user_auth: &'a [u8],
+    /// Data to seal, for a keyed hash object; empty otherwise.
+    pub data: &'a [u8],
+}
+
+impl SensitiveCreate<'_> {
+    pub(super) fn marshal(&self, buf: &mut Vec<u8>) -> Result<(), TpmError> {
+        put_sized_with(buf, |buf| {
+            put_sized(buf, self.user_auth)?;
+            put_sized(buf, self.data)
+        })
+    }
+}
+
+/// Capability data returned by [`Tpm::get_capability`]
+/// (`TPMU_CAPABILITIES`).
+///
+/// [`Tpm::get_capability`]: super::Tpm::get_capability
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CapabilityData {
+    /// Algorithms and their attributes (`TPMA_ALGORITHM`).
+    Algorithms(Vec<(AlgorithmId, u32)>),
+    /// Handles.
+    Handles(Vec<TpmHandle>),
+    /// Implemented commands, with their attributes (`TPMA_CC`). The command
+    /// code is in the low 16 bits.
+    Commands(Vec<u32>),
+    /// Commands that require physical presence.
+    PpCommands(Vec<CommandCode>),
+    /// Commands that are audited.
+    AuditCommands(Vec<CommandCode>),
+    /// PCRs that are allocated in each bank.
+    Pcrs(Vec<PcrSelection>),
+    /// TPM properties and their values.
+    TpmProperties(Vec<(TpmProperty, u32)>),
+    /// PCR properties (`TPM_PT_PCR`), each with the PCRs that have it.
+    PcrProperties(Vec<(u32, u32)>),
+    /// Implemented ECC curves (`TPM_ECC_CURVE`).
+    EccCurves(Vec<u16>),
+}
+
+impl CapabilityData {
+    pub(super) fn unmarshal(reader: &mut Reader) -> Result<Self, TpmError> {
+        Ok(match Capability(reader.u32()?) {
+            Capability::ALGORITHMS => {
+                Self::Algorithms(reader.list(|r| Ok((AlgorithmId(r.u16()?), r.u32()?)))?)
+            }
+            Capability::HANDLES => Self::Handles(reader.list(|r| r.u32().map(TpmHandle))?),
+            Capability::COMMANDS => Self::Commands(reader.list(Reader::u32)?),
+            Capability::PP_COMMANDS => Self::PpCommands(reader.list(|r| r.u32().map(CommandCode))?),
+            Capability::AUDIT_COMMANDS => {
+                Self::AuditCommands(reader.list(|r| r.u32().map(CommandCode))?)
+            }
+            Capability::PCRS => Self::Pcrs(reader.list(PcrSelection::unmarshal)?),
+            Capability::TPM_PROPERTIES => {
+                Self::TpmProperties(reader.list(|r| Ok((TpmProperty(r.u32()?), r.u32()?)))?)
+            }
+            Capability::PCR_PROPERTIES => Self::PcrProperties(
+                reader.list(|r| Ok((r.u32()?, PcrSelection::unmarshal_select(r)?)))?,
+            ),
+            Capability::ECC_CURVES => Self::EccCurves(reader.list(Reader::u16)?),
+            _ => return Err(TpmError::InvalidResponse),
+        })
+    }
+}
@@ -10,6 +10,7 @@
 //! [TCG]: https://trustedcomputinggroup.org/
 //! [TPM]: https://en.wikipedia.org/wiki/Trusted_Platform_Module
 
+use super::event_data::{EventData, EventDataError};
 use super::{AlgorithmId, EventType, HashAlgorithm, PcrIndex};
 use crate::data_types::{Align, PhysicalAddress};
 use crate::proto::unsafe_protocol;
@@ -183,6 +184,18 @@ impl PcrEvent {
         &self.event_data
     }
 
+    /// Decode the [`event_data`] according to the [`event_type`].
+    ///
+    /// # Errors
+    ///
+    /// See [`EventData::parse`].
+    ///
+    /// [`event_data`]: Self::event_data
+    /// [`event_type`]: Self::event_type
+    pub fn decode_event_data(&self) -> core::result::Result<EventData<'_>, EventDataError> {
+        EventData::parse(self.event_type, &self.event_data)
+    }
+
     /// SHA-1 digest of the data hashed for this event.
     #[must_use]
     pub const fn digest(&self) -> Sha1Digest {
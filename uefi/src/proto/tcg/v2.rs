@@ -12,6 +12,7 @@
 //! [TCG]: https://trustedcomputinggroup.org/
 //! [TPM]: https://en.wikipedia.org/wiki/Trusted_Platform_Module
 
+use super::event_data::{EventData, EventDataError, EventLogDump, SpecIdEvent};
 use super::{AlgorithmId, EventType, HashAlgorithm, PcrIndex, v1};
 use crate::data_types::{Align, PhysicalAddress, UnalignedSlice};
 use crate::proto::unsafe_protocol;
@@ -310,6 +311,11 @@ impl<'a> EventLogHeader<'a> {
 ///
 /// This type of event log can contain multiple hash types (e.g. SHA-1, SHA-256,
 /// SHA-512, etc).
+///
+/// The [`cc::CcMeasurement`] protocol returns the same type of event log, with
+/// measurement register indices instead of PCR indices.
+///
+/// [`cc::CcMeasurement`]: super::cc::CcMeasurement
 #[derive(Debug)]
 pub struct EventLog<'a> {
     // Tie the lifetime to the protocol, and by extension, boot services.
@@ -322,6 +328,19 @@ pub struct EventLog<'a> {
 }
 
 impl EventLog<'_> {
+    pub(super) const unsafe fn new(
+        location: *const u8,
+        last_entry: *const u8,
+        is_truncated: bool,
+    ) -> Self {
+        Self {
+            _lifetime: PhantomData,
+            location,
+            last_entry,
+            is_truncated,
+        }
+    }
+
     /// Iterator of events in the log.
     #[must_use]
     pub fn iter(&self) -> EventLogIter<'_> {
@@ -364,6 +383,23 @@ impl EventLog<'_> {
     pub const fn is_truncated(&self) -> bool {
         self.is_truncated
     }
+
+    /// Spec ID event at the beginning of the event log, which is not returned
+    /// by [`iter`](Self::iter).
+    #[must_use]
+    pub fn spec_id_event(&self) -> Option<SpecIdEvent<'_>> {
+        self.header()?;
+        // Safety: the header is valid, so is the event at `location`.
+        let event = unsafe { v1::PcrEvent::from_ptr(self.location) };
+        SpecIdEvent::parse(event.event_data()).ok()
+    }
+
+    /// Get a human-readable dump of the event log, with the decoded data of
+    /// each event.
+    #[must_use]
+    pub const fn dump(&self) -> EventLogDump<'_> {
+        EventLogDump::new(self)
+    }
 }
 
 /// Digests in a PCR event.
@@ -499,6 +535,18 @@ impl<'a> PcrEvent<'a> {
         self.event_data
     }
 
+    /// Decode the [`event_data`] according to the [`event_type`].
+    ///
+    /// # Errors
+    ///
+    /// See [`EventData::parse`].
+    ///
+    /// [`event_data`]: Self::event_data
+    /// [`event_type`]: Self::event_type
+    pub fn decode_event_data(&self) -> core::result::Result<EventData<'_>, EventDataError> {
+        EventData::parse(self.event_type, self.event_data)
+    }
+
     /// Digests of the data hashed for this event.
     #[must_use]
     pub fn digests(&self) -> PcrEventDigests<'_> {
@@ -612,11 +660,12 @@ impl Tcg {
         };
 
         if status.is_success() {
-            let log = EventLog {
-                _lifetime: PhantomData,
-                location: location as *const u8,
-                last_entry: last_entry as *const u8,
-                is_truncated: truncated.into(),
+            let log = unsafe {
+                EventLog::new(
+                    location as *const u8,
+                    last_entry as *const u8,
+                    truncated.into(),
+                )
             };
 
             Ok(log)
@@ -730,6 +779,7 @@ impl Tcg {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
     use alloc::vec::Vec;
     use core::slice;
 
@@ -1032,5 +1082,20 @@ mod tests {
         ]);
 
         assert!(iter.next().is_none());
+
+        let dump = log.dump().to_string();
+        assert!(dump.starts_with("---\nversion: 1\nevents:\n- PCRIndex: 0\n"));
+        assert!(
+            dump.contains("    - Algorithm[3]:\n      algorithmId: SHA512\n      digestSize: 64\n")
+        );
+        assert!(dump.contains(
+            "- EventNum: 0\n  PCRIndex: 0\n  EventType: CRTM_VERSION\n  DigestCount: 4\n"
+        ));
+        assert!(dump.contains(
+            "  - AlgorithmId: SHA1\n    Digest: \"1489f923c4dca729178b3e3233458550d8dddf29\"\n"
+        ));
+        assert!(dump.ends_with(
+            "  EventSize: 16\n  Event:\n    BlobBase: 0x820000\n    BlobLength: 0xe0000\n"
+        ));
     }
 }
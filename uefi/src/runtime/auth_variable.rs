@@ -0,0 +1,364 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Time-based authenticated variable writes.
+//!
+//! Variables with the [`TIME_BASED_AUTHENTICATED_WRITE_ACCESS`] attribute,
+//! such as the Secure Boot signature databases, can only be written with data
+//! that starts with an `EFI_VARIABLE_AUTHENTICATION_2` header. The header has
+//! a timestamp and a PKCS #7 signature of the [data to sign], made with a key
+//! that is authorized to update the variable. [`AuthenticatedWrite`] builds
+//! this data and writes it:
+//!
+//! ```no_run
+//! use uefi::runtime::auth_variable::AuthenticatedWrite;
+//! use uefi::runtime::secure_boot::SignatureDatabaseVariable;
+//!
+//! # fn sign(data: &[u8]) -> Vec<u8> { unimplemented!() }
+//! # fn example(new_hashes: &[u8]) -> Result<(), Box<dyn core::error::Error>> {
+//! let dbx = SignatureDatabaseVariable::DBX;
+//! let write = AuthenticatedWrite::for_database(&dbx, new_hashes)
+//!     .with_timestamp(uefi::runtime::get_time()?)
+//!     .append();
+//!
+//! // Sign with a key in `KEK`, e.g. with a signing service.
+//! let signature = sign(&write.data_to_sign());
+//! write.write(&signature)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! In setup mode, the signature of writes to `KEK`, `db` and `dbx` is not
+//! verified, but the data must still start with the header.
+//!
+//! [`TIME_BASED_AUTHENTICATED_WRITE_ACCESS`]: VariableAttributes::TIME_BASED_AUTHENTICATED_WRITE_ACCESS
+//! [data to sign]: AuthenticatedWrite::data_to_sign
+
+use super::secure_boot::SignatureDatabaseVariable;
+use super::{Time, VariableAttributes, VariableVendor};
+use crate::{CStr16, Error, Status};
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+use uefi_raw::table::runtime::{
+    VariableAuthentication2, WinCertificate, WinCertificateType, WinCertificateUefiGuid,
+};
+
+/// Error returned by [`AuthenticatedWrite`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AuthenticatedWriteError {
+    /// The signature is too large for the certificate header.
+    SignatureTooLarge,
+    /// The firmware rejected the write with [`Status::SECURITY_VIOLATION`].
+    /// The signature is invalid, or not made with a key authorized to update
+    /// the variable, or the timestamp is not later than the timestamp of the
+    /// variable.
+    SecurityViolation,
+    /// The firmware rejected the write with another error. See
+    /// [`set_variable`] for the possible errors.
+    ///
+    /// [`set_variable`]: super::set_variable
+    SetVariable(Error),
+}
+
+impl Display for AuthenticatedWriteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SignatureTooLarge => write!(f, "signature is too large"),
+            Self::SecurityViolation => write!(
+                f,
+                "variable write was not authorized by the signature and timestamp"
+            ),
+            Self::SetVariable(err) => write!(f, "failed to set variable: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for AuthenticatedWriteError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::SetVariable(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for AuthenticatedWriteError {
+    fn from(err: Error) -> Self {
+        if err.status() == Status::SECURITY_VIOLATION {
+            Self::SecurityViolation
+        } else {
+            Self::SetVariable(err)
+        }
+    }
+}
+
+/// Builder of a time-based authenticated variable write
+/// (`EFI_VARIABLE_AUTHENTICATION_2`).
+///
+/// Writing an empty payload without [`append`](Self::append) deletes the
+/// variable.
+#[derive(Clone, Debug)]
+pub struct AuthenticatedWrite<'a> {
+    name: &'a CStr16,
+    vendor: &'a VariableVendor,
+    attributes: VariableAttributes,
+    timestamp: Time,
+    payload: &'a [u8],
+}
+
+impl<'a> AuthenticatedWrite<'a> {
+    /// Attributes of the Secure Boot signature databases, which are the
+    /// default attributes.
+    pub const DEFAULT_ATTRIBUTES: VariableAttributes = VariableAttributes::NON_VOLATILE
+        .union(VariableAttributes::BOOTSERVICE_ACCESS)
+        .union(VariableAttributes::RUNTIME_ACCESS)
+        .union(VariableAttributes::TIME_BASED_AUTHENTICATED_WRITE_ACCESS);
+
+    /// Create a write of `payload` to the variable `name` of `vendor`.
+    ///
+    /// The attributes are [`DEFAULT_ATTRIBUTES`], and the timestamp is zero,
+    /// which is only accepted for new variables and appends.
+    ///
+    /// [`DEFAULT_ATTRIBUTES`]: Self::DEFAULT_ATTRIBUTES
+    #[must_use]
+    pub const fn new(name: &'a CStr16, vendor: &'a VariableVendor, payload: &'a [u8]) -> Self {
+        Self {
+            name,
+            vendor,
+            attributes: Self::DEFAULT_ATTRIBUTES,
+            timestamp: Time::invalid(),
+            payload,
+        }
+    }
+
+    /// Create a write of `payload`, which is a list of signature lists, to a
+    /// signature database.
+    #[must_use]
+    pub const fn for_database(variable: &'a SignatureDatabaseVariable, payload: &'a [u8]) -> Self {
+        Self::new(variable.name(), variable.vendor(), payload)
+    }
+
+    /// Set the attributes of the variable.
+    /// [`TIME_BASED_AUTHENTICATED_WRITE_ACCESS`] is always set.
+    ///
+    /// [`TIME_BASED_AUTHENTICATED_WRITE_ACCESS`]: VariableAttributes::TIME_BASED_AUTHENTICATED_WRITE_ACCESS
+    #[must_use]
+    pub const fn with_attributes(mut self, attributes: VariableAttributes) -> Self {
+        self.attributes =
+            attributes.union(VariableAttributes::TIME_BASED_AUTHENTICATED_WRITE_ACCESS);
+        self
+    }
+
+    /// Set the timestamp of the write, usually the current time from
+    /// [`get_time`].
+    ///
+    /// Only the date and the time to the second are used. Times with a time
+    /// zone are converted to UTC, and local times are assumed to be UTC.
+    ///
+    /// [`get_time`]: super::get_time
+    #[must_use]
+    pub fn with_timestamp(mut self, timestamp: Time) -> Self {
+        self.timestamp = timestamp.to_utc().unwrap_or(timestamp);
+        self
+    }
+
+    /// Append the payload to the variable, rather than replacing it.
+    ///
+    /// For signature databases, the firmware only appends signatures that are
+    /// not in the database yet.
+    #[must_use]
+    pub const fn append(mut self) -> Self {
+        self.attributes = self.attributes.union(VariableAttributes::APPEND_WRITE);
+        self
+    }
+
+    /// Attributes the variable is written with.
+    #[must_use]
+    pub const fn attributes(&self) -> VariableAttributes {
+        self.attributes
+    }
+
+    /// Timestamp of the header, with the fields that must be zero cleared.
+    const fn raw_timestamp(&self) -> uefi_raw::time::Time {
+        uefi_raw::time::Time {
+            year: self.timestamp.year(),
+            month: self.timestamp.month(),
+            day: self.timestamp.day(),
+            hour: self.timestamp.hour(),
+            minute: self.timestamp.minute(),
+            second: self.timestamp.second(),
+            ..uefi_raw::time::Time::invalid()
+        }
+    }
+
+    /// Data that must be signed to authorize the write: the variable name
+    /// without null terminator, the vendor GUID, the attributes, the
+    /// timestamp and the payload.
+    ///
+    /// The signature is a DER-encoded PKCS #7 `SignedData` of this data, with
+    /// a SHA-256 digest, such as the one made by `sign-efi-sig-list` or
+    /// `openssl smime -sign -binary -outform DER -noattr`.
+    #[must_use]
+    pub fn data_to_sign(&self) -> Vec<u8> {
+        let name = self.name.as_bytes();
+        let name = &name[..name.len() - 2];
+        let mut data = Vec::with_capacity(name.len() + 16 + 4 + 16 + self.payload.len());
+        data.extend_from_slice(name);
+        data.extend_from_slice(&self.vendor.0.to_bytes());
+        data.extend_from_slice(&self.attributes.bits().to_le_bytes());
+        extend_time(&mut data, &self.raw_timestamp());
+        data.extend_from_slice(self.payload);
+        data
+    }
+
+    /// Build the data written to the variable: the authentication header with
+    /// the PKCS #7 `signature` of the [data to sign], and the payload.
+    ///
+    /// # Errors
+    ///
+    /// * [`AuthenticatedWriteError::SignatureTooLarge`]: the signature does
+    ///   not fit in the header.
+    ///
+    /// [data to sign]: Self::data_to_sign
+    pub fn to_bytes(&self, signature: &[u8]) -> Result<Vec<u8>, AuthenticatedWriteError> {
+        let header_size = size_of::<VariableAuthentication2>();
+        let certificate_size = header_size - size_of::<uefi_raw::time::Time>() + signature.len();
+        let length = u32::try_from(certificate_size)
+            .map_err(|_| AuthenticatedWriteError::SignatureTooLarge)?;
+        let header = WinCertificate {
+            length,
+            revision: WinCertificate::REVISION,
+            certificate_type: WinCertificateType::EFI_GUID,
+        };
+
+        let mut data = Vec::with_capacity(header_size + signature.len() + self.payload.len());
+        extend_time(&mut data, &self.raw_timestamp());
+        data.extend_from_slice(&header.length.to_le_bytes());
+        data.extend_from_slice(&header.revision.to_le_bytes());
+        data.extend_from_slice(&header.certificate_type.0.to_le_bytes());
+        data.extend_from_slice(&WinCertificateUefiGuid::CERT_TYPE_PKCS7.to_bytes());
+        data.extend_from_slice(signature);
+        data.extend_from_slice(self.payload);
+        Ok(data)
+    }
+
+    /// Write the variable, with the PKCS #7 `signature` of the
+    /// [data to sign].
+    ///
+    /// # Errors
+    ///
+    /// * [`AuthenticatedWriteError::SignatureTooLarge`]: the signature does
+    ///   not fit in the header.
+    /// * [`AuthenticatedWriteError::SecurityViolation`]: the firmware did not
+    ///   authorize the write.
+    /// * [`AuthenticatedWriteError::SetVariable`]: the firmware failed to
+    ///   write the variable for another reason.
+    ///
+    /// [data to sign]: Self::data_to_sign
+    pub fn write(&self, signature: &[u8]) -> Result<(), AuthenticatedWriteError> {
+        let data = self.to_bytes(signature)?;
+        super::set_variable(self.name, self.vendor, self.attributes, &data)?;
+        Ok(())
+    }
+}
+
+/// Append the bytes of `time` to `data`.
+fn extend_time(data: &mut Vec<u8>, time: &uefi_raw::time::Time) {
+    data.extend_from_slice(&time.year.to_le_bytes());
+    data.extend_from_slice(&[time.month, time.day, time.hour, time.minute, time.second]);
+    data.push(time.pad1);
+    data.extend_from_slice(&time.nanosecond.to_le_bytes());
+    data.extend_from_slice(&time.time_zone.to_le_bytes());
+    data.push(time.daylight.bits());
+    data.push(time.pad2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{Daylight, TimeParams};
+    use crate::{cstr16, guid};
+
+    const VENDOR: VariableVendor = VariableVendor(guid!("9baf21cf-e187-497e-ae77-5bd8b0e09703"));
+
+    fn timestamp() -> Time {
+        Time::new(TimeParams {
+            year: 2024,
+            month: 5,
+            day: 6,
+            hour: 1,
+            minute: 2,
+            second: 3,
+            nanosecond: 400,
+            time_zone: Some(60),
+            daylight: Daylight::empty(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_data_to_sign() {
+        let write = AuthenticatedWrite::new(cstr16!("ab"), &VENDOR, b"payload")
+            .with_timestamp(timestamp())
+            .append();
+        assert_eq!(
+            write.attributes(),
+            AuthenticatedWrite::DEFAULT_ATTRIBUTES | VariableAttributes::APPEND_WRITE
+        );
+
+        #[rustfmt::skip]
+        let expected = [
+            // Name
+            b'a', 0, b'b', 0,
+            // Vendor
+            0xcf, 0x21, 0xaf, 0x9b, 0x87, 0xe1, 0x7e, 0x49,
+            0xae, 0x77, 0x5b, 0xd8, 0xb0, 0xe0, 0x97, 0x03,
+            // Attributes
+            0x67, 0x00, 0x00, 0x00,
+            // Timestamp, converted to UTC, without nanoseconds and time zone
+            0xe8, 0x07, 5, 6, 0, 2, 3, 0,
+            0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        assert_eq!(write.data_to_sign(), [&expected[..], b"payload"].concat());
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        let write = AuthenticatedWrite::new(cstr16!("ab"), &VENDOR, b"payload")
+            .with_attributes(VariableAttributes::BOOTSERVICE_ACCESS);
+        assert_eq!(
+            write.attributes(),
+            VariableAttributes::BOOTSERVICE_ACCESS
+                | VariableAttributes::TIME_BASED_AUTHENTICATED_WRITE_ACCESS
+        );
+
+        let data = write.to_bytes(b"pkcs7").unwrap();
+        #[rustfmt::skip]
+        let expected = [
+            // Timestamp
+            0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0,
+            // Length
+            29, 0, 0, 0,
+            // Revision
+            0x00, 0x02,
+            // Certificate type
+            0xf1, 0x0e,
+            // PKCS #7 GUID
+            0x9d, 0xd2, 0xaf, 0x4a, 0xdf, 0x68, 0xee, 0x49,
+            0x8a, 0xa9, 0x34, 0x7d, 0x37, 0x56, 0x65, 0xa7,
+        ];
+        assert_eq!(data, [&expected[..], b"pkcs7", b"payload"].concat());
+    }
+
+    #[test]
+    fn test_error() {
+        assert_eq!(
+            AuthenticatedWriteError::from(Error::from(Status::SECURITY_VIOLATION)),
+            AuthenticatedWriteError::SecurityViolation
+        );
+        assert_eq!(
+            AuthenticatedWriteError::from(Error::from(Status::WRITE_PROTECTED)),
+            AuthenticatedWriteError::SetVariable(Error::from(Status::WRITE_PROTECTED))
+        );
+    }
+}
@@ -0,0 +1,584 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Load options (`EFI_LOAD_OPTION`) and the boot manager variables.
+//!
+//! The boot manager boots the options in the `Boot####` variables, in the
+//! order of the `BootOrder` variable, or the option in the `BootNext`
+//! variable first if it is set. Drivers in the `Driver####` variables are
+//! loaded before, in the order of `DriverOrder`. Each of these variables holds
+//! a [`LoadOption`], which is an image file path and a description.
+//!
+//! An installer can register itself as the first boot option:
+//!
+//! ```no_run
+//! use uefi::cstr16;
+//! use uefi::proto::device_path::DevicePath;
+//! use uefi::runtime::load_option::{self, LoadOption, LoadOptionType};
+//!
+//! # fn example(file_path: &DevicePath) -> uefi::Result {
+//! let option = LoadOption::new(cstr16!("My OS"), file_path);
+//! let number = load_option::insert(LoadOptionType::Boot, &option, 0)?;
+//!
+//! // Boot the new option once, even if it is moved down later.
+//! load_option::set_boot_next(Some(number))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{VariableAttributes, VariableVendor};
+use crate::proto::device_path::DevicePath;
+use crate::{CStr16, CString16, Error, Result, Status, cstr16};
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+pub use uefi_raw::table::runtime::LoadOptionAttributes;
+
+/// Attributes of the boot manager variables.
+const ATTRIBUTES: VariableAttributes = VariableAttributes::NON_VOLATILE
+    .union(VariableAttributes::BOOTSERVICE_ACCESS)
+    .union(VariableAttributes::RUNTIME_ACCESS);
+
+/// Size of the attributes and file path list length at the start of a load
+/// option.
+const HEADER_SIZE: usize = 6;
+
+/// Error returned by [`LoadOption::parse`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LoadOptionError {
+    /// The data is shorter than the sizes it contains.
+    Truncated,
+    /// The description is not a null-terminated UCS-2 string.
+    InvalidDescription,
+    /// The file path list is empty or has an invalid device path.
+    InvalidFilePathList,
+}
+
+impl Display for LoadOptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "load option is truncated"),
+            Self::InvalidDescription => write!(f, "load option has an invalid description"),
+            Self::InvalidFilePathList => write!(f, "load option has an invalid file path list"),
+        }
+    }
+}
+
+impl core::error::Error for LoadOptionError {}
+
+/// Type of a load option, which determines the names of its variables.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum LoadOptionType {
+    /// Boot option, in `Boot####` and `BootOrder`.
+    Boot,
+    /// Driver, in `Driver####` and `DriverOrder`.
+    Driver,
+    /// System preparation application, in `SysPrep####` and `SysPrepOrder`.
+    SysPrep,
+}
+
+impl LoadOptionType {
+    const fn prefix(self) -> &'static str {
+        match self {
+            Self::Boot => "Boot",
+            Self::Driver => "Driver",
+            Self::SysPrep => "SysPrep",
+        }
+    }
+
+    /// Name of the variable with the order of the options, such as
+    /// `BootOrder`.
+    #[must_use]
+    pub const fn order_variable_name(self) -> &'static CStr16 {
+        match self {
+            Self::Boot => cstr16!("BootOrder"),
+            Self::Driver => cstr16!("DriverOrder"),
+            Self::SysPrep => cstr16!("SysPrepOrder"),
+        }
+    }
+
+    /// Name of the variable of option `number`, such as `Boot000A`.
+    #[must_use]
+    pub fn variable_name(self, number: u16) -> CString16 {
+        let name = format!("{}{number:04X}", self.prefix());
+        // OK to unwrap: the name only has ASCII characters.
+        CString16::try_from(name.as_str()).unwrap()
+    }
+
+    /// Get the option number of the variable `name`, if it is an option
+    /// variable of this type.
+    #[must_use]
+    pub fn parse_variable_name(self, name: &CStr16) -> Option<u16> {
+        let mut chars = name.iter().map(|c| char::from(*c));
+        if !self.prefix().chars().all(|p| chars.next() == Some(p)) {
+            return None;
+        }
+        let mut number = 0;
+        for _ in 0..4 {
+            let digit = chars.next()?;
+            // Variable names use uppercase hexadecimal digits.
+            if digit.is_ascii_lowercase() {
+                return None;
+            }
+            number = number * 16 + u16::try_from(digit.to_digit(16)?).ok()?;
+        }
+        chars.next().is_none().then_some(number)
+    }
+}
+
+/// Load option (`EFI_LOAD_OPTION`), stored in `Boot####`, `Driver####` and
+/// `SysPrep####` variables.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoadOption {
+    attributes: LoadOptionAttributes,
+    description: CString16,
+    file_path_list: Vec<u8>,
+    optional_data: Vec<u8>,
+}
+
+impl LoadOption {
+    /// Create an [active](LoadOptionAttributes::ACTIVE) option that loads the
+    /// image at `file_path`, without optional data.
+    #[must_use]
+    pub fn new(description: &CStr16, file_path: &DevicePath) -> Self {
+        Self {
+            attributes: LoadOptionAttributes::ACTIVE,
+            description: description.into(),
+            file_path_list: file_path.as_bytes().to_vec(),
+            optional_data: Vec::new(),
+        }
+    }
+
+    /// Set the attributes of the option.
+    #[must_use]
+    pub const fn with_attributes(mut self, attributes: LoadOptionAttributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Append a device path to the file path list. The meaning of the
+    /// additional paths depends on the image.
+    #[must_use]
+    pub fn with_additional_file_path(mut self, file_path: &DevicePath) -> Self {
+        self.file_path_list.extend_from_slice(file_path.as_bytes());
+        self
+    }
+
+    /// Set the optional data, which is passed to the image as its load
+    /// options.
+    #[must_use]
+    pub fn with_optional_data(mut self, optional_data: &[u8]) -> Self {
+        self.optional_data = optional_data.to_vec();
+        self
+    }
+
+    /// Parse a load option.
+    ///
+    /// # Errors
+    ///
+    /// * [`LoadOptionError::Truncated`]: the data is shorter than the sizes it
+    ///   contains.
+    /// * [`LoadOptionError::InvalidDescription`]: the description is not a
+    ///   null-terminated UCS-2 string.
+    /// * [`LoadOptionError::InvalidFilePathList`]: the file path list is
+    ///   empty or has an invalid device path.
+    pub fn parse(data: &[u8]) -> core::result::Result<Self, LoadOptionError> {
+        let header = data.get(..HEADER_SIZE).ok_or(LoadOptionError::Truncated)?;
+        let attributes = LoadOptionAttributes::from_bits_retain(u32::from_le_bytes(
+            // OK to unwrap: the header is large enough.
+            header[..4].try_into().unwrap(),
+        ));
+        let file_path_list_length = usize::from(u16::from_le_bytes([header[4], header[5]]));
+
+        let rest = &data[HEADER_SIZE..];
+        let (chars, _) = rest.as_chunks::<2>();
+        let description_len = chars
+            .iter()
+            .position(|c| *c == [0, 0])
+            .ok_or(LoadOptionError::Truncated)?
+            + 1;
+        let description = chars[..description_len]
+            .iter()
+            .map(|c| u16::from_le_bytes(*c))
+            .collect::<Vec<_>>();
+        let description =
+            CString16::try_from(description).map_err(|_| LoadOptionError::InvalidDescription)?;
+
+        let rest = &rest[description_len * 2..];
+        if rest.len() < file_path_list_length {
+            return Err(LoadOptionError::Truncated);
+        }
+        let (file_path_list, optional_data) = rest.split_at(file_path_list_length);
+        if file_path_list.is_empty() {
+            return Err(LoadOptionError::InvalidFilePathList);
+        }
+        let mut paths = file_path_list;
+        while !paths.is_empty() {
+            let path =
+                <&DevicePath>::try_from(paths).map_err(|_| LoadOptionError::InvalidFilePathList)?;
+            paths = &paths[path.as_bytes().len()..];
+        }
+
+        Ok(Self {
+            attributes,
+            description,
+            file_path_list: file_path_list.to_vec(),
+            optional_data: optional_data.to_vec(),
+        })
+    }
+
+    /// Serialize the option.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Status::BAD_BUFFER_SIZE`] if the file path list is larger
+    /// than 64 KiB.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let file_path_list_length = u16::try_from(self.file_path_list.len())
+            .map_err(|_| Error::from(Status::BAD_BUFFER_SIZE))?;
+        let description = self.description.as_bytes();
+        let mut data = Vec::with_capacity(
+            HEADER_SIZE + description.len() + self.file_path_list.len() + self.optional_data.len(),
+        );
+        data.extend_from_slice(&self.attributes.bits().to_le_bytes());
+        data.extend_from_slice(&file_path_list_length.to_le_bytes());
+        data.extend_from_slice(description);
+        data.extend_from_slice(&self.file_path_list);
+        data.extend_from_slice(&self.optional_data);
+        Ok(data)
+    }
+
+    /// Attributes of the option.
+    #[must_use]
+    pub const fn attributes(&self) -> LoadOptionAttributes {
+        self.attributes
+    }
+
+    /// Description of the option, shown in the boot menu.
+    #[must_use]
+    pub fn description(&self) -> &CStr16 {
+        &self.description
+    }
+
+    /// Device path of the image.
+    #[must_use]
+    pub fn file_path(&self) -> &DevicePath {
+        // OK to unwrap: the list has at least one valid device path.
+        self.file_paths().next().unwrap()
+    }
+
+    /// Device paths of the file path list, starting with the image.
+    pub fn file_paths(&self) -> impl Iterator<Item = &DevicePath> {
+        let mut paths = self.file_path_list.as_slice();
+        core::iter::from_fn(move || {
+            // The paths are validated, so this only fails at the end.
+            let path = <&DevicePath>::try_from(paths).ok()?;
+            paths = &paths[path.as_bytes().len()..];
+            Some(path)
+        })
+    }
+
+    /// Optional data, which is passed to the image as its load options.
+    #[must_use]
+    pub fn optional_data(&self) -> &[u8] {
+        &self.optional_data
+    }
+
+    /// Read option `number` of type `option_type`.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::VOLUME_CORRUPTED`]: the variable is not a valid load
+    ///   option.
+    /// * See [`get_variable_boxed`] for errors reading the variable.
+    ///
+    /// [`get_variable_boxed`]: super::get_variable_boxed
+    pub fn read(option_type: LoadOptionType, number: u16) -> Result<Self> {
+        let name = option_type.variable_name(number);
+        let (data, _) = super::get_variable_boxed(&name, &VariableVendor::GLOBAL_VARIABLE)?;
+        Self::parse(&data).map_err(|_| Error::from(Status::VOLUME_CORRUPTED))
+    }
+
+    /// Write the option to option `number` of type `option_type`. This does
+    /// not change the order variable.
+    ///
+    /// # Errors
+    ///
+    /// See [`to_bytes`](Self::to_bytes) and [`set_variable`].
+    ///
+    /// [`set_variable`]: super::set_variable
+    pub fn write(&self, option_type: LoadOptionType, number: u16) -> Result {
+        let name = option_type.variable_name(number);
+        super::set_variable(
+            &name,
+            &VariableVendor::GLOBAL_VARIABLE,
+            ATTRIBUTES,
+            &self.to_bytes()?,
+        )
+    }
+}
+
+/// Read a variable holding a list of option numbers, or an empty list if it
+/// does not exist.
+fn read_numbers(name: &CStr16) -> Result<Vec<u16>> {
+    match super::get_variable_boxed(name, &VariableVendor::GLOBAL_VARIABLE) {
+        Ok((data, _)) => {
+            let (numbers, []) = data.as_chunks::<2>() else {
+                return Err(Status::VOLUME_CORRUPTED.into());
+            };
+            Ok(numbers.iter().map(|n| u16::from_le_bytes(*n)).collect())
+        }
+        Err(err) if err.status() == Status::NOT_FOUND => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Write a variable holding a list of option numbers.
+fn write_numbers(name: &CStr16, numbers: &[u16]) -> Result {
+    let data: Vec<u8> = numbers.iter().flat_map(|n| n.to_le_bytes()).collect();
+    super::set_variable(name, &VariableVendor::GLOBAL_VARIABLE, ATTRIBUTES, &data)
+}
+
+/// Get the order of the options of type `option_type`, such as `BootOrder`.
+///
+/// Returns an empty list if the order variable does not exist.
+///
+/// # Errors
+///
+/// * [`Status::VOLUME_CORRUPTED`]: the size of the variable is odd.
+/// * See [`get_variable_boxed`] for errors reading the variable.
+///
+/// [`get_variable_boxed`]: super::get_variable_boxed
+pub fn order(option_type: LoadOptionType) -> Result<Vec<u16>> {
+    read_numbers(option_type.order_variable_name())
+}
+
+/// Set the order of the options of type `option_type`, such as `BootOrder`.
+///
+/// # Errors
+///
+/// See [`set_variable`](super::set_variable).
+pub fn set_order(option_type: LoadOptionType, order: &[u16]) -> Result {
+    write_numbers(option_type.order_variable_name(), order)
+}
+
+/// Get the numbers of all options of type `option_type`, in ascending order,
+/// whether or not they are in the order variable.
+///
+/// # Errors
+///
+/// See [`variable_keys`](super::variable_keys).
+pub fn numbers(option_type: LoadOptionType) -> Result<Vec<u16>> {
+    let mut numbers = Vec::new();
+    for key in super::variable_keys() {
+        let key = key?;
+        if key.vendor == VariableVendor::GLOBAL_VARIABLE
+            && let Some(number) = option_type.parse_variable_name(&key.name)
+        {
+            numbers.push(number);
+        }
+    }
+    numbers.sort_unstable();
+    Ok(numbers)
+}
+
+/// Write `option` to the lowest unused option number of type `option_type`,
+/// and insert the number at `index` in the order variable. The number is
+/// appended if `index` is past the end of the order.
+///
+/// Returns the option number.
+///
+/// # Errors
+///
+/// * [`Status::OUT_OF_RESOURCES`]: all option numbers are used.
+/// * See [`numbers`], [`order`] and [`LoadOption::write`].
+pub fn insert(option_type: LoadOptionType, option: &LoadOption, index: usize) -> Result<u16> {
+    let used = numbers(option_type)?;
+    let number = (0..=u16::MAX)
+        .find(|n| used.binary_search(n).is_err())
+        .ok_or(Error::from(Status::OUT_OF_RESOURCES))?;
+    option.write(option_type, number)?;
+
+    let mut order = order(option_type)?;
+    order.retain(|n| *n != number);
+    order.insert(index.min(order.len()), number);
+    set_order(option_type, &order)?;
+    Ok(number)
+}
+
+/// Delete option `number` of type `option_type`, and remove it from the
+/// order variable. For boot options, `BootNext` is also deleted if it is the
+/// option.
+///
+/// # Errors
+///
+/// See [`order`], [`set_order`] and [`delete_variable`].
+///
+/// [`delete_variable`]: super::delete_variable
+pub fn delete(option_type: LoadOptionType, number: u16) -> Result {
+    let mut order = order(option_type)?;
+    if order.contains(&number) {
+        order.retain(|n| *n != number);
+        set_order(option_type, &order)?;
+    }
+    if option_type == LoadOptionType::Boot && boot_next()? == Some(number) {
+        set_boot_next(None)?;
+    }
+    let name = option_type.variable_name(number);
+    super::delete_variable(&name, &VariableVendor::GLOBAL_VARIABLE)
+}
+
+/// Get the boot option to boot on the next boot only (`BootNext`).
+///
+/// # Errors
+///
+/// * [`Status::VOLUME_CORRUPTED`]: the variable is not a single number.
+/// * See [`get_variable_boxed`] for errors reading the variable.
+///
+/// [`get_variable_boxed`]: super::get_variable_boxed
+pub fn boot_next() -> Result<Option<u16>> {
+    match read_numbers(cstr16!("BootNext"))?.as_slice() {
+        [] => Ok(None),
+        [number] => Ok(Some(*number)),
+        _ => Err(Status::VOLUME_CORRUPTED.into()),
+    }
+}
+
+/// Set the boot option to boot on the next boot only (`BootNext`), or delete
+/// `BootNext` if `number` is `None`.
+///
+/// # Errors
+///
+/// See [`set_variable`](super::set_variable).
+pub fn set_boot_next(number: Option<u16>) -> Result {
+    let name = cstr16!("BootNext");
+    match number {
+        Some(number) => write_numbers(name, &[number]),
+        None => match super::delete_variable(name, &VariableVendor::GLOBAL_VARIABLE) {
+            Err(err) if err.status() == Status::NOT_FOUND => Ok(()),
+            result => result,
+        },
+    }
+}
+
+/// Get the boot option that was booted (`BootCurrent`).
+///
+/// # Errors
+///
+/// * [`Status::NOT_FOUND`]: the image was not booted from a boot option.
+/// * [`Status::VOLUME_CORRUPTED`]: the variable is not a single number.
+/// * See [`get_variable_boxed`] for errors reading the variable.
+///
+/// [`get_variable_boxed`]: super::get_variable_boxed
+pub fn boot_current() -> Result<u16> {
+    match read_numbers(cstr16!("BootCurrent"))?.as_slice() {
+        [] => Err(Status::NOT_FOUND.into()),
+        [number] => Ok(*number),
+        _ => Err(Status::VOLUME_CORRUPTED.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::device_path::build::{self, DevicePathBuilder};
+
+    fn device_path<'a>(buf: &'a mut Vec<u8>, file: &CStr16) -> &'a DevicePath {
+        DevicePathBuilder::with_vec(buf)
+            .push(&build::media::FilePath { path_name: file })
+            .unwrap()
+            .finalize()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_variable_name() {
+        let boot = LoadOptionType::Boot;
+        assert_eq!(boot.variable_name(0xa), cstr16!("Boot000A"));
+        assert_eq!(
+            LoadOptionType::SysPrep.variable_name(0x1234),
+            cstr16!("SysPrep1234")
+        );
+        assert_eq!(boot.parse_variable_name(cstr16!("Boot000A")), Some(0xa));
+        assert_eq!(boot.parse_variable_name(cstr16!("Boot000a")), None);
+        assert_eq!(boot.parse_variable_name(cstr16!("Boot00A")), None);
+        assert_eq!(boot.parse_variable_name(cstr16!("Boot000A0")), None);
+        assert_eq!(boot.parse_variable_name(cstr16!("BootOrder")), None);
+        assert_eq!(
+            LoadOptionType::Driver.parse_variable_name(cstr16!("Boot000A")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_load_option() {
+        let (mut buf1, mut buf2) = (Vec::new(), Vec::new());
+        let path = device_path(&mut buf1, cstr16!("\\a.efi"));
+        let initrd = device_path(&mut buf2, cstr16!("\\b"));
+        let option = LoadOption::new(cstr16!("OS"), path)
+            .with_attributes(LoadOptionAttributes::ACTIVE | LoadOptionAttributes::HIDDEN)
+            .with_additional_file_path(initrd)
+            .with_optional_data(b"data");
+
+        let bytes = option.to_bytes().unwrap();
+        let paths_len = path.as_bytes().len() + initrd.as_bytes().len();
+        assert_eq!(bytes[..4], [0x09, 0, 0, 0]);
+        assert_eq!(
+            usize::from(u16::from_le_bytes([bytes[4], bytes[5]])),
+            paths_len
+        );
+        assert_eq!(bytes[6..12], [b'O', 0, b'S', 0, 0, 0]);
+        assert_eq!(bytes[12..12 + path.as_bytes().len()], *path.as_bytes());
+        assert!(bytes.ends_with(b"data"));
+
+        let parsed = LoadOption::parse(&bytes).unwrap();
+        assert_eq!(parsed, option);
+        assert_eq!(parsed.description(), cstr16!("OS"));
+        assert_eq!(parsed.file_path(), path);
+        assert_eq!(parsed.file_paths().collect::<Vec<_>>(), [path, initrd]);
+        assert_eq!(parsed.optional_data(), b"data");
+    }
+
+    #[test]
+    fn test_load_option_invalid() {
+        let mut buf = Vec::new();
+        let path = device_path(&mut buf, cstr16!("\\a.efi"));
+        let bytes = LoadOption::new(cstr16!("OS"), path).to_bytes().unwrap();
+
+        assert_eq!(
+            LoadOption::parse(&bytes[..5]),
+            Err(LoadOptionError::Truncated)
+        );
+        assert_eq!(
+            LoadOption::parse(&bytes[..bytes.len() - 1]),
+            Err(LoadOptionError::Truncated)
+        );
+
+        // Invalid character in the description.
+        let mut invalid = bytes.clone();
+        invalid[6..8].copy_from_slice(&0xd800u16.to_le_bytes());
+        assert_eq!(
+            LoadOption::parse(&invalid),
+            Err(LoadOptionError::InvalidDescription)
+        );
+
+        // Empty file path list.
+        let mut invalid = bytes[..12].to_vec();
+        invalid[4..6].copy_from_slice(&[0, 0]);
+        assert_eq!(
+            LoadOption::parse(&invalid),
+            Err(LoadOptionError::InvalidFilePathList)
+        );
+
+        // Device path without end node.
+        let mut invalid = bytes;
+        let len = invalid.len();
+        invalid.truncate(len - 4);
+        let new_len = u16::try_from(len - 4 - 12).unwrap();
+        invalid[4..6].copy_from_slice(&new_len.to_le_bytes());
+        assert_eq!(
+            LoadOption::parse(&invalid),
+            Err(LoadOptionError::InvalidFilePathList)
+        );
+    }
+}
@@ -7,9 +7,15 @@
 //! functions after exiting boot services; see the "Calling Convention" section
 //! of the UEFI specification for details.
 
+#[cfg(feature = "alloc")]
+pub mod auth_variable;
+#[cfg(feature = "alloc")]
+pub mod load_option;
+pub mod secure_boot;
+
 mod time;
 
-pub use time::{Time, TimeByteConversionError, TimeError, TimeParams};
+pub use time::{Iso8601, Time, TimeByteConversionError, TimeError, TimeParams, TimeParseError};
 
 use crate::data_types::PhysicalAddress;
 use crate::table::{self, Revision};
@@ -18,6 +24,7 @@ use core::fmt::Debug;
 #[cfg(feature = "alloc")]
 use core::fmt::{self, Display, Formatter};
 use core::ptr::{self, NonNull};
+use uefi_raw::Boolean;
 use uefi_raw::table::boot::MemoryDescriptor;
 
 #[cfg(feature = "alloc")]
@@ -81,6 +88,90 @@ pub unsafe fn set_time(time: &Time) -> Result {
     unsafe { (rt.set_time)(time.cast()) }.to_result()
 }
 
+/// Query the current wakeup alarm clock setting.
+///
+/// See [`WakeupTime`] for details of the information returned.
+///
+/// # Errors
+///
+/// * [`Status::DEVICE_ERROR`]: the wakeup time could not be retrieved due to a
+///   hardware error, or the firmware returned a time with out-of-range fields.
+/// * [`Status::UNSUPPORTED`]: the wakeup timer is not supported on this
+///   platform.
+pub fn get_wakeup_time() -> Result<WakeupTime> {
+    let rt = runtime_services_raw_panicking();
+    let rt = unsafe { rt.as_ref() };
+
+    let mut enabled = Boolean::FALSE;
+    let mut pending = Boolean::FALSE;
+    let mut time = Time::invalid();
+    let time_ptr: *mut Time = &mut time;
+    unsafe { (rt.get_wakeup_time)(&mut enabled, &mut pending, time_ptr.cast()) }.to_result()?;
+
+    if time.is_valid().is_err() {
+        return Err(Status::DEVICE_ERROR.into());
+    }
+
+    Ok(WakeupTime {
+        enabled: enabled.into(),
+        pending: pending.into(),
+        time,
+    })
+}
+
+/// Enables the wakeup alarm clock and sets it to `time`.
+///
+/// If the platform is in a sleeping or off state when the alarm fires, it is
+/// woken up. Setting the wakeup time clears any pending alarm.
+///
+/// During runtime, if a PC-AT CMOS device is present in the platform, the
+/// caller must synchronize access to the device before calling
+/// `set_wakeup_time`.
+///
+/// # Safety
+///
+/// Undefined behavior could happen if multiple tasks try to
+/// use this function at the same time without synchronisation.
+///
+/// # Errors
+///
+/// * [`Status::INVALID_PARAMETER`]: `time` is not valid.
+/// * [`Status::DEVICE_ERROR`]: the wakeup time could not be set due to a
+///   hardware error.
+/// * [`Status::UNSUPPORTED`]: the wakeup timer is not supported on this
+///   platform.
+pub unsafe fn set_wakeup_time(time: &Time) -> Result {
+    let rt = runtime_services_raw_panicking();
+    let rt = unsafe { rt.as_ref() };
+
+    let time: *const Time = time;
+    unsafe { (rt.set_wakeup_time)(Boolean::TRUE, time.cast()) }.to_result()
+}
+
+/// Disables the wakeup alarm clock.
+///
+/// During runtime, if a PC-AT CMOS device is present in the platform, the
+/// caller must synchronize access to the device before calling
+/// `disable_wakeup_time`.
+///
+/// # Safety
+///
+/// Undefined behavior could happen if multiple tasks try to
+/// use this function at the same time without synchronisation.
+///
+/// # Errors
+///
+/// * [`Status::DEVICE_ERROR`]: the wakeup time could not be disabled due to a
+///   hardware error.
+/// * [`Status::UNSUPPORTED`]: the wakeup timer is not supported on this
+///   platform.
+pub unsafe fn disable_wakeup_time() -> Result {
+    let rt = runtime_services_raw_panicking();
+    let rt = unsafe { rt.as_ref() };
+
+    unsafe { (rt.set_wakeup_time)(Boolean::FALSE, ptr::null()) }.to_result()
+}
+
 /// Checks if a variable exists.
 ///
 /// Returns `Ok(true)` if the variable exists, `Ok(false)` if the variable does
@@ -581,6 +672,20 @@ impl Display for VariableKey {
     }
 }
 
+/// Wakeup alarm clock setting returned by [`get_wakeup_time`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WakeupTime {
+    /// Whether the wakeup alarm is enabled.
+    pub enabled: bool,
+
+    /// Whether the wakeup alarm has fired but not yet been acknowledged by
+    /// setting or disabling the wakeup time.
+    pub pending: bool,
+
+    /// The time at which the wakeup alarm is set to fire.
+    pub time: Time,
+}
+
 /// Information about UEFI variable storage space returned by
 /// [`query_variable_info`].
 ///
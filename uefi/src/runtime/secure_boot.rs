@@ -0,0 +1,631 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Secure Boot variables and signature databases.
+//!
+//! Secure Boot is configured by the signature databases `PK`, `KEK`, `db`
+//! and `dbx`, which are lists of certificates and hashes
+//! ([`SignatureLists`]), and reported by the `SecureBoot`, `SetupMode`,
+//! `AuditMode` and `DeployedMode` variables ([`SecureBootState`]):
+//!
+//! ```no_run
+//! use uefi::runtime::secure_boot::{
+//!     SecureBootMode, SecureBootState, SignatureDatabase, SignatureDatabaseVariable,
+//!     SignatureType,
+//! };
+//!
+//! # fn example() -> uefi::Result {
+//! let state = SecureBootState::read()?;
+//! if state.secure_boot && state.mode() == Some(SecureBootMode::Deployed) {
+//!     let dbx = SignatureDatabase::read(SignatureDatabaseVariable::DBX)?;
+//!     let revoked = dbx
+//!         .lists()
+//!         .signatures()
+//!         .filter(|sig| sig.signature_type() == SignatureType::SHA256)
+//!         .count();
+//!     log::info!("{revoked} image hashes are revoked");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{Time, VariableVendor};
+use crate::{CStr16, Guid, Result, Status, cstr16};
+use core::fmt::{self, Display, Formatter};
+
+#[cfg(feature = "alloc")]
+use {super::VariableAttributes, crate::Error, alloc::boxed::Box};
+
+pub use uefi_raw::table::runtime::{SignatureListHeader, SignatureType};
+
+/// Size of the header of a signature list.
+const LIST_HEADER_SIZE: usize = size_of::<SignatureListHeader>();
+
+/// Size of the owner GUID at the start of each signature.
+const OWNER_SIZE: usize = size_of::<Guid>();
+
+/// Size of the time of revocation of the X.509 hash signature types.
+const TIME_SIZE: usize = size_of::<uefi_raw::time::Time>();
+
+/// Error returned by [`SignatureLists::new`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SignatureListError {
+    /// The data is shorter than the sizes in a signature list header.
+    Truncated,
+    /// A signature list has an invalid signature size.
+    InvalidSize,
+}
+
+impl Display for SignatureListError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "signature list is truncated"),
+            Self::InvalidSize => write!(f, "signature list has an invalid signature size"),
+        }
+    }
+}
+
+impl core::error::Error for SignatureListError {}
+
+/// Variable holding a signature database.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SignatureDatabaseVariable {
+    name: &'static CStr16,
+    vendor: VariableVendor,
+}
+
+impl SignatureDatabaseVariable {
+    /// Platform key (`PK`), which authorizes updates of `KEK`.
+    pub const PK: Self = Self::new(cstr16!("PK"), VariableVendor::GLOBAL_VARIABLE);
+
+    /// Key exchange keys (`KEK`), which authorize updates of `db` and `dbx`.
+    pub const KEK: Self = Self::new(cstr16!("KEK"), VariableVendor::GLOBAL_VARIABLE);
+
+    /// Authorized signature database (`db`).
+    pub const DB: Self = Self::new(cstr16!("db"), VariableVendor::IMAGE_SECURITY_DATABASE);
+
+    /// Forbidden signature database (`dbx`).
+    pub const DBX: Self = Self::new(cstr16!("dbx"), VariableVendor::IMAGE_SECURITY_DATABASE);
+
+    /// Authorized timestamp signature database (`dbt`).
+    pub const DBT: Self = Self::new(cstr16!("dbt"), VariableVendor::IMAGE_SECURITY_DATABASE);
+
+    /// Authorized recovery signature database (`dbr`).
+    pub const DBR: Self = Self::new(cstr16!("dbr"), VariableVendor::IMAGE_SECURITY_DATABASE);
+
+    /// Create a signature database variable, such as the default databases
+    /// `PKDefault` or `dbDefault`.
+    #[must_use]
+    pub const fn new(name: &'static CStr16, vendor: VariableVendor) -> Self {
+        Self { name, vendor }
+    }
+
+    /// Name of the variable.
+    #[must_use]
+    pub const fn name(&self) -> &'static CStr16 {
+        self.name
+    }
+
+    /// Vendor of the variable.
+    #[must_use]
+    pub const fn vendor(&self) -> &VariableVendor {
+        &self.vendor
+    }
+}
+
+/// Signature database read from a [`SignatureDatabaseVariable`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct SignatureDatabase {
+    data: Box<[u8]>,
+    attributes: VariableAttributes,
+}
+
+#[cfg(feature = "alloc")]
+impl SignatureDatabase {
+    /// Read and validate a signature database.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::VOLUME_CORRUPTED`]: the variable is not a valid list of
+    ///   signature lists.
+    /// * See [`get_variable_boxed`] for errors reading the variable. In
+    ///   particular, [`Status::NOT_FOUND`] is returned for databases that are
+    ///   not set, such as `PK` in setup mode.
+    ///
+    /// [`get_variable_boxed`]: super::get_variable_boxed
+    pub fn read(variable: SignatureDatabaseVariable) -> Result<Self> {
+        let (data, attributes) = super::get_variable_boxed(variable.name, &variable.vendor)?;
+        SignatureLists::new(&data).map_err(|_| Error::from(Status::VOLUME_CORRUPTED))?;
+        Ok(Self { data, attributes })
+    }
+
+    /// Signature lists of the database.
+    #[must_use]
+    pub fn lists(&self) -> SignatureLists<'_> {
+        SignatureLists { data: &self.data }
+    }
+
+    /// Attributes of the variable.
+    #[must_use]
+    pub const fn attributes(&self) -> VariableAttributes {
+        self.attributes
+    }
+
+    /// Raw data of the variable.
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// Validated signature lists (`EFI_SIGNATURE_LIST`), as stored in signature
+/// database variables.
+#[derive(Clone, Copy, Debug)]
+pub struct SignatureLists<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> SignatureLists<'a> {
+    /// Validate the signature lists in `data`.
+    ///
+    /// The signature size of the known hash types must match the size of
+    /// the hash.
+    ///
+    /// # Errors
+    ///
+    /// * [`SignatureListError::Truncated`]: the data is shorter than the
+    ///   sizes of a list.
+    /// * [`SignatureListError::InvalidSize`]: the signature size of a list
+    ///   is invalid, or not a divisor of the size of its signatures.
+    pub fn new(data: &'a [u8]) -> core::result::Result<Self, SignatureListError> {
+        let mut rest = data;
+        while !rest.is_empty() {
+            let (_, next) = split_list(rest)?;
+            rest = next;
+        }
+        Ok(Self { data })
+    }
+
+    /// Iterator of the signature lists.
+    #[must_use]
+    pub const fn iter(&self) -> SignatureListIter<'a> {
+        SignatureListIter { data: self.data }
+    }
+
+    /// Iterator of the signatures of all lists.
+    pub fn signatures(&self) -> impl Iterator<Item = SignatureData<'a>> + use<'a> {
+        self.iter().flat_map(|list| list.signatures())
+    }
+
+    /// Iterator of the DER-encoded X.509 certificates of all lists.
+    pub fn x509_certificates(&self) -> impl Iterator<Item = &'a [u8]> + use<'a> {
+        self.signatures()
+            .filter(|sig| sig.signature_type() == SignatureType::X509)
+            .map(|sig| sig.data())
+    }
+
+    /// Returns whether a signature of type `signature_type` has the data
+    /// `data`, such as the SHA-256 hash of a revoked image in `dbx`.
+    #[must_use]
+    pub fn contains(&self, signature_type: SignatureType, data: &[u8]) -> bool {
+        self.signatures()
+            .any(|sig| sig.signature_type() == signature_type && sig.data() == data)
+    }
+}
+
+impl<'a> IntoIterator for SignatureLists<'a> {
+    type Item = SignatureList<'a>;
+    type IntoIter = SignatureListIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Split the first signature list from `data`.
+fn split_list(data: &[u8]) -> core::result::Result<(SignatureList<'_>, &[u8]), SignatureListError> {
+    let header = data
+        .get(..LIST_HEADER_SIZE)
+        .ok_or(SignatureListError::Truncated)?;
+    let u32_at = |offset: usize| {
+        // OK to unwrap: the header is large enough.
+        let value = u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+        usize::try_from(value).map_err(|_| SignatureListError::Truncated)
+    };
+    // OK to unwrap: the header is large enough.
+    let signature_type = SignatureType(Guid::from_bytes(header[..16].try_into().unwrap()));
+    let list_size = u32_at(16)?;
+    let header_size = u32_at(20)?;
+    let signature_size = u32_at(24)?;
+
+    if list_size > data.len() {
+        return Err(SignatureListError::Truncated);
+    }
+    let (list, rest) = data.split_at(list_size);
+    let list_header = list
+        .get(LIST_HEADER_SIZE..LIST_HEADER_SIZE.saturating_add(header_size))
+        .ok_or(SignatureListError::Truncated)?;
+    let signatures = &list[LIST_HEADER_SIZE + header_size..];
+
+    let valid_size = match fixed_data_size(signature_type) {
+        Some(size) => signature_size == OWNER_SIZE + size,
+        None => signature_size >= OWNER_SIZE,
+    };
+    if !valid_size || signatures.len() % signature_size != 0 {
+        return Err(SignatureListError::InvalidSize);
+    }
+
+    let list = SignatureList {
+        signature_type,
+        header: list_header,
+        signatures,
+        signature_size,
+    };
+    Ok((list, rest))
+}
+
+/// Size of the data of the signatures of type `signature_type`, if it is
+/// fixed.
+const fn fixed_data_size(signature_type: SignatureType) -> Option<usize> {
+    match signature_type {
+        SignatureType::SHA1 => Some(20),
+        SignatureType::SHA224 => Some(28),
+        SignatureType::SHA256 => Some(32),
+        SignatureType::SHA384 => Some(48),
+        SignatureType::SHA512 => Some(64),
+        SignatureType::X509_SHA256 => Some(32 + TIME_SIZE),
+        SignatureType::X509_SHA384 => Some(48 + TIME_SIZE),
+        SignatureType::X509_SHA512 => Some(64 + TIME_SIZE),
+        _ => None,
+    }
+}
+
+/// Iterator of [`SignatureLists`].
+#[derive(Clone, Debug)]
+pub struct SignatureListIter<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for SignatureListIter<'a> {
+    type Item = SignatureList<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The lists are validated, so the split only fails at the end.
+        let (list, rest) = split_list(self.data).ok()?;
+        self.data = rest;
+        Some(list)
+    }
+}
+
+/// Signature list (`EFI_SIGNATURE_LIST`), whose signatures have the same type
+/// and size.
+#[derive(Clone, Copy, Debug)]
+pub struct SignatureList<'a> {
+    signature_type: SignatureType,
+    header: &'a [u8],
+    signatures: &'a [u8],
+    signature_size: usize,
+}
+
+impl<'a> SignatureList<'a> {
+    /// Type of the signatures.
+    #[must_use]
+    pub const fn signature_type(&self) -> SignatureType {
+        self.signature_type
+    }
+
+    /// Header data of the list, whose format depends on the signature type.
+    /// None of the types defined by the UEFI Specification have header data.
+    #[must_use]
+    pub const fn header(&self) -> &'a [u8] {
+        self.header
+    }
+
+    /// Size in bytes of each signature, including the owner GUID.
+    #[must_use]
+    pub const fn signature_size(&self) -> usize {
+        self.signature_size
+    }
+
+    /// Number of signatures in the list.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.signatures.len() / self.signature_size
+    }
+
+    /// Returns whether the list has no signatures.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// Iterator of the signatures.
+    pub fn signatures(&self) -> impl Iterator<Item = SignatureData<'a>> + use<'a> {
+        let signature_type = self.signature_type;
+        self.signatures
+            .chunks_exact(self.signature_size)
+            .map(move |signature| {
+                let (owner, data) = signature.split_at(OWNER_SIZE);
+                SignatureData {
+                    signature_type,
+                    // OK to unwrap: the owner is 16 bytes.
+                    owner: Guid::from_bytes(owner.try_into().unwrap()),
+                    data,
+                }
+            })
+    }
+}
+
+/// Signature (`EFI_SIGNATURE_DATA`) in a [`SignatureList`].
+#[derive(Clone, Copy, Debug)]
+pub struct SignatureData<'a> {
+    signature_type: SignatureType,
+    owner: Guid,
+    data: &'a [u8],
+}
+
+impl<'a> SignatureData<'a> {
+    /// Type of the signature, from its list.
+    #[must_use]
+    pub const fn signature_type(&self) -> SignatureType {
+        self.signature_type
+    }
+
+    /// GUID of the agent that added the signature.
+    #[must_use]
+    pub const fn owner(&self) -> Guid {
+        self.owner
+    }
+
+    /// Raw data of the signature, whose format depends on the signature type.
+    #[must_use]
+    pub const fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Decode the data of the signature according to its type.
+    #[must_use]
+    pub fn decode(&self) -> Signature<'a> {
+        match self.signature_type {
+            SignatureType::SHA1
+            | SignatureType::SHA224
+            | SignatureType::SHA256
+            | SignatureType::SHA384
+            | SignatureType::SHA512 => Signature::Hash(self.data),
+            SignatureType::X509 => Signature::X509(self.data),
+            SignatureType::X509_SHA256
+            | SignatureType::X509_SHA384
+            | SignatureType::X509_SHA512 => {
+                // The size was validated with the list.
+                let (hash, time) = self.data.split_at(self.data.len() - TIME_SIZE);
+                Signature::X509Hash {
+                    to_be_signed_hash: hash,
+                    time_of_revocation: Time::try_from(time).ok(),
+                }
+            }
+            _ => Signature::Other(self.data),
+        }
+    }
+}
+
+/// Decoded data of a [`SignatureData`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Signature<'a> {
+    /// Hash of an image, for the [`SignatureType::SHA1`],
+    /// [`SignatureType::SHA224`], [`SignatureType::SHA256`],
+    /// [`SignatureType::SHA384`] and [`SignatureType::SHA512`] types.
+    Hash(&'a [u8]),
+    /// DER-encoded X.509 certificate, for the [`SignatureType::X509`] type.
+    X509(&'a [u8]),
+    /// Hash of the to-be-signed part of an X.509 certificate, for the
+    /// [`SignatureType::X509_SHA256`], [`SignatureType::X509_SHA384`] and
+    /// [`SignatureType::X509_SHA512`] types.
+    X509Hash {
+        /// Hash of the to-be-signed part of the certificate.
+        to_be_signed_hash: &'a [u8],
+        /// Time from which signatures by the certificate are revoked, or
+        /// `None` if they are always revoked.
+        time_of_revocation: Option<Time>,
+    },
+    /// Data of another signature type, which is not decoded.
+    Other(&'a [u8]),
+}
+
+/// Secure Boot mode, from the `SetupMode`, `AuditMode` and `DeployedMode`
+/// variables.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SecureBootMode {
+    /// No platform key is enrolled, so the databases can be updated without
+    /// authentication.
+    Setup,
+    /// A platform key is enrolled, and images are verified if Secure Boot is
+    /// enabled.
+    User,
+    /// No platform key is enrolled, and images are verified, but failures are
+    /// only logged in the image execution information table.
+    Audit,
+    /// Like [`User`](Self::User), but the platform cannot go back to setup or
+    /// audit mode without a platform-specific method.
+    Deployed,
+}
+
+/// Secure Boot state, from the `SecureBoot`, `SetupMode`, `AuditMode` and
+/// `DeployedMode` global variables.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SecureBootState {
+    /// Whether images are verified (`SecureBoot`).
+    pub secure_boot: bool,
+    /// Whether no platform key is enrolled (`SetupMode`).
+    pub setup_mode: bool,
+    /// Whether the platform is in audit mode (`AuditMode`).
+    pub audit_mode: bool,
+    /// Whether the platform is in deployed mode (`DeployedMode`).
+    pub deployed_mode: bool,
+}
+
+impl SecureBootState {
+    /// Read the Secure Boot variables.
+    ///
+    /// Variables that do not exist are read as `false`. In particular,
+    /// `AuditMode` and `DeployedMode` only exist since UEFI 2.5, and none of
+    /// the variables exist if the firmware does not support Secure Boot.
+    ///
+    /// # Errors
+    ///
+    /// * [`Status::BUFFER_TOO_SMALL`]: a variable is larger than one byte.
+    /// * See [`get_variable`] for errors reading the variables.
+    ///
+    /// [`get_variable`]: super::get_variable
+    pub fn read() -> Result<Self> {
+        Ok(Self {
+            secure_boot: read_bool(cstr16!("SecureBoot"))?,
+            setup_mode: read_bool(cstr16!("SetupMode"))?,
+            audit_mode: read_bool(cstr16!("AuditMode"))?,
+            deployed_mode: read_bool(cstr16!("DeployedMode"))?,
+        })
+    }
+
+    /// Secure Boot mode, or `None` if the combination of `SetupMode`,
+    /// `AuditMode` and `DeployedMode` is not a valid mode.
+    ///
+    /// Firmware without Secure Boot support has none of the variables, so it
+    /// is reported in [`SecureBootMode::User`] with `secure_boot` disabled.
+    #[must_use]
+    pub const fn mode(&self) -> Option<SecureBootMode> {
+        match (self.setup_mode, self.audit_mode, self.deployed_mode) {
+            (true, false, false) => Some(SecureBootMode::Setup),
+            (false, false, false) => Some(SecureBootMode::User),
+            (true, true, false) => Some(SecureBootMode::Audit),
+            (false, false, true) => Some(SecureBootMode::Deployed),
+            _ => None,
+        }
+    }
+}
+
+/// Read a global one-byte boolean variable, or `false` if it does not exist.
+fn read_bool(name: &CStr16) -> Result<bool> {
+    let mut buf = [0; 1];
+    match super::get_variable(name, &VariableVendor::GLOBAL_VARIABLE, &mut buf) {
+        Ok((value, _)) => Ok(value == [1]),
+        Err(err) if err.status() == Status::NOT_FOUND => Ok(false),
+        Err(err) => Err(err.status().into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guid;
+    use alloc::vec::Vec;
+
+    const OWNER: Guid = guid!("77fa9abd-0359-4d32-bd60-28f4e78f784b");
+
+    fn list(signature_type: SignatureType, signatures: &[&[u8]]) -> Vec<u8> {
+        let signature_size = OWNER_SIZE + signatures[0].len();
+        let list_size = LIST_HEADER_SIZE + signature_size * signatures.len();
+        let mut data = Vec::new();
+        data.extend_from_slice(&signature_type.0.to_bytes());
+        data.extend_from_slice(&u32::try_from(list_size).unwrap().to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&u32::try_from(signature_size).unwrap().to_le_bytes());
+        for signature in signatures {
+            data.extend_from_slice(&OWNER.to_bytes());
+            data.extend_from_slice(signature);
+        }
+        data
+    }
+
+    #[test]
+    fn test_signature_lists() {
+        let mut data = list(SignatureType::X509, &[b"certificate"]);
+        data.extend(list(SignatureType::SHA256, &[&[1; 32], &[2; 32]]));
+        let mut revocation = [3; 32].to_vec();
+        revocation.extend([0; TIME_SIZE]);
+        data.extend(list(SignatureType::X509_SHA256, &[&revocation]));
+
+        let lists = SignatureLists::new(&data).unwrap();
+        let types: Vec<_> = lists.iter().map(|list| list.signature_type()).collect();
+        assert_eq!(
+            types,
+            [
+                SignatureType::X509,
+                SignatureType::SHA256,
+                SignatureType::X509_SHA256
+            ]
+        );
+        assert_eq!(lists.iter().nth(1).unwrap().len(), 2);
+        assert_eq!(lists.signatures().count(), 4);
+        assert!(lists.signatures().all(|sig| sig.owner() == OWNER));
+
+        let certificates: Vec<_> = lists.x509_certificates().collect();
+        assert_eq!(certificates, [b"certificate"]);
+        assert!(lists.contains(SignatureType::SHA256, &[2; 32]));
+        assert!(!lists.contains(SignatureType::SHA256, &[3; 32]));
+
+        let decoded: Vec<_> = lists.signatures().map(|sig| sig.decode()).collect();
+        assert_eq!(decoded[0], Signature::X509(b"certificate"));
+        assert_eq!(decoded[1], Signature::Hash(&[1; 32]));
+        assert_eq!(
+            decoded[3],
+            Signature::X509Hash {
+                to_be_signed_hash: &[3; 32],
+                time_of_revocation: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_signature_lists_invalid() {
+        assert!(SignatureLists::new(&[]).unwrap().iter().next().is_none());
+
+        let data = list(SignatureType::SHA256, &[&[1; 32]]);
+        assert_eq!(
+            SignatureLists::new(&data[..data.len() - 1]).unwrap_err(),
+            SignatureListError::Truncated
+        );
+        assert_eq!(
+            SignatureLists::new(&data[..10]).unwrap_err(),
+            SignatureListError::Truncated
+        );
+
+        // Wrong hash size.
+        let data = list(SignatureType::SHA256, &[&[1; 20]]);
+        assert_eq!(
+            SignatureLists::new(&data).unwrap_err(),
+            SignatureListError::InvalidSize
+        );
+
+        // Signatures not a multiple of the signature size.
+        let mut data = list(SignatureType::X509, &[b"certificate"]);
+        data[16] += 1;
+        data.push(0);
+        assert_eq!(
+            SignatureLists::new(&data).unwrap_err(),
+            SignatureListError::InvalidSize
+        );
+    }
+
+    #[test]
+    fn test_secure_boot_mode() {
+        let state = |setup_mode, audit_mode, deployed_mode| SecureBootState {
+            secure_boot: false,
+            setup_mode,
+            audit_mode,
+            deployed_mode,
+        };
+        assert_eq!(
+            state(true, false, false).mode(),
+            Some(SecureBootMode::Setup)
+        );
+        assert_eq!(
+            state(false, false, false).mode(),
+            Some(SecureBootMode::User)
+        );
+        assert_eq!(state(true, true, false).mode(), Some(SecureBootMode::Audit));
+        assert_eq!(
+            state(false, false, true).mode(),
+            Some(SecureBootMode::Deployed)
+        );
+        assert_eq!(state(true, false, true).mode(), None);
+    }
+}
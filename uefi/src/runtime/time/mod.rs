@@ -3,11 +3,34 @@
 //! Module for UEFI time-related types and definitions and convenience and
 //! abstractions build around these.
 
+use core::cmp::Ordering;
 use core::fmt;
 use core::fmt::{Debug, Display, Formatter};
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::str::FromStr;
+use core::time::Duration;
 use uefi_raw::time::Daylight;
 
+const SECONDS_PER_MINUTE: i64 = 60;
+const SECONDS_PER_HOUR: i64 = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: i64 = 24 * SECONDS_PER_HOUR;
+
 /// Date and time representation.
+///
+/// # Time zones
+///
+/// The [`time_zone`] is the offset in minutes that has to be added to UTC to
+/// get the local time, so `2024-01-01T01:00:00` with a `time_zone` of `60` is
+/// the same instant as `2024-01-01T00:00:00Z`. If [`Daylight::IN_DAYLIGHT`] is
+/// set, the local time is one additional hour ahead of UTC.
+///
+/// A `Time` without a time zone is local time. Because UEFI provides no way to
+/// find out the local offset, such values are treated as UTC whenever an
+/// absolute instant is needed, for example by [`to_unix_timestamp`] and the
+/// [`Ord`] implementation.
+///
+/// [`time_zone`]: Self::time_zone
+/// [`to_unix_timestamp`]: Self::to_unix_timestamp
 #[derive(Copy, Clone, Eq, PartialEq)]
 #[repr(transparent)]
 pub struct Time(uefi_raw::time::Time);
@@ -228,6 +251,458 @@ impl Time {
     }
 }
 
+impl Time {
+    /// Get the offset in seconds that has to be added to UTC to get the local
+    /// time, including daylight savings time.
+    const fn utc_offset_seconds(&self) -> i64 {
+        let mut offset = match self.time_zone() {
+            Some(time_zone) => time_zone as i64 * SECONDS_PER_MINUTE,
+            None => 0,
+        };
+        if self.daylight().contains(Daylight::IN_DAYLIGHT) {
+            offset += SECONDS_PER_HOUR;
+        }
+        offset
+    }
+
+    /// Get the number of seconds since `1970-01-01T00:00:00` of the date and
+    /// time fields, ignoring the time zone.
+    const fn local_seconds(&self) -> i64 {
+        days_from_civil(self.year() as i64, self.month() as i64, self.day() as i64)
+            * SECONDS_PER_DAY
+            + self.hour() as i64 * SECONDS_PER_HOUR
+            + self.minute() as i64 * SECONDS_PER_MINUTE
+            + self.second() as i64
+    }
+
+    /// Create a `Time` from a number of seconds since `1970-01-01T00:00:00`
+    /// in local time, with the given time zone and daylight information.
+    fn from_local_seconds(
+        seconds: i64,
+        nanosecond: u32,
+        time_zone: Option<i16>,
+        daylight: Daylight,
+    ) -> core::result::Result<Self, TimeError> {
+        let days = seconds.div_euclid(SECONDS_PER_DAY);
+        let seconds_of_day = seconds.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+
+        let Ok(year) = u16::try_from(year) else {
+            return Err(TimeError {
+                year: true,
+                ..Default::default()
+            });
+        };
+
+        Self::new(TimeParams {
+            year,
+            month: month as u8,
+            day: day as u8,
+            hour: (seconds_of_day / SECONDS_PER_HOUR) as u8,
+            minute: (seconds_of_day % SECONDS_PER_HOUR / SECONDS_PER_MINUTE) as u8,
+            second: (seconds_of_day % SECONDS_PER_MINUTE) as u8,
+            nanosecond,
+            time_zone,
+            daylight,
+        })
+    }
+
+    /// Get the number of whole seconds since the Unix epoch
+    /// (`1970-01-01T00:00:00Z`).
+    ///
+    /// The [`time_zone`] and [`Daylight::IN_DAYLIGHT`] flag are applied to
+    /// get the UTC time. Local time is treated as UTC. The fraction of a
+    /// second is available from [`nanosecond`].
+    ///
+    /// [`time_zone`]: Self::time_zone
+    /// [`nanosecond`]: Self::nanosecond
+    #[must_use]
+    pub const fn to_unix_timestamp(&self) -> i64 {
+        self.local_seconds() - self.utc_offset_seconds()
+    }
+
+    /// Create a UTC `Time` from the number of whole seconds since the Unix
+    /// epoch (`1970-01-01T00:00:00Z`) and a fraction of a second.
+    ///
+    /// [`TimeError`] is returned if the resulting year is outside of
+    /// `1900..=9999`, or if `nanosecond` is greater than `999_999_999`.
+    pub fn from_unix_timestamp(
+        seconds: i64,
+        nanosecond: u32,
+    ) -> core::result::Result<Self, TimeError> {
+        Self::from_local_seconds(seconds, nanosecond, Some(0), Daylight::empty())
+    }
+
+    /// Convert to the same instant in UTC.
+    ///
+    /// The returned value has a [`time_zone`] of zero and no [`Daylight`]
+    /// flags set. Local time is treated as UTC.
+    ///
+    /// [`TimeError`] is returned if the converted year is outside of
+    /// `1900..=9999`, or if the fields of `self` are invalid.
+    ///
+    /// [`time_zone`]: Self::time_zone
+    pub fn to_utc(&self) -> core::result::Result<Self, TimeError> {
+        self.is_valid()?;
+        Self::from_unix_timestamp(self.to_unix_timestamp(), self.nanosecond())
+    }
+
+    /// Add `duration` to the time, keeping the time zone and daylight
+    /// information.
+    ///
+    /// Returns `None` if the result is outside of the valid range, or if the
+    /// fields of `self` are invalid.
+    #[must_use]
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        // Validate first, so that an invalid nanosecond can't overflow.
+        self.is_valid().ok()?;
+        let mut seconds = i64::try_from(duration.as_secs()).ok()?;
+        let mut nanosecond = self.nanosecond() + duration.subsec_nanos();
+        if nanosecond > 999_999_999 {
+            nanosecond -= 1_000_000_000;
+            seconds = seconds.checked_add(1)?;
+        }
+        self.offset_by(seconds, nanosecond)
+    }
+
+    /// Subtract `duration` from the time, keeping the time zone and daylight
+    /// information.
+    ///
+    /// Returns `None` if the result is outside of the valid range, or if the
+    /// fields of `self` are invalid.
+    #[must_use]
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        self.is_valid().ok()?;
+        let mut seconds = i64::try_from(duration.as_secs()).ok()?;
+        let mut nanosecond = self.nanosecond();
+        if nanosecond < duration.subsec_nanos() {
+            nanosecond += 1_000_000_000;
+            seconds = seconds.checked_add(1)?;
+        }
+        nanosecond -= duration.subsec_nanos();
+        self.offset_by(seconds.checked_neg()?, nanosecond)
+    }
+
+    /// Offset a valid time by `seconds`, and set its nanosecond.
+    fn offset_by(&self, seconds: i64, nanosecond: u32) -> Option<Self> {
+        let seconds = self.local_seconds().checked_add(seconds)?;
+        Self::from_local_seconds(seconds, nanosecond, self.time_zone(), self.daylight()).ok()
+    }
+
+    /// Get the amount of time elapsed from `earlier` to `self`, taking time
+    /// zones into account.
+    ///
+    /// Returns `None` if `earlier` is later than `self`.
+    #[must_use]
+    pub fn checked_duration_since(&self, earlier: &Self) -> Option<Duration> {
+        let mut seconds = self.to_unix_timestamp() - earlier.to_unix_timestamp();
+        let mut nanosecond = i64::from(self.nanosecond()) - i64::from(earlier.nanosecond());
+        if nanosecond < 0 {
+            nanosecond += 1_000_000_000;
+            seconds -= 1;
+        }
+        let seconds = u64::try_from(seconds).ok()?;
+        Some(Duration::new(seconds, nanosecond as u32))
+    }
+
+    /// Get a value that formats the time as an ISO 8601 string, for example
+    /// `2024-02-29T13:37:00.5+01:00`.
+    ///
+    /// The fraction of a second is omitted if it is zero, and the UTC offset
+    /// is omitted for local time. A UTC offset of zero is written as `Z`. The
+    /// written offset includes the [`Daylight::IN_DAYLIGHT`] adjustment, so
+    /// parsing the string with [`Time::from_str`] yields the same instant.
+    #[must_use]
+    pub const fn iso8601(&self) -> Iso8601<'_> {
+        Iso8601(self)
+    }
+}
+
+impl Ord for Time {
+    /// Times are ordered by the instant they represent, see
+    /// [`to_unix_timestamp`] for how time zones are handled. Different
+    /// representations of the same instant are ordered by their fields so
+    /// that the ordering is consistent with [`Eq`].
+    ///
+    /// [`to_unix_timestamp`]: Self::to_unix_timestamp
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |t: &Self| {
+            (
+                t.to_unix_timestamp(),
+                t.nanosecond(),
+                t.year(),
+                t.month(),
+                t.day(),
+                t.hour(),
+                t.minute(),
+                t.second(),
+                t.0.time_zone,
+                t.daylight().bits(),
+            )
+        };
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for Time {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add<Duration> for Time {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// Panics if the result is out of range. See [`Time::checked_add`] for a
+    /// version without panic.
+    fn add(self, duration: Duration) -> Self {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to time")
+    }
+}
+
+impl AddAssign<Duration> for Time {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<Duration> for Time {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// Panics if the result is out of range. See [`Time::checked_sub`] for a
+    /// version without panic.
+    fn sub(self, duration: Duration) -> Self {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from time")
+    }
+}
+
+impl SubAssign<Duration> for Time {
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = *self - duration;
+    }
+}
+
+/// Formats a [`Time`] as an ISO 8601 string. Returned by [`Time::iso8601`].
+#[derive(Clone, Copy, Debug)]
+pub struct Iso8601<'a>(&'a Time);
+
+impl Display for Iso8601<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let time = self.0;
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            time.year(),
+            time.month(),
+            time.day(),
+            time.hour(),
+            time.minute(),
+            time.second()
+        )?;
+        if time.nanosecond() != 0 {
+            let mut nanosecond = time.nanosecond();
+            let mut digits = 9;
+            while nanosecond.is_multiple_of(10) {
+                nanosecond /= 10;
+                digits -= 1;
+            }
+            write!(f, ".{nanosecond:0digits$}")?;
+        }
+        if time.time_zone().is_none() {
+            return Ok(());
+        }
+        let offset_minutes = time.utc_offset_seconds() / SECONDS_PER_MINUTE;
+        if offset_minutes == 0 {
+            write!(f, "Z")
+        } else {
+            let sign = if offset_minutes < 0 { '-' } else { '+' };
+            let offset_minutes = offset_minutes.abs();
+            write!(
+                f,
+                "{sign}{:02}:{:02}",
+                offset_minutes / 60,
+                offset_minutes % 60
+            )
+        }
+    }
+}
+
+/// Error returned from failing to parse a [`Time`] from a string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeParseError {
+    /// The string is not in a supported ISO 8601 format.
+    InvalidFormat,
+    /// One or more fields of the parsed [`Time`] is invalid.
+    InvalidFields(TimeError),
+}
+
+impl Display for TimeParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => write!(f, "the string is not a valid ISO 8601 date and time"),
+            Self::InvalidFields(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl core::error::Error for TimeParseError {}
+
+impl FromStr for Time {
+    type Err = TimeParseError;
+
+    /// Parse an ISO 8601 date and time in the extended format, for example
+    /// `2024-02-29T13:37:00.5+01:00`.
+    ///
+    /// The fraction of a second may have up to nine digits. The UTC offset
+    /// may be `Z`, `±hh:mm`, `±hhmm` or `±hh`; if it is omitted, the result
+    /// is local time. The [`daylight`] of the result is always empty.
+    ///
+    /// [`daylight`]: Self::daylight
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+        let mut parser = Iso8601Parser(s.as_bytes());
+
+        let year = parser.digits(4)? as u16;
+        parser.expect(b"-")?;
+        let month = parser.digits(2)? as u8;
+        parser.expect(b"-")?;
+        let day = parser.digits(2)? as u8;
+        parser.expect(b"Tt ")?;
+        let hour = parser.digits(2)? as u8;
+        parser.expect(b":")?;
+        let minute = parser.digits(2)? as u8;
+        parser.expect(b":")?;
+        let second = parser.digits(2)? as u8;
+
+        let mut nanosecond = 0;
+        if parser.expect(b".,").is_ok() {
+            let mut digits = 0;
+            while let Ok(digit) = parser.digits(1) {
+                if digits == 9 {
+                    return Err(TimeParseError::InvalidFormat);
+                }
+                nanosecond = nanosecond * 10 + digit;
+                digits += 1;
+            }
+            if digits == 0 {
+                return Err(TimeParseError::InvalidFormat);
+            }
+            nanosecond *= 10u32.pow(9 - digits);
+        }
+
+        let time_zone = match parser.0.first() {
+            None => None,
+            Some(b'Z' | b'z') => {
+                parser.0 = &parser.0[1..];
+                Some(0)
+            }
+            Some(&sign @ (b'+' | b'-')) => {
+                parser.0 = &parser.0[1..];
+                let hours = parser.digits(2)?;
+                let minutes = if parser.expect(b":").is_ok() || !parser.0.is_empty() {
+                    parser.digits(2)?
+                } else {
+                    0
+                };
+                if minutes > 59 {
+                    return Err(TimeParseError::InvalidFormat);
+                }
+                let offset = (hours * 60 + minutes) as i16;
+                Some(if sign == b'-' { -offset } else { offset })
+            }
+            Some(_) => return Err(TimeParseError::InvalidFormat),
+        };
+
+        if !parser.0.is_empty() {
+            return Err(TimeParseError::InvalidFormat);
+        }
+
+        Self::new(TimeParams {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond,
+            time_zone,
+            daylight: Daylight::empty(),
+        })
+        .map_err(TimeParseError::InvalidFields)
+    }
+}
+
+/// Minimal cursor over the bytes of an ISO 8601 string.
+struct Iso8601Parser<'a>(&'a [u8]);
+
+impl Iso8601Parser<'_> {
+    /// Consume exactly `count` ASCII digits and return their value.
+    fn digits(&mut self, count: usize) -> core::result::Result<u32, TimeParseError> {
+        let digits = self.0.get(..count).ok_or(TimeParseError::InvalidFormat)?;
+        let mut value = 0;
+        for digit in digits {
+            if !digit.is_ascii_digit() {
+                return Err(TimeParseError::InvalidFormat);
+            }
+            value = value * 10 + u32::from(digit - b'0');
+        }
+        self.0 = &self.0[count..];
+        Ok(value)
+    }
+
+    /// Consume one byte, which must be one of `allowed`.
+    fn expect(&mut self, allowed: &[u8]) -> core::result::Result<(), TimeParseError> {
+        match self.0.first() {
+            Some(byte) if allowed.contains(byte) => {
+                self.0 = &self.0[1..];
+                Ok(())
+            }
+            _ => Err(TimeParseError::InvalidFormat),
+        }
+    }
+}
+
+/// Get the number of days since `1970-01-01` of a date in the proleptic
+/// Gregorian calendar.
+///
+/// See <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Get the `(year, month, day)` of a number of days since `1970-01-01` in the
+/// proleptic Gregorian calendar.
+///
+/// See <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+const fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
 impl Debug for Time {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
@@ -317,3 +792,173 @@ impl TryFrom<&[u8]> for Time {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn time(s: &str) -> Time {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_unix_timestamp() {
+        assert_eq!(time("1970-01-01T00:00:00Z").to_unix_timestamp(), 0);
+        assert_eq!(
+            time("2024-02-29T13:37:00Z").to_unix_timestamp(),
+            1_709_213_820
+        );
+        assert_eq!(
+            time("1900-01-01T00:00:00Z").to_unix_timestamp(),
+            -2_208_988_800
+        );
+        // Local time is treated as UTC.
+        assert_eq!(
+            time("2024-02-29T13:37:00").to_unix_timestamp(),
+            1_709_213_820
+        );
+        assert_eq!(
+            time("2024-02-29T14:37:00+01:00").to_unix_timestamp(),
+            1_709_213_820
+        );
+        assert_eq!(
+            time("2024-02-29T08:07:00-05:30").to_unix_timestamp(),
+            1_709_213_820
+        );
+
+        let t = Time::from_unix_timestamp(1_709_213_820, 5).unwrap();
+        assert_eq!(t, time("2024-02-29T13:37:00.000000005Z"));
+        assert_eq!(
+            Time::from_unix_timestamp(-2_208_988_801, 0),
+            Err(TimeError {
+                year: true,
+                ..Default::default()
+            })
+        );
+        assert!(Time::from_unix_timestamp(0, 1_000_000_000).is_err());
+    }
+
+    #[test]
+    fn test_daylight() {
+        let mut t = Time::new(TimeParams {
+            year: 2024,
+            month: 7,
+            day: 1,
+            hour: 14,
+            minute: 0,
+            second: 0,
+            nanosecond: 0,
+            time_zone: Some(60),
+            daylight: Daylight::ADJUST_DAYLIGHT | Daylight::IN_DAYLIGHT,
+        })
+        .unwrap();
+        assert_eq!(t.to_utc().unwrap(), time("2024-07-01T12:00:00Z"));
+        assert_eq!(t.iso8601().to_string(), "2024-07-01T14:00:00+02:00");
+
+        t += Duration::from_secs(3600);
+        assert_eq!(t.hour(), 15);
+        assert_eq!(t.time_zone(), Some(60));
+        assert_eq!(
+            t.daylight(),
+            Daylight::ADJUST_DAYLIGHT | Daylight::IN_DAYLIGHT
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let t = time("2023-12-31T23:59:59.75Z");
+        assert_eq!(
+            t + Duration::from_millis(500),
+            time("2024-01-01T00:00:00.25Z")
+        );
+        assert_eq!(
+            t - Duration::from_millis(800),
+            time("2023-12-31T23:59:58.95Z")
+        );
+        assert_eq!(
+            time("2024-02-28T12:00:00Z") + Duration::from_secs(86400),
+            time("2024-02-29T12:00:00Z")
+        );
+        assert_eq!(
+            time("2023-02-28T12:00:00Z") + Duration::from_secs(86400),
+            time("2023-03-01T12:00:00Z")
+        );
+        assert_eq!(
+            time("9999-12-31T23:59:59Z").checked_add(Duration::from_secs(1)),
+            None
+        );
+        assert_eq!(
+            time("1900-01-01T00:00:00Z").checked_sub(Duration::from_nanos(1)),
+            None
+        );
+        assert_eq!(Time::invalid().checked_add(Duration::ZERO), None);
+        let mut invalid = t;
+        invalid.0.nanosecond = u32::MAX;
+        assert_eq!(invalid.checked_add(Duration::from_millis(500)), None);
+        assert_eq!(invalid.checked_sub(Duration::from_millis(500)), None);
+
+        assert_eq!(
+            time("2024-01-01T00:00:00.25Z").checked_duration_since(&t),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            t.checked_duration_since(&time("2024-01-01T00:00:00.25Z")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ord() {
+        let utc = time("2024-02-29T13:37:00Z");
+        let offset = time("2024-02-29T14:37:00+01:00");
+        assert_ne!(utc, offset);
+        assert_ne!(utc.cmp(&offset), Ordering::Equal);
+        assert!(time("2024-02-29T14:00:00+01:00") < utc);
+        assert!(time("2024-02-29T13:37:00.1Z") > utc);
+        assert_eq!(utc.cmp(&utc), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_iso8601() {
+        for s in [
+            "2024-02-29T13:37:00Z",
+            "2024-02-29T13:37:00.5+01:00",
+            "2024-02-29T13:37:00.123456789-09:30",
+            "1999-12-31T23:59:59",
+        ] {
+            assert_eq!(time(s).iso8601().to_string(), s);
+        }
+
+        assert_eq!(
+            time("2024-02-29 13:37:00,25+0100"),
+            time("2024-02-29T13:37:00.25+01:00")
+        );
+        assert_eq!(
+            time("2024-02-29t13:37:00-01"),
+            time("2024-02-29T13:37:00-01:00")
+        );
+
+        for s in [
+            "",
+            "2024-02-29",
+            "2024-02-29T13:37",
+            "2024-02-29T13:37:00.",
+            "2024-02-29T13:37:00.1234567890Z",
+            "2024-02-29T13:37:00+1",
+            "2024-02-29T13:37:00+01:60",
+            "2024-02-29T13:37:00+01:",
+            "2024-02-29T13:37:00Zjunk",
+            "24-02-29T13:37:00Z",
+        ] {
+            assert_eq!(s.parse::<Time>(), Err(TimeParseError::InvalidFormat), "{s}");
+        }
+        assert_eq!(
+            "2024-13-29T13:37:00Z".parse::<Time>(),
+            Err(TimeParseError::InvalidFields(TimeError {
+                month: true,
+                ..Default::default()
+            }))
+        );
+    }
+}
@@ -10,10 +10,10 @@
 //! panic otherwise. See each function's documentation for details.
 
 use crate::proto::console::text::{Input, Output};
-use crate::table::cfg::ConfigTableEntry;
+use crate::table::cfg::{ConfigTable, ConfigTableEntry};
 use crate::table::{self, Revision};
 use crate::{CStr16, Char16};
-use core::slice;
+use core::{iter, slice};
 
 /// Get the firmware vendor string.
 #[must_use]
@@ -87,6 +87,51 @@ where
     f(slice)
 }
 
+/// Find the vendor table `T` in the configuration table, and read it.
+///
+/// The entry for [`T::GUID`] is used if it exists. Otherwise, the first of
+/// [`T::COMPATIBLE_GUIDS`] with an entry is used. Entries with a null address
+/// are ignored.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use uefi::system;
+/// use uefi::table::esrt::Esrt;
+///
+/// let esrt = system::find_config_table::<Esrt>().unwrap();
+/// for entry in esrt.entries() {
+///     println!("{}: version {}", entry.fw_class, entry.fw_version);
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns [`T::NOT_FOUND`] if there is no entry for the table, or any error
+/// returned by [`T::from_entry`] if the table is invalid.
+///
+/// [`T::GUID`]: ConfigTable::GUID
+/// [`T::COMPATIBLE_GUIDS`]: ConfigTable::COMPATIBLE_GUIDS
+/// [`T::NOT_FOUND`]: ConfigTable::NOT_FOUND
+/// [`T::from_entry`]: ConfigTable::from_entry
+pub fn find_config_table<T: ConfigTable>() -> Result<T, T::Error> {
+    let entry = with_config_table(|entries| {
+        iter::once(&T::GUID)
+            .chain(T::COMPATIBLE_GUIDS)
+            .find_map(|&guid| {
+                entries
+                    .iter()
+                    .find(|entry| entry.guid == guid && !entry.address.is_null())
+            })
+            .map(|entry| (entry.guid, entry.address))
+    });
+    let (guid, address) = entry.ok_or(T::NOT_FOUND)?;
+
+    // SAFETY: the address is the non-null address of the entry `guid`, and
+    // the firmware guarantees the table is valid.
+    unsafe { T::from_entry(guid, address) }
+}
+
 /// Call `f` with the [`Input`] protocol attached to stdin.
 ///
 /// # Panics
@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Boot Graphics Resource Table (BGRT).
+
+use super::{AcpiError, AcpiTable, Sdt, Signature, check_sdt, read_u16, read_u32, read_u64};
+
+/// Length of the table.
+const LEN: usize = 56;
+
+newtype_enum! {
+    /// Format of the boot image described by the [`Bgrt`].
+    pub enum BgrtImageType: u8 => {
+        /// Bitmap (BMP) image.
+        BITMAP = 0,
+    }
+}
+
+/// Clockwise rotation of the boot image relative to the panel's native
+/// orientation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum BgrtOrientation {
+    /// Not rotated.
+    Degrees0,
+    /// Rotated by 90 degrees.
+    Degrees90,
+    /// Rotated by 180 degrees.
+    Degrees180,
+    /// Rotated by 270 degrees.
+    Degrees270,
+}
+
+/// Boot Graphics Resource Table, with signature `BGRT`.
+///
+/// Describes the image the firmware drew on the screen during boot, so that
+/// the operating system can keep it displayed.
+#[derive(Clone, Copy, Debug)]
+pub struct Bgrt<'a> {
+    sdt: Sdt<'a>,
+}
+
+impl<'a> AcpiTable<'a> for Bgrt<'a> {
+    const SIGNATURE: Signature = Signature::BGRT;
+
+    fn from_sdt(sdt: Sdt<'a>) -> Result<Self, AcpiError> {
+        check_sdt(&sdt, Self::SIGNATURE, LEN)?;
+        Ok(Self { sdt })
+    }
+}
+
+impl<'a> Bgrt<'a> {
+    /// Get the underlying table.
+    #[must_use]
+    pub const fn sdt(&self) -> Sdt<'a> {
+        self.sdt
+    }
+
+    /// Get the table version. Must be 1.
+    #[must_use]
+    pub fn version(&self) -> u16 {
+        read_u16(self.sdt.as_bytes(), 36).unwrap()
+    }
+
+    const fn status(&self) -> u8 {
+        self.sdt.as_bytes()[38]
+    }
+
+    /// Returns whether the image is currently displayed.
+    #[must_use]
+    pub const fn is_displayed(&self) -> bool {
+        self.status() & 1 != 0
+    }
+
+    /// Get the orientation of the image.
+    #[must_use]
+    pub const fn orientation(&self) -> BgrtOrientation {
+        match (self.status() >> 1) & 0b11 {
+            0 => BgrtOrientation::Degrees0,
+            1 => BgrtOrientation::Degrees90,
+            2 => BgrtOrientation::Degrees180,
+            _ => BgrtOrientation::Degrees270,
+        }
+    }
+
+    /// Get the format of the image.
+    #[must_use]
+    pub const fn image_type(&self) -> BgrtImageType {
+        BgrtImageType(self.sdt.as_bytes()[39])
+    }
+
+    /// Get the physical address of the image.
+    #[must_use]
+    pub fn image_address(&self) -> u64 {
+        read_u64(self.sdt.as_bytes(), 40).unwrap()
+    }
+
+    /// Get the position of the upper left corner of the image on the
+    /// screen, in pixels.
+    #[must_use]
+    pub fn image_offset(&self) -> (u32, u32) {
+        let bytes = self.sdt.as_bytes();
+        (read_u32(bytes, 48).unwrap(), read_u32(bytes, 52).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::build_sdt;
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_bgrt() {
+        let mut data = Vec::from([1, 0, 0b101, 0]);
+        data.extend_from_slice(&0x7e00_0000u64.to_le_bytes());
+        data.extend_from_slice(&300u32.to_le_bytes());
+        data.extend_from_slice(&200u32.to_le_bytes());
+        let bytes = build_sdt(Signature::BGRT, 1, &data);
+
+        let bgrt = Bgrt::from_sdt(Sdt::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(bgrt.version(), 1);
+        assert!(bgrt.is_displayed());
+        assert_eq!(bgrt.orientation(), BgrtOrientation::Degrees180);
+        assert_eq!(bgrt.image_type(), BgrtImageType::BITMAP);
+        assert_eq!(bgrt.image_address(), 0x7e00_0000);
+        assert_eq!(bgrt.image_offset(), (300, 200));
+    }
+}
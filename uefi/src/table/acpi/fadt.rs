@@ -0,0 +1,340 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Fixed ACPI Description Table (FADT).
+
+use super::{AcpiError, AcpiTable, GenericAddress, Sdt, Signature, check_sdt, read_u16, read_u32};
+use bitflags::bitflags;
+
+/// Length of the ACPI 1.0 FADT, which all versions start with.
+const MIN_LEN: usize = 116;
+
+newtype_enum! {
+    /// Preferred power management profile of the system.
+    pub enum PmProfile: u8 => {
+        /// Unspecified.
+        UNSPECIFIED = 0,
+        /// Desktop.
+        DESKTOP = 1,
+        /// Mobile.
+        MOBILE = 2,
+        /// Workstation.
+        WORKSTATION = 3,
+        /// Enterprise server.
+        ENTERPRISE_SERVER = 4,
+        /// Small office/home office server.
+        SOHO_SERVER = 5,
+        /// Appliance PC.
+        APPLIANCE_PC = 6,
+        /// Performance server.
+        PERFORMANCE_SERVER = 7,
+        /// Tablet.
+        TABLET = 8,
+    }
+}
+
+bitflags! {
+    /// Fixed feature flags of the FADT.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct FadtFlags: u32 {
+        /// The `WBINVD` instruction works correctly.
+        const WBINVD = 1 << 0;
+        /// The `WBINVD` instruction flushes but does not invalidate caches.
+        const WBINVD_FLUSH = 1 << 1;
+        /// C1 power state is supported on all processors.
+        const PROC_C1 = 1 << 2;
+        /// C2 power state works on multiprocessor systems.
+        const P_LVL2_UP = 1 << 3;
+        /// The power button is a control method device instead of a fixed
+        /// feature.
+        const PWR_BUTTON = 1 << 4;
+        /// The sleep button is a control method device instead of a fixed
+        /// feature.
+        const SLP_BUTTON = 1 << 5;
+        /// RTC wake status is not in the fixed register space.
+        const FIX_RTC = 1 << 6;
+        /// The RTC can wake the system from S4.
+        const RTC_S4 = 1 << 7;
+        /// The PM timer is 32 bits wide instead of 24.
+        const TMR_VAL_EXT = 1 << 8;
+        /// The system can be docked.
+        const DCK_CAP = 1 << 9;
+        /// The reset register is supported.
+        const RESET_REG_SUP = 1 << 10;
+        /// The system has a sealed case.
+        const SEALED_CASE = 1 << 11;
+        /// The system cannot detect the monitor or keyboard.
+        const HEADLESS = 1 << 12;
+        /// A processor native instruction must be executed after writing the
+        /// sleep type register.
+        const CPU_SW_SLP = 1 << 13;
+        /// The platform supports PCI Express wake status.
+        const PCI_EXP_WAK = 1 << 14;
+        /// The platform timer should be used for OS timekeeping.
+        const USE_PLATFORM_CLOCK = 1 << 15;
+        /// The RTC status bit is valid after waking from S4.
+        const S4_RTC_STS_VALID = 1 << 16;
+        /// The platform supports remote power on.
+        const REMOTE_POWER_ON_CAPABLE = 1 << 17;
+        /// All local APICs must use cluster destination mode.
+        const FORCE_APIC_CLUSTER_MODEL = 1 << 18;
+        /// All local xAPICs must use physical destination mode.
+        const FORCE_APIC_PHYSICAL_DESTINATION_MODE = 1 << 19;
+        /// The system uses the hardware-reduced ACPI interface.
+        const HW_REDUCED_ACPI = 1 << 20;
+        /// S0 idle is at least as power efficient as S3.
+        const LOW_POWER_S0_IDLE_CAPABLE = 1 << 21;
+    }
+}
+
+bitflags! {
+    /// IA-PC boot architecture flags of the FADT.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct IaPcBootArch: u16 {
+        /// The system has legacy devices, such as a parallel port.
+        const LEGACY_DEVICES = 1 << 0;
+        /// The system has an 8042 keyboard controller.
+        const I8042 = 1 << 1;
+        /// VGA must not be probed.
+        const VGA_NOT_PRESENT = 1 << 2;
+        /// MSIs must not be enabled.
+        const MSI_NOT_SUPPORTED = 1 << 3;
+        /// PCIe ASPM must not be enabled.
+        const PCIE_ASPM_CONTROLS = 1 << 4;
+        /// The CMOS RTC is not present.
+        const CMOS_RTC_NOT_PRESENT = 1 << 5;
+    }
+}
+
+/// Fixed ACPI Description Table (FADT), with signature `FACP`.
+///
+/// Fields added in later ACPI revisions return `None` if the table is too
+/// short to contain them.
+#[derive(Clone, Copy, Debug)]
+pub struct Fadt<'a> {
+    sdt: Sdt<'a>,
+}
+
+impl<'a> AcpiTable<'a> for Fadt<'a> {
+    const SIGNATURE: Signature = Signature::FADT;
+
+    fn from_sdt(sdt: Sdt<'a>) -> Result<Self, AcpiError> {
+        check_sdt(&sdt, Self::SIGNATURE, MIN_LEN)?;
+        Ok(Self { sdt })
+    }
+}
+
+impl<'a> Fadt<'a> {
+    /// Get the underlying table.
+    #[must_use]
+    pub const fn sdt(&self) -> Sdt<'a> {
+        self.sdt
+    }
+
+    const fn bytes(&self) -> &'a [u8] {
+        self.sdt.as_bytes()
+    }
+
+    /// Read the 64-bit address at `x_offset`, falling back to the 32-bit
+    /// address at `offset`. Zero addresses are ignored.
+    fn address(&self, x_offset: usize, offset: usize) -> Option<u64> {
+        super::read_u64(self.bytes(), x_offset)
+            .filter(|address| *address != 0)
+            .or_else(|| read_u32(self.bytes(), offset).map(u64::from))
+            .filter(|address| *address != 0)
+    }
+
+    /// Get the physical address of the FACS.
+    #[must_use]
+    pub fn firmware_ctrl(&self) -> Option<u64> {
+        self.address(132, 36)
+    }
+
+    /// Get the physical address of the DSDT.
+    #[must_use]
+    pub fn dsdt_address(&self) -> Option<u64> {
+        self.address(140, 40)
+    }
+
+    /// Get the preferred power management profile.
+    #[must_use]
+    pub const fn preferred_pm_profile(&self) -> PmProfile {
+        PmProfile(self.bytes()[45])
+    }
+
+    /// Get the system vector of the SCI interrupt.
+    #[must_use]
+    pub fn sci_interrupt(&self) -> u16 {
+        read_u16(self.bytes(), 46).unwrap()
+    }
+
+    /// Get the I/O port of the SMI command register, or zero if System
+    /// Management Mode is not supported.
+    #[must_use]
+    pub fn smi_command_port(&self) -> u32 {
+        read_u32(self.bytes(), 48).unwrap()
+    }
+
+    /// Get the values to write to the SMI command register to enable and
+    /// disable ACPI mode.
+    #[must_use]
+    pub const fn acpi_enable_disable(&self) -> (u8, u8) {
+        (self.bytes()[52], self.bytes()[53])
+    }
+
+    /// Get the PM timer register.
+    #[must_use]
+    pub fn pm_timer_block(&self) -> Option<GenericAddress> {
+        self.generic_address(208, 76, 91)
+    }
+
+    /// Get the PM1a control register.
+    #[must_use]
+    pub fn pm1a_control_block(&self) -> Option<GenericAddress> {
+        self.generic_address(172, 64, 89)
+    }
+
+    /// Get the register at the extended offset `x_offset`, falling back to
+    /// the I/O port at `offset` with the length at `len_offset`.
+    fn generic_address(
+        &self,
+        x_offset: usize,
+        offset: usize,
+        len_offset: usize,
+    ) -> Option<GenericAddress> {
+        GenericAddress::read(self.bytes(), x_offset).or_else(|| {
+            let port = read_u32(self.bytes(), offset).filter(|port| *port != 0)?;
+            Some(GenericAddress {
+                address_space: super::AddressSpace::SYSTEM_IO,
+                bit_width: self.bytes()[len_offset].wrapping_mul(8),
+                bit_offset: 0,
+                access_size: 0,
+                address: u64::from(port),
+            })
+        })
+    }
+
+    /// Get the index of the century in the RTC CMOS memory, if supported.
+    #[must_use]
+    pub fn century(&self) -> Option<u8> {
+        Some(self.bytes()[108]).filter(|century| *century != 0)
+    }
+
+    /// Get the IA-PC boot architecture flags.
+    #[must_use]
+    pub fn iapc_boot_arch(&self) -> IaPcBootArch {
+        IaPcBootArch::from_bits_retain(read_u16(self.bytes(), 109).unwrap())
+    }
+
+    /// Get the fixed feature flags.
+    #[must_use]
+    pub fn flags(&self) -> FadtFlags {
+        FadtFlags::from_bits_retain(read_u32(self.bytes(), 112).unwrap())
+    }
+
+    /// Get the reset register and the value to write to it to reset the
+    /// system, if [`FadtFlags::RESET_REG_SUP`] is set.
+    #[must_use]
+    pub fn reset_register(&self) -> Option<(GenericAddress, u8)> {
+        if !self.flags().contains(FadtFlags::RESET_REG_SUP) {
+            return None;
+        }
+        Some((
+            GenericAddress::read(self.bytes(), 116)?,
+            *self.bytes().get(128)?,
+        ))
+    }
+
+    /// Get the ARM boot architecture flags.
+    #[must_use]
+    pub fn arm_boot_arch(&self) -> Option<u16> {
+        read_u16(self.bytes(), 129)
+    }
+
+    /// Get the FADT minor version. The major version is the table revision.
+    #[must_use]
+    pub fn minor_version(&self) -> Option<u8> {
+        self.bytes().get(131).copied()
+    }
+
+    /// Get the sleep control register of hardware-reduced ACPI systems.
+    #[must_use]
+    pub fn sleep_control_register(&self) -> Option<GenericAddress> {
+        GenericAddress::read(self.bytes(), 244)
+    }
+
+    /// Get the sleep status register of hardware-reduced ACPI systems.
+    #[must_use]
+    pub fn sleep_status_register(&self) -> Option<GenericAddress> {
+        GenericAddress::read(self.bytes(), 256)
+    }
+
+    /// Get the hypervisor vendor identity.
+    #[must_use]
+    pub fn hypervisor_vendor_identity(&self) -> Option<u64> {
+        super::read_u64(self.bytes(), 268)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::build_sdt;
+    use super::super::{AddressSpace, SDT_HEADER_SIZE};
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_fadt() {
+        let mut data = vec![0; 276 - SDT_HEADER_SIZE];
+        let mut put = |offset: usize, bytes: &[u8]| {
+            let offset = offset - SDT_HEADER_SIZE;
+            data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        };
+        put(40, &0x1000u32.to_le_bytes());
+        put(45, &[PmProfile::MOBILE.0]);
+        put(46, &9u16.to_le_bytes());
+        put(76, &0x608u32.to_le_bytes());
+        put(91, &[4]);
+        put(108, &[0x32]);
+        put(109, &IaPcBootArch::I8042.bits().to_le_bytes());
+        let flags = FadtFlags::RESET_REG_SUP | FadtFlags::TMR_VAL_EXT;
+        put(112, &flags.bits().to_le_bytes());
+        put(116, &[1, 8, 0, 1, 0xf9, 0x0c, 0, 0, 0, 0, 0, 0]);
+        put(128, &[6]);
+        put(131, &[5]);
+        put(140, &0x2000u64.to_le_bytes());
+        let bytes = build_sdt(Signature::FADT, 6, &data);
+
+        let fadt = Fadt::from_sdt(Sdt::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(fadt.dsdt_address(), Some(0x2000));
+        assert_eq!(fadt.firmware_ctrl(), None);
+        assert_eq!(fadt.preferred_pm_profile(), PmProfile::MOBILE);
+        assert_eq!(fadt.sci_interrupt(), 9);
+        assert_eq!(fadt.century(), Some(0x32));
+        assert_eq!(fadt.iapc_boot_arch(), IaPcBootArch::I8042);
+        assert_eq!(fadt.flags(), flags);
+        assert_eq!(fadt.minor_version(), Some(5));
+        let pm_timer = fadt.pm_timer_block().unwrap();
+        assert_eq!(pm_timer.address_space, AddressSpace::SYSTEM_IO);
+        assert_eq!(pm_timer.address, 0x608);
+        assert_eq!(pm_timer.bit_width, 32);
+        let (reset, value) = fadt.reset_register().unwrap();
+        assert_eq!(reset.address_space, AddressSpace::SYSTEM_IO);
+        assert_eq!(reset.address, 0xcf9);
+        assert_eq!(value, 6);
+
+        // ACPI 1.0 FADT.
+        let bytes = build_sdt(Signature::FADT, 1, &data[..MIN_LEN - SDT_HEADER_SIZE]);
+        let fadt = Fadt::from_sdt(Sdt::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(fadt.dsdt_address(), Some(0x1000));
+        assert_eq!(fadt.minor_version(), None);
+        assert_eq!(fadt.reset_register(), None);
+
+        let bytes = build_sdt(Signature::FADT, 1, &data[..40]);
+        assert_eq!(
+            Fadt::from_sdt(Sdt::from_bytes(&bytes).unwrap()).unwrap_err(),
+            AcpiError::InvalidLength
+        );
+    }
+}
@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! High Precision Event Timer table (HPET).
+
+use super::{AcpiError, AcpiTable, GenericAddress, Sdt, Signature, check_sdt, read_u16, read_u32};
+
+/// Length of the table.
+const LEN: usize = 56;
+
+/// High Precision Event Timer table, with signature `HPET`.
+#[derive(Clone, Copy, Debug)]
+pub struct Hpet<'a> {
+    sdt: Sdt<'a>,
+}
+
+impl<'a> AcpiTable<'a> for Hpet<'a> {
+    const SIGNATURE: Signature = Signature::HPET;
+
+    fn from_sdt(sdt: Sdt<'a>) -> Result<Self, AcpiError> {
+        check_sdt(&sdt, Self::SIGNATURE, LEN)?;
+        Ok(Self { sdt })
+    }
+}
+
+impl<'a> Hpet<'a> {
+    /// Get the underlying table.
+    #[must_use]
+    pub const fn sdt(&self) -> Sdt<'a> {
+        self.sdt
+    }
+
+    fn event_timer_block_id(&self) -> u32 {
+        read_u32(self.sdt.as_bytes(), 36).unwrap()
+    }
+
+    /// Get the hardware revision ID.
+    #[must_use]
+    pub fn hardware_revision(&self) -> u8 {
+        self.event_timer_block_id() as u8
+    }
+
+    /// Get the number of comparators in the first timer block.
+    #[must_use]
+    pub fn comparator_count(&self) -> u8 {
+        ((self.event_timer_block_id() >> 8) & 0x1f) as u8 + 1
+    }
+
+    /// Returns whether the main counter is 64 bits wide.
+    #[must_use]
+    pub fn is_64bit(&self) -> bool {
+        self.event_timer_block_id() & (1 << 13) != 0
+    }
+
+    /// Returns whether the timer supports legacy replacement IRQ routing.
+    #[must_use]
+    pub fn legacy_replacement_capable(&self) -> bool {
+        self.event_timer_block_id() & (1 << 15) != 0
+    }
+
+    /// Get the PCI vendor ID of the first timer block.
+    #[must_use]
+    pub fn pci_vendor_id(&self) -> u16 {
+        (self.event_timer_block_id() >> 16) as u16
+    }
+
+    /// Get the address of the timer registers.
+    #[must_use]
+    pub fn base_address(&self) -> Option<GenericAddress> {
+        GenericAddress::read(self.sdt.as_bytes(), 40)
+    }
+
+    /// Get the sequence number of the HPET.
+    #[must_use]
+    pub const fn hpet_number(&self) -> u8 {
+        self.sdt.as_bytes()[52]
+    }
+
+    /// Get the minimum clock tick in periodic mode.
+    #[must_use]
+    pub fn minimum_tick(&self) -> u16 {
+        read_u16(self.sdt.as_bytes(), 53).unwrap()
+    }
+
+    /// Get the page protection and OEM attributes.
+    #[must_use]
+    pub const fn page_protection(&self) -> u8 {
+        self.sdt.as_bytes()[55]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::AddressSpace;
+    use super::super::tests::build_sdt;
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_hpet() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&0x8086_a201u32.to_le_bytes());
+        data.extend_from_slice(&[0, 64, 0, 0]);
+        data.extend_from_slice(&0xfed0_0000u64.to_le_bytes());
+        data.push(0);
+        data.extend_from_slice(&0x80u16.to_le_bytes());
+        data.push(0);
+        let bytes = build_sdt(Signature::HPET, 1, &data);
+
+        let hpet = Hpet::from_sdt(Sdt::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(hpet.hardware_revision(), 1);
+        assert_eq!(hpet.comparator_count(), 3);
+        assert!(hpet.is_64bit());
+        assert!(hpet.legacy_replacement_capable());
+        assert_eq!(hpet.pci_vendor_id(), 0x8086);
+        let base = hpet.base_address().unwrap();
+        assert_eq!(base.address_space, AddressSpace::SYSTEM_MEMORY);
+        assert_eq!(base.address, 0xfed0_0000);
+        assert_eq!(hpet.minimum_tick(), 0x80);
+    }
+}
@@ -0,0 +1,436 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Multiple APIC Description Table (MADT).
+
+use super::{AcpiError, AcpiTable, Sdt, Signature, check_sdt, read_u16, read_u32, read_u64};
+use bitflags::bitflags;
+use core::iter::FusedIterator;
+
+/// Offset of the first interrupt controller structure.
+const ENTRIES_OFFSET: usize = 44;
+
+bitflags! {
+    /// Flags of processor entries in the MADT.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct LocalApicFlags: u32 {
+        /// The processor is usable.
+        const ENABLED = 1 << 0;
+        /// The processor can be enabled at runtime, if it is not
+        /// [`ENABLED`](Self::ENABLED).
+        const ONLINE_CAPABLE = 1 << 1;
+    }
+}
+
+/// Polarity and trigger mode flags (MPS INTI flags) of interrupt entries in
+/// the MADT.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct MpsIntiFlags(pub u16);
+
+impl MpsIntiFlags {
+    /// Get the polarity of the interrupt signal.
+    #[must_use]
+    pub const fn polarity(self) -> Polarity {
+        match self.0 & 0b11 {
+            0b00 => Polarity::ConformsToBus,
+            0b01 => Polarity::ActiveHigh,
+            0b10 => Polarity::Reserved,
+            _ => Polarity::ActiveLow,
+        }
+    }
+
+    /// Get the trigger mode of the interrupt signal.
+    #[must_use]
+    pub const fn trigger_mode(self) -> TriggerMode {
+        match (self.0 >> 2) & 0b11 {
+            0b00 => TriggerMode::ConformsToBus,
+            0b01 => TriggerMode::Edge,
+            0b10 => TriggerMode::Reserved,
+            _ => TriggerMode::Level,
+        }
+    }
+}
+
+/// Polarity of an interrupt signal.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Polarity {
+    /// Conforms to the specification of the bus.
+    ConformsToBus,
+    /// Active high.
+    ActiveHigh,
+    /// Reserved value.
+    Reserved,
+    /// Active low.
+    ActiveLow,
+}
+
+/// Trigger mode of an interrupt signal.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TriggerMode {
+    /// Conforms to the specification of the bus.
+    ConformsToBus,
+    /// Edge-triggered.
+    Edge,
+    /// Reserved value.
+    Reserved,
+    /// Level-triggered.
+    Level,
+}
+
+/// Processor Local APIC structure (type 0).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LocalApic {
+    /// ACPI processor UID.
+    pub processor_uid: u8,
+    /// Local APIC ID of the processor.
+    pub apic_id: u8,
+    /// Processor flags.
+    pub flags: LocalApicFlags,
+}
+
+/// I/O APIC structure (type 1).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IoApic {
+    /// I/O APIC ID.
+    pub io_apic_id: u8,
+    /// Physical address of the I/O APIC registers.
+    pub address: u32,
+    /// First global system interrupt handled by this I/O APIC.
+    pub gsi_base: u32,
+}
+
+/// Interrupt Source Override structure (type 2).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InterruptSourceOverride {
+    /// Bus of the source, always 0 (ISA).
+    pub bus: u8,
+    /// Bus-relative interrupt source (IRQ).
+    pub source: u8,
+    /// Global system interrupt the source is mapped to.
+    pub gsi: u32,
+    /// Polarity and trigger mode.
+    pub flags: MpsIntiFlags,
+}
+
+/// Non-Maskable Interrupt Source structure (type 3).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NmiSource {
+    /// Polarity and trigger mode.
+    pub flags: MpsIntiFlags,
+    /// Global system interrupt of the NMI.
+    pub gsi: u32,
+}
+
+/// Local APIC NMI structure (type 4).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LocalApicNmi {
+    /// ACPI processor UID, or `0xff` for all processors.
+    pub processor_uid: u8,
+    /// Polarity and trigger mode.
+    pub flags: MpsIntiFlags,
+    /// Local APIC `LINT#` input the NMI is connected to.
+    pub lint: u8,
+}
+
+/// Local APIC Address Override structure (type 5).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LocalApicAddressOverride {
+    /// 64-bit physical address of the local APIC.
+    pub address: u64,
+}
+
+/// Processor Local x2APIC structure (type 9).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LocalX2Apic {
+    /// x2APIC ID of the processor.
+    pub x2apic_id: u32,
+    /// Processor flags.
+    pub flags: LocalApicFlags,
+    /// ACPI processor UID.
+    pub processor_uid: u32,
+}
+
+/// Local x2APIC NMI structure (type 10).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LocalX2ApicNmi {
+    /// Polarity and trigger mode.
+    pub flags: MpsIntiFlags,
+    /// ACPI processor UID, or `0xffffffff` for all processors.
+    pub processor_uid: u32,
+    /// Local x2APIC `LINT#` input the NMI is connected to.
+    pub lint: u8,
+}
+
+/// GIC CPU Interface structure (type 11), describing an Arm processor.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Gicc {
+    /// GIC CPU interface number.
+    pub cpu_interface_number: u32,
+    /// ACPI processor UID.
+    pub processor_uid: u32,
+    /// Processor flags.
+    pub flags: LocalApicFlags,
+    /// Physical address of the GIC CPU interface registers.
+    pub physical_base_address: u64,
+    /// Physical address of the GIC redistributor, if not described by a
+    /// separate structure.
+    pub gicr_base_address: u64,
+    /// Affinity value of the processor's `MPIDR` register.
+    pub mpidr: u64,
+}
+
+/// GIC Distributor structure (type 12).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Gicd {
+    /// GIC ID.
+    pub gic_id: u32,
+    /// Physical address of the distributor registers.
+    pub physical_base_address: u64,
+    /// GIC version, or 0 if it must be detected.
+    pub gic_version: u8,
+}
+
+/// Interrupt controller structure in the [`Madt`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MadtEntry<'a> {
+    /// Processor Local APIC.
+    LocalApic(LocalApic),
+    /// I/O APIC.
+    IoApic(IoApic),
+    /// Interrupt Source Override.
+    InterruptSourceOverride(InterruptSourceOverride),
+    /// Non-Maskable Interrupt Source.
+    NmiSource(NmiSource),
+    /// Local APIC NMI.
+    LocalApicNmi(LocalApicNmi),
+    /// Local APIC Address Override.
+    LocalApicAddressOverride(LocalApicAddressOverride),
+    /// Processor Local x2APIC.
+    LocalX2Apic(LocalX2Apic),
+    /// Local x2APIC NMI.
+    LocalX2ApicNmi(LocalX2ApicNmi),
+    /// GIC CPU Interface.
+    Gicc(Gicc),
+    /// GIC Distributor.
+    Gicd(Gicd),
+    /// Structure of another type, or one that is too short for its type.
+    Other {
+        /// Structure type.
+        entry_type: u8,
+        /// Structure contents, including the type and length bytes.
+        data: &'a [u8],
+    },
+}
+
+impl<'a> MadtEntry<'a> {
+    /// Parse the structure in `data`, which includes the type and length.
+    fn parse(data: &'a [u8]) -> Self {
+        let entry_type = data[0];
+        Self::parse_known(entry_type, data).unwrap_or(Self::Other { entry_type, data })
+    }
+
+    fn parse_known(entry_type: u8, data: &[u8]) -> Option<Self> {
+        let u8_at = |offset: usize| data.get(offset).copied();
+        let flags = |offset| read_u32(data, offset).map(LocalApicFlags::from_bits_retain);
+        let inti = |offset| read_u16(data, offset).map(MpsIntiFlags);
+        Some(match entry_type {
+            0 => Self::LocalApic(LocalApic {
+                processor_uid: u8_at(2)?,
+                apic_id: u8_at(3)?,
+                flags: flags(4)?,
+            }),
+            1 => Self::IoApic(IoApic {
+                io_apic_id: u8_at(2)?,
+                address: read_u32(data, 4)?,
+                gsi_base: read_u32(data, 8)?,
+            }),
+            2 => Self::InterruptSourceOverride(InterruptSourceOverride {
+                bus: u8_at(2)?,
+                source: u8_at(3)?,
+                gsi: read_u32(data, 4)?,
+                flags: inti(8)?,
+            }),
+            3 => Self::NmiSource(NmiSource {
+                flags: inti(2)?,
+                gsi: read_u32(data, 4)?,
+            }),
+            4 => Self::LocalApicNmi(LocalApicNmi {
+                processor_uid: u8_at(2)?,
+                flags: inti(3)?,
+                lint: u8_at(5)?,
+            }),
+            5 => Self::LocalApicAddressOverride(LocalApicAddressOverride {
+                address: read_u64(data, 4)?,
+            }),
+            9 => Self::LocalX2Apic(LocalX2Apic {
+                x2apic_id: read_u32(data, 4)?,
+                flags: flags(8)?,
+                processor_uid: read_u32(data, 12)?,
+            }),
+            10 => Self::LocalX2ApicNmi(LocalX2ApicNmi {
+                flags: inti(2)?,
+                processor_uid: read_u32(data, 4)?,
+                lint: u8_at(8)?,
+            }),
+            11 => Self::Gicc(Gicc {
+                cpu_interface_number: read_u32(data, 4)?,
+                processor_uid: read_u32(data, 8)?,
+                flags: flags(12)?,
+                physical_base_address: read_u64(data, 32)?,
+                gicr_base_address: read_u64(data, 60)?,
+                mpidr: read_u64(data, 68)?,
+            }),
+            12 => Self::Gicd(Gicd {
+                gic_id: read_u32(data, 4)?,
+                physical_base_address: read_u64(data, 8)?,
+                gic_version: u8_at(20)?,
+            }),
+            _ => return None,
+        })
+    }
+}
+
+/// Multiple APIC Description Table (MADT), with signature `APIC`.
+#[derive(Clone, Copy, Debug)]
+pub struct Madt<'a> {
+    sdt: Sdt<'a>,
+}
+
+impl<'a> AcpiTable<'a> for Madt<'a> {
+    const SIGNATURE: Signature = Signature::MADT;
+
+    fn from_sdt(sdt: Sdt<'a>) -> Result<Self, AcpiError> {
+        check_sdt(&sdt, Self::SIGNATURE, ENTRIES_OFFSET)?;
+        Ok(Self { sdt })
+    }
+}
+
+impl<'a> Madt<'a> {
+    /// Get the underlying table.
+    #[must_use]
+    pub const fn sdt(&self) -> Sdt<'a> {
+        self.sdt
+    }
+
+    /// Get the 32-bit physical address of the local APIC. This may be
+    /// overridden by a [`LocalApicAddressOverride`] entry.
+    #[must_use]
+    pub fn local_apic_address(&self) -> u32 {
+        read_u32(self.sdt.as_bytes(), 36).unwrap()
+    }
+
+    /// Returns whether the system also has dual 8259 PICs, which must be
+    /// disabled before enabling the APICs.
+    #[must_use]
+    pub fn has_pcat_compat(&self) -> bool {
+        read_u32(self.sdt.as_bytes(), 40).unwrap() & 1 != 0
+    }
+
+    /// Get an iterator over the interrupt controller structures.
+    #[must_use]
+    pub fn entries(&self) -> MadtEntries<'a> {
+        MadtEntries {
+            data: &self.sdt.as_bytes()[ENTRIES_OFFSET..],
+        }
+    }
+}
+
+/// Iterator over the entries of the [`Madt`].
+///
+/// Iteration stops at the first structure with an invalid length.
+#[derive(Clone, Debug)]
+pub struct MadtEntries<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for MadtEntries<'a> {
+    type Item = MadtEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = usize::from(*self.data.get(1)?);
+        if len < 2 || len > self.data.len() {
+            self.data = &[];
+            return None;
+        }
+        let (entry, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(MadtEntry::parse(entry))
+    }
+}
+
+impl FusedIterator for MadtEntries<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::build_sdt;
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_madt() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&0xfee0_0000u32.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        // Local APIC.
+        data.extend_from_slice(&[0, 8, 1, 2, 1, 0, 0, 0]);
+        // I/O APIC.
+        data.extend_from_slice(&[1, 12, 3, 0]);
+        data.extend_from_slice(&0xfec0_0000u32.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        // Interrupt source override.
+        data.extend_from_slice(&[2, 10, 0, 0, 2, 0, 0, 0, 0x0d, 0]);
+        // Local x2APIC.
+        data.extend_from_slice(&[9, 16, 0, 0]);
+        data.extend_from_slice(&0x100u32.to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&7u32.to_le_bytes());
+        // Unknown type.
+        data.extend_from_slice(&[0x7f, 3, 0xaa]);
+        // Truncated local APIC.
+        data.extend_from_slice(&[0, 4, 1, 2]);
+        // Invalid length, ends iteration.
+        data.extend_from_slice(&[0, 0]);
+        let bytes = build_sdt(Signature::MADT, 5, &data);
+
+        let madt = Madt::from_sdt(Sdt::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(madt.local_apic_address(), 0xfee0_0000);
+        assert!(madt.has_pcat_compat());
+        assert_eq!(MpsIntiFlags(0x0d).polarity(), Polarity::ActiveHigh);
+        assert_eq!(MpsIntiFlags(0x0d).trigger_mode(), TriggerMode::Level);
+        let entries: Vec<_> = madt.entries().collect();
+        assert_eq!(
+            entries,
+            [
+                MadtEntry::LocalApic(LocalApic {
+                    processor_uid: 1,
+                    apic_id: 2,
+                    flags: LocalApicFlags::ENABLED
+                }),
+                MadtEntry::IoApic(IoApic {
+                    io_apic_id: 3,
+                    address: 0xfec0_0000,
+                    gsi_base: 0
+                }),
+                MadtEntry::InterruptSourceOverride(InterruptSourceOverride {
+                    bus: 0,
+                    source: 0,
+                    gsi: 2,
+                    flags: MpsIntiFlags(0x0d)
+                }),
+                MadtEntry::LocalX2Apic(LocalX2Apic {
+                    x2apic_id: 0x100,
+                    flags: LocalApicFlags::ENABLED | LocalApicFlags::ONLINE_CAPABLE,
+                    processor_uid: 7
+                }),
+                MadtEntry::Other {
+                    entry_type: 0x7f,
+                    data: &[0x7f, 3, 0xaa]
+                },
+                MadtEntry::Other {
+                    entry_type: 0,
+                    data: &[0, 4, 1, 2]
+                },
+            ]
+        );
+    }
+}
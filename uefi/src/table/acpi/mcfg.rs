@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! PCI Express memory-mapped configuration space table (MCFG).
+
+use super::{AcpiError, AcpiTable, Sdt, Signature, check_sdt, read_u16, read_u64};
+use core::iter::FusedIterator;
+use core::slice;
+
+/// Offset of the first configuration space allocation.
+const ENTRIES_OFFSET: usize = 44;
+
+/// Size of a configuration space allocation.
+const ENTRY_SIZE: usize = 16;
+
+/// Memory-mapped configuration space allocation of a PCI segment group.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct McfgEntry {
+    /// Physical base address of the configuration space.
+    pub base_address: u64,
+    /// PCI segment group number.
+    pub segment_group: u16,
+    /// First PCI bus number decoded by this allocation.
+    pub start_bus: u8,
+    /// Last PCI bus number decoded by this allocation.
+    pub end_bus: u8,
+}
+
+impl McfgEntry {
+    /// Get the physical address of the configuration space of a PCI
+    /// function, or `None` if `bus` is not decoded by this allocation or
+    /// `device` or `function` are out of range.
+    #[must_use]
+    pub const fn config_address(&self, bus: u8, device: u8, function: u8) -> Option<u64> {
+        if bus < self.start_bus || bus > self.end_bus || device >= 32 || function >= 8 {
+            return None;
+        }
+        let offset = ((bus as u64) << 20) | ((device as u64) << 15) | ((function as u64) << 12);
+        Some(self.base_address + offset)
+    }
+}
+
+/// PCI Express memory-mapped configuration space table, with signature
+/// `MCFG`.
+#[derive(Clone, Copy, Debug)]
+pub struct Mcfg<'a> {
+    sdt: Sdt<'a>,
+}
+
+impl<'a> AcpiTable<'a> for Mcfg<'a> {
+    const SIGNATURE: Signature = Signature::MCFG;
+
+    fn from_sdt(sdt: Sdt<'a>) -> Result<Self, AcpiError> {
+        check_sdt(&sdt, Self::SIGNATURE, ENTRIES_OFFSET)?;
+        Ok(Self { sdt })
+    }
+}
+
+impl<'a> Mcfg<'a> {
+    /// Get the underlying table.
+    #[must_use]
+    pub const fn sdt(&self) -> Sdt<'a> {
+        self.sdt
+    }
+
+    /// Get an iterator over the configuration space allocations.
+    #[must_use]
+    pub fn entries(&self) -> McfgEntries<'a> {
+        McfgEntries {
+            chunks: self.sdt.as_bytes()[ENTRIES_OFFSET..]
+                .as_chunks::<ENTRY_SIZE>()
+                .0
+                .iter(),
+        }
+    }
+
+    /// Find the allocation decoding `bus` in `segment_group`.
+    #[must_use]
+    pub fn find(&self, segment_group: u16, bus: u8) -> Option<McfgEntry> {
+        self.entries().find(|entry| {
+            entry.segment_group == segment_group && (entry.start_bus..=entry.end_bus).contains(&bus)
+        })
+    }
+}
+
+/// Iterator over the entries of the [`Mcfg`].
+#[derive(Clone, Debug)]
+pub struct McfgEntries<'a> {
+    chunks: slice::Iter<'a, [u8; ENTRY_SIZE]>,
+}
+
+impl Iterator for McfgEntries<'_> {
+    type Item = McfgEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.chunks.next()?;
+        Some(McfgEntry {
+            base_address: read_u64(entry, 0).unwrap(),
+            segment_group: read_u16(entry, 8).unwrap(),
+            start_bus: entry[10],
+            end_bus: entry[11],
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl ExactSizeIterator for McfgEntries<'_> {}
+impl FusedIterator for McfgEntries<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::build_sdt;
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_mcfg() {
+        let mut data = Vec::from([0; 8]);
+        data.extend_from_slice(&0xb000_0000u64.to_le_bytes());
+        data.extend_from_slice(&[0, 0, 0, 0xff, 0, 0, 0, 0]);
+        data.extend_from_slice(&0xe000_0000u64.to_le_bytes());
+        data.extend_from_slice(&[1, 0, 0x10, 0x1f, 0, 0, 0, 0]);
+        let bytes = build_sdt(Signature::MCFG, 1, &data);
+
+        let mcfg = Mcfg::from_sdt(Sdt::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(mcfg.entries().len(), 2);
+        let entry = mcfg.find(1, 0x12).unwrap();
+        assert_eq!(
+            entry,
+            McfgEntry {
+                base_address: 0xe000_0000,
+                segment_group: 1,
+                start_bus: 0x10,
+                end_bus: 0x1f,
+            }
+        );
+        assert_eq!(
+            entry.config_address(0x12, 3, 1),
+            Some(0xe120_1000 + (3 << 15))
+        );
+        assert_eq!(entry.config_address(0x20, 0, 0), None);
+        assert_eq!(mcfg.find(1, 0x20), None);
+    }
+}
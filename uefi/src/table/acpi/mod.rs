@@ -0,0 +1,818 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Reader for the ACPI tables provided by the firmware.
+//!
+//! The firmware publishes the Root System Description Pointer ([`Rsdp`]) in
+//! the configuration table. It points to the XSDT (or the RSDT on ACPI 1.0
+//! systems), which lists the addresses of all other tables. [`AcpiTables`]
+//! validates the RSDP and walks the root table, verifying the checksum of
+//! each table it returns.
+//!
+//! Tables can be looked up by [`Signature`], which returns a generic
+//! [`Sdt`], or by type, which returns a typed view:
+//!
+//! ```no_run
+//! use uefi::table::acpi::{AcpiTables, Madt, MadtEntry};
+//!
+//! # fn example() -> Result<(), uefi::table::acpi::AcpiError> {
+//! let tables = AcpiTables::find()?;
+//! let madt = tables.find_table::<Madt>()?;
+//! for entry in madt.entries() {
+//!     if let MadtEntry::IoApic(io_apic) = entry {
+//!         log::info!("I/O APIC at {:#x}", io_apic.address);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! All tables are accessed through their physical addresses, so they must be
+//! identity-mapped, as they are while boot services are active. The tables
+//! are usually in ACPI reclaim memory, so they stay valid after exiting boot
+//! services until the OS reuses that memory.
+//!
+//! See <https://uefi.org/specs/ACPI/6.5/05_ACPI_Software_Programming_Model.html>.
+
+mod bgrt;
+mod fadt;
+mod hpet;
+mod madt;
+mod mcfg;
+mod spcr;
+
+pub use bgrt::{Bgrt, BgrtImageType, BgrtOrientation};
+pub use fadt::{Fadt, FadtFlags, IaPcBootArch, PmProfile};
+pub use hpet::Hpet;
+pub use madt::{
+    Gicc, Gicd, InterruptSourceOverride, IoApic, LocalApic, LocalApicAddressOverride,
+    LocalApicFlags, LocalApicNmi, LocalX2Apic, LocalX2ApicNmi, Madt, MadtEntries, MadtEntry,
+    MpsIntiFlags, NmiSource, Polarity, TriggerMode,
+};
+pub use mcfg::{Mcfg, McfgEntries, McfgEntry};
+pub use spcr::{Spcr, SpcrInterfaceType};
+
+use crate::table::cfg::{ConfigTable, ConfigTableEntry};
+use crate::{Guid, system};
+use core::ffi::c_void;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::iter::FusedIterator;
+use core::slice;
+
+/// Size of the ACPI 1.0 RSDP.
+const RSDP_V1_SIZE: usize = 20;
+
+/// Size of the ACPI 2.0+ RSDP.
+const RSDP_V2_SIZE: usize = 36;
+
+/// Size of the [`Sdt`] header.
+const SDT_HEADER_SIZE: usize = 36;
+
+/// Errors that may happen when reading ACPI tables.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AcpiError {
+    /// The system table has no RSDP.
+    RsdpNotFound,
+    /// The RSDP or a table has the wrong signature.
+    InvalidSignature,
+    /// The checksum of the RSDP or a table is wrong.
+    InvalidChecksum,
+    /// The RSDP or a table is too small, or its length is inconsistent.
+    InvalidLength,
+    /// A table address is null.
+    NullAddress,
+    /// No table with the given signature exists.
+    TableNotFound(Signature),
+}
+
+impl Display for AcpiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RsdpNotFound => write!(f, "ACPI RSDP not found"),
+            Self::InvalidSignature => write!(f, "invalid ACPI table signature"),
+            Self::InvalidChecksum => write!(f, "invalid ACPI table checksum"),
+            Self::InvalidLength => write!(f, "invalid ACPI table length"),
+            Self::NullAddress => write!(f, "null ACPI table address"),
+            Self::TableNotFound(signature) => write!(f, "ACPI table {signature} not found"),
+        }
+    }
+}
+
+impl core::error::Error for AcpiError {}
+
+/// Four-byte signature identifying an ACPI table.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Signature(pub [u8; 4]);
+
+impl Signature {
+    /// Fixed ACPI Description Table.
+    pub const FADT: Self = Self(*b"FACP");
+    /// Firmware ACPI Control Structure.
+    pub const FACS: Self = Self(*b"FACS");
+    /// Differentiated System Description Table.
+    pub const DSDT: Self = Self(*b"DSDT");
+    /// Secondary System Description Table.
+    pub const SSDT: Self = Self(*b"SSDT");
+    /// Multiple APIC Description Table.
+    pub const MADT: Self = Self(*b"APIC");
+    /// PCI Express memory-mapped configuration space table.
+    pub const MCFG: Self = Self(*b"MCFG");
+    /// High Precision Event Timer table.
+    pub const HPET: Self = Self(*b"HPET");
+    /// Serial Port Console Redirection table.
+    pub const SPCR: Self = Self(*b"SPCR");
+    /// Boot Graphics Resource Table.
+    pub const BGRT: Self = Self(*b"BGRT");
+    /// Root System Description Table.
+    pub const RSDT: Self = Self(*b"RSDT");
+    /// Extended System Description Table.
+    pub const XSDT: Self = Self(*b"XSDT");
+}
+
+impl Debug for Signature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{self}\"")
+    }
+}
+
+impl Display for Signature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            let c = if byte.is_ascii_graphic() || byte == b' ' {
+                char::from(byte)
+            } else {
+                '?'
+            };
+            write!(f, "{c}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compute the 8-bit sum of `bytes`, which must be zero for valid tables.
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |sum, byte| sum.wrapping_add(*byte))
+}
+
+/// Read `N` bytes at `offset`.
+fn read_bytes<const N: usize>(bytes: &[u8], offset: usize) -> Option<[u8; N]> {
+    bytes.get(offset..offset.checked_add(N)?)?.try_into().ok()
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    read_bytes(bytes, offset).map(u16::from_le_bytes)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    read_bytes(bytes, offset).map(u32::from_le_bytes)
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    read_bytes(bytes, offset).map(u64::from_le_bytes)
+}
+
+/// Create a slice of `len` bytes at `address`.
+///
+/// # Safety
+///
+/// The memory must be valid for reads for lifetime `'a`.
+unsafe fn phys_slice<'a>(address: u64, len: usize) -> Result<&'a [u8], AcpiError> {
+    if address == 0 {
+        return Err(AcpiError::NullAddress);
+    }
+    let ptr = usize::try_from(address).map_err(|_| AcpiError::InvalidLength)? as *const u8;
+    // SAFETY: the caller guarantees the memory is valid.
+    Ok(unsafe { slice::from_raw_parts(ptr, len) })
+}
+
+newtype_enum! {
+    /// Address space of a [`GenericAddress`].
+    pub enum AddressSpace: u8 => {
+        /// System memory.
+        SYSTEM_MEMORY = 0x00,
+        /// System I/O ports.
+        SYSTEM_IO = 0x01,
+        /// PCI configuration space.
+        PCI_CONFIG = 0x02,
+        /// Embedded controller.
+        EMBEDDED_CONTROLLER = 0x03,
+        /// SMBus.
+        SMBUS = 0x04,
+        /// System CMOS.
+        SYSTEM_CMOS = 0x05,
+        /// PCI BAR target.
+        PCI_BAR_TARGET = 0x06,
+        /// IPMI.
+        IPMI = 0x07,
+        /// General purpose I/O.
+        GPIO = 0x08,
+        /// Generic serial bus.
+        GENERIC_SERIAL_BUS = 0x09,
+        /// Platform communications channel.
+        PCC = 0x0a,
+        /// Platform runtime mechanism.
+        PRM = 0x0b,
+        /// Functional fixed hardware.
+        FUNCTIONAL_FIXED_HARDWARE = 0x7f,
+    }
+}
+
+/// Generic Address Structure, describing the location of a register.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GenericAddress {
+    /// Address space of the register.
+    pub address_space: AddressSpace,
+    /// Size of the register in bits.
+    pub bit_width: u8,
+    /// Offset of the register in bits.
+    pub bit_offset: u8,
+    /// Access size: 0 is undefined, 1 to 4 are byte to qword accesses.
+    pub access_size: u8,
+    /// Address of the register in its address space.
+    pub address: u64,
+}
+
+impl GenericAddress {
+    /// Size of the structure in bytes.
+    pub const SIZE: usize = 12;
+
+    /// Read the structure at `offset`, returning `None` if it is out of
+    /// bounds or the address is zero.
+    fn read(bytes: &[u8], offset: usize) -> Option<Self> {
+        let raw: [u8; Self::SIZE] = read_bytes(bytes, offset)?;
+        let address = read_u64(&raw, 4)?;
+        (address != 0).then_some(Self {
+            address_space: AddressSpace(raw[0]),
+            bit_width: raw[1],
+            bit_offset: raw[2],
+            access_size: raw[3],
+            address,
+        })
+    }
+}
+
+/// Root System Description Pointer.
+#[derive(Clone, Copy, Debug)]
+pub struct Rsdp<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Rsdp<'a> {
+    /// Signature at the start of the RSDP.
+    pub const SIGNATURE: [u8; 8] = *b"RSD PTR ";
+
+    /// Read the RSDP at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to an RSDP that is valid for reads for its full size
+    /// for lifetime `'a`.
+    ///
+    /// # Errors
+    ///
+    /// See [`from_bytes`](Self::from_bytes).
+    pub unsafe fn from_ptr(ptr: *const u8) -> Result<Self, AcpiError> {
+        // SAFETY: the caller guarantees the ACPI 1.0 fields are readable.
+        let v1 = unsafe { slice::from_raw_parts(ptr, RSDP_V1_SIZE) };
+        let len = if v1[15] >= 2 {
+            // SAFETY: the caller guarantees the ACPI 2.0 fields are readable.
+            let v2 = unsafe { slice::from_raw_parts(ptr, RSDP_V2_SIZE) };
+            read_u32(v2, 20).unwrap() as usize
+        } else {
+            RSDP_V1_SIZE
+        };
+        // SAFETY: the caller guarantees the whole RSDP is readable.
+        Self::from_bytes(unsafe { slice::from_raw_parts(ptr, len.max(RSDP_V1_SIZE)) })
+    }
+
+    /// Parse and validate the RSDP in `bytes`.
+    ///
+    /// # Errors
+    ///
+    /// * [`AcpiError::InvalidLength`]: `bytes` is too small.
+    /// * [`AcpiError::InvalidSignature`]: the signature is wrong.
+    /// * [`AcpiError::InvalidChecksum`]: a checksum is wrong.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, AcpiError> {
+        let v1 = bytes.get(..RSDP_V1_SIZE).ok_or(AcpiError::InvalidLength)?;
+        if v1[..8] != Self::SIGNATURE {
+            return Err(AcpiError::InvalidSignature);
+        }
+        if checksum(v1) != 0 {
+            return Err(AcpiError::InvalidChecksum);
+        }
+        if v1[15] < 2 {
+            return Ok(Self { bytes: v1 });
+        }
+
+        let len = read_u32(bytes, 20).ok_or(AcpiError::InvalidLength)? as usize;
+        if len < RSDP_V2_SIZE {
+            return Err(AcpiError::InvalidLength);
+        }
+        let bytes = bytes.get(..len).ok_or(AcpiError::InvalidLength)?;
+        if checksum(bytes) != 0 {
+            return Err(AcpiError::InvalidChecksum);
+        }
+        Ok(Self { bytes })
+    }
+
+    /// Get the revision: 0 for ACPI 1.0, 2 for ACPI 2.0 and later.
+    #[must_use]
+    pub const fn revision(&self) -> u8 {
+        self.bytes[15]
+    }
+
+    /// Get the OEM ID.
+    #[must_use]
+    pub fn oem_id(&self) -> [u8; 6] {
+        read_bytes(self.bytes, 9).unwrap()
+    }
+
+    /// Get the physical address of the RSDT.
+    #[must_use]
+    pub fn rsdt_address(&self) -> u32 {
+        read_u32(self.bytes, 16).unwrap()
+    }
+
+    /// Get the physical address of the XSDT, if this is an ACPI 2.0+ RSDP.
+    #[must_use]
+    pub fn xsdt_address(&self) -> Option<u64> {
+        read_u64(self.bytes, 24)
+    }
+}
+
+/// System Description Table: a table with the standard ACPI header, whose
+/// checksum has been validated.
+#[derive(Clone, Copy)]
+pub struct Sdt<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Sdt<'a> {
+    /// Read the table at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a table that is valid for reads for its full
+    /// length, as given by its header, for lifetime `'a`.
+    ///
+    /// # Errors
+    ///
+    /// See [`from_bytes`](Self::from_bytes).
+    pub unsafe fn from_ptr(ptr: *const u8) -> Result<Self, AcpiError> {
+        // SAFETY: the caller guarantees the table is readable.
+        unsafe { Self::from_address(ptr as u64) }
+    }
+
+    /// Read the table at the physical `address`.
+    unsafe fn from_address(address: u64) -> Result<Self, AcpiError> {
+        // SAFETY: the caller guarantees the header is readable.
+        let header = unsafe { phys_slice(address, SDT_HEADER_SIZE)? };
+        let len = read_u32(header, 4).unwrap() as usize;
+        // SAFETY: the caller guarantees the whole table is readable.
+        Self::from_bytes(unsafe { phys_slice(address, len.max(SDT_HEADER_SIZE))? })
+    }
+
+    /// Parse the table in `bytes` and validate its length and checksum.
+    /// Trailing bytes are ignored.
+    ///
+    /// # Errors
+    ///
+    /// * [`AcpiError::InvalidLength`]: `bytes` is smaller than the header or
+    ///   the length in the header.
+    /// * [`AcpiError::InvalidChecksum`]: the checksum is wrong.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, AcpiError> {
+        let len = read_u32(bytes, 4).ok_or(AcpiError::InvalidLength)? as usize;
+        if len < SDT_HEADER_SIZE {
+            return Err(AcpiError::InvalidLength);
+        }
+        let bytes = bytes.get(..len).ok_or(AcpiError::InvalidLength)?;
+        if checksum(bytes) != 0 {
+            return Err(AcpiError::InvalidChecksum);
+        }
+        Ok(Self { bytes })
+    }
+
+    /// Get the signature of the table.
+    #[must_use]
+    pub fn signature(&self) -> Signature {
+        Signature(read_bytes(self.bytes, 0).unwrap())
+    }
+
+    /// Get the length of the table in bytes, including the header.
+    #[must_use]
+    pub const fn length(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Get the revision of the table structure.
+    #[must_use]
+    pub const fn revision(&self) -> u8 {
+        self.bytes[8]
+    }
+
+    /// Get the OEM ID.
+    #[must_use]
+    pub fn oem_id(&self) -> [u8; 6] {
+        read_bytes(self.bytes, 10).unwrap()
+    }
+
+    /// Get the OEM table ID.
+    #[must_use]
+    pub fn oem_table_id(&self) -> [u8; 8] {
+        read_bytes(self.bytes, 16).unwrap()
+    }
+
+    /// Get the OEM revision.
+    #[must_use]
+    pub fn oem_revision(&self) -> u32 {
+        read_u32(self.bytes, 24).unwrap()
+    }
+
+    /// Get the vendor ID of the tool that created the table.
+    #[must_use]
+    pub fn creator_id(&self) -> [u8; 4] {
+        read_bytes(self.bytes, 28).unwrap()
+    }
+
+    /// Get the revision of the tool that created the table.
+    #[must_use]
+    pub fn creator_revision(&self) -> u32 {
+        read_u32(self.bytes, 32).unwrap()
+    }
+
+    /// Get the whole table, including the header.
+    #[must_use]
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Get the table contents following the header.
+    #[must_use]
+    pub fn data(&self) -> &'a [u8] {
+        &self.bytes[SDT_HEADER_SIZE..]
+    }
+}
+
+impl Debug for Sdt<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sdt")
+            .field("signature", &self.signature())
+            .field("length", &self.length())
+            .field("revision", &self.revision())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Typed view of an ACPI table.
+pub trait AcpiTable<'a>: Sized {
+    /// Signature of the table.
+    const SIGNATURE: Signature;
+
+    /// Create the view from a generic table.
+    ///
+    /// # Errors
+    ///
+    /// * [`AcpiError::InvalidSignature`]: the signature of `sdt` is not
+    ///   [`SIGNATURE`](Self::SIGNATURE).
+    /// * [`AcpiError::InvalidLength`]: the table is too small.
+    fn from_sdt(sdt: Sdt<'a>) -> Result<Self, AcpiError>;
+}
+
+/// Check the signature and minimum length of `sdt` for a typed view.
+fn check_sdt(sdt: &Sdt<'_>, signature: Signature, min_len: usize) -> Result<(), AcpiError> {
+    if sdt.signature() != signature {
+        Err(AcpiError::InvalidSignature)
+    } else if sdt.length() < min_len {
+        Err(AcpiError::InvalidLength)
+    } else {
+        Ok(())
+    }
+}
+
+/// The ACPI tables, found through the [`Rsdp`].
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone, Copy, Debug)]
+pub struct AcpiTables<'a> {
+    rsdp: Rsdp<'a>,
+    root: Sdt<'a>,
+}
+
+impl<'a> AcpiTables<'a> {
+    /// Locate the RSDP in the system configuration table, preferring the
+    /// ACPI 2.0+ entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AcpiError::RsdpNotFound`] if the firmware does not provide
+    /// ACPI tables, or any other error if the RSDP or root table is invalid.
+    pub fn find() -> Result<AcpiTables<'static>, AcpiError> {
+        system::find_config_table()
+    }
+
+    /// Read the ACPI tables, starting from the RSDP at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid RSDP. The RSDP and all tables it refers to
+    /// must be identity-mapped and valid for reads for lifetime `'a`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RSDP or the root table is invalid.
+    pub unsafe fn from_rsdp(ptr: *const u8) -> Result<Self, AcpiError> {
+        // SAFETY: the caller guarantees the RSDP is valid.
+        let rsdp = unsafe { Rsdp::from_ptr(ptr)? };
+        let (address, signature) = match rsdp.xsdt_address() {
+            Some(address) if address != 0 => (address, Signature::XSDT),
+            _ => (u64::from(rsdp.rsdt_address()), Signature::RSDT),
+        };
+        // SAFETY: the caller guarantees the root table is valid.
+        let root = unsafe { Sdt::from_address(address)? };
+        if root.signature() != signature {
+            return Err(AcpiError::InvalidSignature);
+        }
+        Ok(Self { rsdp, root })
+    }
+
+    /// Get the RSDP.
+    #[must_use]
+    pub const fn rsdp(&self) -> Rsdp<'a> {
+        self.rsdp
+    }
+
+    /// Get the root table, which is the XSDT if available, and the RSDT
+    /// otherwise.
+    #[must_use]
+    pub const fn root(&self) -> Sdt<'a> {
+        self.root
+    }
+
+    /// Get an iterator over the tables listed in the root table.
+    ///
+    /// Tables with an invalid length or checksum are returned as errors.
+    #[must_use]
+    pub fn tables(&self) -> Tables<'a> {
+        let entry_size = if self.root.signature() == Signature::XSDT {
+            8
+        } else {
+            4
+        };
+        Tables {
+            entries: self.root.data(),
+            entry_size,
+        }
+    }
+
+    /// Find the first table with `signature`.
+    ///
+    /// The DSDT is not listed in the root table; use [`dsdt`](Self::dsdt)
+    /// for it.
+    ///
+    /// # Errors
+    ///
+    /// * [`AcpiError::TableNotFound`]: there is no such table.
+    /// * [`AcpiError::InvalidLength`], [`AcpiError::InvalidChecksum`]: the
+    ///   table is invalid.
+    pub fn find_by_signature(&self, signature: Signature) -> Result<Sdt<'a>, AcpiError> {
+        self.tables()
+            .addresses()
+            .find(|address| {
+                // SAFETY: the caller of `from_rsdp` guarantees all tables are
+                // valid.
+                unsafe { phys_slice(*address, 4) }.is_ok_and(|bytes| bytes == signature.0)
+            })
+            // SAFETY: as above.
+            .map_or(Err(AcpiError::TableNotFound(signature)), |address| unsafe {
+                Sdt::from_address(address)
+            })
+    }
+
+    /// Find the table of type `T`.
+    ///
+    /// # Errors
+    ///
+    /// See [`find_by_signature`](Self::find_by_signature) and
+    /// [`AcpiTable::from_sdt`].
+    pub fn find_table<T: AcpiTable<'a>>(&self) -> Result<T, AcpiError> {
+        T::from_sdt(self.find_by_signature(T::SIGNATURE)?)
+    }
+
+    /// Get the DSDT, which is referenced by the FADT.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the FADT or DSDT is missing or invalid.
+    pub fn dsdt(&self) -> Result<Sdt<'a>, AcpiError> {
+        let address = self
+            .find_table::<Fadt>()?
+            .dsdt_address()
+            .ok_or(AcpiError::TableNotFound(Signature::DSDT))?;
+        // SAFETY: the caller of `from_rsdp` guarantees all tables are valid.
+        let dsdt = unsafe { Sdt::from_address(address)? };
+        if dsdt.signature() != Signature::DSDT {
+            return Err(AcpiError::InvalidSignature);
+        }
+        Ok(dsdt)
+    }
+}
+
+impl ConfigTable for AcpiTables<'static> {
+    const GUID: Guid = ConfigTableEntry::ACPI2_GUID;
+    const COMPATIBLE_GUIDS: &'static [Guid] = &[ConfigTableEntry::ACPI_GUID];
+    type Error = AcpiError;
+    const NOT_FOUND: AcpiError = AcpiError::RsdpNotFound;
+
+    unsafe fn from_entry(_guid: Guid, address: *const c_void) -> Result<Self, AcpiError> {
+        // SAFETY: the firmware guarantees the tables are valid.
+        unsafe { Self::from_rsdp(address.cast()) }
+    }
+}
+
+/// Iterator over the tables listed in the root table, returned by
+/// [`AcpiTables::tables`].
+#[derive(Clone, Debug)]
+pub struct Tables<'a> {
+    entries: &'a [u8],
+    entry_size: usize,
+}
+
+impl Tables<'_> {
+    /// Iterate over the raw table addresses.
+    fn addresses(self) -> impl Iterator<Item = u64> {
+        self.entries
+            .chunks_exact(self.entry_size)
+            .map(|entry| match *entry {
+                [a, b, c, d] => u64::from(u32::from_le_bytes([a, b, c, d])),
+                _ => read_u64(entry, 0).unwrap(),
+            })
+    }
+}
+
+impl<'a> Iterator for Tables<'a> {
+    type Item = Result<Sdt<'a>, AcpiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let address = self.clone().addresses().next()?;
+        self.entries = &self.entries[self.entry_size..];
+        // SAFETY: the caller of `AcpiTables::from_rsdp` guarantees all tables
+        // are valid.
+        Some(unsafe { Sdt::from_address(address) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.entries.len() / self.entry_size;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Tables<'_> {}
+impl FusedIterator for Tables<'_> {}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    /// Build a table with a valid checksum.
+    pub fn build_sdt(signature: Signature, revision: u8, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&signature.0);
+        bytes.extend_from_slice(&((SDT_HEADER_SIZE + data.len()) as u32).to_le_bytes());
+        bytes.push(revision);
+        bytes.push(0);
+        bytes.extend_from_slice(b"UEFIRS");
+        bytes.extend_from_slice(b"TESTTBL ");
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(b"RUST");
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes[9] = 0u8.wrapping_sub(checksum(&bytes));
+        bytes
+    }
+
+    fn build_rsdp(revision: u8, rsdt: u32, xsdt: u64) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&Rsdp::SIGNATURE);
+        bytes.push(0);
+        bytes.extend_from_slice(b"UEFIRS");
+        bytes.push(revision);
+        bytes.extend_from_slice(&rsdt.to_le_bytes());
+        bytes[8] = 0u8.wrapping_sub(checksum(&bytes));
+        if revision >= 2 {
+            bytes.extend_from_slice(&(RSDP_V2_SIZE as u32).to_le_bytes());
+            bytes.extend_from_slice(&xsdt.to_le_bytes());
+            bytes.extend_from_slice(&[0; 4]);
+            bytes[32] = 0u8.wrapping_sub(checksum(&bytes));
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_rsdp() {
+        let rsdp = build_rsdp(2, 0x1234, 0x5678);
+        let parsed = Rsdp::from_bytes(&rsdp).unwrap();
+        assert_eq!(parsed.revision(), 2);
+        assert_eq!(&parsed.oem_id(), b"UEFIRS");
+        assert_eq!(parsed.rsdt_address(), 0x1234);
+        assert_eq!(parsed.xsdt_address(), Some(0x5678));
+
+        let rsdp_v1 = build_rsdp(0, 0x1234, 0);
+        let parsed = Rsdp::from_bytes(&rsdp_v1).unwrap();
+        assert_eq!(parsed.xsdt_address(), None);
+
+        let mut bad = rsdp.clone();
+        bad[30] ^= 1;
+        assert_eq!(
+            Rsdp::from_bytes(&bad).unwrap_err(),
+            AcpiError::InvalidChecksum
+        );
+        let mut bad = rsdp;
+        bad[0] = b'X';
+        assert_eq!(
+            Rsdp::from_bytes(&bad).unwrap_err(),
+            AcpiError::InvalidSignature
+        );
+    }
+
+    #[test]
+    fn test_sdt() {
+        let bytes = build_sdt(Signature::HPET, 1, &[1, 2, 3]);
+        let sdt = Sdt::from_bytes(&bytes).unwrap();
+        assert_eq!(sdt.signature(), Signature::HPET);
+        assert_eq!(sdt.length(), 39);
+        assert_eq!(sdt.revision(), 1);
+        assert_eq!(&sdt.oem_table_id(), b"TESTTBL ");
+        assert_eq!(sdt.data(), [1, 2, 3]);
+
+        let mut bad = bytes.clone();
+        bad[37] = 0;
+        assert_eq!(
+            Sdt::from_bytes(&bad).unwrap_err(),
+            AcpiError::InvalidChecksum
+        );
+        assert_eq!(
+            Sdt::from_bytes(&bytes[..38]).unwrap_err(),
+            AcpiError::InvalidLength
+        );
+    }
+
+    #[test]
+    fn test_walk() {
+        for xsdt in [true, false] {
+            let hpet = build_sdt(Signature::HPET, 1, &[0; 20]);
+            let mut bad_spcr = build_sdt(Signature::SPCR, 2, &[0; 44]);
+            bad_spcr[40] = 1;
+            let dsdt = build_sdt(Signature::DSDT, 2, &[]);
+            let mut fadt_data = vec![0; 244 - SDT_HEADER_SIZE];
+            fadt_data[140 - SDT_HEADER_SIZE..148 - SDT_HEADER_SIZE]
+                .copy_from_slice(&(dsdt.as_ptr() as u64).to_le_bytes());
+            let fadt = build_sdt(Signature::FADT, 6, &fadt_data);
+
+            let mut entries = Vec::new();
+            for table in [&hpet, &bad_spcr, &fadt] {
+                let address = table.as_ptr() as u64;
+                if xsdt {
+                    entries.extend_from_slice(&address.to_le_bytes());
+                } else {
+                    // Host addresses may not fit in 32 bits; skip the RSDT
+                    // case on such systems.
+                    let Ok(address) = u32::try_from(address) else {
+                        return;
+                    };
+                    entries.extend_from_slice(&address.to_le_bytes());
+                }
+            }
+            let (root, rsdp) = if xsdt {
+                let root = build_sdt(Signature::XSDT, 1, &entries);
+                let rsdp = build_rsdp(2, 0, root.as_ptr() as u64);
+                (root, rsdp)
+            } else {
+                let root = build_sdt(Signature::RSDT, 1, &entries);
+                let rsdp = build_rsdp(0, root.as_ptr() as u32, 0);
+                (root, rsdp)
+            };
+
+            let tables = unsafe { AcpiTables::from_rsdp(rsdp.as_ptr()) }.unwrap();
+            assert_eq!(tables.root().as_bytes(), root.as_slice());
+            assert_eq!(tables.tables().len(), 3);
+            assert_eq!(tables.tables().filter(Result::is_ok).count(), 2);
+            assert_eq!(
+                tables
+                    .find_by_signature(Signature::HPET)
+                    .unwrap()
+                    .as_bytes(),
+                hpet.as_slice()
+            );
+            assert_eq!(
+                tables.find_by_signature(Signature::SPCR).unwrap_err(),
+                AcpiError::InvalidChecksum
+            );
+            assert_eq!(
+                tables.find_by_signature(Signature::MCFG).unwrap_err(),
+                AcpiError::TableNotFound(Signature::MCFG)
+            );
+            assert_eq!(tables.dsdt().unwrap().as_bytes(), dsdt.as_slice());
+        }
+    }
+}
@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Serial Port Console Redirection table (SPCR).
+
+use super::{AcpiError, AcpiTable, GenericAddress, Sdt, Signature, check_sdt, read_u16, read_u32};
+
+/// Length of the revision 1 table.
+const MIN_LEN: usize = 80;
+
+newtype_enum! {
+    /// Type of the serial port interface described by the [`Spcr`].
+    pub enum SpcrInterfaceType: u8 => {
+        /// Fully 16550-compatible.
+        FULL_16550 = 0x00,
+        /// Fully 16450-compatible.
+        FULL_16450 = 0x01,
+        /// MAX311xE SPI UART.
+        MAX311XE_SPI = 0x02,
+        /// ARM PL011 UART.
+        ARM_PL011 = 0x03,
+        /// MSM8x60 UART.
+        MSM8X60 = 0x04,
+        /// Nvidia 16550 UART.
+        NVIDIA_16550 = 0x05,
+        /// TI OMAP UART.
+        TI_OMAP = 0x06,
+        /// APM88xxxx UART.
+        APM88XXXX = 0x08,
+        /// MSM8974 UART.
+        MSM8974 = 0x09,
+        /// SAM5250 UART.
+        SAM5250 = 0x0a,
+        /// Intel USIF UART.
+        INTEL_USIF = 0x0b,
+        /// i.MX 6 UART.
+        IMX6 = 0x0c,
+        /// ARM SBSA generic UART, 32-bit access only.
+        ARM_SBSA_32BIT = 0x0d,
+        /// ARM SBSA generic UART.
+        ARM_SBSA_GENERIC = 0x0e,
+        /// ARM DCC.
+        ARM_DCC = 0x0f,
+        /// BCM2835 UART.
+        BCM2835 = 0x10,
+        /// SDM845 UART at 1.8432 MHz.
+        SDM845_18432 = 0x11,
+        /// 16550-compatible, with the register layout given by the
+        /// generic address.
+        GENERIC_16550 = 0x12,
+        /// SDM845 UART at 7.372 MHz.
+        SDM845_7372 = 0x13,
+        /// Intel LPSS UART.
+        INTEL_LPSS = 0x14,
+        /// RISC-V SBI console.
+        RISCV_SBI = 0x15,
+    }
+}
+
+/// Serial Port Console Redirection table, with signature `SPCR`.
+#[derive(Clone, Copy, Debug)]
+pub struct Spcr<'a> {
+    sdt: Sdt<'a>,
+}
+
+impl<'a> AcpiTable<'a> for Spcr<'a> {
+    const SIGNATURE: Signature = Signature::SPCR;
+
+    fn from_sdt(sdt: Sdt<'a>) -> Result<Self, AcpiError> {
+        check_sdt(&sdt, Self::SIGNATURE, MIN_LEN)?;
+        Ok(Self { sdt })
+    }
+}
+
+impl<'a> Spcr<'a> {
+    /// Get the underlying table.
+    #[must_use]
+    pub const fn sdt(&self) -> Sdt<'a> {
+        self.sdt
+    }
+
+    const fn byte(&self, offset: usize) -> u8 {
+        self.sdt.as_bytes()[offset]
+    }
+
+    /// Get the type of the serial port interface.
+    #[must_use]
+    pub const fn interface_type(&self) -> SpcrInterfaceType {
+        SpcrInterfaceType(self.byte(36))
+    }
+
+    /// Get the address of the serial port registers.
+    #[must_use]
+    pub fn base_address(&self) -> Option<GenericAddress> {
+        GenericAddress::read(self.sdt.as_bytes(), 40)
+    }
+
+    /// Get the supported interrupt types, as a bitmask of PC-AT 8259
+    /// (bit 0), I/O APIC (bit 1), SAPIC (bit 2), GIC (bit 3), and PLIC or
+    /// APLIC (bit 4).
+    #[must_use]
+    pub const fn interrupt_type(&self) -> u8 {
+        self.byte(52)
+    }
+
+    /// Get the PC-AT IRQ used by the port.
+    #[must_use]
+    pub const fn irq(&self) -> u8 {
+        self.byte(53)
+    }
+
+    /// Get the global system interrupt used by the port.
+    #[must_use]
+    pub fn global_system_interrupt(&self) -> u32 {
+        read_u32(self.sdt.as_bytes(), 54).unwrap()
+    }
+
+    /// Get the baud rate configured by the firmware, or `None` if the
+    /// port is left as already configured.
+    ///
+    /// Revision 4 tables may give a baud rate that can't be expressed in the
+    /// legacy field; see [`precise_baud_rate`](Self::precise_baud_rate).
+    #[must_use]
+    pub const fn baud_rate(&self) -> Option<u32> {
+        match self.byte(58) {
+            3 => Some(9600),
+            4 => Some(19200),
+            6 => Some(57600),
+            7 => Some(115_200),
+            _ => None,
+        }
+    }
+
+    /// Get the parity setting. Zero means no parity.
+    #[must_use]
+    pub const fn parity(&self) -> u8 {
+        self.byte(59)
+    }
+
+    /// Get the stop bits setting. One means one stop bit.
+    #[must_use]
+    pub const fn stop_bits(&self) -> u8 {
+        self.byte(60)
+    }
+
+    /// Get the flow control setting, as a bitmask of DCD (bit 0), RTS/CTS
+    /// (bit 1), and XON/XOFF (bit 2).
+    #[must_use]
+    pub const fn flow_control(&self) -> u8 {
+        self.byte(61)
+    }
+
+    /// Get the terminal type: VT100 (0), extended VT100 (1), VT-UTF8 (2),
+    /// or ANSI (3).
+    #[must_use]
+    pub const fn terminal_type(&self) -> u8 {
+        self.byte(62)
+    }
+
+    /// Get the PCI device and vendor IDs of the port, or `None` if it is
+    /// not a PCI device.
+    #[must_use]
+    pub fn pci_id(&self) -> Option<(u16, u16)> {
+        let bytes = self.sdt.as_bytes();
+        let device = read_u16(bytes, 64).unwrap();
+        let vendor = read_u16(bytes, 66).unwrap();
+        (device != 0xffff || vendor != 0xffff).then_some((device, vendor))
+    }
+
+    /// Get the PCI segment, bus, device, and function numbers of the port.
+    ///
+    /// Only meaningful if [`pci_id`](Self::pci_id) is `Some`.
+    #[must_use]
+    pub const fn pci_location(&self) -> (u8, u8, u8, u8) {
+        (self.byte(75), self.byte(68), self.byte(69), self.byte(70))
+    }
+
+    /// Get the PCI flags. Bit 0 set means the operating system must not
+    /// suppress plug and play enumeration or disable power management.
+    #[must_use]
+    pub fn pci_flags(&self) -> u32 {
+        read_u32(self.sdt.as_bytes(), 71).unwrap()
+    }
+
+    /// Get the frequency of the UART clock in Hz, or `None` if unknown or
+    /// not given by the table (revision 3 and later).
+    #[must_use]
+    pub fn uart_clock_frequency(&self) -> Option<u32> {
+        if self.sdt.revision() < 3 {
+            return None;
+        }
+        read_u32(self.sdt.as_bytes(), 76).filter(|&freq| freq != 0)
+    }
+
+    /// Get the precise baud rate, or `None` if not given by the table
+    /// (revision 4 and later). Overrides [`baud_rate`](Self::baud_rate).
+    #[must_use]
+    pub fn precise_baud_rate(&self) -> Option<u32> {
+        if self.sdt.revision() < 4 {
+            return None;
+        }
+        read_u32(self.sdt.as_bytes(), 80).filter(|&rate| rate != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::AddressSpace;
+    use super::super::tests::build_sdt;
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_spcr() {
+        let mut data = Vec::from([0x03, 0, 0, 0]);
+        data.extend_from_slice(&[0, 32, 0, 3]);
+        data.extend_from_slice(&0x0900_0000u64.to_le_bytes());
+        data.extend_from_slice(&[0x08, 0]);
+        data.extend_from_slice(&33u32.to_le_bytes());
+        data.extend_from_slice(&[7, 0, 1, 0, 0, 0]);
+        data.extend_from_slice(&[0xff; 4]);
+        data.extend_from_slice(&[0; 3]);
+        data.extend_from_slice(&[0; 4]);
+        data.push(0);
+        data.extend_from_slice(&24_000_000u32.to_le_bytes());
+        data.extend_from_slice(&1_500_000u32.to_le_bytes());
+        let bytes = build_sdt(Signature::SPCR, 4, &data);
+
+        let spcr = Spcr::from_sdt(Sdt::from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(spcr.interface_type(), SpcrInterfaceType::ARM_PL011);
+        let base = spcr.base_address().unwrap();
+        assert_eq!(base.address_space, AddressSpace::SYSTEM_MEMORY);
+        assert_eq!(base.address, 0x0900_0000);
+        assert_eq!(spcr.interrupt_type(), 0x08);
+        assert_eq!(spcr.global_system_interrupt(), 33);
+        assert_eq!(spcr.baud_rate(), Some(115_200));
+        assert_eq!(spcr.stop_bits(), 1);
+        assert_eq!(spcr.pci_id(), None);
+        assert_eq!(spcr.uart_clock_frequency(), Some(24_000_000));
+        assert_eq!(spcr.precise_baud_rate(), Some(1_500_000));
+    }
+}
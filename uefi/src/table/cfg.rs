@@ -84,6 +84,87 @@ impl ConfigTableEntry {
     /// The properties table is used to provide additional info
     /// about the UEFI implementation.
     pub const PROPERTIES_TABLE_GUID: Guid = guid!("880aaca3-4adc-4a04-9079-b747340825e5");
+
+    /// Entry pointing to the flattened device tree (DTB) passed to the OS.
+    pub const DEVICE_TREE_GUID: Guid = guid!("b1b621d5-f19c-41a5-830b-d9152c69aae0");
+}
+
+/// A typed view of a vendor table in the configuration table.
+///
+/// Implementations validate the table, such as its signature, version and
+/// length, when reading it. Use [`system::find_config_table`] to locate a
+/// table, and [`boot::install_config_table`] to publish one.
+///
+/// # Example
+///
+/// ```
+/// use core::ffi::c_void;
+/// use uefi::table::cfg::ConfigTable;
+/// use uefi::{Guid, guid};
+///
+/// #[repr(C)]
+/// struct MyTable {
+///     signature: u64,
+///     value: u32,
+/// }
+///
+/// impl MyTable {
+///     const SIGNATURE: u64 = u64::from_le_bytes(*b"MY_TABLE");
+/// }
+///
+/// #[derive(Debug)]
+/// enum MyTableError {
+///     NotFound,
+///     InvalidSignature,
+/// }
+///
+/// impl ConfigTable for &'static MyTable {
+///     const GUID: Guid = guid!("4bec53c4-5fc1-48a1-ab12-df214907d29f");
+///     type Error = MyTableError;
+///     const NOT_FOUND: MyTableError = MyTableError::NotFound;
+///
+///     unsafe fn from_entry(_guid: Guid, address: *const c_void) -> Result<Self, MyTableError> {
+///         // SAFETY: the caller guarantees the table is valid.
+///         let table = unsafe { &*address.cast::<MyTable>() };
+///         if table.signature != MyTable::SIGNATURE {
+///             return Err(MyTableError::InvalidSignature);
+///         }
+///         Ok(table)
+///     }
+/// }
+/// ```
+///
+/// [`system::find_config_table`]: crate::system::find_config_table
+/// [`boot::install_config_table`]: crate::boot::install_config_table
+pub trait ConfigTable: Sized {
+    /// GUID of the configuration table entry that points to the table.
+    const GUID: Guid;
+
+    /// GUIDs of older entries that point to a compatible table. They are
+    /// only used if there is no entry for [`GUID`](Self::GUID), in the order
+    /// given here.
+    const COMPATIBLE_GUIDS: &'static [Guid] = &[];
+
+    /// Error returned if the table is not found or is invalid.
+    type Error;
+
+    /// Error returned if the configuration table has no entry for the table.
+    const NOT_FOUND: Self::Error;
+
+    /// Read and validate the table at `address`, which was found in the
+    /// configuration table entry `guid`.
+    ///
+    /// # Safety
+    ///
+    /// `address` must be the non-null address of the configuration table
+    /// entry `guid`, which is either [`GUID`](Self::GUID) or one of
+    /// [`COMPATIBLE_GUIDS`](Self::COMPATIBLE_GUIDS). The table must be valid
+    /// for as long as the returned value is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table is invalid.
+    unsafe fn from_entry(guid: Guid, address: *const c_void) -> Result<Self, Self::Error>;
 }
 
 /// This table contains additional information about the UEFI implementation.
@@ -0,0 +1,398 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Reader for the Debug Image Info Table.
+//!
+//! The firmware keeps a list of all loaded images in this table, so that
+//! debuggers can find them. [`DebugImageInfoTable::resolve`] uses it to map
+//! an instruction address to the image that contains it, which helps to
+//! interpret fault addresses and backtraces in firmware made of many images:
+//!
+//! ```no_run
+//! use uefi::table::debug_image_info::DebugImageInfoTable;
+//!
+//! # fn example(fault_address: usize) -> Result<(), uefi::table::debug_image_info::DebugImageInfoError> {
+//! let table = DebugImageInfoTable::find()?;
+//! match table.resolve(fault_address) {
+//!     // Prints something like `Shell.efi+0x1f3a`.
+//!     Some(location) => log::error!("fault at {location}"),
+//!     None => log::error!("fault at {fault_address:#x}"),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! See <https://uefi.org/specs/UEFI/2.10/18_Protocols_Debugger_Support.html#efi-debug-support-table>.
+
+use crate::proto::device_path::media::FilePath;
+use crate::proto::device_path::{DevicePath, DevicePathNodeEnum};
+use crate::proto::loaded_image::LoadedImage;
+use crate::table::cfg::{ConfigTable, ConfigTableEntry};
+use crate::{Guid, Handle, system};
+use core::ffi::c_void;
+use core::fmt::{self, Display, Formatter, Write};
+use core::ops::Range;
+use core::{char, slice};
+use uefi_raw::table::configuration::{DebugImageInfoNormal, DebugImageInfoTableHeader};
+
+pub use uefi_raw::table::configuration::DebugImageInfoUpdateStatus;
+
+/// Errors that may happen when reading the [`DebugImageInfoTable`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DebugImageInfoError {
+    /// The system table has no debug image info table.
+    NotFound,
+    /// The firmware is updating the table.
+    UpdateInProgress,
+}
+
+impl Display for DebugImageInfoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "debug image info table not found"),
+            Self::UpdateInProgress => write!(f, "debug image info table is being updated"),
+        }
+    }
+}
+
+impl core::error::Error for DebugImageInfoError {}
+
+/// The Debug Image Info Table.
+///
+/// The firmware updates the table when images are loaded or unloaded, so the
+/// reader should not be kept across calls that may load or unload images.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone, Copy, Debug)]
+pub struct DebugImageInfoTable<'a> {
+    update_status: DebugImageInfoUpdateStatus,
+    entries: &'a [*mut DebugImageInfoNormal],
+}
+
+impl<'a> DebugImageInfoTable<'a> {
+    /// Locate the table in the system configuration table.
+    ///
+    /// # Errors
+    ///
+    /// * [`DebugImageInfoError::NotFound`]: the firmware does not provide the
+    ///   table.
+    /// * [`DebugImageInfoError::UpdateInProgress`]: the firmware is updating
+    ///   the table.
+    pub fn find() -> Result<DebugImageInfoTable<'static>, DebugImageInfoError> {
+        system::find_config_table()
+    }
+
+    /// Read the table at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid table header. The entries of the table,
+    /// and the loaded image protocols they point to, must be valid for
+    /// lifetime `'a`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DebugImageInfoError::UpdateInProgress`] if the firmware is
+    /// updating the table.
+    pub unsafe fn from_ptr(
+        ptr: *const DebugImageInfoTableHeader,
+    ) -> Result<Self, DebugImageInfoError> {
+        // SAFETY: the caller guarantees the header is valid. The status is
+        // updated concurrently by the firmware.
+        let update_status = unsafe { (&raw const (*ptr).update_status).read_volatile() };
+        if update_status.contains(DebugImageInfoUpdateStatus::UPDATE_IN_PROGRESS) {
+            return Err(DebugImageInfoError::UpdateInProgress);
+        }
+        // SAFETY: the caller guarantees the header is valid.
+        let header = unsafe { &*ptr };
+        let entries = if header.efi_debug_image_info_table.is_null() {
+            &[][..]
+        } else {
+            // SAFETY: the caller guarantees the entries are valid.
+            unsafe {
+                slice::from_raw_parts(
+                    header.efi_debug_image_info_table,
+                    header.table_size as usize,
+                )
+            }
+        };
+        Ok(Self {
+            update_status,
+            entries,
+        })
+    }
+
+    /// Get the update status of the table when it was read.
+    #[must_use]
+    pub const fn update_status(&self) -> DebugImageInfoUpdateStatus {
+        self.update_status
+    }
+
+    /// Get an iterator over the loaded images.
+    pub fn images(&self) -> impl Iterator<Item = DebugImage<'a>> + use<'a> {
+        self.entries.iter().filter_map(|&entry| {
+            // SAFETY: entries are null or valid, per the requirements of
+            // `from_ptr`.
+            let entry = unsafe { entry.as_ref() }?;
+            if entry.image_info_type != DebugImageInfoNormal::TYPE_NORMAL {
+                return None;
+            }
+            // SAFETY: `LoadedImage` is a transparent wrapper of the raw
+            // protocol, which is valid per the requirements of `from_ptr`.
+            let loaded_image = unsafe {
+                entry
+                    .loaded_image_protocol_instance
+                    .cast::<LoadedImage>()
+                    .as_ref()
+            }?;
+            Some(DebugImage {
+                // SAFETY: the firmware guarantees the handle is valid.
+                handle: unsafe { Handle::from_ptr(entry.image_handle) },
+                loaded_image,
+            })
+        })
+    }
+
+    /// Find the image that contains `address`.
+    #[must_use]
+    pub fn find_image(&self, address: usize) -> Option<DebugImage<'a>> {
+        self.images().find(|image| image.contains(address))
+    }
+
+    /// Map `address` to the image that contains it and the offset of the
+    /// address in the image.
+    #[must_use]
+    pub fn resolve(&self, address: usize) -> Option<ImageLocation<'a>> {
+        let image = self.find_image(address)?;
+        Some(ImageLocation {
+            offset: address - image.range().start,
+            image,
+        })
+    }
+}
+
+impl ConfigTable for DebugImageInfoTable<'static> {
+    const GUID: Guid = ConfigTableEntry::DEBUG_IMAGE_INFO_GUID;
+    type Error = DebugImageInfoError;
+    const NOT_FOUND: DebugImageInfoError = DebugImageInfoError::NotFound;
+
+    unsafe fn from_entry(_guid: Guid, address: *const c_void) -> Result<Self, DebugImageInfoError> {
+        // SAFETY: the firmware guarantees the table is valid.
+        unsafe { Self::from_ptr(address.cast()) }
+    }
+}
+
+/// A loaded image listed in the [`DebugImageInfoTable`].
+#[derive(Clone, Copy, Debug)]
+pub struct DebugImage<'a> {
+    handle: Option<Handle>,
+    loaded_image: &'a LoadedImage,
+}
+
+impl<'a> DebugImage<'a> {
+    /// Get the handle of the image.
+    #[must_use]
+    pub const fn handle(&self) -> Option<Handle> {
+        self.handle
+    }
+
+    /// Get the loaded image protocol of the image.
+    #[must_use]
+    pub const fn loaded_image(&self) -> &'a LoadedImage {
+        self.loaded_image
+    }
+
+    /// Get the address range of the image in memory.
+    #[must_use]
+    pub fn range(&self) -> Range<usize> {
+        let (base, size) = self.loaded_image.info();
+        let base = base as usize;
+        base..base.saturating_add(size as usize)
+    }
+
+    /// Returns whether `address` is in the image.
+    #[must_use]
+    pub fn contains(&self, address: usize) -> bool {
+        self.range().contains(&address)
+    }
+
+    /// Get the path of the image file, relative to the device it was loaded
+    /// from.
+    #[must_use]
+    pub fn file_path(&self) -> Option<&'a DevicePath> {
+        self.loaded_image.file_path()
+    }
+
+    /// Get the name of the image, from the last file path or firmware file
+    /// node of its [`file_path`](Self::file_path).
+    #[must_use]
+    pub fn name(&self) -> Option<ImageName<'a>> {
+        self.file_path()?
+            .node_iter()
+            .filter_map(|node| match node.as_enum().ok()? {
+                DevicePathNodeEnum::MediaFilePath(path) => Some(ImageName::FilePath(path)),
+                DevicePathNodeEnum::MediaPiwgFirmwareFile(file) => {
+                    let guid = file.data().get(..16)?;
+                    Some(ImageName::FirmwareFile(Guid::from_bytes(
+                        guid.try_into().ok()?,
+                    )))
+                }
+                _ => None,
+            })
+            .last()
+    }
+}
+
+/// Name of a [`DebugImage`].
+#[derive(Clone, Copy, Debug)]
+pub enum ImageName<'a> {
+    /// Path of an image loaded from a file system.
+    FilePath(&'a FilePath),
+    /// Name of an image loaded from a firmware volume.
+    FirmwareFile(Guid),
+}
+
+impl Display for ImageName<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FilePath(path) => {
+                let units = path.path_name().into_iter().take_while(|&unit| unit != 0);
+                for c in char::decode_utf16(units) {
+                    f.write_char(c.unwrap_or(char::REPLACEMENT_CHARACTER))?;
+                }
+                Ok(())
+            }
+            Self::FirmwareFile(guid) => write!(f, "{guid}"),
+        }
+    }
+}
+
+/// Location of an address in a [`DebugImage`].
+///
+/// The location is displayed as the image name and offset, such as
+/// `\EFI\BOOT\BOOTX64.EFI+0x1f3a`, or as the image base and offset if the
+/// image has no name.
+#[derive(Clone, Copy, Debug)]
+pub struct ImageLocation<'a> {
+    /// Image that contains the address.
+    pub image: DebugImage<'a>,
+    /// Offset of the address from the start of the image.
+    pub offset: usize,
+}
+
+impl Display for ImageLocation<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.image.name() {
+            Some(name) => write!(f, "{name}+{:#x}", self.offset),
+            None => write!(f, "{:#x}+{:#x}", self.image.range().start, self.offset),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::device_path::build::{self, DevicePathBuilder};
+    use crate::{cstr16, guid};
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    use core::ffi::c_void;
+    use core::ptr;
+    use uefi_raw::protocol::device_path::DevicePathProtocol;
+    use uefi_raw::protocol::loaded_image::LoadedImageProtocol;
+    use uefi_raw::table::boot::MemoryType;
+
+    fn loaded_image(base: usize, size: u64, file_path: Option<&DevicePath>) -> LoadedImageProtocol {
+        LoadedImageProtocol {
+            revision: 0x1000,
+            parent_handle: ptr::null_mut(),
+            system_table: ptr::null(),
+            device_handle: ptr::null_mut(),
+            file_path: file_path.map_or(ptr::null(), |path| {
+                path.as_ffi_ptr().cast::<DevicePathProtocol>()
+            }),
+            reserved: ptr::null(),
+            load_options_size: 0,
+            load_options: ptr::null(),
+            image_base: base as *const c_void,
+            image_size: size,
+            image_code_type: MemoryType::BOOT_SERVICES_CODE,
+            image_data_type: MemoryType::BOOT_SERVICES_DATA,
+            unload: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut buf = Vec::new();
+        let shell_path = DevicePathBuilder::with_vec(&mut buf)
+            .push(&build::media::FilePath {
+                path_name: cstr16!(r"\EFI\BOOT\Shell.efi"),
+            })
+            .unwrap()
+            .finalize()
+            .unwrap();
+        let mut buf = Vec::new();
+        let driver_path = DevicePathBuilder::with_vec(&mut buf)
+            .push(&build::media::PiwgFirmwareFile {
+                data: &guid!("7c04a583-9e3e-4f1c-ad65-e05268d0b4d1").to_bytes(),
+            })
+            .unwrap()
+            .finalize()
+            .unwrap();
+
+        let shell = loaded_image(0x10_0000, 0x2_0000, Some(shell_path));
+        let driver = loaded_image(0x20_0000, 0x1000, Some(driver_path));
+        let anonymous = loaded_image(0x30_0000, 0x1000, None);
+        let mut entries: Vec<DebugImageInfoNormal> = [&shell, &driver, &anonymous]
+            .into_iter()
+            .map(|image| DebugImageInfoNormal {
+                image_info_type: DebugImageInfoNormal::TYPE_NORMAL,
+                loaded_image_protocol_instance: image,
+                image_handle: ptr::null_mut(),
+            })
+            .collect();
+        let mut pointers: Vec<*mut DebugImageInfoNormal> =
+            entries.iter_mut().map(ptr::from_mut).collect();
+        // Unloaded images leave null entries.
+        pointers.insert(1, ptr::null_mut());
+        let header = DebugImageInfoTableHeader {
+            update_status: DebugImageInfoUpdateStatus::TABLE_MODIFIED,
+            table_size: pointers.len() as u32,
+            efi_debug_image_info_table: pointers.as_mut_ptr(),
+        };
+
+        // SAFETY: the table and the images are valid.
+        let table = unsafe { DebugImageInfoTable::from_ptr(&header) }.unwrap();
+        assert_eq!(
+            table.update_status(),
+            DebugImageInfoUpdateStatus::TABLE_MODIFIED
+        );
+        assert_eq!(table.images().count(), 3);
+        assert_eq!(
+            table.find_image(0x10_1000).unwrap().range(),
+            0x10_0000..0x12_0000
+        );
+        assert!(table.find_image(0x12_0000).is_none());
+
+        assert_eq!(
+            table.resolve(0x10_1f3a).unwrap().to_string(),
+            r"\EFI\BOOT\Shell.efi+0x1f3a"
+        );
+        assert_eq!(
+            table.resolve(0x20_0010).unwrap().to_string(),
+            "7c04a583-9e3e-4f1c-ad65-e05268d0b4d1+0x10"
+        );
+        let location = table.resolve(0x30_0fff).unwrap();
+        assert_eq!(location.offset, 0xfff);
+        assert_eq!(location.to_string(), "0x300000+0xfff");
+
+        let header = DebugImageInfoTableHeader {
+            update_status: DebugImageInfoUpdateStatus::UPDATE_IN_PROGRESS,
+            table_size: 0,
+            efi_debug_image_info_table: ptr::null_mut(),
+        };
+        // SAFETY: the table is valid.
+        let result = unsafe { DebugImageInfoTable::from_ptr(&header) };
+        assert_eq!(result.unwrap_err(), DebugImageInfoError::UpdateInProgress);
+    }
+}
@@ -0,0 +1,319 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Reader for the EFI System Resource Table (ESRT).
+//!
+//! The ESRT lists the firmware resources of the system that can be updated
+//! with update capsules, along with their current version, the lowest
+//! version they can be updated to, and the result of the last update
+//! attempt.
+//!
+//! ```no_run
+//! use uefi::table::esrt::Esrt;
+//!
+//! # fn example() -> Result<(), uefi::table::esrt::EsrtError> {
+//! let esrt = Esrt::find()?;
+//! for entry in esrt.entries() {
+//!     log::info!(
+//!         "{}: {:?} version {} (lowest supported {}), last attempt: {:?}",
+//!         entry.fw_class,
+//!         entry.fw_type,
+//!         entry.fw_version,
+//!         entry.lowest_supported_fw_version,
+//!         entry.last_attempt_status,
+//!     );
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! See <https://uefi.org/specs/UEFI/2.10/23_Firmware_Update_and_Reporting.html#efi-system-resource-table>.
+
+use crate::table::cfg::{ConfigTable, ConfigTableEntry};
+use crate::{Guid, system};
+use core::ffi::c_void;
+use core::fmt::{self, Display, Formatter};
+use core::iter::FusedIterator;
+use core::slice;
+use uefi_raw::capsule::SystemResourceTable;
+
+pub use uefi_raw::capsule::{FirmwareType, LastAttemptStatus, SystemResourceEntry};
+
+const HEADER_SIZE: usize = size_of::<SystemResourceTable>();
+const ENTRY_SIZE: usize = size_of::<SystemResourceEntry>();
+
+/// Errors that may happen when reading the [`Esrt`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EsrtError {
+    /// The system table has no ESRT.
+    NotFound,
+    /// The buffer is too small for the table.
+    InvalidSize,
+    /// The entry version is not supported.
+    UnsupportedVersion(u64),
+    /// The entry count is larger than the maximum entry count.
+    InvalidCount,
+}
+
+impl Display for EsrtError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "ESRT not found"),
+            Self::InvalidSize => write!(f, "ESRT is truncated"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported ESRT resource version {version}")
+            }
+            Self::InvalidCount => write!(f, "ESRT resource count exceeds its maximum"),
+        }
+    }
+}
+
+impl core::error::Error for EsrtError {}
+
+/// The EFI System Resource Table.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone, Copy, Debug)]
+pub struct Esrt<'a> {
+    header: SystemResourceTable,
+    entries: &'a [u8],
+}
+
+impl<'a> Esrt<'a> {
+    /// Locate the table in the system configuration table.
+    ///
+    /// The table is placed in boot services data by the firmware, so it
+    /// should not be used after exiting boot services unless it was copied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EsrtError::NotFound`] if the firmware does not provide the
+    /// table, or any other error if the table is invalid.
+    pub fn find() -> Result<Esrt<'static>, EsrtError> {
+        system::find_config_table()
+    }
+
+    /// Read the table at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to an ESRT that is valid for reads for its full size,
+    /// as given by its header, for lifetime `'a`.
+    ///
+    /// # Errors
+    ///
+    /// See [`from_bytes`](Self::from_bytes).
+    pub unsafe fn from_ptr(ptr: *const u8) -> Result<Self, EsrtError> {
+        // SAFETY: the caller guarantees the header is readable.
+        let header = unsafe { ptr.cast::<SystemResourceTable>().read_unaligned() };
+        let size = (header.fw_resource_count as usize)
+            .checked_mul(ENTRY_SIZE)
+            .and_then(|size| size.checked_add(HEADER_SIZE))
+            .ok_or(EsrtError::InvalidSize)?;
+        // SAFETY: the caller guarantees the whole table is readable.
+        Self::from_bytes(unsafe { slice::from_raw_parts(ptr, size) })
+    }
+
+    /// Parse the table from `bytes`, which must contain the header and all
+    /// entries. Trailing bytes are ignored.
+    ///
+    /// # Errors
+    ///
+    /// * [`EsrtError::InvalidSize`]: `bytes` is too small.
+    /// * [`EsrtError::UnsupportedVersion`]: the entry version is not
+    ///   supported.
+    /// * [`EsrtError::InvalidCount`]: the entry count exceeds the maximum.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, EsrtError> {
+        let header = bytes.get(..HEADER_SIZE).ok_or(EsrtError::InvalidSize)?;
+        // SAFETY: `header` is large enough, and all bit patterns are valid.
+        let header = unsafe {
+            header
+                .as_ptr()
+                .cast::<SystemResourceTable>()
+                .read_unaligned()
+        };
+
+        if header.fw_resource_version != SystemResourceTable::FIRMWARE_RESOURCE_VERSION {
+            return Err(EsrtError::UnsupportedVersion(header.fw_resource_version));
+        }
+        if header.fw_resource_count > header.fw_resource_count_max {
+            return Err(EsrtError::InvalidCount);
+        }
+
+        let len = (header.fw_resource_count as usize)
+            .checked_mul(ENTRY_SIZE)
+            .ok_or(EsrtError::InvalidSize)?;
+        let entries = bytes
+            .get(HEADER_SIZE..)
+            .and_then(|rest| rest.get(..len))
+            .ok_or(EsrtError::InvalidSize)?;
+
+        Ok(Self { header, entries })
+    }
+
+    /// Get the number of entries in the table.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.header.fw_resource_count as usize
+    }
+
+    /// Returns whether the table has no entries.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the maximum number of entries the table can hold.
+    #[must_use]
+    pub const fn max_len(&self) -> usize {
+        self.header.fw_resource_count_max as usize
+    }
+
+    /// Get an iterator over the entries of the table.
+    #[must_use]
+    pub fn entries(&self) -> EsrtEntries<'a> {
+        EsrtEntries {
+            chunks: self.entries.as_chunks::<ENTRY_SIZE>().0.iter(),
+        }
+    }
+
+    /// Find the entry for the firmware resource with class `fw_class`.
+    #[must_use]
+    pub fn find_entry(&self, fw_class: &crate::Guid) -> Option<SystemResourceEntry> {
+        self.entries().find(|entry| entry.fw_class == *fw_class)
+    }
+}
+
+impl ConfigTable for Esrt<'static> {
+    const GUID: Guid = ConfigTableEntry::ESRT_GUID;
+    type Error = EsrtError;
+    const NOT_FOUND: EsrtError = EsrtError::NotFound;
+
+    unsafe fn from_entry(_guid: Guid, address: *const c_void) -> Result<Self, EsrtError> {
+        // SAFETY: the firmware guarantees the table is valid.
+        unsafe { Self::from_ptr(address.cast()) }
+    }
+}
+
+/// Iterator over the entries of the [`Esrt`].
+#[derive(Clone, Debug)]
+pub struct EsrtEntries<'a> {
+    chunks: slice::Iter<'a, [u8; ENTRY_SIZE]>,
+}
+
+impl Iterator for EsrtEntries<'_> {
+    type Item = SystemResourceEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next()?;
+        // SAFETY: `chunk` is large enough, and all bit patterns are valid.
+        Some(unsafe {
+            chunk
+                .as_ptr()
+                .cast::<SystemResourceEntry>()
+                .read_unaligned()
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl ExactSizeIterator for EsrtEntries<'_> {}
+impl FusedIterator for EsrtEntries<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guid;
+    use alloc::vec::Vec;
+    use uefi_raw::capsule::CapsuleFlags;
+
+    fn entry_bytes(entry: &SystemResourceEntry) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&entry.fw_class.to_bytes());
+        for field in [
+            entry.fw_type.0,
+            entry.fw_version,
+            entry.lowest_supported_fw_version,
+            entry.capsule_flags.bits(),
+            entry.last_attempt_version,
+            entry.last_attempt_status.0,
+        ] {
+            bytes.extend_from_slice(&field.to_ne_bytes());
+        }
+        bytes
+    }
+
+    fn table_bytes(count: u32, max: u32, version: u64, entries: &[SystemResourceEntry]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&count.to_ne_bytes());
+        bytes.extend_from_slice(&max.to_ne_bytes());
+        bytes.extend_from_slice(&version.to_ne_bytes());
+        for entry in entries {
+            bytes.extend(entry_bytes(entry));
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_parse() {
+        let entries = [
+            SystemResourceEntry {
+                fw_class: guid!("3c6a4b5e-8c6d-4e5f-9a0b-1c2d3e4f5a6b"),
+                fw_type: FirmwareType::SYSTEM_FIRMWARE,
+                fw_version: 0x10002,
+                lowest_supported_fw_version: 0x10000,
+                capsule_flags: CapsuleFlags::PERSIST_ACROSS_RESET,
+                last_attempt_version: 0x10002,
+                last_attempt_status: LastAttemptStatus::SUCCESS,
+            },
+            SystemResourceEntry {
+                fw_class: guid!("a1b2c3d4-e5f6-4789-8abc-def012345678"),
+                fw_type: FirmwareType::DEVICE_FIRMWARE,
+                fw_version: 7,
+                lowest_supported_fw_version: 5,
+                capsule_flags: CapsuleFlags::empty(),
+                last_attempt_version: 8,
+                last_attempt_status: LastAttemptStatus(0x1234),
+            },
+        ];
+        // Offset by one byte to check that unaligned tables work.
+        let mut bytes = Vec::from([0]);
+        bytes.extend(table_bytes(2, 4, 1, &entries));
+        let esrt = Esrt::from_bytes(&bytes[1..]).unwrap();
+
+        assert_eq!(esrt.len(), 2);
+        assert_eq!(esrt.max_len(), 4);
+        assert_eq!(esrt.entries().len(), 2);
+        assert_eq!(esrt.entries().collect::<Vec<_>>(), entries);
+        assert_eq!(esrt.find_entry(&entries[1].fw_class), Some(entries[1]));
+        assert!(!entries[0].last_attempt_status.is_vendor_specific());
+        assert!(entries[1].last_attempt_status.is_vendor_specific());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(
+            Esrt::from_bytes(&[0; 8]).unwrap_err(),
+            EsrtError::InvalidSize
+        );
+        assert_eq!(
+            Esrt::from_bytes(&table_bytes(0, 0, 2, &[])).unwrap_err(),
+            EsrtError::UnsupportedVersion(2)
+        );
+        assert_eq!(
+            Esrt::from_bytes(&table_bytes(2, 1, 1, &[])).unwrap_err(),
+            EsrtError::InvalidCount
+        );
+        assert_eq!(
+            Esrt::from_bytes(&table_bytes(1, 1, 1, &[])).unwrap_err(),
+            EsrtError::InvalidSize
+        );
+        assert!(
+            Esrt::from_bytes(&table_bytes(0, 0, 1, &[]))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,312 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Editing of device trees.
+
+use super::{
+    FDT_BEGIN_NODE, FDT_END_NODE, FDT_PROP, Fdt, FdtError, HEADER_SIZE, MAGIC, MemoryReservation,
+    Node, RESERVATION_SIZE, VERSION, align4,
+};
+use alloc::vec::Vec;
+
+/// Oldest version that is backwards compatible with [`VERSION`].
+const LAST_COMPATIBLE_VERSION: u32 = 16;
+
+/// Editable copy of a device tree.
+///
+/// The editor copies the blocks of a device tree, applies changes to its
+/// nodes and properties, and serializes the result into a new device tree
+/// with [`build`](Self::build):
+///
+/// ```no_run
+/// use uefi::table::fdt::{Fdt, FdtEditor};
+///
+/// # fn example(initrd_start: u64, initrd_end: u64) -> Result<(), uefi::table::fdt::FdtError> {
+/// let mut editor = FdtEditor::new(&Fdt::find()?);
+/// editor.set_bootargs("console=ttyAMA0 root=/dev/vda2")?;
+/// editor.set_initrd(initrd_start, initrd_end)?;
+/// // Leave room for the fixups of the firmware.
+/// let dtb = editor.build(0x1000);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FdtEditor {
+    boot_cpuid_phys: u32,
+    reservations: Vec<MemoryReservation>,
+    structs: Vec<u8>,
+    strings: Vec<u8>,
+}
+
+impl FdtEditor {
+    /// Create an editor from a copy of `fdt`.
+    #[must_use]
+    pub fn new(fdt: &Fdt<'_>) -> Self {
+        Self {
+            boot_cpuid_phys: fdt.boot_cpuid_phys(),
+            reservations: fdt.memory_reservations().collect(),
+            structs: fdt.structs.to_vec(),
+            strings: fdt.strings.to_vec(),
+        }
+    }
+
+    /// Get a view of the edited device tree, to look up nodes.
+    fn view(&self) -> Fdt<'_> {
+        Fdt::view(&self.structs, &self.strings)
+    }
+
+    fn find_node(&self, path: &str) -> Result<Node<'_>, FdtError> {
+        self.view().find_node(path).ok_or(FdtError::NodeNotFound)
+    }
+
+    /// Get the offset of `name` in the strings block, adding it if needed.
+    fn string_offset(&mut self, name: &str) -> u32 {
+        let existing = (0..self.strings.len()).find(|&offset| {
+            (offset == 0 || self.strings[offset - 1] == 0)
+                && self.strings[offset..].starts_with(name.as_bytes())
+                && self.strings.get(offset + name.len()) == Some(&0)
+        });
+        let offset = existing.unwrap_or_else(|| {
+            let offset = self.strings.len();
+            self.strings.extend_from_slice(name.as_bytes());
+            self.strings.push(0);
+            offset
+        });
+        offset as u32
+    }
+
+    /// Add a node called `name` to the node at `parent_path`. Nothing is
+    /// done if the node already exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FdtError::NodeNotFound`] if the parent node does not exist.
+    pub fn add_node(&mut self, parent_path: &str, name: &str) -> Result<(), FdtError> {
+        let parent = self.find_node(parent_path)?;
+        if parent.children().any(|child| child.name() == name) {
+            return Ok(());
+        }
+        let offset = parent.end_offset();
+
+        let mut tokens = Vec::new();
+        tokens.extend_from_slice(&FDT_BEGIN_NODE.to_be_bytes());
+        tokens.extend_from_slice(name.as_bytes());
+        tokens.push(0);
+        tokens.resize(align4(tokens.len()), 0);
+        tokens.extend_from_slice(&FDT_END_NODE.to_be_bytes());
+        self.structs.splice(offset..offset, tokens);
+        Ok(())
+    }
+
+    /// Set the property `name` of the node at `path` to `value`, replacing
+    /// the existing value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FdtError::NodeNotFound`] if the node does not exist.
+    pub fn set_property(&mut self, path: &str, name: &str, value: &[u8]) -> Result<(), FdtError> {
+        let node = self.find_node(path)?;
+        let mut properties = node.properties();
+        let mut range = node.props_offset..node.props_offset;
+        while let Some((start, end, property)) = properties.next_entry() {
+            if property.name() == name {
+                range = start..end;
+                break;
+            }
+        }
+
+        let name_offset = self.string_offset(name);
+        let mut token = Vec::new();
+        token.extend_from_slice(&FDT_PROP.to_be_bytes());
+        token.extend_from_slice(&(value.len() as u32).to_be_bytes());
+        token.extend_from_slice(&name_offset.to_be_bytes());
+        token.extend_from_slice(value);
+        token.resize(align4(token.len()), 0);
+        self.structs.splice(range, token);
+        Ok(())
+    }
+
+    /// Set the property `name` of the node at `path` to a null-terminated
+    /// string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FdtError::NodeNotFound`] if the node does not exist.
+    pub fn set_property_str(
+        &mut self,
+        path: &str,
+        name: &str,
+        value: &str,
+    ) -> Result<(), FdtError> {
+        let mut bytes = Vec::with_capacity(value.len() + 1);
+        bytes.extend_from_slice(value.as_bytes());
+        bytes.push(0);
+        self.set_property(path, name, &bytes)
+    }
+
+    /// Set the property `name` of the node at `path` to a 64-bit value of two
+    /// cells.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FdtError::NodeNotFound`] if the node does not exist.
+    pub fn set_property_u64(&mut self, path: &str, name: &str, value: u64) -> Result<(), FdtError> {
+        self.set_property(path, name, &value.to_be_bytes())
+    }
+
+    /// Remove the property `name` of the node at `path`. Returns whether the
+    /// property existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FdtError::NodeNotFound`] if the node does not exist.
+    pub fn remove_property(&mut self, path: &str, name: &str) -> Result<bool, FdtError> {
+        let mut properties = self.find_node(path)?.properties();
+        while let Some((start, end, property)) = properties.next_entry() {
+            if property.name() == name {
+                self.structs.drain(start..end);
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Add an entry to the memory reservation block.
+    pub fn add_memory_reservation(&mut self, address: u64, size: u64) {
+        self.reservations.push(MemoryReservation { address, size });
+    }
+
+    /// Set the kernel command line in `/chosen`, creating the node if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FdtError::NodeNotFound`] if the root node is missing, which
+    /// can't happen for a valid device tree.
+    pub fn set_bootargs(&mut self, bootargs: &str) -> Result<(), FdtError> {
+        self.add_node("/", "chosen")?;
+        self.set_property_str("/chosen", "bootargs", bootargs)
+    }
+
+    /// Set the physical start and end addresses of the initrd in `/chosen`,
+    /// creating the node if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FdtError::NodeNotFound`] if the root node is missing, which
+    /// can't happen for a valid device tree.
+    pub fn set_initrd(&mut self, start: u64, end: u64) -> Result<(), FdtError> {
+        self.add_node("/", "chosen")?;
+        self.set_property_u64("/chosen", "linux,initrd-start", start)?;
+        self.set_property_u64("/chosen", "linux,initrd-end", end)
+    }
+
+    /// Serialize the edited device tree. The total size includes
+    /// `extra_space` zeroed bytes at the end, which let the device tree grow
+    /// in place, for example when applying firmware fixups.
+    #[must_use]
+    pub fn build(&self, extra_space: usize) -> Vec<u8> {
+        let reservations_offset = HEADER_SIZE;
+        let structs_offset = reservations_offset + (self.reservations.len() + 1) * RESERVATION_SIZE;
+        let strings_offset = structs_offset + self.structs.len();
+        let size = strings_offset + self.strings.len();
+        let total_size = size + extra_space;
+
+        let mut bytes = Vec::with_capacity(total_size);
+        for field in [
+            MAGIC,
+            total_size as u32,
+            structs_offset as u32,
+            strings_offset as u32,
+            reservations_offset as u32,
+            VERSION,
+            LAST_COMPATIBLE_VERSION,
+            self.boot_cpuid_phys,
+            self.strings.len() as u32,
+            self.structs.len() as u32,
+        ] {
+            bytes.extend_from_slice(&field.to_be_bytes());
+        }
+        for reservation in self.reservations.iter().chain([&MemoryReservation {
+            address: 0,
+            size: 0,
+        }]) {
+            bytes.extend_from_slice(&reservation.address.to_be_bytes());
+            bytes.extend_from_slice(&reservation.size.to_be_bytes());
+        }
+        bytes.extend_from_slice(&self.structs);
+        bytes.extend_from_slice(&self.strings);
+        bytes.resize(total_size, 0);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{Builder, build_test_fdt};
+    use super::*;
+
+    #[test]
+    fn test_edit() {
+        let bytes = build_test_fdt();
+        let mut editor = FdtEditor::new(&Fdt::from_bytes(&bytes).unwrap());
+        editor.set_bootargs("console=ttyAMA0 quiet").unwrap();
+        editor.set_initrd(0x5000_0000, 0x5100_0000).unwrap();
+        editor
+            .set_property_str("/pl011@9000000", "status", "disabled")
+            .unwrap();
+        assert!(
+            editor
+                .remove_property("/memory@40000000", "device_type")
+                .unwrap()
+        );
+        assert!(
+            !editor
+                .remove_property("/memory@40000000", "missing")
+                .unwrap()
+        );
+        assert_eq!(
+            editor.set_property("/missing", "a", &[]),
+            Err(FdtError::NodeNotFound)
+        );
+        editor.add_memory_reservation(0x6000_0000, 0x2000);
+
+        let bytes = editor.build(0x100);
+        let fdt = Fdt::from_bytes(&bytes).unwrap();
+        assert_eq!(fdt.total_size(), bytes.len());
+        assert_eq!(fdt.memory_reservations().count(), 2);
+        let chosen = fdt.chosen().unwrap();
+        assert_eq!(chosen.bootargs(), Some("console=ttyAMA0 quiet"));
+        assert_eq!(chosen.initrd(), Some((0x5000_0000, 0x5100_0000)));
+        assert!(!fdt.find_node("/pl011").unwrap().is_enabled());
+        assert!(
+            fdt.memory_reservations()
+                .any(|entry| entry.address == 0x6000_0000)
+        );
+        // The memory node is still found by name.
+        assert_eq!(fdt.memory().count(), 1);
+        // Other nodes are unchanged.
+        assert_eq!(fdt.root().children().count(), 7);
+        assert_eq!(fdt.reserved_memory().count(), 2);
+    }
+
+    #[test]
+    fn test_add_chosen() {
+        let bytes = Builder::default()
+            .begin("")
+            .begin("cpus")
+            .end()
+            .end()
+            .build();
+        let mut editor = FdtEditor::new(&Fdt::from_bytes(&bytes).unwrap());
+        editor.set_bootargs("quiet").unwrap();
+        editor.set_bootargs("loglevel=7").unwrap();
+
+        let bytes = editor.build(0);
+        let fdt = Fdt::from_bytes(&bytes).unwrap();
+        let names: Vec<_> = fdt.root().children().map(|node| node.name()).collect();
+        assert_eq!(names, ["cpus", "chosen"]);
+        assert_eq!(fdt.chosen().unwrap().bootargs(), Some("loglevel=7"));
+        assert_eq!(fdt.chosen().unwrap().node().properties().count(), 1);
+        // The property name is stored once.
+        assert_eq!(editor.strings, b"bootargs\0");
+    }
+}
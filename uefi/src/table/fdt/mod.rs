@@ -0,0 +1,1293 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Reader for the flattened device tree (FDT).
+//!
+//! On Arm and RISC-V platforms, the firmware may describe the hardware with a
+//! device tree blob (DTB) that it publishes in the configuration table.
+//! [`Fdt`] validates the blob and gives zero-copy access to its [`Node`]s and
+//! [`Property`]s, along with helpers for the nodes a loader usually needs:
+//!
+//! ```no_run
+//! use uefi::table::fdt::Fdt;
+//!
+//! # fn example() -> Result<(), uefi::table::fdt::FdtError> {
+//! let fdt = Fdt::find()?;
+//! if let Some(chosen) = fdt.chosen() {
+//!     log::info!("bootargs: {:?}", chosen.bootargs());
+//! }
+//! for region in fdt.memory() {
+//!     log::info!("RAM at {:#x}, {:#x} bytes", region.address, region.size);
+//! }
+//! if let Some(uart) = fdt.find_node("/pl011@9000000") {
+//!     log::info!("UART compatible with {:?}", uart.compatible().collect::<Vec<_>>());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With the `alloc` feature, [`FdtEditor`] creates a modified copy of a
+//! device tree, for example to set the kernel command line and initrd in
+//! `/chosen`. The [`DtFixup`] protocol lets the firmware apply its own
+//! fixups to the result.
+//!
+//! See <https://devicetree-specification.readthedocs.io/en/stable/flattened-format.html>.
+//!
+//! [`FdtEditor`]: edit::FdtEditor
+//! [`DtFixup`]: crate::proto::device_tree::DtFixup
+
+#[cfg(feature = "alloc")]
+mod edit;
+
+#[cfg(feature = "alloc")]
+pub use edit::FdtEditor;
+
+use crate::table::cfg::{ConfigTable, ConfigTableEntry};
+use crate::{Guid, system};
+use core::ffi::c_void;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::iter::FusedIterator;
+use core::{slice, str};
+
+/// Magic value at the start of the header.
+const MAGIC: u32 = 0xd00d_feed;
+
+/// Size of the version 17 header.
+const HEADER_SIZE: usize = 40;
+
+/// Version of the format written by [`FdtEditor`](edit::FdtEditor).
+const VERSION: u32 = 17;
+
+/// Size of a memory reservation block entry.
+const RESERVATION_SIZE: usize = 16;
+
+const FDT_BEGIN_NODE: u32 = 1;
+const FDT_END_NODE: u32 = 2;
+const FDT_PROP: u32 = 3;
+const FDT_NOP: u32 = 4;
+const FDT_END: u32 = 9;
+
+/// Errors that may happen when reading or editing a device tree.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FdtError {
+    /// The system table has no device tree.
+    NotFound,
+    /// The header has the wrong magic value.
+    InvalidMagic,
+    /// The device tree version is not compatible with version 17.
+    UnsupportedVersion(u32),
+    /// The buffer is too small for the device tree, or a block is out of
+    /// bounds.
+    InvalidSize,
+    /// The structure block or the memory reservation block is malformed.
+    InvalidStructure,
+    /// The node to edit does not exist.
+    NodeNotFound,
+}
+
+impl Display for FdtError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "device tree not found"),
+            Self::InvalidMagic => write!(f, "invalid device tree magic"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported device tree version {version}")
+            }
+            Self::InvalidSize => write!(f, "device tree is truncated"),
+            Self::InvalidStructure => write!(f, "malformed device tree structure"),
+            Self::NodeNotFound => write!(f, "device tree node not found"),
+        }
+    }
+}
+
+impl core::error::Error for FdtError {}
+
+fn read_be32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_be_bytes(bytes.try_into().ok()?))
+}
+
+fn read_be64(bytes: &[u8], offset: usize) -> Option<u64> {
+    let bytes = bytes.get(offset..offset.checked_add(8)?)?;
+    Some(u64::from_be_bytes(bytes.try_into().ok()?))
+}
+
+const fn align4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+/// Read the null-terminated string at `offset`.
+fn read_str(bytes: &[u8], offset: usize) -> Option<&str> {
+    let bytes = bytes.get(offset..)?;
+    let len = bytes.iter().position(|byte| *byte == 0)?;
+    str::from_utf8(&bytes[..len]).ok()
+}
+
+/// Token of the structure block.
+#[derive(Clone, Copy, Debug)]
+enum Token<'a> {
+    BeginNode(&'a str),
+    EndNode,
+    Prop { name_offset: u32, value: &'a [u8] },
+    Nop,
+    End,
+}
+
+/// Read the token at `offset`, and return it with the offset of the next
+/// token.
+fn read_token(structs: &[u8], offset: usize) -> Option<(Token<'_>, usize)> {
+    let token = read_be32(structs, offset)?;
+    let offset = offset + 4;
+    match token {
+        FDT_BEGIN_NODE => {
+            let name = read_str(structs, offset)?;
+            Some((Token::BeginNode(name), align4(offset + name.len() + 1)))
+        }
+        FDT_END_NODE => Some((Token::EndNode, offset)),
+        FDT_PROP => {
+            let len = read_be32(structs, offset)? as usize;
+            let name_offset = read_be32(structs, offset + 4)?;
+            let start = offset + 8;
+            let value = structs.get(start..start.checked_add(len)?)?;
+            Some((Token::Prop { name_offset, value }, align4(start + len)))
+        }
+        FDT_NOP => Some((Token::Nop, offset)),
+        FDT_END => Some((Token::End, offset)),
+        _ => None,
+    }
+}
+
+/// Check that the structure block is a single well-formed tree whose
+/// property names are valid, and return its length up to the end token.
+fn validate_structs(structs: &[u8], strings: &[u8]) -> Result<usize, FdtError> {
+    let mut offset = 0;
+    let mut depth = 0usize;
+    let mut has_root = false;
+    loop {
+        let (token, next) = read_token(structs, offset).ok_or(FdtError::InvalidStructure)?;
+        match token {
+            Token::BeginNode(_) => {
+                if depth == 0 {
+                    if has_root {
+                        return Err(FdtError::InvalidStructure);
+                    }
+                    has_root = true;
+                }
+                depth += 1;
+            }
+            Token::EndNode => {
+                depth = depth.checked_sub(1).ok_or(FdtError::InvalidStructure)?;
+            }
+            Token::Prop { name_offset, .. } => {
+                if depth == 0 || read_str(strings, name_offset as usize).is_none() {
+                    return Err(FdtError::InvalidStructure);
+                }
+            }
+            Token::Nop => {}
+            Token::End => {
+                if depth != 0 || !has_root {
+                    return Err(FdtError::InvalidStructure);
+                }
+                return Ok(next);
+            }
+        }
+        offset = next;
+    }
+}
+
+/// Entry of the memory reservation block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MemoryReservation {
+    /// Physical address of the reserved region.
+    pub address: u64,
+    /// Size of the reserved region in bytes.
+    pub size: u64,
+}
+
+/// A flattened device tree.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone, Copy)]
+pub struct Fdt<'a> {
+    bytes: &'a [u8],
+    reservations: &'a [u8],
+    structs: &'a [u8],
+    strings: &'a [u8],
+}
+
+impl<'a> Fdt<'a> {
+    /// Locate the device tree in the system configuration table.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FdtError::NotFound`] if the firmware does not provide a
+    /// device tree, or any other error if the device tree is invalid.
+    pub fn find() -> Result<Fdt<'static>, FdtError> {
+        system::find_config_table()
+    }
+
+    /// Read the device tree at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a device tree that is valid for reads for its full
+    /// size, as given by its header, for lifetime `'a`.
+    ///
+    /// # Errors
+    ///
+    /// See [`from_bytes`](Self::from_bytes).
+    pub unsafe fn from_ptr(ptr: *const u8) -> Result<Self, FdtError> {
+        // SAFETY: the caller guarantees the header is readable.
+        let header = unsafe { slice::from_raw_parts(ptr, 8) };
+        if read_be32(header, 0) != Some(MAGIC) {
+            return Err(FdtError::InvalidMagic);
+        }
+        let size = read_be32(header, 4).unwrap() as usize;
+        // SAFETY: the caller guarantees the whole device tree is readable.
+        Self::from_bytes(unsafe { slice::from_raw_parts(ptr, size.max(HEADER_SIZE)) })
+    }
+
+    /// Parse the device tree in `bytes`. Trailing bytes are ignored.
+    ///
+    /// The whole structure block is validated, so that the accessors of the
+    /// device tree never fail on malformed data.
+    ///
+    /// # Errors
+    ///
+    /// * [`FdtError::InvalidMagic`]: `bytes` does not start with a device
+    ///   tree header.
+    /// * [`FdtError::UnsupportedVersion`]: the device tree is not compatible
+    ///   with version 17 of the format.
+    /// * [`FdtError::InvalidSize`]: `bytes` is too small, or a block is out
+    ///   of bounds.
+    /// * [`FdtError::InvalidStructure`]: a block is malformed.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, FdtError> {
+        let field = |index: usize| read_be32(bytes, index * 4).ok_or(FdtError::InvalidSize);
+        if field(0)? != MAGIC {
+            return Err(FdtError::InvalidMagic);
+        }
+        let version = field(5)?;
+        if version < VERSION || field(6)? > VERSION {
+            return Err(FdtError::UnsupportedVersion(version));
+        }
+        let total_size = field(1)? as usize;
+        let bytes = bytes.get(..total_size).ok_or(FdtError::InvalidSize)?;
+        if total_size < HEADER_SIZE {
+            return Err(FdtError::InvalidSize);
+        }
+        let block = |offset: u32, size: u32| {
+            let offset = offset as usize;
+            offset
+                .checked_add(size as usize)
+                .and_then(|end| bytes.get(offset..end))
+                .ok_or(FdtError::InvalidSize)
+        };
+        let structs = block(field(2)?, field(9)?)?;
+        let strings = block(field(3)?, field(8)?)?;
+        let structs = &structs[..validate_structs(structs, strings)?];
+
+        // The memory reservation block ends with an empty entry.
+        let reservations = bytes
+            .get(field(4)? as usize..)
+            .ok_or(FdtError::InvalidSize)?;
+        let count = reservations
+            .as_chunks::<RESERVATION_SIZE>()
+            .0
+            .iter()
+            .position(|entry| *entry == [0; RESERVATION_SIZE])
+            .ok_or(FdtError::InvalidStructure)?;
+        let reservations = &reservations[..count * RESERVATION_SIZE];
+
+        Ok(Self {
+            bytes,
+            reservations,
+            structs,
+            strings,
+        })
+    }
+
+    /// Create a view of the blocks of a device tree that is being edited.
+    /// Only the node accessors may be used on the view.
+    #[cfg(feature = "alloc")]
+    const fn view(structs: &'a [u8], strings: &'a [u8]) -> Self {
+        Self {
+            bytes: &[],
+            reservations: &[],
+            structs,
+            strings,
+        }
+    }
+
+    /// Get the bytes of the device tree, with the size given by its header.
+    #[must_use]
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Get the total size of the device tree in bytes.
+    #[must_use]
+    pub const fn total_size(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Get the version of the device tree format.
+    #[must_use]
+    pub fn version(&self) -> u32 {
+        read_be32(self.bytes, 20).unwrap()
+    }
+
+    /// Get the physical ID of the boot CPU.
+    #[must_use]
+    pub fn boot_cpuid_phys(&self) -> u32 {
+        read_be32(self.bytes, 28).unwrap()
+    }
+
+    /// Get an iterator over the entries of the memory reservation block.
+    ///
+    /// These regions must not be used by the OS. Regions described by the
+    /// `/reserved-memory` node are returned by
+    /// [`reserved_memory`](Self::reserved_memory) instead.
+    #[must_use]
+    pub fn memory_reservations(&self) -> MemoryReservations<'a> {
+        MemoryReservations {
+            chunks: self.reservations.as_chunks::<RESERVATION_SIZE>().0.iter(),
+        }
+    }
+
+    /// Get the root node.
+    #[must_use]
+    pub fn root(&self) -> Node<'a> {
+        let (token, props_offset) = read_token(self.structs, 0).unwrap();
+        let Token::BeginNode(name) = token else {
+            unreachable!("validated on creation")
+        };
+        // The root node must have `#address-cells` and `#size-cells`
+        // properties, but fall back to the defaults of child nodes.
+        Node {
+            fdt: *self,
+            props_offset,
+            name,
+            parent_cells: Cells::DEFAULT,
+        }
+    }
+
+    /// Find a node by its path, such as `/soc/serial@10000000`.
+    ///
+    /// A path component without a unit address matches a node with any unit
+    /// address, so `/memory` finds `/memory@40000000`. Paths that don't
+    /// start with `/` are resolved through the `/aliases` node.
+    #[must_use]
+    pub fn find_node(&self, path: &str) -> Option<Node<'a>> {
+        let (node, rest) = if let Some(rest) = path.strip_prefix('/') {
+            (self.root(), rest)
+        } else {
+            let (alias, rest) = path.split_once('/').unwrap_or((path, ""));
+            let target = self.find_node("/aliases")?.property(alias)?.as_str()?;
+            if !target.starts_with('/') {
+                return None;
+            }
+            (self.find_node(target)?, rest)
+        };
+        rest.split('/')
+            .filter(|component| !component.is_empty())
+            .try_fold(node, |node, component| node.child(component))
+    }
+
+    /// Find the node with `phandle`.
+    #[must_use]
+    pub fn find_phandle(&self, phandle: u32) -> Option<Node<'a>> {
+        fn find<'a>(node: Node<'a>, phandle: u32) -> Option<Node<'a>> {
+            if node.phandle() == Some(phandle) {
+                return Some(node);
+            }
+            node.children().find_map(|child| find(child, phandle))
+        }
+        find(self.root(), phandle)
+    }
+
+    /// Get the `/chosen` node, which holds parameters passed to the OS.
+    #[must_use]
+    pub fn chosen(&self) -> Option<Chosen<'a>> {
+        self.find_node("/chosen").map(Chosen)
+    }
+
+    /// Get an iterator over the physical memory regions described by the
+    /// `/memory` nodes.
+    pub fn memory(&self) -> impl Iterator<Item = RegEntry> + use<'a> {
+        self.root()
+            .children()
+            .filter(|node| node.device_type() == Some("memory") || node.base_name() == "memory")
+            .filter(Node::is_enabled)
+            .filter_map(|node| node.reg().ok().flatten())
+            .flatten()
+    }
+
+    /// Get an iterator over the children of the `/reserved-memory` node.
+    pub fn reserved_memory(&self) -> impl Iterator<Item = ReservedMemory<'a>> + use<'a> {
+        self.find_node("/reserved-memory")
+            .into_iter()
+            .flat_map(|node| node.children())
+            .filter(Node::is_enabled)
+            .map(ReservedMemory)
+    }
+}
+
+impl ConfigTable for Fdt<'static> {
+    const GUID: Guid = ConfigTableEntry::DEVICE_TREE_GUID;
+    type Error = FdtError;
+    const NOT_FOUND: FdtError = FdtError::NotFound;
+
+    unsafe fn from_entry(_guid: Guid, address: *const c_void) -> Result<Self, FdtError> {
+        // SAFETY: the firmware guarantees the device tree is valid.
+        unsafe { Self::from_ptr(address.cast()) }
+    }
+}
+
+impl Debug for Fdt<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Fdt")
+            .field("version", &self.version())
+            .field("total_size", &self.total_size())
+            .field("boot_cpuid_phys", &self.boot_cpuid_phys())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Iterator over the entries of the memory reservation block.
+#[derive(Clone, Debug)]
+pub struct MemoryReservations<'a> {
+    chunks: slice::Iter<'a, [u8; RESERVATION_SIZE]>,
+}
+
+impl Iterator for MemoryReservations<'_> {
+    type Item = MemoryReservation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.chunks.next()?;
+        Some(MemoryReservation {
+            address: read_be64(entry, 0).unwrap(),
+            size: read_be64(entry, 8).unwrap(),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl ExactSizeIterator for MemoryReservations<'_> {}
+impl FusedIterator for MemoryReservations<'_> {}
+
+/// Number of cells used to encode addresses and sizes in `reg` properties.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Cells {
+    address: u32,
+    size: u32,
+}
+
+impl Cells {
+    const DEFAULT: Self = Self {
+        address: 2,
+        size: 1,
+    };
+}
+
+/// A node of a device tree.
+#[derive(Clone, Copy)]
+pub struct Node<'a> {
+    fdt: Fdt<'a>,
+    /// Offset of the first token after the node name.
+    props_offset: usize,
+    name: &'a str,
+    /// Cells of the parent node, used to decode `reg`.
+    parent_cells: Cells,
+}
+
+impl<'a> Node<'a> {
+    /// Get the name of the node, including the unit address. The name of
+    /// the root node is empty.
+    #[must_use]
+    pub const fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Get the name of the node without the unit address.
+    #[must_use]
+    pub fn base_name(&self) -> &'a str {
+        self.name
+            .split_once('@')
+            .map_or(self.name, |(name, _)| name)
+    }
+
+    /// Get the unit address of the node, which is the part of the name after
+    /// `@`.
+    #[must_use]
+    pub fn unit_address(&self) -> Option<&'a str> {
+        self.name.split_once('@').map(|(_, address)| address)
+    }
+
+    /// Get an iterator over the properties of the node.
+    #[must_use]
+    pub const fn properties(&self) -> Properties<'a> {
+        Properties {
+            fdt: self.fdt,
+            offset: self.props_offset,
+        }
+    }
+
+    /// Find the property called `name`.
+    #[must_use]
+    pub fn property(&self, name: &str) -> Option<Property<'a>> {
+        self.properties().find(|property| property.name == name)
+    }
+
+    /// Get an iterator over the child nodes.
+    #[must_use]
+    pub fn children(&self) -> Children<'a> {
+        Children {
+            fdt: self.fdt,
+            offset: self.props_offset,
+            depth: 0,
+            cells: Cells {
+                address: self.address_cells(),
+                size: self.size_cells(),
+            },
+        }
+    }
+
+    /// Find the child called `name`. If `name` has no unit address, it
+    /// matches a child with any unit address.
+    #[must_use]
+    pub fn child(&self, name: &str) -> Option<Self> {
+        self.children()
+            .find(|child| child.name == name || (!name.contains('@') && child.base_name() == name))
+    }
+
+    /// Get the phandle of the node, which other nodes use to refer to it.
+    #[must_use]
+    pub fn phandle(&self) -> Option<u32> {
+        self.property("phandle")
+            .or_else(|| self.property("linux,phandle"))?
+            .as_u32()
+    }
+
+    /// Get an iterator over the entries of the `compatible` property, from
+    /// most to least specific.
+    #[must_use]
+    pub fn compatible(&self) -> StringList<'a> {
+        self.property("compatible")
+            .map_or(StringList { rest: &[] }, |property| property.strings())
+    }
+
+    /// Returns whether `compatible` is one of the entries of the
+    /// `compatible` property.
+    #[must_use]
+    pub fn is_compatible(&self, compatible: &str) -> bool {
+        self.compatible().any(|entry| entry == compatible)
+    }
+
+    /// Get the `device_type` property.
+    #[must_use]
+    pub fn device_type(&self) -> Option<&'a str> {
+        self.property("device_type")?.as_str()
+    }
+
+    /// Returns whether the device is enabled, which is the case if its
+    /// `status` property is missing, `okay`, or `ok`.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.property("status")
+            .is_none_or(|status| matches!(status.as_str(), Some("okay" | "ok")))
+    }
+
+    /// Get the number of cells used for addresses in the `reg` property of
+    /// child nodes.
+    #[must_use]
+    pub fn address_cells(&self) -> u32 {
+        self.property("#address-cells")
+            .and_then(|property| property.as_u32())
+            .unwrap_or(Cells::DEFAULT.address)
+    }
+
+    /// Get the number of cells used for sizes in the `reg` property of child
+    /// nodes.
+    #[must_use]
+    pub fn size_cells(&self) -> u32 {
+        self.property("#size-cells")
+            .and_then(|property| property.as_u32())
+            .unwrap_or(Cells::DEFAULT.size)
+    }
+
+    /// Get an iterator over the regions of the `reg` property, decoded with
+    /// the cell sizes of the parent node.
+    ///
+    /// Returns `None` if the node has no `reg` property. Addresses and sizes
+    /// of more than two cells are truncated to their low 64 bits.
+    ///
+    /// # Errors
+    ///
+    /// * [`FdtError::InvalidStructure`]: the parent node has no address
+    ///   cells.
+    /// * [`FdtError::InvalidSize`]: the size of the property is not a
+    ///   multiple of the size of an entry, or the cell counts overflow.
+    pub fn reg(&self) -> Result<Option<Reg<'a>>, FdtError> {
+        let Some(property) = self.property("reg") else {
+            return Ok(None);
+        };
+        let cells = self.parent_cells;
+        if cells.address == 0 {
+            return Err(FdtError::InvalidStructure);
+        }
+        let entry_len = (cells.address as usize)
+            .checked_add(cells.size as usize)
+            .and_then(|cells| cells.checked_mul(4))
+            .ok_or(FdtError::InvalidSize)?;
+        if !property.value.len().is_multiple_of(entry_len) {
+            return Err(FdtError::InvalidSize);
+        }
+        Ok(Some(Reg {
+            value: property.value,
+            address_cells: cells.address as usize,
+            size_cells: cells.size as usize,
+        }))
+    }
+
+    /// Get the offset of the end node token.
+    #[cfg(feature = "alloc")]
+    fn end_offset(&self) -> usize {
+        let mut offset = self.props_offset;
+        let mut depth = 0usize;
+        loop {
+            let (token, next) = read_token(self.fdt.structs, offset).unwrap();
+            match token {
+                Token::BeginNode(_) => depth += 1,
+                Token::EndNode if depth == 0 => return offset,
+                Token::EndNode => depth -= 1,
+                _ => {}
+            }
+            offset = next;
+        }
+    }
+}
+
+impl Debug for Node<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Node")
+            .field("name", &self.name)
+            .field("properties", &self.properties())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Iterator over the properties of a [`Node`].
+#[derive(Clone)]
+pub struct Properties<'a> {
+    fdt: Fdt<'a>,
+    offset: usize,
+}
+
+impl<'a> Properties<'a> {
+    /// Get the next property, with the offsets of its token and of the next
+    /// token.
+    fn next_entry(&mut self) -> Option<(usize, usize, Property<'a>)> {
+        loop {
+            let start = self.offset;
+            let (token, next) = read_token(self.fdt.structs, start)?;
+            match token {
+                Token::Prop { name_offset, value } => {
+                    self.offset = next;
+                    let name = read_str(self.fdt.strings, name_offset as usize)?;
+                    return Some((start, next, Property { name, value }));
+                }
+                Token::Nop => self.offset = next,
+                _ => {
+                    self.offset = usize::MAX;
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Properties<'a> {
+    type Item = Property<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_entry().map(|(_, _, property)| property)
+    }
+}
+
+impl FusedIterator for Properties<'_> {}
+
+impl Debug for Properties<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// Iterator over the children of a [`Node`].
+#[derive(Clone)]
+pub struct Children<'a> {
+    fdt: Fdt<'a>,
+    offset: usize,
+    /// Depth relative to the parent node.
+    depth: usize,
+    /// Cells of the parent node.
+    cells: Cells,
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = Node<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let offset = self.offset;
+            let Some((token, next)) = read_token(self.fdt.structs, offset) else {
+                self.offset = usize::MAX;
+                return None;
+            };
+            self.offset = next;
+            match token {
+                Token::BeginNode(name) => {
+                    self.depth += 1;
+                    if self.depth == 1 {
+                        return Some(Node {
+                            fdt: self.fdt,
+                            props_offset: next,
+                            name,
+                            parent_cells: self.cells,
+                        });
+                    }
+                }
+                Token::EndNode if self.depth == 0 => {
+                    self.offset = usize::MAX;
+                    return None;
+                }
+                Token::EndNode => self.depth -= 1,
+                Token::Prop { .. } | Token::Nop => {}
+                Token::End => {
+                    self.offset = usize::MAX;
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl FusedIterator for Children<'_> {}
+
+impl Debug for Children<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.clone().map(|node| node.name))
+            .finish()
+    }
+}
+
+/// A property of a [`Node`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Property<'a> {
+    name: &'a str,
+    value: &'a [u8],
+}
+
+impl<'a> Property<'a> {
+    /// Get the name of the property.
+    #[must_use]
+    pub const fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Get the raw value of the property.
+    #[must_use]
+    pub const fn value(&self) -> &'a [u8] {
+        self.value
+    }
+
+    /// Get the value as a single big-endian cell.
+    #[must_use]
+    pub fn as_u32(&self) -> Option<u32> {
+        (self.value.len() == 4).then(|| read_be32(self.value, 0).unwrap())
+    }
+
+    /// Get the value as one or two big-endian cells.
+    #[must_use]
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.value.len() {
+            4 => self.as_u32().map(u64::from),
+            8 => read_be64(self.value, 0),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a single null-terminated string.
+    #[must_use]
+    pub fn as_str(&self) -> Option<&'a str> {
+        let (last, value) = self.value.split_last()?;
+        if *last != 0 || value.contains(&0) {
+            return None;
+        }
+        str::from_utf8(value).ok()
+    }
+
+    /// Get an iterator over the strings of a string list value.
+    #[must_use]
+    pub const fn strings(&self) -> StringList<'a> {
+        StringList { rest: self.value }
+    }
+
+    /// Get an iterator over the big-endian cells of the value. Trailing
+    /// bytes that don't form a whole cell are ignored.
+    pub fn cells(&self) -> impl Iterator<Item = u32> + use<'a> {
+        self.value
+            .as_chunks::<4>()
+            .0
+            .iter()
+            .map(|cell| u32::from_be_bytes(*cell))
+    }
+}
+
+/// Iterator over the strings of a string list property value. Iteration
+/// stops at the first string that is not valid UTF-8.
+#[derive(Clone, Debug)]
+pub struct StringList<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for StringList<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.rest.iter().position(|byte| *byte == 0)?;
+        let (string, rest) = self.rest.split_at(len);
+        self.rest = &rest[1..];
+        let string = str::from_utf8(string).ok();
+        if string.is_none() {
+            self.rest = &[];
+        }
+        string
+    }
+}
+
+impl FusedIterator for StringList<'_> {}
+
+/// Region of a `reg` property.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RegEntry {
+    /// Address of the region, in the address space of the parent node.
+    pub address: u64,
+    /// Size of the region in bytes. Zero if the parent node has no size
+    /// cells.
+    pub size: u64,
+}
+
+/// Iterator over the regions of a `reg` property.
+#[derive(Clone, Debug)]
+pub struct Reg<'a> {
+    value: &'a [u8],
+    address_cells: usize,
+    size_cells: usize,
+}
+
+impl Reg<'_> {
+    fn read(&mut self, cells: usize) -> Option<u64> {
+        let (value, rest) = self.value.split_at_checked(cells.checked_mul(4)?)?;
+        self.value = rest;
+        Some(value.as_chunks::<4>().0.iter().fold(0, |acc, cell| {
+            (acc << 32) | u64::from(u32::from_be_bytes(*cell))
+        }))
+    }
+}
+
+impl Iterator for Reg<'_> {
+    type Item = RegEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.value.is_empty() {
+            return None;
+        }
+        let entry = self
+            .read(self.address_cells)
+            .zip(self.read(self.size_cells));
+        let Some((address, size)) = entry else {
+            // Unreachable with the sizes checked by `Node::reg`, but keep the
+            // iterator fused.
+            self.value = &[];
+            return None;
+        };
+        Some(RegEntry { address, size })
+    }
+}
+
+impl FusedIterator for Reg<'_> {}
+
+/// The `/chosen` node, which holds parameters passed to the OS.
+#[derive(Clone, Copy, Debug)]
+pub struct Chosen<'a>(Node<'a>);
+
+impl<'a> Chosen<'a> {
+    /// Get the underlying node.
+    #[must_use]
+    pub const fn node(&self) -> Node<'a> {
+        self.0
+    }
+
+    /// Get the kernel command line.
+    #[must_use]
+    pub fn bootargs(&self) -> Option<&'a str> {
+        self.0.property("bootargs")?.as_str()
+    }
+
+    /// Get the path of the console device, optionally followed by `:` and
+    /// console options.
+    #[must_use]
+    pub fn stdout_path(&self) -> Option<&'a str> {
+        self.0.property("stdout-path")?.as_str()
+    }
+
+    /// Get the physical start and end addresses of the initrd.
+    #[must_use]
+    pub fn initrd(&self) -> Option<(u64, u64)> {
+        let start = self.0.property("linux,initrd-start")?.as_u64()?;
+        let end = self.0.property("linux,initrd-end")?.as_u64()?;
+        Some((start, end))
+    }
+}
+
+/// A child of the `/reserved-memory` node.
+#[derive(Clone, Copy, Debug)]
+pub struct ReservedMemory<'a>(Node<'a>);
+
+impl<'a> ReservedMemory<'a> {
+    /// Get the underlying node.
+    #[must_use]
+    pub const fn node(&self) -> Node<'a> {
+        self.0
+    }
+
+    /// Get the statically reserved regions, or `None` if the region is
+    /// allocated dynamically by the OS.
+    ///
+    /// # Errors
+    ///
+    /// See [`Node::reg`].
+    pub fn regions(&self) -> Result<Option<Reg<'a>>, FdtError> {
+        self.0.reg()
+    }
+
+    /// Get the size of a dynamically allocated region.
+    #[must_use]
+    pub fn size(&self) -> Option<u64> {
+        self.0.property("size")?.as_u64()
+    }
+
+    /// Returns whether the OS must not create a mapping of the region.
+    #[must_use]
+    pub fn is_no_map(&self) -> bool {
+        self.0.property("no-map").is_some()
+    }
+
+    /// Returns whether the OS may use the region when the device driver
+    /// that owns it doesn't.
+    #[must_use]
+    pub fn is_reusable(&self) -> bool {
+        self.0.property("reusable").is_some()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// Builder of device trees for tests.
+    #[derive(Default)]
+    pub struct Builder {
+        structs: Vec<u8>,
+        strings: Vec<u8>,
+        reservations: Vec<(u64, u64)>,
+    }
+
+    impl Builder {
+        pub fn begin(&mut self, name: &str) -> &mut Self {
+            self.structs
+                .extend_from_slice(&FDT_BEGIN_NODE.to_be_bytes());
+            self.structs.extend_from_slice(name.as_bytes());
+            self.structs.push(0);
+            self.structs.resize(align4(self.structs.len()), 0);
+            self
+        }
+
+        pub fn end(&mut self) -> &mut Self {
+            self.structs.extend_from_slice(&FDT_END_NODE.to_be_bytes());
+            self
+        }
+
+        pub fn prop(&mut self, name: &str, value: &[u8]) -> &mut Self {
+            let name_offset = self.strings.len() as u32;
+            self.strings.extend_from_slice(name.as_bytes());
+            self.strings.push(0);
+            self.structs.extend_from_slice(&FDT_PROP.to_be_bytes());
+            self.structs
+                .extend_from_slice(&(value.len() as u32).to_be_bytes());
+            self.structs.extend_from_slice(&name_offset.to_be_bytes());
+            self.structs.extend_from_slice(value);
+            self.structs.resize(align4(self.structs.len()), 0);
+            self
+        }
+
+        pub fn prop_u32(&mut self, name: &str, value: u32) -> &mut Self {
+            self.prop(name, &value.to_be_bytes())
+        }
+
+        pub fn prop_cells(&mut self, name: &str, cells: &[u32]) -> &mut Self {
+            let value: Vec<u8> = cells.iter().flat_map(|cell| cell.to_be_bytes()).collect();
+            self.prop(name, &value)
+        }
+
+        pub fn prop_str(&mut self, name: &str, value: &str) -> &mut Self {
+            let mut bytes = Vec::from(value.as_bytes());
+            bytes.push(0);
+            self.prop(name, &bytes)
+        }
+
+        pub fn reserve(&mut self, address: u64, size: u64) -> &mut Self {
+            self.reservations.push((address, size));
+            self
+        }
+
+        pub fn build(&mut self) -> Vec<u8> {
+            let mut structs = self.structs.clone();
+            structs.extend_from_slice(&FDT_END.to_be_bytes());
+            let reservations_offset = HEADER_SIZE;
+            let structs_offset = reservations_offset + (self.reservations.len() + 1) * 16;
+            let strings_offset = structs_offset + structs.len();
+            let total_size = strings_offset + self.strings.len();
+
+            let mut bytes = Vec::new();
+            for field in [
+                MAGIC,
+                total_size as u32,
+                structs_offset as u32,
+                strings_offset as u32,
+                reservations_offset as u32,
+                VERSION,
+                16,
+                0,
+                self.strings.len() as u32,
+                structs.len() as u32,
+            ] {
+                bytes.extend_from_slice(&field.to_be_bytes());
+            }
+            for (address, size) in self.reservations.iter().chain([&(0, 0)]) {
+                bytes.extend_from_slice(&address.to_be_bytes());
+                bytes.extend_from_slice(&size.to_be_bytes());
+            }
+            bytes.extend_from_slice(&structs);
+            bytes.extend_from_slice(&self.strings);
+            bytes
+        }
+    }
+
+    /// Build a device tree similar to the one of QEMU's Arm virt machine.
+    pub fn build_test_fdt() -> Vec<u8> {
+        Builder::default()
+            .reserve(0x4800_0000, 0x1000)
+            .begin("")
+            .prop_u32("#address-cells", 2)
+            .prop_u32("#size-cells", 2)
+            .prop("compatible", b"linux,dummy-virt\0")
+            .begin("aliases")
+            .prop_str("serial0", "/pl011@9000000")
+            .end()
+            .begin("chosen")
+            .prop_str("bootargs", "console=ttyAMA0")
+            .prop_u32("linux,initrd-start", 0x4400_0000)
+            .prop_cells("linux,initrd-end", &[0, 0x4480_0000])
+            .end()
+            .begin("memory@40000000")
+            .prop_str("device_type", "memory")
+            .prop_cells("reg", &[0, 0x4000_0000, 0, 0x2000_0000])
+            .end()
+            .begin("memory@100000000")
+            .prop_str("device_type", "memory")
+            .prop_str("status", "disabled")
+            .prop_cells("reg", &[1, 0, 0, 0x1000_0000])
+            .end()
+            .begin("reserved-memory")
+            .prop_u32("#address-cells", 2)
+            .prop_u32("#size-cells", 2)
+            .prop("ranges", &[])
+            .begin("secmon@5e000000")
+            .prop("no-map", &[])
+            .prop_cells("reg", &[0, 0x5e00_0000, 0, 0x20_0000])
+            .end()
+            .begin("linux,cma")
+            .prop("compatible", b"shared-dma-pool\0")
+            .prop("reusable", &[])
+            .prop_cells("size", &[0, 0x400_0000])
+            .end()
+            .end()
+            .begin("intc@8000000")
+            .prop("compatible", b"arm,cortex-a15-gic\0arm,gic-v2\0")
+            .prop_u32("phandle", 0x8001)
+            .prop_cells("reg", &[0, 0x800_0000, 0, 0x1_0000])
+            .end()
+            .begin("pl011@9000000")
+            .prop("compatible", b"arm,pl011\0arm,primecell\0")
+            .prop_u32("interrupt-parent", 0x8001)
+            .prop_cells("reg", &[0, 0x900_0000, 0, 0x1000])
+            .end()
+            .end()
+            .build()
+    }
+
+    #[test]
+    fn test_header() {
+        let bytes = build_test_fdt();
+        let fdt = Fdt::from_bytes(&bytes).unwrap();
+        assert_eq!(fdt.version(), VERSION);
+        assert_eq!(fdt.total_size(), bytes.len());
+        // SAFETY: `bytes` is a valid device tree.
+        let fdt = unsafe { Fdt::from_ptr(bytes.as_ptr()) }.unwrap();
+        assert_eq!(fdt.as_bytes(), bytes);
+        assert_eq!(
+            fdt.memory_reservations().collect::<Vec<_>>(),
+            [MemoryReservation {
+                address: 0x4800_0000,
+                size: 0x1000
+            }]
+        );
+
+        let mut bad = bytes.clone();
+        bad[0] = 0;
+        assert_eq!(Fdt::from_bytes(&bad).unwrap_err(), FdtError::InvalidMagic);
+        let mut bad = bytes.clone();
+        bad[23] = 16;
+        assert_eq!(
+            Fdt::from_bytes(&bad).unwrap_err(),
+            FdtError::UnsupportedVersion(16)
+        );
+        assert_eq!(
+            Fdt::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(),
+            FdtError::InvalidSize
+        );
+
+        // A node that is not closed.
+        let bytes = Builder::default().begin("").begin("a").end().build();
+        assert_eq!(
+            Fdt::from_bytes(&bytes).unwrap_err(),
+            FdtError::InvalidStructure
+        );
+    }
+
+    #[test]
+    fn test_nodes() {
+        let bytes = build_test_fdt();
+        let fdt = Fdt::from_bytes(&bytes).unwrap();
+
+        let root = fdt.root();
+        assert_eq!(root.name(), "");
+        assert!(root.is_compatible("linux,dummy-virt"));
+        assert_eq!(root.children().count(), 7);
+        assert_eq!(root.properties().count(), 3);
+
+        let uart = fdt.find_node("/pl011@9000000").unwrap();
+        assert_eq!(uart.base_name(), "pl011");
+        assert_eq!(uart.unit_address(), Some("9000000"));
+        assert_eq!(
+            uart.compatible().collect::<Vec<_>>(),
+            ["arm,pl011", "arm,primecell"]
+        );
+        assert_eq!(
+            uart.reg().unwrap().unwrap().collect::<Vec<_>>(),
+            [RegEntry {
+                address: 0x900_0000,
+                size: 0x1000
+            }]
+        );
+        assert!(uart.is_enabled());
+        assert_eq!(fdt.find_node("/pl011").unwrap().name(), uart.name());
+        assert_eq!(fdt.find_node("serial0").unwrap().name(), uart.name());
+        assert!(fdt.find_node("/pl011@9000001").is_none());
+        assert!(fdt.find_node("/chosen/missing").is_none());
+
+        let phandle = uart.property("interrupt-parent").unwrap().as_u32().unwrap();
+        let intc = fdt.find_phandle(phandle).unwrap();
+        assert_eq!(intc.name(), "intc@8000000");
+        assert!(fdt.find_phandle(1).is_none());
+
+        let property = intc.property("reg").unwrap();
+        assert_eq!(property.name(), "reg");
+        assert_eq!(
+            property.cells().collect::<Vec<_>>(),
+            [0, 0x800_0000, 0, 0x1_0000]
+        );
+        assert_eq!(property.as_u32(), None);
+        assert_eq!(property.as_str(), None);
+    }
+
+    #[test]
+    fn test_chosen_and_memory() {
+        let bytes = build_test_fdt();
+        let fdt = Fdt::from_bytes(&bytes).unwrap();
+
+        let chosen = fdt.chosen().unwrap();
+        assert_eq!(chosen.bootargs(), Some("console=ttyAMA0"));
+        assert_eq!(chosen.stdout_path(), None);
+        assert_eq!(chosen.initrd(), Some((0x4400_0000, 0x4480_0000)));
+
+        // The disabled memory node is skipped.
+        assert_eq!(
+            fdt.memory().collect::<Vec<_>>(),
+            [RegEntry {
+                address: 0x4000_0000,
+                size: 0x2000_0000
+            }]
+        );
+
+        let reserved: Vec<_> = fdt.reserved_memory().collect();
+        assert_eq!(reserved.len(), 2);
+        assert!(reserved[0].is_no_map());
+        assert_eq!(
+            reserved[0].regions().unwrap().unwrap().collect::<Vec<_>>(),
+            [RegEntry {
+                address: 0x5e00_0000,
+                size: 0x20_0000
+            }]
+        );
+        assert!(reserved[1].regions().unwrap().is_none());
+        assert!(reserved[1].is_reusable());
+        assert_eq!(reserved[1].size(), Some(0x400_0000));
+    }
+
+    #[test]
+    fn test_reg_errors() {
+        let bytes = Builder::default()
+            .begin("")
+            .begin("huge")
+            .prop_u32("#address-cells", u32::MAX)
+            .prop_u32("#size-cells", u32::MAX)
+            .begin("child")
+            .prop_cells("reg", &[0, 0x1000])
+            .end()
+            .end()
+            .begin("empty")
+            .prop_u32("#address-cells", 0)
+            .begin("child")
+            .prop_cells("reg", &[0x1000])
+            .end()
+            .end()
+            .begin("truncated")
+            .prop_cells("reg", &[0, 0x1000])
+            .end()
+            .end()
+            .build();
+        let fdt = Fdt::from_bytes(&bytes).unwrap();
+        let reg = |path| fdt.find_node(path).unwrap().reg().map(|_| ());
+
+        assert_eq!(reg("/huge/child"), Err(FdtError::InvalidSize));
+        assert_eq!(reg("/empty/child"), Err(FdtError::InvalidStructure));
+        assert_eq!(reg("/truncated"), Err(FdtError::InvalidSize));
+        assert_eq!(reg("/"), Ok(()));
+    }
+}
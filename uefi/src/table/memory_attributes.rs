@@ -0,0 +1,390 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Reader for the EFI Memory Attributes Table.
+//!
+//! The firmware installs this table to describe the memory protection of the
+//! runtime services images. Each descriptor covers a part of a runtime
+//! services code or data region of the regular memory map, and its
+//! [`MemoryAttribute::RUNTIME`], [`MemoryAttribute::READ_ONLY`], and
+//! [`MemoryAttribute::EXECUTE_PROTECT`] attributes tell the OS how to map
+//! it, for example RX for code and NX for data.
+//!
+//! [`MemoryAttributesTable`] implements [`MemoryMap`], so it can be iterated
+//! and compared with the regular memory map.
+//!
+//! See <https://uefi.org/specs/UEFI/2.10/04_EFI_System_Table.html#efi-memory-attributes-table>.
+
+use crate::mem::memory_map::{
+    MemoryAttribute, MemoryDescriptor, MemoryMap, MemoryMapIter, MemoryMapKey, MemoryMapMeta,
+};
+use crate::table::cfg::{ConfigTable, ConfigTableEntry};
+use crate::{Guid, system};
+use core::ffi::c_void;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::ops::Index;
+use core::slice;
+
+pub use uefi_raw::table::configuration::MemoryAttributesTableFlags;
+
+/// Size of the table header preceding the descriptors.
+const HEADER_SIZE: usize = size_of::<uefi_raw::table::configuration::MemoryAttributesTable>();
+
+/// Errors that may happen when reading the [`MemoryAttributesTable`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MemoryAttributesTableError {
+    /// The system table has no memory attributes table.
+    NotFound,
+    /// The table is not 8-byte aligned.
+    Misaligned,
+    /// The buffer is too small for the table.
+    InvalidSize,
+    /// The table version is not supported.
+    UnsupportedVersion(u32),
+    /// The descriptor size is smaller than [`MemoryDescriptor`] or not a
+    /// multiple of eight.
+    InvalidDescriptorSize(u32),
+}
+
+impl Display for MemoryAttributesTableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "memory attributes table not found"),
+            Self::Misaligned => write!(f, "memory attributes table is misaligned"),
+            Self::InvalidSize => write!(f, "memory attributes table is truncated"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported memory attributes table version {version}")
+            }
+            Self::InvalidDescriptorSize(size) => {
+                write!(f, "invalid memory attributes descriptor size {size}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for MemoryAttributesTableError {}
+
+/// The EFI Memory Attributes Table.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Debug)]
+pub struct MemoryAttributesTable<'a> {
+    header: uefi_raw::table::configuration::MemoryAttributesTable,
+    descriptors: &'a [u8],
+}
+
+impl<'a> MemoryAttributesTable<'a> {
+    /// Locate the table in the system configuration table.
+    ///
+    /// The table is placed in runtime services data by the firmware, so it
+    /// stays valid after exiting boot services. It is identity-mapped, so
+    /// this must be called before switching to a different address space.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MemoryAttributesTableError::NotFound`] if the firmware does
+    /// not provide the table, or any other error if the table is invalid.
+    pub fn find() -> Result<MemoryAttributesTable<'static>, MemoryAttributesTableError> {
+        system::find_config_table()
+    }
+
+    /// Read the table at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a memory attributes table that is valid for
+    /// reads for its full size, as given by its header, for lifetime `'a`.
+    ///
+    /// # Errors
+    ///
+    /// See [`from_bytes`](Self::from_bytes).
+    pub unsafe fn from_ptr(ptr: *const u8) -> Result<Self, MemoryAttributesTableError> {
+        if !ptr.cast::<u64>().is_aligned() {
+            return Err(MemoryAttributesTableError::Misaligned);
+        }
+        // SAFETY: the caller guarantees the header is readable.
+        let header = unsafe { slice::from_raw_parts(ptr, HEADER_SIZE) };
+        let (count, descriptor_size) = parse_header(header)?;
+        let size = count
+            .checked_mul(descriptor_size)
+            .and_then(|size| size.checked_add(HEADER_SIZE))
+            .ok_or(MemoryAttributesTableError::InvalidSize)?;
+        // SAFETY: the caller guarantees the whole table is readable.
+        Self::from_bytes(unsafe { slice::from_raw_parts(ptr, size) })
+    }
+
+    /// Parse the table from `bytes`, which must contain the header and all
+    /// descriptors. Trailing bytes are ignored.
+    ///
+    /// # Errors
+    ///
+    /// * [`MemoryAttributesTableError::Misaligned`]: `bytes` is not 8-byte
+    ///   aligned.
+    /// * [`MemoryAttributesTableError::InvalidSize`]: `bytes` is too small.
+    /// * [`MemoryAttributesTableError::UnsupportedVersion`]: the version is
+    ///   not 1 or 2.
+    /// * [`MemoryAttributesTableError::InvalidDescriptorSize`]: the descriptor
+    ///   size is too small or misaligned.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, MemoryAttributesTableError> {
+        if !bytes.as_ptr().cast::<u64>().is_aligned() {
+            return Err(MemoryAttributesTableError::Misaligned);
+        }
+        let (count, descriptor_size) = parse_header(bytes)?;
+        let len = count
+            .checked_mul(descriptor_size)
+            .ok_or(MemoryAttributesTableError::InvalidSize)?;
+        let descriptors = bytes
+            .get(HEADER_SIZE..)
+            .and_then(|rest| rest.get(..len))
+            .ok_or(MemoryAttributesTableError::InvalidSize)?;
+
+        // SAFETY: `parse_header` checked that the header is present.
+        let header = unsafe {
+            bytes
+                .as_ptr()
+                .cast::<uefi_raw::table::configuration::MemoryAttributesTable>()
+                .read()
+        };
+        Ok(Self {
+            header,
+            descriptors,
+        })
+    }
+
+    /// Get the version of the table.
+    #[must_use]
+    pub const fn version(&self) -> u32 {
+        self.header.version
+    }
+
+    /// Get the flags of the table. These are always empty for version 1.
+    #[must_use]
+    pub const fn flags(&self) -> MemoryAttributesTableFlags {
+        self.header.flags
+    }
+
+    /// Find the descriptor containing the physical address `addr`.
+    ///
+    /// Descriptors whose end overflows the address space are ignored.
+    #[must_use]
+    pub fn find_descriptor(&self, addr: u64) -> Option<&MemoryDescriptor> {
+        self.entries().find(|desc| {
+            desc.page_count
+                .checked_mul(crate::boot::PAGE_SIZE as u64)
+                .and_then(|size| desc.phys_start.checked_add(size))
+                .is_some_and(|end| (desc.phys_start..end).contains(&addr))
+        })
+    }
+
+    /// Check whether `desc` has a W^X compliant mapping, i.e. it is either
+    /// read-only or not executable.
+    #[must_use]
+    pub const fn is_wx_compliant(desc: &MemoryDescriptor) -> bool {
+        desc.att
+            .intersects(MemoryAttribute::READ_ONLY.union(MemoryAttribute::EXECUTE_PROTECT))
+    }
+}
+
+impl ConfigTable for MemoryAttributesTable<'static> {
+    const GUID: Guid = ConfigTableEntry::MEMORY_ATTRIBUTES_GUID;
+    type Error = MemoryAttributesTableError;
+    const NOT_FOUND: MemoryAttributesTableError = MemoryAttributesTableError::NotFound;
+
+    unsafe fn from_entry(
+        _guid: Guid,
+        address: *const c_void,
+    ) -> Result<Self, MemoryAttributesTableError> {
+        // SAFETY: the firmware guarantees the table is valid and never freed.
+        unsafe { Self::from_ptr(address.cast()) }
+    }
+}
+
+/// Validate the header in `bytes`, returning the descriptor count and size.
+fn parse_header(bytes: &[u8]) -> Result<(usize, usize), MemoryAttributesTableError> {
+    let field = |index: usize| -> Result<u32, MemoryAttributesTableError> {
+        bytes
+            .get(index * 4..index * 4 + 4)
+            .map(|field| u32::from_ne_bytes(field.try_into().unwrap()))
+            .ok_or(MemoryAttributesTableError::InvalidSize)
+    };
+    let version = field(0)?;
+    let count = field(1)?;
+    let descriptor_size = field(2)?;
+    field(3)?;
+
+    if !matches!(
+        version,
+        uefi_raw::table::configuration::MemoryAttributesTable::VERSION_1
+            | uefi_raw::table::configuration::MemoryAttributesTable::VERSION_2
+    ) {
+        return Err(MemoryAttributesTableError::UnsupportedVersion(version));
+    }
+    if (descriptor_size as usize) < size_of::<MemoryDescriptor>() || descriptor_size % 8 != 0 {
+        return Err(MemoryAttributesTableError::InvalidDescriptorSize(
+            descriptor_size,
+        ));
+    }
+    Ok((count as usize, descriptor_size as usize))
+}
+
+impl MemoryMap for MemoryAttributesTable<'_> {
+    fn meta(&self) -> MemoryMapMeta {
+        MemoryMapMeta {
+            map_size: self.descriptors.len(),
+            desc_size: self.header.descriptor_size as usize,
+            map_key: MemoryMapKey::default(),
+            desc_version: MemoryDescriptor::VERSION,
+        }
+    }
+
+    fn key(&self) -> MemoryMapKey {
+        MemoryMapKey::default()
+    }
+
+    fn len(&self) -> usize {
+        self.header.number_of_entries as usize
+    }
+
+    fn buffer(&self) -> &[u8] {
+        self.descriptors
+    }
+
+    fn entries(&self) -> MemoryMapIter<'_> {
+        MemoryMapIter {
+            memory_map: self,
+            index: 0,
+        }
+    }
+}
+
+impl Index<usize> for MemoryAttributesTable<'_> {
+    type Output = MemoryDescriptor;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boot::MemoryType;
+    use alloc::vec::Vec;
+
+    /// Build a table with the given header fields and descriptors, stored in
+    /// `u64`s for alignment.
+    fn build(version: u32, descriptor_size: u32, descriptors: &[MemoryDescriptor]) -> Vec<u64> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&version.to_ne_bytes());
+        bytes.extend_from_slice(&(descriptors.len() as u32).to_ne_bytes());
+        bytes.extend_from_slice(&descriptor_size.to_ne_bytes());
+        bytes.extend_from_slice(&1u32.to_ne_bytes());
+        for desc in descriptors {
+            let start = bytes.len();
+            bytes.extend_from_slice(&desc.ty.0.to_ne_bytes());
+            bytes.extend_from_slice(&0u32.to_ne_bytes());
+            bytes.extend_from_slice(&desc.phys_start.to_ne_bytes());
+            bytes.extend_from_slice(&desc.virt_start.to_ne_bytes());
+            bytes.extend_from_slice(&desc.page_count.to_ne_bytes());
+            bytes.extend_from_slice(&desc.att.bits().to_ne_bytes());
+            bytes.resize(start + descriptor_size as usize, 0);
+        }
+        bytes
+            .chunks(8)
+            .map(|chunk| {
+                let mut word = [0; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u64::from_ne_bytes(word)
+            })
+            .collect()
+    }
+
+    fn as_bytes(words: &[u64]) -> &[u8] {
+        unsafe { slice::from_raw_parts(words.as_ptr().cast(), words.len() * 8) }
+    }
+
+    const fn desc(ty: MemoryType, phys_start: u64, att: MemoryAttribute) -> MemoryDescriptor {
+        MemoryDescriptor {
+            ty,
+            phys_start,
+            virt_start: 0,
+            page_count: 2,
+            att,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let descriptors = [
+            desc(
+                MemoryType::RUNTIME_SERVICES_CODE,
+                0x1000,
+                MemoryAttribute::RUNTIME.union(MemoryAttribute::READ_ONLY),
+            ),
+            desc(
+                MemoryType::RUNTIME_SERVICES_DATA,
+                0x3000,
+                MemoryAttribute::RUNTIME.union(MemoryAttribute::EXECUTE_PROTECT),
+            ),
+        ];
+        let words = build(2, 48, &descriptors);
+        let table = MemoryAttributesTable::from_bytes(as_bytes(&words)).unwrap();
+
+        assert_eq!(table.version(), 2);
+        assert_eq!(
+            table.flags(),
+            MemoryAttributesTableFlags::RT_FORWARD_CONTROL_FLOW_GUARD
+        );
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.meta().desc_size, 48);
+        assert_eq!(table.entries().copied().collect::<Vec<_>>(), descriptors);
+        assert!(table.entries().all(MemoryAttributesTable::is_wx_compliant));
+        assert_eq!(table.find_descriptor(0x4fff), Some(&descriptors[1]));
+        assert_eq!(table.find_descriptor(0x5000), None);
+    }
+
+    #[test]
+    fn test_find_descriptor_overflow() {
+        let descriptors = [MemoryDescriptor {
+            page_count: u64::MAX,
+            ..desc(
+                MemoryType::RUNTIME_SERVICES_DATA,
+                0x1000,
+                MemoryAttribute::RUNTIME,
+            )
+        }];
+        let words = build(2, 48, &descriptors);
+        let table = MemoryAttributesTable::from_bytes(as_bytes(&words)).unwrap();
+        assert_eq!(table.find_descriptor(0x1000), None);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let descriptors = [desc(
+            MemoryType::RUNTIME_SERVICES_CODE,
+            0x1000,
+            MemoryAttribute::RUNTIME,
+        )];
+
+        let words = build(3, 48, &descriptors);
+        assert_eq!(
+            MemoryAttributesTable::from_bytes(as_bytes(&words)).unwrap_err(),
+            MemoryAttributesTableError::UnsupportedVersion(3)
+        );
+
+        let words = build(1, 36, &descriptors);
+        assert_eq!(
+            MemoryAttributesTable::from_bytes(as_bytes(&words)).unwrap_err(),
+            MemoryAttributesTableError::InvalidDescriptorSize(36)
+        );
+
+        let words = build(1, 48, &descriptors);
+        assert_eq!(
+            MemoryAttributesTable::from_bytes(&as_bytes(&words)[..40]).unwrap_err(),
+            MemoryAttributesTableError::InvalidSize
+        );
+        assert_eq!(
+            MemoryAttributesTable::from_bytes(&as_bytes(&words)[4..]).unwrap_err(),
+            MemoryAttributesTableError::Misaligned
+        );
+    }
+}
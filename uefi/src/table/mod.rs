@@ -2,7 +2,13 @@
 
 //! Standard UEFI tables.
 
+pub mod acpi;
 pub mod cfg;
+pub mod debug_image_info;
+pub mod esrt;
+pub mod fdt;
+pub mod memory_attributes;
+pub mod smbios;
 
 mod header;
 
@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Baseboard information (type 2).
+
+use super::{SmbiosStructure, Structure, StructureType, check_structure};
+use crate::CStr8;
+use bitflags::bitflags;
+
+/// Length of the structure without optional fields.
+const MIN_LEN: usize = 0x08;
+
+/// Offset of the first contained object handle.
+const HANDLES_OFFSET: usize = 0x0f;
+
+bitflags! {
+    /// Features of a baseboard.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct BaseboardFeatures: u8 {
+        /// The board is a hosting board, such as a motherboard.
+        const HOSTING = 1 << 0;
+        /// The board requires at least one daughter board or auxiliary card.
+        const REQUIRES_DAUGHTER_BOARD = 1 << 1;
+        /// The board is removable.
+        const REMOVABLE = 1 << 2;
+        /// The board is replaceable.
+        const REPLACEABLE = 1 << 3;
+        /// The board is hot swappable.
+        const HOT_SWAPPABLE = 1 << 4;
+    }
+}
+
+newtype_enum! {
+    /// Type of a baseboard.
+    pub enum BoardType: u8 => {
+        /// Unknown.
+        UNKNOWN = 0x01,
+        /// Other.
+        OTHER = 0x02,
+        /// Server blade.
+        SERVER_BLADE = 0x03,
+        /// Connectivity switch.
+        CONNECTIVITY_SWITCH = 0x04,
+        /// System management module.
+        SYSTEM_MANAGEMENT_MODULE = 0x05,
+        /// Processor module.
+        PROCESSOR_MODULE = 0x06,
+        /// I/O module.
+        IO_MODULE = 0x07,
+        /// Memory module.
+        MEMORY_MODULE = 0x08,
+        /// Daughter board.
+        DAUGHTER_BOARD = 0x09,
+        /// Motherboard, including processor, memory, and I/O.
+        MOTHERBOARD = 0x0a,
+        /// Processor and memory module.
+        PROCESSOR_MEMORY_MODULE = 0x0b,
+        /// Processor and I/O module.
+        PROCESSOR_IO_MODULE = 0x0c,
+        /// Interconnect board.
+        INTERCONNECT_BOARD = 0x0d,
+    }
+}
+
+/// Baseboard (or module) information (type 2).
+#[derive(Clone, Copy, Debug)]
+pub struct BaseboardInformation<'a> {
+    structure: Structure<'a>,
+}
+
+impl<'a> SmbiosStructure<'a> for BaseboardInformation<'a> {
+    const TYPE: StructureType = StructureType::BASEBOARD;
+
+    fn from_structure(structure: Structure<'a>) -> Option<Self> {
+        check_structure(&structure, Self::TYPE, MIN_LEN).then_some(Self { structure })
+    }
+}
+
+impl<'a> BaseboardInformation<'a> {
+    /// Get the underlying structure.
+    #[must_use]
+    pub const fn structure(&self) -> Structure<'a> {
+        self.structure
+    }
+
+    /// Get the name of the board manufacturer.
+    #[must_use]
+    pub fn manufacturer(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x04)
+    }
+
+    /// Get the product name.
+    #[must_use]
+    pub fn product(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x05)
+    }
+
+    /// Get the board version.
+    #[must_use]
+    pub fn version(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x06)
+    }
+
+    /// Get the serial number.
+    #[must_use]
+    pub fn serial_number(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x07)
+    }
+
+    /// Get the asset tag.
+    #[must_use]
+    pub fn asset_tag(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x08)
+    }
+
+    /// Get the features of the board.
+    #[must_use]
+    pub fn features(&self) -> Option<BaseboardFeatures> {
+        self.structure
+            .byte(0x09)
+            .map(BaseboardFeatures::from_bits_retain)
+    }
+
+    /// Get the location of the board within the chassis.
+    #[must_use]
+    pub fn location_in_chassis(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x0a)
+    }
+
+    /// Get the handle of the chassis structure the board is in.
+    #[must_use]
+    pub fn chassis_handle(&self) -> Option<u16> {
+        self.structure.word(0x0b)
+    }
+
+    /// Get the type of the board.
+    #[must_use]
+    pub fn board_type(&self) -> Option<BoardType> {
+        self.structure.byte(0x0d).map(BoardType)
+    }
+
+    /// Get an iterator over the handles of the structures contained in the
+    /// board, such as processors or memory devices.
+    pub fn contained_object_handles(&self) -> impl Iterator<Item = u16> + use<'a> {
+        let count = usize::from(self.structure.byte(0x0e).unwrap_or(0));
+        let handles = self
+            .structure
+            .formatted()
+            .get(HANDLES_OFFSET..)
+            .unwrap_or_default();
+        let handles = handles.get(..count * 2).unwrap_or_default();
+        handles
+            .as_chunks::<2>()
+            .0
+            .iter()
+            .map(|handle| u16::from_le_bytes(*handle))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{build_structure, parse_structure};
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_baseboard() {
+        let mut data = Vec::from([1, 2, 3, 0, 0, 0x09, 0]);
+        data.extend_from_slice(&0x0003u16.to_le_bytes());
+        data.extend_from_slice(&[0x0a, 2]);
+        data.extend_from_slice(&0x0400u16.to_le_bytes());
+        data.extend_from_slice(&0x1100u16.to_le_bytes());
+        let bytes = build_structure(
+            StructureType::BASEBOARD,
+            2,
+            &data,
+            &["Vendor", "Board", "1.0"],
+        );
+
+        let board = BaseboardInformation::from_structure(parse_structure(&bytes)).unwrap();
+        assert_eq!(board.manufacturer().unwrap().to_string(), "Vendor");
+        assert_eq!(board.product().unwrap().to_string(), "Board");
+        assert_eq!(board.version().unwrap().to_string(), "1.0");
+        assert!(board.serial_number().is_none());
+        assert_eq!(
+            board.features(),
+            Some(BaseboardFeatures::HOSTING | BaseboardFeatures::REPLACEABLE)
+        );
+        assert_eq!(board.chassis_handle(), Some(3));
+        assert_eq!(board.board_type(), Some(BoardType::MOTHERBOARD));
+        assert_eq!(
+            board.contained_object_handles().collect::<Vec<_>>(),
+            [0x0400, 0x1100]
+        );
+
+        // The handle count is larger than the structure.
+        let bytes = build_structure(
+            StructureType::BASEBOARD,
+            2,
+            &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4],
+            &[],
+        );
+        let board = BaseboardInformation::from_structure(parse_structure(&bytes)).unwrap();
+        assert_eq!(board.contained_object_handles().count(), 0);
+    }
+}
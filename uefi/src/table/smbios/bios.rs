@@ -0,0 +1,244 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! BIOS information (type 0).
+
+use super::{SmbiosStructure, Structure, StructureType, check_structure};
+use crate::CStr8;
+use bitflags::bitflags;
+
+/// Length of the SMBIOS 2.0 structure.
+const MIN_LEN: usize = 0x12;
+
+bitflags! {
+    /// Features supported by the BIOS.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct BiosCharacteristics: u64 {
+        /// The BIOS characteristics are not supported.
+        const NOT_SUPPORTED = 1 << 3;
+        /// ISA is supported.
+        const ISA = 1 << 4;
+        /// MCA is supported.
+        const MCA = 1 << 5;
+        /// EISA is supported.
+        const EISA = 1 << 6;
+        /// PCI is supported.
+        const PCI = 1 << 7;
+        /// PC card (PCMCIA) is supported.
+        const PC_CARD = 1 << 8;
+        /// Plug and Play is supported.
+        const PLUG_AND_PLAY = 1 << 9;
+        /// APM is supported.
+        const APM = 1 << 10;
+        /// The BIOS is upgradeable (flash).
+        const UPGRADEABLE = 1 << 11;
+        /// BIOS shadowing is allowed.
+        const SHADOWING = 1 << 12;
+        /// VL-VESA is supported.
+        const VL_VESA = 1 << 13;
+        /// ESCD is supported.
+        const ESCD = 1 << 14;
+        /// Boot from CD is supported.
+        const BOOT_FROM_CD = 1 << 15;
+        /// Selectable boot is supported.
+        const SELECTABLE_BOOT = 1 << 16;
+        /// The BIOS ROM is socketed.
+        const ROM_SOCKETED = 1 << 17;
+        /// Boot from PC card (PCMCIA) is supported.
+        const BOOT_FROM_PC_CARD = 1 << 18;
+        /// The Enhanced Disk Drive specification is supported.
+        const EDD = 1 << 19;
+    }
+}
+
+bitflags! {
+    /// Extended features supported by the BIOS.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct BiosCharacteristicsExt: u16 {
+        /// ACPI is supported.
+        const ACPI = 1 << 0;
+        /// USB legacy is supported.
+        const USB_LEGACY = 1 << 1;
+        /// AGP is supported.
+        const AGP = 1 << 2;
+        /// I2O boot is supported.
+        const I2O_BOOT = 1 << 3;
+        /// LS-120 SuperDisk boot is supported.
+        const LS120_BOOT = 1 << 4;
+        /// ATAPI ZIP drive boot is supported.
+        const ATAPI_ZIP_BOOT = 1 << 5;
+        /// IEEE 1394 boot is supported.
+        const IEEE1394_BOOT = 1 << 6;
+        /// Smart battery is supported.
+        const SMART_BATTERY = 1 << 7;
+        /// The BIOS Boot Specification is supported.
+        const BIOS_BOOT_SPECIFICATION = 1 << 8;
+        /// Function key-initiated network service boot is supported.
+        const NETWORK_BOOT = 1 << 9;
+        /// Targeted content distribution is enabled.
+        const TARGETED_CONTENT_DISTRIBUTION = 1 << 10;
+        /// UEFI is supported.
+        const UEFI = 1 << 11;
+        /// The system is a virtual machine.
+        const VIRTUAL_MACHINE = 1 << 12;
+        /// Manufacturing mode is supported.
+        const MANUFACTURING_MODE_SUPPORTED = 1 << 13;
+        /// Manufacturing mode is enabled.
+        const MANUFACTURING_MODE_ENABLED = 1 << 14;
+    }
+}
+
+/// BIOS information (type 0).
+#[derive(Clone, Copy, Debug)]
+pub struct BiosInformation<'a> {
+    structure: Structure<'a>,
+}
+
+impl<'a> SmbiosStructure<'a> for BiosInformation<'a> {
+    const TYPE: StructureType = StructureType::BIOS;
+
+    fn from_structure(structure: Structure<'a>) -> Option<Self> {
+        check_structure(&structure, Self::TYPE, MIN_LEN).then_some(Self { structure })
+    }
+}
+
+impl<'a> BiosInformation<'a> {
+    /// Get the underlying structure.
+    #[must_use]
+    pub const fn structure(&self) -> Structure<'a> {
+        self.structure
+    }
+
+    /// Get the name of the BIOS vendor.
+    #[must_use]
+    pub fn vendor(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x04)
+    }
+
+    /// Get the BIOS version.
+    #[must_use]
+    pub fn version(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x05)
+    }
+
+    /// Get the segment of the BIOS start address in legacy memory, or zero
+    /// on UEFI systems.
+    #[must_use]
+    pub fn starting_segment(&self) -> u16 {
+        self.structure.word(0x06).unwrap()
+    }
+
+    /// Get the BIOS release date, usually in the `mm/dd/yyyy` format.
+    #[must_use]
+    pub fn release_date(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x08)
+    }
+
+    /// Get the size of the physical device containing the BIOS in bytes, or
+    /// `None` if the size is given in a reserved unit.
+    #[must_use]
+    pub fn rom_size(&self) -> Option<u64> {
+        let size = self.structure.byte(0x09).unwrap();
+        if size != 0xff {
+            return Some((u64::from(size) + 1) << 16);
+        }
+        // Sizes of 16 MiB and more are given in the extended field.
+        let extended = self.structure.word(0x18)?;
+        let size = u64::from(extended & 0x3fff);
+        match extended >> 14 {
+            0 => Some(size << 20),
+            1 => Some(size << 30),
+            _ => None,
+        }
+    }
+
+    /// Get the features supported by the BIOS.
+    #[must_use]
+    pub fn characteristics(&self) -> BiosCharacteristics {
+        BiosCharacteristics::from_bits_retain(self.structure.qword(0x0a).unwrap())
+    }
+
+    /// Get the extended features supported by the BIOS (SMBIOS 2.4+).
+    #[must_use]
+    pub fn characteristics_ext(&self) -> Option<BiosCharacteristicsExt> {
+        self.structure
+            .word(0x12)
+            .map(BiosCharacteristicsExt::from_bits_retain)
+    }
+
+    /// Get the major and minor release of the BIOS (SMBIOS 2.4+).
+    #[must_use]
+    pub fn bios_release(&self) -> Option<(u8, u8)> {
+        self.release(0x14)
+    }
+
+    /// Get the major and minor release of the embedded controller firmware
+    /// (SMBIOS 2.4+).
+    #[must_use]
+    pub fn embedded_controller_release(&self) -> Option<(u8, u8)> {
+        self.release(0x16)
+    }
+
+    fn release(&self, offset: usize) -> Option<(u8, u8)> {
+        let major = self.structure.byte(offset)?;
+        let minor = self.structure.byte(offset + 1)?;
+        (major != 0xff).then_some((major, minor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::DecodedStructure;
+    use super::super::tests::{build_structure, parse_structure};
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_bios() {
+        let mut data = Vec::from([1, 2]);
+        data.extend_from_slice(&0xe800u16.to_le_bytes());
+        data.extend_from_slice(&[3, 0xff]);
+        data.extend_from_slice(&0x0000_0000_0001_0880u64.to_le_bytes());
+        data.extend_from_slice(&[0x01, 0x18, 1, 2, 0xff, 0xff]);
+        data.extend_from_slice(&0x4020u16.to_le_bytes());
+        let bytes = build_structure(
+            StructureType::BIOS,
+            0,
+            &data,
+            &["EDK II", "1.0", "01/02/2024"],
+        );
+
+        let DecodedStructure::Bios(bios) = parse_structure(&bytes).decode() else {
+            panic!("not decoded as BIOS information");
+        };
+        assert_eq!(bios.vendor().unwrap().to_string(), "EDK II");
+        assert_eq!(bios.version().unwrap().to_string(), "1.0");
+        assert_eq!(bios.release_date().unwrap().to_string(), "01/02/2024");
+        assert_eq!(bios.starting_segment(), 0xe800);
+        assert_eq!(bios.rom_size(), Some(32 << 30));
+        assert_eq!(
+            bios.characteristics(),
+            BiosCharacteristics::PCI
+                | BiosCharacteristics::UPGRADEABLE
+                | BiosCharacteristics::SELECTABLE_BOOT
+        );
+        assert_eq!(
+            bios.characteristics_ext(),
+            Some(
+                BiosCharacteristicsExt::ACPI
+                    | BiosCharacteristicsExt::UEFI
+                    | BiosCharacteristicsExt::VIRTUAL_MACHINE
+            )
+        );
+        assert_eq!(bios.bios_release(), Some((1, 2)));
+        assert_eq!(bios.embedded_controller_release(), None);
+    }
+
+    #[test]
+    fn test_bios_too_small() {
+        let bytes = build_structure(StructureType::BIOS, 0, &[1, 2], &[]);
+        assert!(BiosInformation::from_structure(parse_structure(&bytes)).is_none());
+    }
+}
@@ -0,0 +1,275 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! System enclosure or chassis (type 3).
+
+use super::{SmbiosStructure, Structure, StructureType, check_structure};
+use crate::CStr8;
+
+/// Length of the SMBIOS 2.0 structure.
+const MIN_LEN: usize = 0x09;
+
+newtype_enum! {
+    /// Type of a chassis.
+    pub enum ChassisType: u8 => {
+        /// Other.
+        OTHER = 0x01,
+        /// Unknown.
+        UNKNOWN = 0x02,
+        /// Desktop.
+        DESKTOP = 0x03,
+        /// Low profile desktop.
+        LOW_PROFILE_DESKTOP = 0x04,
+        /// Pizza box.
+        PIZZA_BOX = 0x05,
+        /// Mini tower.
+        MINI_TOWER = 0x06,
+        /// Tower.
+        TOWER = 0x07,
+        /// Portable.
+        PORTABLE = 0x08,
+        /// Laptop.
+        LAPTOP = 0x09,
+        /// Notebook.
+        NOTEBOOK = 0x0a,
+        /// Hand held.
+        HAND_HELD = 0x0b,
+        /// Docking station.
+        DOCKING_STATION = 0x0c,
+        /// All in one.
+        ALL_IN_ONE = 0x0d,
+        /// Sub notebook.
+        SUB_NOTEBOOK = 0x0e,
+        /// Space-saving.
+        SPACE_SAVING = 0x0f,
+        /// Lunch box.
+        LUNCH_BOX = 0x10,
+        /// Main server chassis.
+        MAIN_SERVER_CHASSIS = 0x11,
+        /// Expansion chassis.
+        EXPANSION_CHASSIS = 0x12,
+        /// Sub chassis.
+        SUB_CHASSIS = 0x13,
+        /// Bus expansion chassis.
+        BUS_EXPANSION_CHASSIS = 0x14,
+        /// Peripheral chassis.
+        PERIPHERAL_CHASSIS = 0x15,
+        /// RAID chassis.
+        RAID_CHASSIS = 0x16,
+        /// Rack mount chassis.
+        RACK_MOUNT_CHASSIS = 0x17,
+        /// Sealed-case PC.
+        SEALED_CASE_PC = 0x18,
+        /// Multi-system chassis.
+        MULTI_SYSTEM_CHASSIS = 0x19,
+        /// Compact PCI.
+        COMPACT_PCI = 0x1a,
+        /// Advanced TCA.
+        ADVANCED_TCA = 0x1b,
+        /// Blade.
+        BLADE = 0x1c,
+        /// Blade enclosure.
+        BLADE_ENCLOSURE = 0x1d,
+        /// Tablet.
+        TABLET = 0x1e,
+        /// Convertible.
+        CONVERTIBLE = 0x1f,
+        /// Detachable.
+        DETACHABLE = 0x20,
+        /// IoT gateway.
+        IOT_GATEWAY = 0x21,
+        /// Embedded PC.
+        EMBEDDED_PC = 0x22,
+        /// Mini PC.
+        MINI_PC = 0x23,
+        /// Stick PC.
+        STICK_PC = 0x24,
+    }
+}
+
+newtype_enum! {
+    /// State of a chassis or of its power supply or thermal system.
+    pub enum ChassisState: u8 => {
+        /// Other.
+        OTHER = 0x01,
+        /// Unknown.
+        UNKNOWN = 0x02,
+        /// Safe.
+        SAFE = 0x03,
+        /// Warning.
+        WARNING = 0x04,
+        /// Critical.
+        CRITICAL = 0x05,
+        /// Non-recoverable.
+        NON_RECOVERABLE = 0x06,
+    }
+}
+
+newtype_enum! {
+    /// Physical security status of a chassis.
+    pub enum ChassisSecurityStatus: u8 => {
+        /// Other.
+        OTHER = 0x01,
+        /// Unknown.
+        UNKNOWN = 0x02,
+        /// None.
+        NONE = 0x03,
+        /// External interfaces are locked out.
+        EXTERNAL_INTERFACE_LOCKED_OUT = 0x04,
+        /// External interfaces are enabled.
+        EXTERNAL_INTERFACE_ENABLED = 0x05,
+    }
+}
+
+/// System enclosure or chassis (type 3).
+#[derive(Clone, Copy, Debug)]
+pub struct ChassisInformation<'a> {
+    structure: Structure<'a>,
+}
+
+impl<'a> SmbiosStructure<'a> for ChassisInformation<'a> {
+    const TYPE: StructureType = StructureType::CHASSIS;
+
+    fn from_structure(structure: Structure<'a>) -> Option<Self> {
+        check_structure(&structure, Self::TYPE, MIN_LEN).then_some(Self { structure })
+    }
+}
+
+impl<'a> ChassisInformation<'a> {
+    /// Get the underlying structure.
+    #[must_use]
+    pub const fn structure(&self) -> Structure<'a> {
+        self.structure
+    }
+
+    /// Get the name of the chassis manufacturer.
+    #[must_use]
+    pub fn manufacturer(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x04)
+    }
+
+    /// Get the type of the chassis.
+    #[must_use]
+    pub fn chassis_type(&self) -> ChassisType {
+        ChassisType(self.structure.byte(0x05).unwrap() & 0x7f)
+    }
+
+    /// Returns whether the chassis has a lock.
+    #[must_use]
+    pub fn has_lock(&self) -> bool {
+        self.structure.byte(0x05).unwrap() & 0x80 != 0
+    }
+
+    /// Get the chassis version.
+    #[must_use]
+    pub fn version(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x06)
+    }
+
+    /// Get the serial number.
+    #[must_use]
+    pub fn serial_number(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x07)
+    }
+
+    /// Get the asset tag.
+    #[must_use]
+    pub fn asset_tag(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x08)
+    }
+
+    /// Get the state of the chassis when it was last booted (SMBIOS 2.1+).
+    #[must_use]
+    pub fn boot_up_state(&self) -> Option<ChassisState> {
+        self.structure.byte(0x09).map(ChassisState)
+    }
+
+    /// Get the state of the power supply when the chassis was last booted
+    /// (SMBIOS 2.1+).
+    #[must_use]
+    pub fn power_supply_state(&self) -> Option<ChassisState> {
+        self.structure.byte(0x0a).map(ChassisState)
+    }
+
+    /// Get the thermal state of the chassis when it was last booted
+    /// (SMBIOS 2.1+).
+    #[must_use]
+    pub fn thermal_state(&self) -> Option<ChassisState> {
+        self.structure.byte(0x0b).map(ChassisState)
+    }
+
+    /// Get the physical security status of the chassis when it was last
+    /// booted (SMBIOS 2.1+).
+    #[must_use]
+    pub fn security_status(&self) -> Option<ChassisSecurityStatus> {
+        self.structure.byte(0x0c).map(ChassisSecurityStatus)
+    }
+
+    /// Get the OEM-defined information (SMBIOS 2.3+).
+    #[must_use]
+    pub fn oem_defined(&self) -> Option<u32> {
+        self.structure.dword(0x0d)
+    }
+
+    /// Get the height of the chassis in rack units, or `None` if
+    /// unspecified (SMBIOS 2.3+).
+    #[must_use]
+    pub fn height(&self) -> Option<u8> {
+        self.structure.byte(0x11).filter(|&height| height != 0)
+    }
+
+    /// Get the number of power cords of the chassis, or `None` if
+    /// unspecified (SMBIOS 2.3+).
+    #[must_use]
+    pub fn power_cord_count(&self) -> Option<u8> {
+        self.structure.byte(0x12).filter(|&count| count != 0)
+    }
+
+    /// Get the SKU number of the chassis (SMBIOS 2.7+).
+    #[must_use]
+    pub fn sku_number(&self) -> Option<&'a CStr8> {
+        // The SKU number follows the variable-size list of contained
+        // elements.
+        let count = usize::from(self.structure.byte(0x13)?);
+        let record_len = usize::from(self.structure.byte(0x14)?);
+        self.structure.string_at(0x15 + count * record_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{build_structure, parse_structure};
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_chassis() {
+        let mut data = Vec::from([1, 0x83, 0, 2, 0, 3, 3, 4, 3]);
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&[2, 1, 2, 3]);
+        data.extend_from_slice(&[0; 6]);
+        data.push(3);
+        let bytes = build_structure(StructureType::CHASSIS, 3, &data, &["Vendor", "S/N", "SKU"]);
+
+        let chassis = ChassisInformation::from_structure(parse_structure(&bytes)).unwrap();
+        assert_eq!(chassis.manufacturer().unwrap().to_string(), "Vendor");
+        assert_eq!(chassis.chassis_type(), ChassisType::DESKTOP);
+        assert!(chassis.has_lock());
+        assert!(chassis.version().is_none());
+        assert_eq!(chassis.serial_number().unwrap().to_string(), "S/N");
+        assert_eq!(chassis.boot_up_state(), Some(ChassisState::SAFE));
+        assert_eq!(chassis.thermal_state(), Some(ChassisState::WARNING));
+        assert_eq!(chassis.security_status(), Some(ChassisSecurityStatus::NONE));
+        assert_eq!(chassis.height(), Some(2));
+        assert_eq!(chassis.power_cord_count(), Some(1));
+        assert_eq!(chassis.sku_number().unwrap().to_string(), "SKU");
+
+        // SMBIOS 2.0 structures only have the basic fields.
+        let bytes = build_structure(StructureType::CHASSIS, 3, &[0, 0x17, 0, 0, 0], &[]);
+        let chassis = ChassisInformation::from_structure(parse_structure(&bytes)).unwrap();
+        assert_eq!(chassis.chassis_type(), ChassisType::RACK_MOUNT_CHASSIS);
+        assert!(!chassis.has_lock());
+        assert_eq!(chassis.boot_up_state(), None);
+        assert!(chassis.sku_number().is_none());
+    }
+}
@@ -0,0 +1,416 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Memory device (type 17).
+
+use super::{SmbiosStructure, Structure, StructureType, check_structure};
+use crate::CStr8;
+use bitflags::bitflags;
+
+/// Length of the SMBIOS 2.1 structure.
+const MIN_LEN: usize = 0x15;
+
+newtype_enum! {
+    /// Form factor of a memory device.
+    pub enum MemoryFormFactor: u8 => {
+        /// Other.
+        OTHER = 0x01,
+        /// Unknown.
+        UNKNOWN = 0x02,
+        /// SIMM.
+        SIMM = 0x03,
+        /// SIP.
+        SIP = 0x04,
+        /// Chip.
+        CHIP = 0x05,
+        /// DIP.
+        DIP = 0x06,
+        /// ZIP.
+        ZIP = 0x07,
+        /// Proprietary card.
+        PROPRIETARY_CARD = 0x08,
+        /// DIMM.
+        DIMM = 0x09,
+        /// TSOP.
+        TSOP = 0x0a,
+        /// Row of chips.
+        ROW_OF_CHIPS = 0x0b,
+        /// RIMM.
+        RIMM = 0x0c,
+        /// SODIMM.
+        SODIMM = 0x0d,
+        /// SRIMM.
+        SRIMM = 0x0e,
+        /// FB-DIMM.
+        FB_DIMM = 0x0f,
+        /// Die.
+        DIE = 0x10,
+        /// CAMM.
+        CAMM = 0x11,
+    }
+}
+
+newtype_enum! {
+    /// Type of a memory device.
+    pub enum MemoryDeviceType: u8 => {
+        /// Other.
+        OTHER = 0x01,
+        /// Unknown.
+        UNKNOWN = 0x02,
+        /// DRAM.
+        DRAM = 0x03,
+        /// EDRAM.
+        EDRAM = 0x04,
+        /// VRAM.
+        VRAM = 0x05,
+        /// SRAM.
+        SRAM = 0x06,
+        /// RAM.
+        RAM = 0x07,
+        /// ROM.
+        ROM = 0x08,
+        /// Flash.
+        FLASH = 0x09,
+        /// EEPROM.
+        EEPROM = 0x0a,
+        /// FEPROM.
+        FEPROM = 0x0b,
+        /// EPROM.
+        EPROM = 0x0c,
+        /// CDRAM.
+        CDRAM = 0x0d,
+        /// 3DRAM.
+        THREE_D_RAM = 0x0e,
+        /// SDRAM.
+        SDRAM = 0x0f,
+        /// SGRAM.
+        SGRAM = 0x10,
+        /// RDRAM.
+        RDRAM = 0x11,
+        /// DDR.
+        DDR = 0x12,
+        /// DDR2.
+        DDR2 = 0x13,
+        /// DDR2 FB-DIMM.
+        DDR2_FB_DIMM = 0x14,
+        /// DDR3.
+        DDR3 = 0x18,
+        /// FBD2.
+        FBD2 = 0x19,
+        /// DDR4.
+        DDR4 = 0x1a,
+        /// LPDDR.
+        LPDDR = 0x1b,
+        /// LPDDR2.
+        LPDDR2 = 0x1c,
+        /// LPDDR3.
+        LPDDR3 = 0x1d,
+        /// LPDDR4.
+        LPDDR4 = 0x1e,
+        /// Logical non-volatile device.
+        LOGICAL_NON_VOLATILE = 0x1f,
+        /// HBM.
+        HBM = 0x20,
+        /// HBM2.
+        HBM2 = 0x21,
+        /// DDR5.
+        DDR5 = 0x22,
+        /// LPDDR5.
+        LPDDR5 = 0x23,
+        /// HBM3.
+        HBM3 = 0x24,
+    }
+}
+
+bitflags! {
+    /// Additional details about the type of a memory device.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct MemoryTypeDetail: u16 {
+        /// Other.
+        const OTHER = 1 << 1;
+        /// Unknown.
+        const UNKNOWN = 1 << 2;
+        /// Fast-paged.
+        const FAST_PAGED = 1 << 3;
+        /// Static column.
+        const STATIC_COLUMN = 1 << 4;
+        /// Pseudo-static.
+        const PSEUDO_STATIC = 1 << 5;
+        /// RAMBUS.
+        const RAMBUS = 1 << 6;
+        /// Synchronous.
+        const SYNCHRONOUS = 1 << 7;
+        /// CMOS.
+        const CMOS = 1 << 8;
+        /// EDO.
+        const EDO = 1 << 9;
+        /// Window DRAM.
+        const WINDOW_DRAM = 1 << 10;
+        /// Cache DRAM.
+        const CACHE_DRAM = 1 << 11;
+        /// Non-volatile.
+        const NON_VOLATILE = 1 << 12;
+        /// Registered (buffered).
+        const REGISTERED = 1 << 13;
+        /// Unbuffered (unregistered).
+        const UNBUFFERED = 1 << 14;
+        /// LRDIMM.
+        const LRDIMM = 1 << 15;
+    }
+}
+
+/// Memory device (type 17), such as a DIMM slot.
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryDevice<'a> {
+    structure: Structure<'a>,
+}
+
+impl<'a> SmbiosStructure<'a> for MemoryDevice<'a> {
+    const TYPE: StructureType = StructureType::MEMORY_DEVICE;
+
+    fn from_structure(structure: Structure<'a>) -> Option<Self> {
+        check_structure(&structure, Self::TYPE, MIN_LEN).then_some(Self { structure })
+    }
+}
+
+impl<'a> MemoryDevice<'a> {
+    /// Get the underlying structure.
+    #[must_use]
+    pub const fn structure(&self) -> Structure<'a> {
+        self.structure
+    }
+
+    /// Get the handle of the physical memory array the device belongs to.
+    #[must_use]
+    pub fn physical_memory_array_handle(&self) -> u16 {
+        self.structure.word(0x04).unwrap()
+    }
+
+    /// Get the handle of the structure holding error information for the
+    /// device, or `None` if not provided or no error was detected.
+    #[must_use]
+    pub fn error_information_handle(&self) -> Option<u16> {
+        self.structure.word(0x06).filter(|&handle| handle < 0xfffe)
+    }
+
+    /// Get the total width of the device in bits, including error
+    /// correction bits, or `None` if unknown.
+    #[must_use]
+    pub fn total_width(&self) -> Option<u16> {
+        self.structure.word(0x08).filter(|&width| width != 0xffff)
+    }
+
+    /// Get the data width of the device in bits, or `None` if unknown.
+    #[must_use]
+    pub fn data_width(&self) -> Option<u16> {
+        self.structure.word(0x0a).filter(|&width| width != 0xffff)
+    }
+
+    /// Returns whether a memory device is installed in the socket.
+    #[must_use]
+    pub fn is_installed(&self) -> bool {
+        self.structure.word(0x0c).unwrap() != 0
+    }
+
+    /// Get the size of the device in bytes, or `None` if no device is
+    /// installed or the size is unknown.
+    #[must_use]
+    pub fn size(&self) -> Option<u64> {
+        match self.structure.word(0x0c).unwrap() {
+            0 | 0xffff => None,
+            // Sizes of 32 GiB and more are given in MiB in the extended field.
+            0x7fff => {
+                let size = self.structure.dword(0x1c)? & 0x7fff_ffff;
+                Some(u64::from(size) << 20)
+            }
+            size if size & 0x8000 != 0 => Some(u64::from(size & 0x7fff) << 10),
+            size => Some(u64::from(size) << 20),
+        }
+    }
+
+    /// Get the form factor of the device.
+    #[must_use]
+    pub fn form_factor(&self) -> MemoryFormFactor {
+        MemoryFormFactor(self.structure.byte(0x0e).unwrap())
+    }
+
+    /// Get the set of devices that must be populated together, or `None` if
+    /// the device is not part of a set or the set is unknown.
+    #[must_use]
+    pub fn device_set(&self) -> Option<u8> {
+        self.structure
+            .byte(0x0f)
+            .filter(|&set| set != 0 && set != 0xff)
+    }
+
+    /// Get the label of the socket or board position of the device, such as
+    /// `DIMM 0`.
+    #[must_use]
+    pub fn device_locator(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x10)
+    }
+
+    /// Get the label of the bank the device is in, such as `BANK 0`.
+    #[must_use]
+    pub fn bank_locator(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x11)
+    }
+
+    /// Get the type of the device.
+    #[must_use]
+    pub fn memory_type(&self) -> MemoryDeviceType {
+        MemoryDeviceType(self.structure.byte(0x12).unwrap())
+    }
+
+    /// Get additional details about the type of the device.
+    #[must_use]
+    pub fn type_detail(&self) -> MemoryTypeDetail {
+        MemoryTypeDetail::from_bits_retain(self.structure.word(0x13).unwrap())
+    }
+
+    /// Get the maximum speed of the device in megatransfers per second, or
+    /// `None` if unknown (SMBIOS 2.3+).
+    #[must_use]
+    pub fn speed(&self) -> Option<u32> {
+        self.speed_at(0x15, 0x54)
+    }
+
+    /// Get the name of the device manufacturer (SMBIOS 2.3+).
+    #[must_use]
+    pub fn manufacturer(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x17)
+    }
+
+    /// Get the serial number (SMBIOS 2.3+).
+    #[must_use]
+    pub fn serial_number(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x18)
+    }
+
+    /// Get the asset tag (SMBIOS 2.3+).
+    #[must_use]
+    pub fn asset_tag(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x19)
+    }
+
+    /// Get the part number (SMBIOS 2.3+).
+    #[must_use]
+    pub fn part_number(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x1a)
+    }
+
+    /// Get the number of ranks, or `None` if unknown (SMBIOS 2.6+).
+    #[must_use]
+    pub fn rank(&self) -> Option<u8> {
+        self.structure
+            .byte(0x1b)
+            .map(|attributes| attributes & 0x0f)
+            .filter(|&rank| rank != 0)
+    }
+
+    /// Get the configured speed of the device in megatransfers per second,
+    /// or `None` if unknown (SMBIOS 2.7+).
+    #[must_use]
+    pub fn configured_speed(&self) -> Option<u32> {
+        self.speed_at(0x20, 0x58)
+    }
+
+    /// Get the configured voltage of the device in millivolts, or `None` if
+    /// unknown (SMBIOS 2.8+).
+    #[must_use]
+    pub fn configured_voltage(&self) -> Option<u16> {
+        self.structure.word(0x26).filter(|&voltage| voltage != 0)
+    }
+
+    /// Read a speed stored in the word at `offset`, or in the dword at
+    /// `extended_offset` (SMBIOS 3.3+) if it doesn't fit in a word.
+    fn speed_at(&self, offset: usize, extended_offset: usize) -> Option<u32> {
+        match self.structure.word(offset)? {
+            0 => None,
+            0xffff => self
+                .structure
+                .dword(extended_offset)
+                .filter(|&speed| speed != 0),
+            speed => Some(u32::from(speed)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{build_structure, parse_structure};
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    fn build_memory_device(size: u16, extended_size: u32, speed: u16) -> Vec<u8> {
+        let mut data = Vec::new();
+        for word in [0x1000u16, 0xfffe, 72, 64, size] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        data.extend_from_slice(&[0x09, 0, 1, 2, 0x22]);
+        data.extend_from_slice(&0x2080u16.to_le_bytes());
+        data.extend_from_slice(&speed.to_le_bytes());
+        data.extend_from_slice(&[3, 0, 0, 4, 0x02]);
+        data.extend_from_slice(&extended_size.to_le_bytes());
+        data.extend_from_slice(&4800u16.to_le_bytes());
+        for voltage in [1100u16, 1100, 1100] {
+            data.extend_from_slice(&voltage.to_le_bytes());
+        }
+        data.resize(0x54 - 4, 0);
+        data.extend_from_slice(&8000u32.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        build_structure(
+            StructureType::MEMORY_DEVICE,
+            0x1100,
+            &data,
+            &["DIMM 0", "BANK 0", "Samsung", "M321R4GA3BB6-CQK"],
+        )
+    }
+
+    #[test]
+    fn test_memory_device() {
+        let bytes = build_memory_device(0x4000, 0, 4800);
+        let dimm = MemoryDevice::from_structure(parse_structure(&bytes)).unwrap();
+        assert_eq!(dimm.physical_memory_array_handle(), 0x1000);
+        assert_eq!(dimm.error_information_handle(), None);
+        assert_eq!(dimm.total_width(), Some(72));
+        assert_eq!(dimm.data_width(), Some(64));
+        assert!(dimm.is_installed());
+        assert_eq!(dimm.size(), Some(16 << 30));
+        assert_eq!(dimm.form_factor(), MemoryFormFactor::DIMM);
+        assert_eq!(dimm.device_set(), None);
+        assert_eq!(dimm.device_locator().unwrap().to_string(), "DIMM 0");
+        assert_eq!(dimm.bank_locator().unwrap().to_string(), "BANK 0");
+        assert_eq!(dimm.memory_type(), MemoryDeviceType::DDR5);
+        assert_eq!(
+            dimm.type_detail(),
+            MemoryTypeDetail::SYNCHRONOUS | MemoryTypeDetail::REGISTERED
+        );
+        assert_eq!(dimm.speed(), Some(4800));
+        assert_eq!(dimm.manufacturer().unwrap().to_string(), "Samsung");
+        assert!(dimm.serial_number().is_none());
+        assert_eq!(dimm.part_number().unwrap().to_string(), "M321R4GA3BB6-CQK");
+        assert_eq!(dimm.rank(), Some(2));
+        assert_eq!(dimm.configured_speed(), Some(4800));
+        assert_eq!(dimm.configured_voltage(), Some(1100));
+
+        // Extended size and speed.
+        let bytes = build_memory_device(0x7fff, 64 << 10, 0xffff);
+        let dimm = MemoryDevice::from_structure(parse_structure(&bytes)).unwrap();
+        assert_eq!(dimm.size(), Some(64 << 30));
+        assert_eq!(dimm.speed(), Some(8000));
+
+        // Size in KiB.
+        let bytes = build_memory_device(0x8200, 0, 0);
+        let dimm = MemoryDevice::from_structure(parse_structure(&bytes)).unwrap();
+        assert_eq!(dimm.size(), Some(512 << 10));
+        assert_eq!(dimm.speed(), None);
+
+        // Empty socket.
+        let bytes = build_memory_device(0, 0, 0);
+        let dimm = MemoryDevice::from_structure(parse_structure(&bytes)).unwrap();
+        assert!(!dimm.is_installed());
+        assert_eq!(dimm.size(), None);
+    }
+}
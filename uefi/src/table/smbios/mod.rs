@@ -0,0 +1,772 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Reader for the SMBIOS structure table.
+//!
+//! The firmware publishes an SMBIOS entry point in the configuration table:
+//! the 32-bit entry point of SMBIOS 2.x, the 64-bit entry point of SMBIOS
+//! 3.x, or both. [`Smbios`] validates the entry point and iterates over the
+//! [`Structure`]s of the table it points to.
+//!
+//! Each structure has a formatted area, whose layout depends on its type,
+//! followed by a set of strings that fields of the formatted area refer to
+//! by index. Common structure types can be decoded into typed views:
+//!
+//! ```no_run
+//! use uefi::table::smbios::{DecodedStructure, Smbios, SystemInformation};
+//!
+//! # fn example() -> Result<(), uefi::table::smbios::SmbiosError> {
+//! let smbios = Smbios::find()?;
+//! log::info!("SMBIOS {}", smbios.version());
+//! if let Some(system) = smbios.find_structure::<SystemInformation>() {
+//!     log::info!("System UUID: {:?}", system.uuid());
+//! }
+//! for structure in smbios.structures() {
+//!     match structure.decode() {
+//!         DecodedStructure::MemoryDevice(dimm) => {
+//!             log::info!("{:?}: {:?} bytes", dimm.device_locator(), dimm.size());
+//!         }
+//!         DecodedStructure::Other(other) => {
+//!             log::info!("type {:?}: {} bytes", other.ty(), other.formatted().len());
+//!         }
+//!         _ => {}
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The table is accessed through its physical address, so it must be
+//! identity-mapped, as it is while boot services are active.
+//!
+//! See <https://www.dmtf.org/standards/smbios>.
+
+mod baseboard;
+mod bios;
+mod chassis;
+mod memory_device;
+mod processor;
+mod system;
+
+pub use baseboard::{BaseboardFeatures, BaseboardInformation, BoardType};
+pub use bios::{BiosCharacteristics, BiosCharacteristicsExt, BiosInformation};
+pub use chassis::{ChassisInformation, ChassisSecurityStatus, ChassisState, ChassisType};
+pub use memory_device::{MemoryDevice, MemoryDeviceType, MemoryFormFactor, MemoryTypeDetail};
+pub use processor::{
+    ProcessorCharacteristics, ProcessorInformation, ProcessorStatus, ProcessorType,
+};
+pub use system::{SystemInformation, WakeUpType};
+
+use crate::table::cfg::{ConfigTable, ConfigTableEntry};
+use crate::{CStr8, Guid};
+use core::ffi::c_void;
+use core::fmt::{self, Display, Formatter};
+use core::iter::FusedIterator;
+use core::slice;
+
+/// Anchor of the SMBIOS 2.x entry point.
+const ANCHOR_V2: &[u8; 4] = b"_SM_";
+
+/// Intermediate anchor of the SMBIOS 2.x entry point.
+const INTERMEDIATE_ANCHOR: &[u8; 5] = b"_DMI_";
+
+/// Anchor of the SMBIOS 3.x entry point.
+const ANCHOR_V3: &[u8; 5] = b"_SM3_";
+
+/// Minimum length of the SMBIOS 2.x entry point. The specification requires
+/// 0x1f bytes, but some implementations of SMBIOS 2.1 report 0x1e.
+const ENTRY_POINT_V2_MIN_LEN: usize = 0x1e;
+
+/// Length of the SMBIOS 3.x entry point.
+const ENTRY_POINT_V3_LEN: usize = 0x18;
+
+/// Size of the header of a [`Structure`].
+const HEADER_SIZE: usize = 4;
+
+/// Errors that may happen when reading the SMBIOS table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SmbiosError {
+    /// The system table has no SMBIOS entry point.
+    NotFound,
+    /// The entry point has an unknown anchor.
+    InvalidAnchor,
+    /// The checksum of the entry point is wrong.
+    InvalidChecksum,
+    /// The entry point is too small.
+    InvalidLength,
+    /// The structure table address is null or not addressable.
+    InvalidAddress,
+}
+
+impl Display for SmbiosError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "SMBIOS entry point not found"),
+            Self::InvalidAnchor => write!(f, "invalid SMBIOS entry point anchor"),
+            Self::InvalidChecksum => write!(f, "invalid SMBIOS entry point checksum"),
+            Self::InvalidLength => write!(f, "invalid SMBIOS entry point length"),
+            Self::InvalidAddress => write!(f, "invalid SMBIOS structure table address"),
+        }
+    }
+}
+
+impl core::error::Error for SmbiosError {}
+
+/// Compute the 8-bit sum of `bytes`, which must be zero for valid entry
+/// points.
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |sum, byte| sum.wrapping_add(*byte))
+}
+
+/// Read `N` bytes at `offset`.
+fn read_bytes<const N: usize>(bytes: &[u8], offset: usize) -> Option<[u8; N]> {
+    bytes.get(offset..offset.checked_add(N)?)?.try_into().ok()
+}
+
+/// Version of the SMBIOS specification the table conforms to.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SmbiosVersion {
+    /// Major version.
+    pub major: u8,
+    /// Minor version.
+    pub minor: u8,
+    /// Document revision. Always zero for SMBIOS 2.x entry points.
+    pub docrev: u8,
+}
+
+impl Display for SmbiosVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.docrev)
+    }
+}
+
+/// SMBIOS entry point, which locates the structure table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EntryPoint {
+    /// Version of the SMBIOS specification.
+    pub version: SmbiosVersion,
+    /// Whether this is the 64-bit entry point of SMBIOS 3.x.
+    pub is_64bit: bool,
+    /// Physical address of the structure table.
+    pub table_address: u64,
+    /// Length of the structure table in bytes. For 64-bit entry points, this
+    /// is the maximum length, and the table ends with the end-of-table
+    /// structure.
+    pub table_len: u32,
+    /// Number of structures in the table. Only given by 32-bit entry points.
+    pub structure_count: Option<u16>,
+}
+
+impl EntryPoint {
+    /// Read the entry point at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to an SMBIOS entry point that is valid for reads for
+    /// its full length.
+    ///
+    /// # Errors
+    ///
+    /// See [`from_bytes`](Self::from_bytes).
+    pub unsafe fn from_ptr(ptr: *const u8) -> Result<Self, SmbiosError> {
+        // SAFETY: both entry points are at least this large.
+        let anchor = unsafe { slice::from_raw_parts(ptr, ANCHOR_V3.len()) };
+        let len_offset = if anchor == ANCHOR_V3 { 6 } else { 5 };
+        // SAFETY: the caller guarantees the entry point is valid.
+        let len = unsafe { ptr.add(len_offset).read() };
+        let len = usize::from(len).max(len_offset + 1);
+        // SAFETY: the caller guarantees the entry point is valid.
+        Self::from_bytes(unsafe { slice::from_raw_parts(ptr, len) })
+    }
+
+    /// Parse the entry point from `bytes`. Trailing bytes are ignored.
+    ///
+    /// # Errors
+    ///
+    /// * [`SmbiosError::InvalidAnchor`]: `bytes` does not start with an
+    ///   SMBIOS anchor string.
+    /// * [`SmbiosError::InvalidLength`]: `bytes` or the length of the entry
+    ///   point is too small.
+    /// * [`SmbiosError::InvalidChecksum`]: a checksum is wrong.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SmbiosError> {
+        if bytes.starts_with(ANCHOR_V3) {
+            Self::from_bytes_v3(bytes)
+        } else if bytes.starts_with(ANCHOR_V2) {
+            Self::from_bytes_v2(bytes)
+        } else {
+            Err(SmbiosError::InvalidAnchor)
+        }
+    }
+
+    fn from_bytes_v2(bytes: &[u8]) -> Result<Self, SmbiosError> {
+        let len = usize::from(*bytes.get(5).ok_or(SmbiosError::InvalidLength)?);
+        if len < ENTRY_POINT_V2_MIN_LEN {
+            return Err(SmbiosError::InvalidLength);
+        }
+        let bytes = bytes.get(..len).ok_or(SmbiosError::InvalidLength)?;
+        if checksum(bytes) != 0 {
+            return Err(SmbiosError::InvalidChecksum);
+        }
+        if &bytes[16..21] != INTERMEDIATE_ANCHOR {
+            return Err(SmbiosError::InvalidAnchor);
+        }
+        if checksum(&bytes[16..len.min(0x1f)]) != 0 {
+            return Err(SmbiosError::InvalidChecksum);
+        }
+
+        Ok(Self {
+            version: SmbiosVersion {
+                major: bytes[6],
+                minor: bytes[7],
+                docrev: 0,
+            },
+            is_64bit: false,
+            table_address: u32::from_le_bytes(read_bytes(bytes, 24).unwrap()).into(),
+            table_len: u16::from_le_bytes(read_bytes(bytes, 22).unwrap()).into(),
+            structure_count: Some(u16::from_le_bytes(read_bytes(bytes, 28).unwrap())),
+        })
+    }
+
+    fn from_bytes_v3(bytes: &[u8]) -> Result<Self, SmbiosError> {
+        let len = usize::from(*bytes.get(6).ok_or(SmbiosError::InvalidLength)?);
+        if len < ENTRY_POINT_V3_LEN {
+            return Err(SmbiosError::InvalidLength);
+        }
+        let bytes = bytes.get(..len).ok_or(SmbiosError::InvalidLength)?;
+        if checksum(bytes) != 0 {
+            return Err(SmbiosError::InvalidChecksum);
+        }
+
+        Ok(Self {
+            version: SmbiosVersion {
+                major: bytes[7],
+                minor: bytes[8],
+                docrev: bytes[9],
+            },
+            is_64bit: true,
+            table_address: u64::from_le_bytes(read_bytes(bytes, 16).unwrap()),
+            table_len: u32::from_le_bytes(read_bytes(bytes, 12).unwrap()),
+            structure_count: None,
+        })
+    }
+}
+
+newtype_enum! {
+    /// Type of a [`Structure`].
+    pub enum StructureType: u8 => {
+        /// BIOS information, decoded as [`BiosInformation`].
+        BIOS = 0,
+        /// System information, decoded as [`SystemInformation`].
+        SYSTEM = 1,
+        /// Baseboard information, decoded as [`BaseboardInformation`].
+        BASEBOARD = 2,
+        /// System enclosure or chassis, decoded as [`ChassisInformation`].
+        CHASSIS = 3,
+        /// Processor information, decoded as [`ProcessorInformation`].
+        PROCESSOR = 4,
+        /// Cache information.
+        CACHE = 7,
+        /// Port connector information.
+        PORT_CONNECTOR = 8,
+        /// System slots.
+        SYSTEM_SLOTS = 9,
+        /// OEM strings.
+        OEM_STRINGS = 11,
+        /// System configuration options.
+        SYSTEM_CONFIGURATION_OPTIONS = 12,
+        /// Physical memory array.
+        PHYSICAL_MEMORY_ARRAY = 16,
+        /// Memory device, decoded as [`MemoryDevice`].
+        MEMORY_DEVICE = 17,
+        /// Memory array mapped address.
+        MEMORY_ARRAY_MAPPED_ADDRESS = 19,
+        /// System boot information.
+        SYSTEM_BOOT = 32,
+        /// Inactive structure.
+        INACTIVE = 126,
+        /// End of the table.
+        END_OF_TABLE = 127,
+    }
+}
+
+/// A structure of the SMBIOS table: a formatted area and a set of strings.
+#[derive(Clone, Copy, Debug)]
+pub struct Structure<'a> {
+    formatted: &'a [u8],
+    strings: &'a [u8],
+}
+
+impl<'a> Structure<'a> {
+    /// Parse the structure at the start of `bytes`, and return it with the
+    /// remaining bytes.
+    fn parse(bytes: &'a [u8]) -> Option<(Self, &'a [u8])> {
+        let len = usize::from(*bytes.get(1)?);
+        if len < HEADER_SIZE || bytes.len() < len {
+            return None;
+        }
+        let (formatted, rest) = bytes.split_at(len);
+        // The string set ends with two null bytes, even if it is empty.
+        let end = rest.windows(2).position(|window| window == [0, 0])? + 2;
+        let (strings, rest) = rest.split_at(end);
+        Some((Self { formatted, strings }, rest))
+    }
+
+    /// Get the type of the structure.
+    #[must_use]
+    pub const fn ty(&self) -> StructureType {
+        StructureType(self.formatted[0])
+    }
+
+    /// Get the handle of the structure, which other structures use to refer
+    /// to it.
+    #[must_use]
+    pub const fn handle(&self) -> u16 {
+        u16::from_le_bytes([self.formatted[2], self.formatted[3]])
+    }
+
+    /// Get the formatted area of the structure, including the header.
+    #[must_use]
+    pub const fn formatted(&self) -> &'a [u8] {
+        self.formatted
+    }
+
+    /// Get the raw string set of the structure, including the terminating
+    /// null bytes.
+    #[must_use]
+    pub const fn raw_strings(&self) -> &'a [u8] {
+        self.strings
+    }
+
+    /// Get an iterator over the strings of the structure.
+    #[must_use]
+    pub const fn strings(&self) -> Strings<'a> {
+        Strings { rest: self.strings }
+    }
+
+    /// Get the string with the one-based `index`. Index zero means that no
+    /// string is present.
+    #[must_use]
+    pub fn string(&self, index: u8) -> Option<&'a CStr8> {
+        self.strings().nth(usize::from(index).checked_sub(1)?)
+    }
+
+    /// Decode the structure if it has one of the supported types. Structures
+    /// of other types, or that are too small for their type, are returned
+    /// as [`DecodedStructure::Other`].
+    #[must_use]
+    pub fn decode(self) -> DecodedStructure<'a> {
+        fn decode<'a, T: SmbiosStructure<'a>>(
+            structure: Structure<'a>,
+            variant: fn(T) -> DecodedStructure<'a>,
+        ) -> DecodedStructure<'a> {
+            T::from_structure(structure).map_or(DecodedStructure::Other(structure), variant)
+        }
+
+        match self.ty() {
+            StructureType::BIOS => decode(self, DecodedStructure::Bios),
+            StructureType::SYSTEM => decode(self, DecodedStructure::System),
+            StructureType::BASEBOARD => decode(self, DecodedStructure::Baseboard),
+            StructureType::CHASSIS => decode(self, DecodedStructure::Chassis),
+            StructureType::PROCESSOR => decode(self, DecodedStructure::Processor),
+            StructureType::MEMORY_DEVICE => decode(self, DecodedStructure::MemoryDevice),
+            _ => DecodedStructure::Other(self),
+        }
+    }
+
+    fn byte(&self, offset: usize) -> Option<u8> {
+        self.formatted.get(offset).copied()
+    }
+
+    fn word(&self, offset: usize) -> Option<u16> {
+        read_bytes(self.formatted, offset).map(u16::from_le_bytes)
+    }
+
+    fn dword(&self, offset: usize) -> Option<u32> {
+        read_bytes(self.formatted, offset).map(u32::from_le_bytes)
+    }
+
+    fn qword(&self, offset: usize) -> Option<u64> {
+        read_bytes(self.formatted, offset).map(u64::from_le_bytes)
+    }
+
+    /// Get the string whose index is stored at `offset`.
+    fn string_at(&self, offset: usize) -> Option<&'a CStr8> {
+        self.string(self.byte(offset)?)
+    }
+}
+
+/// Iterator over the strings of a [`Structure`].
+#[derive(Clone, Debug)]
+pub struct Strings<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for Strings<'a> {
+    type Item = &'a CStr8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let string = match self.rest.iter().position(|byte| *byte == 0) {
+            Some(len) if len > 0 => {
+                let (string, rest) = self.rest.split_at(len + 1);
+                self.rest = rest;
+                CStr8::from_bytes_with_nul(string).ok()
+            }
+            _ => None,
+        };
+        // Stay exhausted once the end of the strings is reached.
+        if string.is_none() {
+            self.rest = &[];
+        }
+        string
+    }
+}
+
+impl FusedIterator for Strings<'_> {}
+
+/// Trait for typed views of a [`Structure`].
+pub trait SmbiosStructure<'a>: Sized {
+    /// Type of the structure.
+    const TYPE: StructureType;
+
+    /// Create the typed view, or return `None` if the structure has another
+    /// type or is too small.
+    fn from_structure(structure: Structure<'a>) -> Option<Self>;
+}
+
+/// A [`Structure`] decoded according to its type.
+#[derive(Clone, Copy, Debug)]
+pub enum DecodedStructure<'a> {
+    /// BIOS information (type 0).
+    Bios(BiosInformation<'a>),
+    /// System information (type 1).
+    System(SystemInformation<'a>),
+    /// Baseboard information (type 2).
+    Baseboard(BaseboardInformation<'a>),
+    /// System enclosure or chassis (type 3).
+    Chassis(ChassisInformation<'a>),
+    /// Processor information (type 4).
+    Processor(ProcessorInformation<'a>),
+    /// Memory device (type 17).
+    MemoryDevice(MemoryDevice<'a>),
+    /// A structure of another type.
+    Other(Structure<'a>),
+}
+
+/// Check the type and minimum length of a structure for a typed view.
+fn check_structure(structure: &Structure<'_>, ty: StructureType, min_len: usize) -> bool {
+    structure.ty() == ty && structure.formatted.len() >= min_len
+}
+
+/// The SMBIOS structure table.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone, Copy, Debug)]
+pub struct Smbios<'a> {
+    entry_point: EntryPoint,
+    table: &'a [u8],
+}
+
+impl<'a> Smbios<'a> {
+    /// Locate the SMBIOS table through the entry points in the system
+    /// configuration table, preferring the 64-bit SMBIOS 3.x entry point.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SmbiosError::NotFound`] if the firmware does not provide
+    /// SMBIOS, or any other error if the entry point is invalid.
+    pub fn find() -> Result<Smbios<'static>, SmbiosError> {
+        crate::system::find_config_table()
+    }
+
+    /// Read the SMBIOS table, starting from the entry point at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid SMBIOS entry point. The structure table it
+    /// refers to must be identity-mapped and valid for reads for lifetime
+    /// `'a`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry point is invalid.
+    pub unsafe fn from_entry_point(ptr: *const u8) -> Result<Self, SmbiosError> {
+        // SAFETY: the caller guarantees the entry point is valid.
+        let entry_point = unsafe { EntryPoint::from_ptr(ptr)? };
+        if entry_point.table_address == 0 {
+            return Err(SmbiosError::InvalidAddress);
+        }
+        let address =
+            usize::try_from(entry_point.table_address).map_err(|_| SmbiosError::InvalidAddress)?;
+        // SAFETY: the caller guarantees the table is valid.
+        let table =
+            unsafe { slice::from_raw_parts(address as *const u8, entry_point.table_len as usize) };
+        Ok(Self::from_table(entry_point, table))
+    }
+
+    /// Create a reader for the structure table in `table`, described by
+    /// `entry_point`. Bytes after the table length given by the entry point
+    /// are ignored.
+    #[must_use]
+    pub fn from_table(entry_point: EntryPoint, table: &'a [u8]) -> Self {
+        let len = table.len().min(entry_point.table_len as usize);
+        Self {
+            entry_point,
+            table: &table[..len],
+        }
+    }
+
+    /// Get the entry point.
+    #[must_use]
+    pub const fn entry_point(&self) -> EntryPoint {
+        self.entry_point
+    }
+
+    /// Get the version of the SMBIOS specification the table conforms to.
+    #[must_use]
+    pub const fn version(&self) -> SmbiosVersion {
+        self.entry_point.version
+    }
+
+    /// Get an iterator over the structures of the table.
+    #[must_use]
+    pub fn structures(&self) -> Structures<'a> {
+        Structures {
+            rest: self.table,
+            remaining: self.entry_point.structure_count.map(usize::from),
+        }
+    }
+
+    /// Find the structure with `handle`.
+    #[must_use]
+    pub fn find_by_handle(&self, handle: u16) -> Option<Structure<'a>> {
+        self.structures()
+            .find(|structure| structure.handle() == handle)
+    }
+
+    /// Get an iterator over the structures with type `ty`.
+    pub fn find_by_type(&self, ty: StructureType) -> impl Iterator<Item = Structure<'a>> + use<'a> {
+        self.structures()
+            .filter(move |structure| structure.ty() == ty)
+    }
+
+    /// Find the first structure of type `T`.
+    #[must_use]
+    pub fn find_structure<T: SmbiosStructure<'a>>(&self) -> Option<T> {
+        self.find_structures().next()
+    }
+
+    /// Get an iterator over the structures of type `T`.
+    pub fn find_structures<T: SmbiosStructure<'a>>(&self) -> impl Iterator<Item = T> + use<'a, T> {
+        self.find_by_type(T::TYPE).filter_map(T::from_structure)
+    }
+}
+
+impl ConfigTable for Smbios<'static> {
+    const GUID: Guid = ConfigTableEntry::SMBIOS3_GUID;
+    const COMPATIBLE_GUIDS: &'static [Guid] = &[ConfigTableEntry::SMBIOS_GUID];
+    type Error = SmbiosError;
+    const NOT_FOUND: SmbiosError = SmbiosError::NotFound;
+
+    unsafe fn from_entry(_guid: Guid, address: *const c_void) -> Result<Self, SmbiosError> {
+        // SAFETY: the firmware guarantees the entry point and table are valid.
+        unsafe { Self::from_entry_point(address.cast()) }
+    }
+}
+
+/// Iterator over the structures of the [`Smbios`] table.
+///
+/// Iteration stops at the end-of-table structure, after the number of
+/// structures given by the entry point, or at the first malformed structure.
+#[derive(Clone, Debug)]
+pub struct Structures<'a> {
+    rest: &'a [u8],
+    remaining: Option<usize>,
+}
+
+impl<'a> Iterator for Structures<'a> {
+    type Item = Structure<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        let Some((structure, rest)) = Structure::parse(self.rest) else {
+            self.rest = &[];
+            return None;
+        };
+        if structure.ty() == StructureType::END_OF_TABLE {
+            self.rest = &[];
+            return None;
+        }
+        self.rest = rest;
+        self.remaining = self.remaining.map(|remaining| remaining - 1);
+        Some(structure)
+    }
+}
+
+impl FusedIterator for Structures<'_> {}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    /// Build a structure with the formatted area `data` after the header.
+    pub fn build_structure(
+        ty: StructureType,
+        handle: u16,
+        data: &[u8],
+        strings: &[&str],
+    ) -> Vec<u8> {
+        let mut bytes = Vec::from([ty.0, (HEADER_SIZE + data.len()) as u8]);
+        bytes.extend_from_slice(&handle.to_le_bytes());
+        bytes.extend_from_slice(data);
+        for string in strings {
+            bytes.extend_from_slice(string.as_bytes());
+            bytes.push(0);
+        }
+        if strings.is_empty() {
+            bytes.push(0);
+        }
+        bytes.push(0);
+        bytes
+    }
+
+    /// Parse a single structure built by [`build_structure`].
+    pub fn parse_structure(bytes: &[u8]) -> Structure<'_> {
+        let (structure, rest) = Structure::parse(bytes).unwrap();
+        assert!(rest.is_empty());
+        structure
+    }
+
+    fn build_entry_point_v2(table_address: u32, table_len: u16, count: u16) -> Vec<u8> {
+        let mut bytes = Vec::from(*ANCHOR_V2);
+        bytes.extend_from_slice(&[0, 0x1f, 2, 8]);
+        bytes.extend_from_slice(&0x100u16.to_le_bytes());
+        bytes.extend_from_slice(&[0; 6]);
+        bytes.extend_from_slice(INTERMEDIATE_ANCHOR);
+        bytes.push(0);
+        bytes.extend_from_slice(&table_len.to_le_bytes());
+        bytes.extend_from_slice(&table_address.to_le_bytes());
+        bytes.extend_from_slice(&count.to_le_bytes());
+        bytes.push(0x28);
+        bytes[21] = 0u8.wrapping_sub(checksum(&bytes[16..]));
+        bytes[4] = 0u8.wrapping_sub(checksum(&bytes));
+        bytes
+    }
+
+    fn build_entry_point_v3(table_address: u64, table_len: u32) -> Vec<u8> {
+        let mut bytes = Vec::from(*ANCHOR_V3);
+        bytes.extend_from_slice(&[0, 0x18, 3, 7, 1, 1, 0]);
+        bytes.extend_from_slice(&table_len.to_le_bytes());
+        bytes.extend_from_slice(&table_address.to_le_bytes());
+        bytes[5] = 0u8.wrapping_sub(checksum(&bytes));
+        bytes
+    }
+
+    #[test]
+    fn test_entry_point() {
+        let bytes = build_entry_point_v2(0x000f_0000, 0x1234, 42);
+        let entry_point = EntryPoint::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            entry_point,
+            EntryPoint {
+                version: SmbiosVersion {
+                    major: 2,
+                    minor: 8,
+                    docrev: 0
+                },
+                is_64bit: false,
+                table_address: 0x000f_0000,
+                table_len: 0x1234,
+                structure_count: Some(42),
+            }
+        );
+        // SAFETY: `bytes` is a valid entry point.
+        assert_eq!(
+            unsafe { EntryPoint::from_ptr(bytes.as_ptr()) },
+            Ok(entry_point)
+        );
+
+        let mut bad = bytes.clone();
+        bad[0x1e] ^= 1;
+        assert_eq!(
+            EntryPoint::from_bytes(&bad),
+            Err(SmbiosError::InvalidChecksum)
+        );
+        assert_eq!(
+            EntryPoint::from_bytes(&bytes[..0x10]),
+            Err(SmbiosError::InvalidLength)
+        );
+
+        let bytes = build_entry_point_v3(0x7fff_0000, 0x800);
+        let entry_point = EntryPoint::from_bytes(&bytes).unwrap();
+        assert_eq!(entry_point.version.to_string(), "3.7.1");
+        assert!(entry_point.is_64bit);
+        assert_eq!(entry_point.table_address, 0x7fff_0000);
+        assert_eq!(entry_point.table_len, 0x800);
+        assert_eq!(entry_point.structure_count, None);
+        // SAFETY: `bytes` is a valid entry point.
+        assert_eq!(
+            unsafe { EntryPoint::from_ptr(bytes.as_ptr()) },
+            Ok(entry_point)
+        );
+
+        assert_eq!(
+            EntryPoint::from_bytes(b"_XX_ not an entry point"),
+            Err(SmbiosError::InvalidAnchor)
+        );
+    }
+
+    #[test]
+    fn test_structures() {
+        let mut table = Vec::new();
+        table.extend(build_structure(
+            StructureType::OEM_STRINGS,
+            0x10,
+            &[2],
+            &["first", "second"],
+        ));
+        table.extend(build_structure(StructureType(0x80), 0x11, &[1, 2, 3], &[]));
+        table.extend(build_structure(
+            StructureType::END_OF_TABLE,
+            0xfeff,
+            &[],
+            &[],
+        ));
+        table.extend(build_structure(StructureType(0x81), 0x12, &[], &[]));
+
+        let entry_point =
+            EntryPoint::from_bytes(&build_entry_point_v3(1, table.len() as u32)).unwrap();
+        let smbios = Smbios::from_table(entry_point, &table);
+        assert_eq!(smbios.structures().count(), 2);
+
+        let oem = smbios.find_by_handle(0x10).unwrap();
+        assert_eq!(oem.ty(), StructureType::OEM_STRINGS);
+        assert_eq!(oem.formatted(), [11, 5, 0x10, 0, 2]);
+        assert_eq!(oem.strings().count(), 2);
+        let mut strings = oem.strings();
+        strings.by_ref().for_each(drop);
+        assert!(strings.next().is_none());
+        assert!(oem.string(0).is_none());
+        assert_eq!(oem.string(1).unwrap().to_string(), "first");
+        assert_eq!(oem.string(2).unwrap().to_string(), "second");
+        assert!(oem.string(3).is_none());
+
+        let vendor = smbios.find_by_type(StructureType(0x80)).next().unwrap();
+        assert_eq!(vendor.handle(), 0x11);
+        assert_eq!(vendor.strings().count(), 0);
+        assert_eq!(vendor.raw_strings(), [0, 0]);
+        assert!(matches!(vendor.decode(), DecodedStructure::Other(_)));
+
+        // The structure count of 32-bit entry points limits iteration.
+        let entry_point =
+            EntryPoint::from_bytes(&build_entry_point_v2(1, table.len() as u16, 1)).unwrap();
+        let smbios = Smbios::from_table(entry_point, &table);
+        assert_eq!(smbios.structures().count(), 1);
+
+        // Iteration stops at a truncated structure.
+        let smbios = Smbios::from_table(entry_point, &table[..10]);
+        assert_eq!(smbios.structures().count(), 0);
+    }
+}
@@ -0,0 +1,336 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Processor information (type 4).
+
+use super::{SmbiosStructure, Structure, StructureType, check_structure};
+use crate::CStr8;
+use bitflags::bitflags;
+
+/// Length of the SMBIOS 2.0 structure.
+const MIN_LEN: usize = 0x1a;
+
+newtype_enum! {
+    /// Type of a processor.
+    pub enum ProcessorType: u8 => {
+        /// Other.
+        OTHER = 0x01,
+        /// Unknown.
+        UNKNOWN = 0x02,
+        /// Central processor.
+        CENTRAL = 0x03,
+        /// Math processor.
+        MATH = 0x04,
+        /// DSP processor.
+        DSP = 0x05,
+        /// Video processor.
+        VIDEO = 0x06,
+    }
+}
+
+newtype_enum! {
+    /// Status of a processor.
+    pub enum ProcessorStatus: u8 => {
+        /// Unknown.
+        UNKNOWN = 0x00,
+        /// Enabled.
+        ENABLED = 0x01,
+        /// Disabled by the user through the BIOS setup.
+        DISABLED_BY_USER = 0x02,
+        /// Disabled by the BIOS because of a POST error.
+        DISABLED_BY_BIOS = 0x03,
+        /// Idle, waiting to be enabled.
+        IDLE = 0x04,
+        /// Other.
+        OTHER = 0x07,
+    }
+}
+
+bitflags! {
+    /// Features of a processor.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct ProcessorCharacteristics: u16 {
+        /// The characteristics are unknown.
+        const UNKNOWN = 1 << 1;
+        /// 64-bit capable.
+        const CAPABLE_64BIT = 1 << 2;
+        /// Multi-core.
+        const MULTI_CORE = 1 << 3;
+        /// Hardware threads.
+        const HARDWARE_THREAD = 1 << 4;
+        /// Execute protection.
+        const EXECUTE_PROTECTION = 1 << 5;
+        /// Enhanced virtualization.
+        const ENHANCED_VIRTUALIZATION = 1 << 6;
+        /// Power and performance control.
+        const POWER_PERFORMANCE_CONTROL = 1 << 7;
+        /// 128-bit capable.
+        const CAPABLE_128BIT = 1 << 8;
+        /// The processor ID holds the Arm SoC ID.
+        const ARM64_SOC_ID = 1 << 9;
+    }
+}
+
+/// Processor information (type 4).
+#[derive(Clone, Copy, Debug)]
+pub struct ProcessorInformation<'a> {
+    structure: Structure<'a>,
+}
+
+impl<'a> SmbiosStructure<'a> for ProcessorInformation<'a> {
+    const TYPE: StructureType = StructureType::PROCESSOR;
+
+    fn from_structure(structure: Structure<'a>) -> Option<Self> {
+        check_structure(&structure, Self::TYPE, MIN_LEN).then_some(Self { structure })
+    }
+}
+
+impl<'a> ProcessorInformation<'a> {
+    /// Get the underlying structure.
+    #[must_use]
+    pub const fn structure(&self) -> Structure<'a> {
+        self.structure
+    }
+
+    /// Get the designation of the processor socket, such as `CPU 0`.
+    #[must_use]
+    pub fn socket_designation(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x04)
+    }
+
+    /// Get the type of the processor.
+    #[must_use]
+    pub fn processor_type(&self) -> ProcessorType {
+        ProcessorType(self.structure.byte(0x05).unwrap())
+    }
+
+    /// Get the processor family, taking the extended family field of
+    /// SMBIOS 2.6+ into account.
+    #[must_use]
+    pub fn family(&self) -> u16 {
+        match self.structure.byte(0x06).unwrap() {
+            0xfe => self.structure.word(0x28).unwrap_or(0xfe),
+            family => u16::from(family),
+        }
+    }
+
+    /// Get the name of the processor manufacturer.
+    #[must_use]
+    pub fn manufacturer(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x07)
+    }
+
+    /// Get the raw processor identification data. On x86, this holds the
+    /// `EAX` and `EDX` values of `CPUID` leaf 1.
+    #[must_use]
+    pub fn processor_id(&self) -> u64 {
+        self.structure.qword(0x08).unwrap()
+    }
+
+    /// Get the processor version.
+    #[must_use]
+    pub fn version(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x10)
+    }
+
+    /// Get the processor voltage in tenths of a volt. For legacy voltage
+    /// fields, the lowest supported voltage is returned.
+    #[must_use]
+    pub fn voltage(&self) -> Option<u8> {
+        let voltage = self.structure.byte(0x11).unwrap();
+        if voltage & 0x80 != 0 {
+            Some(voltage & 0x7f)
+        } else if voltage & 0b100 != 0 {
+            Some(29)
+        } else if voltage & 0b10 != 0 {
+            Some(33)
+        } else if voltage & 0b1 != 0 {
+            Some(50)
+        } else {
+            None
+        }
+    }
+
+    /// Get the external clock frequency in MHz, or `None` if unknown.
+    #[must_use]
+    pub fn external_clock(&self) -> Option<u16> {
+        self.structure.word(0x12).filter(|&clock| clock != 0)
+    }
+
+    /// Get the maximum speed supported by the system in MHz, or `None` if
+    /// unknown.
+    #[must_use]
+    pub fn max_speed(&self) -> Option<u16> {
+        self.structure.word(0x14).filter(|&speed| speed != 0)
+    }
+
+    /// Get the speed of the processor at boot in MHz, or `None` if unknown.
+    #[must_use]
+    pub fn current_speed(&self) -> Option<u16> {
+        self.structure.word(0x16).filter(|&speed| speed != 0)
+    }
+
+    /// Returns whether the socket is populated.
+    #[must_use]
+    pub fn is_populated(&self) -> bool {
+        self.structure.byte(0x18).unwrap() & 0x40 != 0
+    }
+
+    /// Get the status of the processor.
+    #[must_use]
+    pub fn status(&self) -> ProcessorStatus {
+        ProcessorStatus(self.structure.byte(0x18).unwrap() & 0x07)
+    }
+
+    /// Get the raw processor upgrade (socket type) field.
+    #[must_use]
+    pub fn upgrade(&self) -> u8 {
+        self.structure.byte(0x19).unwrap()
+    }
+
+    /// Get the handles of the L1, L2, and L3 cache structures
+    /// (SMBIOS 2.1+). `None` means the cache level is not present.
+    #[must_use]
+    pub fn cache_handles(&self) -> [Option<u16>; 3] {
+        [0x1a, 0x1c, 0x1e].map(|offset| {
+            self.structure
+                .word(offset)
+                .filter(|&handle| handle != 0xffff)
+        })
+    }
+
+    /// Get the serial number (SMBIOS 2.3+).
+    #[must_use]
+    pub fn serial_number(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x20)
+    }
+
+    /// Get the asset tag (SMBIOS 2.3+).
+    #[must_use]
+    pub fn asset_tag(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x21)
+    }
+
+    /// Get the part number (SMBIOS 2.3+).
+    #[must_use]
+    pub fn part_number(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x22)
+    }
+
+    /// Get the number of cores per socket, or `None` if unknown
+    /// (SMBIOS 2.5+).
+    #[must_use]
+    pub fn core_count(&self) -> Option<u16> {
+        self.count(0x23, 0x2a)
+    }
+
+    /// Get the number of enabled cores per socket, or `None` if unknown
+    /// (SMBIOS 2.5+).
+    #[must_use]
+    pub fn core_enabled(&self) -> Option<u16> {
+        self.count(0x24, 0x2c)
+    }
+
+    /// Get the number of threads per socket, or `None` if unknown
+    /// (SMBIOS 2.5+).
+    #[must_use]
+    pub fn thread_count(&self) -> Option<u16> {
+        self.count(0x25, 0x2e)
+    }
+
+    /// Get the number of enabled threads per socket, or `None` if unknown
+    /// (SMBIOS 3.6+).
+    #[must_use]
+    pub fn thread_enabled(&self) -> Option<u16> {
+        self.structure
+            .word(0x30)
+            .filter(|&count| count != 0 && count != 0xffff)
+    }
+
+    /// Get the features of the processor (SMBIOS 2.5+).
+    #[must_use]
+    pub fn characteristics(&self) -> Option<ProcessorCharacteristics> {
+        self.structure
+            .word(0x26)
+            .map(ProcessorCharacteristics::from_bits_retain)
+    }
+
+    /// Read a count stored in the byte at `offset`, or in the word at
+    /// `extended_offset` (SMBIOS 3.0+) if it doesn't fit in a byte.
+    fn count(&self, offset: usize, extended_offset: usize) -> Option<u16> {
+        match self.structure.byte(offset)? {
+            0 => None,
+            0xff => match self.structure.word(extended_offset) {
+                Some(0 | 0xffff) => None,
+                Some(count) => Some(count),
+                None => Some(0xff),
+            },
+            count => Some(u16::from(count)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{build_structure, parse_structure};
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_processor() {
+        let mut data = Vec::from([1, 3, 0xfe, 2]);
+        data.extend_from_slice(&0x178b_fbff_0080_0f11u64.to_le_bytes());
+        data.extend_from_slice(&[3, 0x8c]);
+        for word in [100u16, 4000, 3000] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        data.extend_from_slice(&[0x41, 0x01]);
+        for handle in [0x10u16, 0x11, 0xffff] {
+            data.extend_from_slice(&handle.to_le_bytes());
+        }
+        data.extend_from_slice(&[0, 0, 0, 0xff, 0xff, 0xff]);
+        data.extend_from_slice(&0x00fcu16.to_le_bytes());
+        data.extend_from_slice(&0x0107u16.to_le_bytes());
+        for word in [256u16, 256, 512, 512] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        let bytes = build_structure(
+            StructureType::PROCESSOR,
+            4,
+            &data,
+            &["CPU 0", "AuthenticAMD", "AMD EPYC"],
+        );
+
+        let cpu = ProcessorInformation::from_structure(parse_structure(&bytes)).unwrap();
+        assert_eq!(cpu.socket_designation().unwrap().to_string(), "CPU 0");
+        assert_eq!(cpu.processor_type(), ProcessorType::CENTRAL);
+        assert_eq!(cpu.family(), 0x107);
+        assert_eq!(cpu.manufacturer().unwrap().to_string(), "AuthenticAMD");
+        assert_eq!(cpu.processor_id(), 0x178b_fbff_0080_0f11);
+        assert_eq!(cpu.version().unwrap().to_string(), "AMD EPYC");
+        assert_eq!(cpu.voltage(), Some(12));
+        assert_eq!(cpu.external_clock(), Some(100));
+        assert_eq!(cpu.max_speed(), Some(4000));
+        assert_eq!(cpu.current_speed(), Some(3000));
+        assert!(cpu.is_populated());
+        assert_eq!(cpu.status(), ProcessorStatus::ENABLED);
+        assert_eq!(cpu.cache_handles(), [Some(0x10), Some(0x11), None]);
+        assert!(cpu.serial_number().is_none());
+        assert_eq!(cpu.core_count(), Some(256));
+        assert_eq!(cpu.core_enabled(), Some(256));
+        assert_eq!(cpu.thread_count(), Some(512));
+        assert_eq!(cpu.thread_enabled(), Some(512));
+        assert!(cpu.characteristics().unwrap().contains(
+            ProcessorCharacteristics::CAPABLE_64BIT | ProcessorCharacteristics::MULTI_CORE
+        ));
+
+        // SMBIOS 2.0 structures only have the basic fields.
+        let bytes = build_structure(StructureType::PROCESSOR, 4, &data[..0x16], &[]);
+        let cpu = ProcessorInformation::from_structure(parse_structure(&bytes)).unwrap();
+        assert_eq!(cpu.family(), 0xfe);
+        assert_eq!(cpu.cache_handles(), [None; 3]);
+        assert_eq!(cpu.core_count(), None);
+        assert_eq!(cpu.characteristics(), None);
+    }
+}
@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! System information (type 1).
+
+use super::{SmbiosStructure, Structure, StructureType, check_structure, read_bytes};
+use crate::{CStr8, Guid};
+
+/// Length of the SMBIOS 2.0 structure.
+const MIN_LEN: usize = 0x08;
+
+newtype_enum! {
+    /// Event that caused the system to power up.
+    pub enum WakeUpType: u8 => {
+        /// Reserved.
+        RESERVED = 0x00,
+        /// Other.
+        OTHER = 0x01,
+        /// Unknown.
+        UNKNOWN = 0x02,
+        /// APM timer.
+        APM_TIMER = 0x03,
+        /// Modem ring.
+        MODEM_RING = 0x04,
+        /// LAN remote.
+        LAN_REMOTE = 0x05,
+        /// Power switch.
+        POWER_SWITCH = 0x06,
+        /// PCI PME#.
+        PCI_PME = 0x07,
+        /// AC power restored.
+        AC_POWER_RESTORED = 0x08,
+    }
+}
+
+/// System information (type 1).
+#[derive(Clone, Copy, Debug)]
+pub struct SystemInformation<'a> {
+    structure: Structure<'a>,
+}
+
+impl<'a> SmbiosStructure<'a> for SystemInformation<'a> {
+    const TYPE: StructureType = StructureType::SYSTEM;
+
+    fn from_structure(structure: Structure<'a>) -> Option<Self> {
+        check_structure(&structure, Self::TYPE, MIN_LEN).then_some(Self { structure })
+    }
+}
+
+impl<'a> SystemInformation<'a> {
+    /// Get the underlying structure.
+    #[must_use]
+    pub const fn structure(&self) -> Structure<'a> {
+        self.structure
+    }
+
+    /// Get the name of the system manufacturer.
+    #[must_use]
+    pub fn manufacturer(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x04)
+    }
+
+    /// Get the product name.
+    #[must_use]
+    pub fn product_name(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x05)
+    }
+
+    /// Get the product version.
+    #[must_use]
+    pub fn version(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x06)
+    }
+
+    /// Get the serial number.
+    #[must_use]
+    pub fn serial_number(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x07)
+    }
+
+    /// Get the system UUID (SMBIOS 2.1+), or `None` if it is not present.
+    ///
+    /// The UUID is decoded with the byte order of SMBIOS 2.6 and later, which
+    /// matches the byte order of [`Guid`]. Older implementations may store
+    /// all fields in big-endian order.
+    #[must_use]
+    pub fn uuid(&self) -> Option<Guid> {
+        let bytes: [u8; 16] = read_bytes(self.structure.formatted(), 0x08)?;
+        // All zeros means the UUID is not present, and all ones means it is
+        // not present but can be set.
+        if bytes == [0; 16] || bytes == [0xff; 16] {
+            return None;
+        }
+        Some(Guid::from_bytes(bytes))
+    }
+
+    /// Get the event that caused the system to power up (SMBIOS 2.1+).
+    #[must_use]
+    pub fn wake_up_type(&self) -> Option<WakeUpType> {
+        self.structure.byte(0x18).map(WakeUpType)
+    }
+
+    /// Get the SKU number, which identifies the configuration of the system
+    /// (SMBIOS 2.4+).
+    #[must_use]
+    pub fn sku_number(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x19)
+    }
+
+    /// Get the family the system belongs to (SMBIOS 2.4+).
+    #[must_use]
+    pub fn family(&self) -> Option<&'a CStr8> {
+        self.structure.string_at(0x1a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{build_structure, parse_structure};
+    use super::*;
+    use crate::guid;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_system() {
+        let uuid = guid!("01234567-89ab-cdef-0123-456789abcdef");
+        let mut data = Vec::from([1, 2, 0, 3]);
+        data.extend_from_slice(&uuid.to_bytes());
+        data.extend_from_slice(&[6, 0, 4]);
+        let bytes = build_structure(
+            StructureType::SYSTEM,
+            1,
+            &data,
+            &["QEMU", "Standard PC", "KVM", "Virtual Machine"],
+        );
+
+        let system = SystemInformation::from_structure(parse_structure(&bytes)).unwrap();
+        assert_eq!(system.manufacturer().unwrap().to_string(), "QEMU");
+        assert_eq!(system.product_name().unwrap().to_string(), "Standard PC");
+        assert!(system.version().is_none());
+        assert_eq!(system.serial_number().unwrap().to_string(), "KVM");
+        assert_eq!(system.uuid(), Some(uuid));
+        assert_eq!(system.wake_up_type(), Some(WakeUpType::POWER_SWITCH));
+        assert!(system.sku_number().is_none());
+        assert_eq!(system.family().unwrap().to_string(), "Virtual Machine");
+
+        // SMBIOS 2.0 structures have no UUID.
+        let bytes = build_structure(StructureType::SYSTEM, 1, &[0, 0, 0, 0], &[]);
+        let system = SystemInformation::from_structure(parse_structure(&bytes)).unwrap();
+        assert_eq!(system.uuid(), None);
+        assert_eq!(system.wake_up_type(), None);
+    }
+}
@@ -50,6 +50,7 @@ impl Package {
 pub enum Feature {
     // `uefi` features.
     Alloc,
+    AllocationTracking,
     GlobalAllocator,
     LogDebugcon,
     Logger,
@@ -70,6 +71,7 @@ impl Feature {
     fn as_str(&self) -> &'static str {
         match self {
             Self::Alloc => "alloc",
+            Self::AllocationTracking => "allocation_tracking",
             Self::GlobalAllocator => "global_allocator",
             Self::LogDebugcon => "log-debugcon",
             Self::Logger => "logger",
@@ -91,6 +93,7 @@ impl Feature {
         match package {
             Package::Uefi => vec![
                 Self::Alloc,
+                Self::AllocationTracking,
                 Self::GlobalAllocator,
                 Self::LogDebugcon,
                 Self::Logger,
@@ -122,7 +125,7 @@ impl Feature {
             base_features.extend([Self::Unstable])
         }
         if runtime_features {
-            base_features.extend([Self::GlobalAllocator])
+            base_features.extend([Self::GlobalAllocator, Self::AllocationTracking])
         }
         base_features
     }
@@ -391,7 +394,7 @@ mod tests {
         );
         assert_eq!(
             Feature::comma_separated_string(&Feature::more_code(false, true)),
-            "alloc,log-debugcon,logger,global_allocator"
+            "alloc,log-debugcon,logger,global_allocator,allocation_tracking"
         );
         assert_eq!(
             Feature::comma_separated_string(&Feature::more_code(true, false)),
@@ -399,7 +402,7 @@ mod tests {
         );
         assert_eq!(
             Feature::comma_separated_string(&Feature::more_code(true, true)),
-            "alloc,log-debugcon,logger,unstable,global_allocator"
+            "alloc,log-debugcon,logger,unstable,global_allocator,allocation_tracking"
         );
     }
 